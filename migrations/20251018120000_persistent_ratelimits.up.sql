@@ -0,0 +1,11 @@
+-- Add up migration script here
+
+CREATE TABLE ratelimits (
+    limiter TEXT NOT NULL,
+    key TEXT NOT NULL,
+    -- The earliest point in time at which the next request is allowed through without using up any burst capacity, as defined by GCRA
+    theoretical_arrival TIMESTAMP WITH TIME ZONE NOT NULL,
+    PRIMARY KEY (limiter, key)
+);
+
+CREATE INDEX ratelimits_theoretical_arrival_idx ON ratelimits (theoretical_arrival);
@@ -0,0 +1,8 @@
+-- Add up migration script here
+
+-- Quotas of ratelimits overridden at runtime by administrators, taking precedence over both the defaults and the configured quotas
+CREATE TABLE ratelimit_quotas (
+    limiter TEXT PRIMARY KEY,
+    capacity INTEGER NOT NULL CHECK (capacity > 0),
+    seconds BIGINT NOT NULL CHECK (seconds > 0)
+);
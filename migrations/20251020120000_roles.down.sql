@@ -0,0 +1,6 @@
+-- Add down migration script here
+
+DROP TABLE member_roles;
+DROP TABLE role_assignments;
+DROP TABLE role_implications;
+DROP TABLE roles;
@@ -0,0 +1,28 @@
+-- Add up migration script here
+
+-- Roles a user can hold. Roles with a bit set correspond to the permissions defined in code, and are held via the bit of the same
+-- value in members.permissions. All other roles are defined at runtime by administrators and are held via member_roles.
+CREATE TABLE roles (
+    name TEXT PRIMARY KEY CHECK (name <> '' AND name = TRIM(name)),
+    bit INTEGER UNIQUE CHECK (bit > 0 AND bit <= 32768 AND bit & (bit - 1) = 0)
+);
+
+-- Implications between roles in addition to the ones defined in code
+CREATE TABLE role_implications (
+    role TEXT NOT NULL REFERENCES roles(name) ON DELETE CASCADE,
+    implies TEXT NOT NULL REFERENCES roles(name) ON DELETE CASCADE,
+    PRIMARY KEY (role, implies)
+);
+
+-- Assignment relations between roles in addition to the ones defined in code
+CREATE TABLE role_assignments (
+    role TEXT NOT NULL REFERENCES roles(name) ON DELETE CASCADE,
+    assigns TEXT NOT NULL REFERENCES roles(name) ON DELETE CASCADE,
+    PRIMARY KEY (role, assigns)
+);
+
+CREATE TABLE member_roles (
+    member_id INTEGER NOT NULL REFERENCES members(member_id) ON DELETE CASCADE,
+    role TEXT NOT NULL REFERENCES roles(name) ON DELETE CASCADE,
+    PRIMARY KEY (member_id, role)
+);
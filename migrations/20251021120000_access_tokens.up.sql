@@ -0,0 +1,16 @@
+-- Add up migration script here
+
+-- Personal access tokens. Unlike the tokens handed out on login, these can be revoked individually, can expire and can be
+-- restricted in scope
+CREATE TABLE access_tokens (
+    id SERIAL PRIMARY KEY,
+    member_id INTEGER NOT NULL REFERENCES members(member_id) ON DELETE CASCADE,
+    name TEXT NOT NULL CHECK (name <> ''),
+    read_only BOOLEAN NOT NULL DEFAULT FALSE,
+    -- The names of the roles this token is restricted to. NULL means the token has all permissions of its owner
+    permissions TEXT[],
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMP WITH TIME ZONE
+);
+
+CREATE INDEX access_tokens_member_id_idx ON access_tokens(member_id);
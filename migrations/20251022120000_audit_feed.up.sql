@@ -0,0 +1,43 @@
+-- Add up migration script here
+
+-- All audit log tables inherit from audit_log2, so audit_id is unique across (and increasing throughout) all of them. This
+-- view merges them into a single, uniformly shaped feed. Any newly added audit log table needs to be added here as well.
+--
+-- For modifications, `data` contains the previous values of the columns that were changed (as recorded by the audit
+-- triggers, which do not record changes from NULL). Creators are identified by the demon they
+-- belong to, with the player in question given in `data`.
+CREATE VIEW audit_feed AS
+    SELECT audit_id, time, userid, 'demon' AS entity, id AS entity_id, 'addition' AS action, NULL::JSONB AS data FROM demon_additions
+    UNION ALL
+    SELECT audit_id, time, userid, 'demon', id, 'modification', jsonb_strip_nulls(to_jsonb(m) - ARRAY['time', 'audit_id', 'userid', 'id']) FROM demon_modifications m
+    UNION ALL
+    SELECT audit_id, time, userid, 'creator', demon, 'addition', jsonb_build_object('player', creator) FROM creator_additions
+    UNION ALL
+    SELECT audit_id, time, userid, 'creator', demon, 'deletion', jsonb_build_object('player', creator) FROM creator_deletions
+    UNION ALL
+    SELECT audit_id, time, userid, 'player', id, 'addition', NULL FROM player_additions
+    UNION ALL
+    SELECT audit_id, time, userid, 'player', id, 'modification', jsonb_strip_nulls(to_jsonb(m) - ARRAY['time', 'audit_id', 'userid', 'id']) FROM player_modifications m
+    UNION ALL
+    SELECT audit_id, time, userid, 'player', id, 'deletion', NULL FROM player_deletions
+    UNION ALL
+    SELECT audit_id, time, userid, 'record', id, 'addition', NULL FROM record_additions
+    UNION ALL
+    SELECT audit_id, time, userid, 'record', id, 'modification', jsonb_strip_nulls(to_jsonb(m) - ARRAY['time', 'audit_id', 'userid', 'id']) FROM record_modifications m
+    UNION ALL
+    SELECT audit_id, time, userid, 'record', id, 'deletion', NULL FROM record_deletions
+    UNION ALL
+    SELECT audit_id, time, userid, 'record_note', id, 'addition', NULL FROM record_notes_additions
+    UNION ALL
+    SELECT audit_id, time, userid, 'record_note', id, 'modification', jsonb_strip_nulls(to_jsonb(m) - ARRAY['time', 'audit_id', 'userid', 'id']) FROM record_notes_modifications m
+    UNION ALL
+    SELECT audit_id, time, userid, 'record_note', id, 'deletion', NULL FROM record_notes_deletions
+    UNION ALL
+    SELECT audit_id, time, userid, 'submitter', submitter, 'modification', jsonb_strip_nulls(to_jsonb(m) - ARRAY['time', 'audit_id', 'userid', 'submitter']) FROM submitter_modifications m
+    UNION ALL
+    SELECT audit_id, time, userid, 'user', id, 'addition', NULL FROM user_additions
+    UNION ALL
+    -- permissions are stored as BIT(16), which would otherwise be rendered as a string of zeros and ones
+    SELECT audit_id, time, userid, 'user', id, 'modification', jsonb_strip_nulls(to_jsonb(m) - ARRAY['time', 'audit_id', 'userid', 'id'] || jsonb_build_object('permissions', m.permissions::INTEGER)) FROM user_modifications m
+    UNION ALL
+    SELECT audit_id, time, userid, 'user', id, 'deletion', NULL FROM user_deletions;
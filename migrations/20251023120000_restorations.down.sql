@@ -0,0 +1,96 @@
+-- Add down migration script here
+
+CREATE OR REPLACE VIEW audit_feed AS
+    SELECT audit_id, time, userid, 'demon' AS entity, id AS entity_id, 'addition' AS action, NULL::JSONB AS data FROM demon_additions
+    UNION ALL
+    SELECT audit_id, time, userid, 'demon', id, 'modification', jsonb_strip_nulls(to_jsonb(m) - ARRAY['time', 'audit_id', 'userid', 'id']) FROM demon_modifications m
+    UNION ALL
+    SELECT audit_id, time, userid, 'creator', demon, 'addition', jsonb_build_object('player', creator) FROM creator_additions
+    UNION ALL
+    SELECT audit_id, time, userid, 'creator', demon, 'deletion', jsonb_build_object('player', creator) FROM creator_deletions
+    UNION ALL
+    SELECT audit_id, time, userid, 'player', id, 'addition', NULL FROM player_additions
+    UNION ALL
+    SELECT audit_id, time, userid, 'player', id, 'modification', jsonb_strip_nulls(to_jsonb(m) - ARRAY['time', 'audit_id', 'userid', 'id']) FROM player_modifications m
+    UNION ALL
+    SELECT audit_id, time, userid, 'player', id, 'deletion', NULL FROM player_deletions
+    UNION ALL
+    SELECT audit_id, time, userid, 'record', id, 'addition', NULL FROM record_additions
+    UNION ALL
+    SELECT audit_id, time, userid, 'record', id, 'modification', jsonb_strip_nulls(to_jsonb(m) - ARRAY['time', 'audit_id', 'userid', 'id']) FROM record_modifications m
+    UNION ALL
+    SELECT audit_id, time, userid, 'record', id, 'deletion', NULL FROM record_deletions
+    UNION ALL
+    SELECT audit_id, time, userid, 'record_note', id, 'addition', NULL FROM record_notes_additions
+    UNION ALL
+    SELECT audit_id, time, userid, 'record_note', id, 'modification', jsonb_strip_nulls(to_jsonb(m) - ARRAY['time', 'audit_id', 'userid', 'id']) FROM record_notes_modifications m
+    UNION ALL
+    SELECT audit_id, time, userid, 'record_note', id, 'deletion', NULL FROM record_notes_deletions
+    UNION ALL
+    SELECT audit_id, time, userid, 'submitter', submitter, 'modification', jsonb_strip_nulls(to_jsonb(m) - ARRAY['time', 'audit_id', 'userid', 'submitter']) FROM submitter_modifications m
+    UNION ALL
+    SELECT audit_id, time, userid, 'user', id, 'addition', NULL FROM user_additions
+    UNION ALL
+    -- permissions are stored as BIT(16), which would otherwise be rendered as a string of zeros and ones
+    SELECT audit_id, time, userid, 'user', id, 'modification', jsonb_strip_nulls(to_jsonb(m) - ARRAY['time', 'audit_id', 'userid', 'id'] || jsonb_build_object('permissions', m.permissions::INTEGER)) FROM user_modifications m
+    UNION ALL
+    SELECT audit_id, time, userid, 'user', id, 'deletion', NULL FROM user_deletions;
+
+DROP TABLE record_restorations;
+DROP TABLE player_restorations;
+DROP TABLE creator_restorations;
+
+CREATE OR REPLACE FUNCTION audit_record_addition() RETURNS trigger AS $record_add_trigger$
+    BEGIN
+        INSERT INTO record_additions (userid, id) (SELECT id, NEW.id FROM active_user LIMIT 1);
+        RETURN NEW;
+    END;
+$record_add_trigger$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION audit_player_addition() RETURNS trigger AS $record_addition_trigger$
+    BEGIN
+        INSERT INTO player_additions(userid, id)
+        (SELECT id, NEW.id FROM active_user LIMIT 1);
+
+        RETURN NEW;
+    END;
+$record_addition_trigger$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION audit_record_notes_addition() RETURNS trigger AS $record_notes_add_trigger$
+    BEGIN
+        INSERT INTO record_notes_additions (userid, id) (SELECT id, NEW.id FROM active_user LIMIT 1);
+        RETURN NEW;
+    END;
+$record_notes_add_trigger$ LANGUAGE plpgsql;
+
+DROP TRIGGER record_note_deletion_trigger ON record_notes;
+CREATE TRIGGER record_note_deletion_trigger AFTER DELETE ON record_notes FOR EACH ROW EXECUTE PROCEDURE audit_record_notes_modification();
+
+CREATE OR REPLACE FUNCTION audit_record_notes_deletion() RETURNS trigger AS $record_notes_deletion_trigger$
+    BEGIN
+        INSERT INTO record_notes_modifications (userid, id, record, content)
+            (SELECT id, OLD.id, OLD.record, OLD.content FROM active_user LIMIT 1);
+
+        INSERT INTO record_notes_deletion (userid, id)
+            (SELECT id, OLD.id FROM active_user LIMIT 1);
+
+        RETURN NEW;
+    END
+$record_notes_deletion_trigger$ LANGUAGE plpgsql;
+
+ALTER TABLE record_notes_deletions DROP COLUMN record, DROP COLUMN content, DROP COLUMN is_public;
+
+CREATE OR REPLACE FUNCTION audit_record_deletion() RETURNS trigger AS $record_deletion_trigger$
+    BEGIN
+        INSERT INTO record_modifications (userid, id, progress, video, status_, player, demon)
+            (SELECT id, OLD.id, OLD.progress, OLD.video, OLD.status_, OLD.player, OLD.demon
+            FROM active_user LIMIT 1);
+
+        INSERT INTO record_deletions (userid, id)
+            (SELECT id, OLD.id FROM active_user LIMIT 1);
+
+        RETURN NULL;
+    END;
+$record_deletion_trigger$ LANGUAGE plpgsql;
+
+ALTER TABLE record_modifications DROP COLUMN submitter, DROP COLUMN raw_footage;
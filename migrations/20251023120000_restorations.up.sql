@@ -0,0 +1,122 @@
+-- Add up migration script here
+
+-- Deleting a record stores a snapshot of the deleted row in record_modifications. Also include the submitter and raw
+-- footage in it, since a record cannot be restored without them.
+ALTER TABLE record_modifications ADD COLUMN submitter INTEGER NULL, ADD COLUMN raw_footage TEXT NULL;
+
+CREATE OR REPLACE FUNCTION audit_record_deletion() RETURNS trigger AS $record_deletion_trigger$
+    BEGIN
+        INSERT INTO record_modifications (userid, id, progress, video, status_, player, demon, submitter, raw_footage)
+            (SELECT id, OLD.id, OLD.progress, OLD.video, OLD.status_, OLD.player, OLD.demon, OLD.submitter, OLD.raw_footage
+            FROM active_user LIMIT 1);
+
+        INSERT INTO record_deletions (userid, id)
+            (SELECT id, OLD.id FROM active_user LIMIT 1);
+
+        RETURN NULL;
+    END;
+$record_deletion_trigger$ LANGUAGE plpgsql;
+
+-- The deletion trigger on record_notes used to call the modification trigger function, so note deletions were never
+-- recorded. Unlike for records, the snapshot of the deleted note goes into the deletion entry itself, as
+-- record_notes_modifications is used to determine a note's editors and whether it was transferred.
+ALTER TABLE record_notes_deletions ADD COLUMN record INTEGER NULL, ADD COLUMN content TEXT NULL, ADD COLUMN is_public BOOLEAN NULL;
+
+CREATE OR REPLACE FUNCTION audit_record_notes_deletion() RETURNS trigger AS $record_notes_deletion_trigger$
+    BEGIN
+        INSERT INTO record_notes_deletions (userid, id, record, content, is_public)
+            (SELECT id, OLD.id, OLD.record, OLD.content, OLD.is_public FROM active_user LIMIT 1);
+
+        RETURN NULL;
+    END
+$record_notes_deletion_trigger$ LANGUAGE plpgsql;
+
+DROP TRIGGER record_note_deletion_trigger ON record_notes;
+CREATE TRIGGER record_note_deletion_trigger AFTER DELETE ON record_notes FOR EACH ROW EXECUTE PROCEDURE audit_record_notes_deletion();
+
+-- Restoring a deleted record, player or note re-inserts it under its original id. The addition triggers skip such rows, so
+-- that the original addition entry (and with it, for example, the author of a note) is kept, and the restoration is recorded
+-- in one of the tables below instead. Creators have no id, so restoring them additionally creates a regular addition entry.
+-- `deletion` is the audit_id of the deletion that was undone.
+CREATE TABLE record_restorations (
+    id INTEGER NOT NULL,
+    deletion INTEGER NOT NULL
+) INHERITS (audit_log2);
+
+CREATE TABLE player_restorations (
+    id INTEGER NOT NULL,
+    deletion INTEGER NOT NULL
+) INHERITS (audit_log2);
+
+CREATE TABLE creator_restorations (
+    creator INTEGER NOT NULL,
+    demon INTEGER NOT NULL,
+    deletion INTEGER NOT NULL
+) INHERITS (audit_log2);
+
+CREATE OR REPLACE FUNCTION audit_record_addition() RETURNS trigger AS $record_add_trigger$
+    BEGIN
+        INSERT INTO record_additions (userid, id)
+            (SELECT id, NEW.id FROM active_user WHERE NOT EXISTS (SELECT 1 FROM record_additions WHERE record_additions.id = NEW.id) LIMIT 1);
+        RETURN NEW;
+    END;
+$record_add_trigger$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION audit_player_addition() RETURNS trigger AS $record_addition_trigger$
+    BEGIN
+        INSERT INTO player_additions(userid, id)
+        (SELECT id, NEW.id FROM active_user WHERE NOT EXISTS (SELECT 1 FROM player_additions WHERE player_additions.id = NEW.id) LIMIT 1);
+
+        RETURN NEW;
+    END;
+$record_addition_trigger$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION audit_record_notes_addition() RETURNS trigger AS $record_notes_add_trigger$
+    BEGIN
+        INSERT INTO record_notes_additions (userid, id)
+            (SELECT id, NEW.id FROM active_user WHERE NOT EXISTS (SELECT 1 FROM record_notes_additions WHERE record_notes_additions.id = NEW.id) LIMIT 1);
+        RETURN NEW;
+    END;
+$record_notes_add_trigger$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE VIEW audit_feed AS
+    SELECT audit_id, time, userid, 'demon' AS entity, id AS entity_id, 'addition' AS action, NULL::JSONB AS data FROM demon_additions
+    UNION ALL
+    SELECT audit_id, time, userid, 'demon', id, 'modification', jsonb_strip_nulls(to_jsonb(m) - ARRAY['time', 'audit_id', 'userid', 'id']) FROM demon_modifications m
+    UNION ALL
+    SELECT audit_id, time, userid, 'creator', demon, 'addition', jsonb_build_object('player', creator) FROM creator_additions
+    UNION ALL
+    SELECT audit_id, time, userid, 'creator', demon, 'deletion', jsonb_build_object('player', creator) FROM creator_deletions
+    UNION ALL
+    SELECT audit_id, time, userid, 'player', id, 'addition', NULL FROM player_additions
+    UNION ALL
+    SELECT audit_id, time, userid, 'player', id, 'modification', jsonb_strip_nulls(to_jsonb(m) - ARRAY['time', 'audit_id', 'userid', 'id']) FROM player_modifications m
+    UNION ALL
+    SELECT audit_id, time, userid, 'player', id, 'deletion', NULL FROM player_deletions
+    UNION ALL
+    SELECT audit_id, time, userid, 'record', id, 'addition', NULL FROM record_additions
+    UNION ALL
+    SELECT audit_id, time, userid, 'record', id, 'modification', jsonb_strip_nulls(to_jsonb(m) - ARRAY['time', 'audit_id', 'userid', 'id']) FROM record_modifications m
+    UNION ALL
+    SELECT audit_id, time, userid, 'record', id, 'deletion', NULL FROM record_deletions
+    UNION ALL
+    SELECT audit_id, time, userid, 'record_note', id, 'addition', NULL FROM record_notes_additions
+    UNION ALL
+    SELECT audit_id, time, userid, 'record_note', id, 'modification', jsonb_strip_nulls(to_jsonb(m) - ARRAY['time', 'audit_id', 'userid', 'id']) FROM record_notes_modifications m
+    UNION ALL
+    SELECT audit_id, time, userid, 'record_note', id, 'deletion', NULL FROM record_notes_deletions
+    UNION ALL
+    SELECT audit_id, time, userid, 'submitter', submitter, 'modification', jsonb_strip_nulls(to_jsonb(m) - ARRAY['time', 'audit_id', 'userid', 'submitter']) FROM submitter_modifications m
+    UNION ALL
+    SELECT audit_id, time, userid, 'user', id, 'addition', NULL FROM user_additions
+    UNION ALL
+    -- permissions are stored as BIT(16), which would otherwise be rendered as a string of zeros and ones
+    SELECT audit_id, time, userid, 'user', id, 'modification', jsonb_strip_nulls(to_jsonb(m) - ARRAY['time', 'audit_id', 'userid', 'id'] || jsonb_build_object('permissions', m.permissions::INTEGER)) FROM user_modifications m
+    UNION ALL
+    SELECT audit_id, time, userid, 'user', id, 'deletion', NULL FROM user_deletions
+    UNION ALL
+    SELECT audit_id, time, userid, 'creator', demon, 'restoration', jsonb_build_object('player', creator, 'deletion', deletion) FROM creator_restorations
+    UNION ALL
+    SELECT audit_id, time, userid, 'player', id, 'restoration', jsonb_build_object('deletion', deletion) FROM player_restorations
+    UNION ALL
+    SELECT audit_id, time, userid, 'record', id, 'restoration', jsonb_build_object('deletion', deletion) FROM record_restorations;
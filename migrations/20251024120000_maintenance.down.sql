@@ -0,0 +1,4 @@
+-- Add down migration script here
+
+DROP TABLE maintenance_messages;
+DROP TABLE maintenance_window;
@@ -0,0 +1,16 @@
+-- Add up migration script here
+
+-- The maintenance window scheduled by administrators at runtime. There is at most one window, hence the single-row table.
+-- A NULL start means "starting immediately", a NULL end means "until the window is removed".
+CREATE TABLE maintenance_window (
+    id BOOLEAN PRIMARY KEY DEFAULT TRUE CHECK (id),
+    starts_at TIMESTAMPTZ NULL,
+    ends_at TIMESTAMPTZ NULL,
+    CHECK (starts_at IS NULL OR ends_at IS NULL OR starts_at < ends_at)
+);
+
+-- The message displayed to users during the maintenance window, per language
+CREATE TABLE maintenance_messages (
+    language TEXT PRIMARY KEY,
+    message TEXT NOT NULL
+);
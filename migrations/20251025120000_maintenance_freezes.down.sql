@@ -0,0 +1,3 @@
+-- Add down migration script here
+
+ALTER TABLE maintenance_window DROP COLUMN frozen;
@@ -0,0 +1,4 @@
+-- Add up migration script here
+
+-- The route groups (e.g. record submissions) frozen during the maintenance window. If empty, all mutating requests are rejected.
+ALTER TABLE maintenance_window ADD COLUMN frozen TEXT[] NOT NULL DEFAULT '{}';
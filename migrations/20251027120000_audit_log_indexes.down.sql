@@ -0,0 +1,22 @@
+-- Add down migration script here
+
+DROP INDEX creator_additions_demon_idx;
+DROP INDEX creator_deletions_demon_idx;
+DROP INDEX creator_restorations_demon_idx;
+DROP INDEX demon_additions_id_idx;
+DROP INDEX demon_modifications_id_idx;
+DROP INDEX player_additions_id_idx;
+DROP INDEX player_modifications_id_idx;
+DROP INDEX player_deletions_id_idx;
+DROP INDEX player_restorations_id_idx;
+DROP INDEX record_additions_id_idx;
+DROP INDEX record_modifications_id_idx;
+DROP INDEX record_deletions_id_idx;
+DROP INDEX record_restorations_id_idx;
+DROP INDEX record_notes_additions_id_idx;
+DROP INDEX record_notes_modifications_id_idx;
+DROP INDEX record_notes_deletions_id_idx;
+DROP INDEX submitter_modifications_submitter_idx;
+DROP INDEX user_additions_id_idx;
+DROP INDEX user_modifications_id_idx;
+DROP INDEX user_deletions_id_idx;
@@ -0,0 +1,24 @@
+-- Add up migration script here
+
+-- Looking up the audit log of a single entity (e.g. to determine when it was last modified) filters the audit_feed view by
+-- entity id, which postgres pushes down into each of the audit log tables
+CREATE INDEX creator_additions_demon_idx ON creator_additions (demon);
+CREATE INDEX creator_deletions_demon_idx ON creator_deletions (demon);
+CREATE INDEX creator_restorations_demon_idx ON creator_restorations (demon);
+CREATE INDEX demon_additions_id_idx ON demon_additions (id);
+CREATE INDEX demon_modifications_id_idx ON demon_modifications (id);
+CREATE INDEX player_additions_id_idx ON player_additions (id);
+CREATE INDEX player_modifications_id_idx ON player_modifications (id);
+CREATE INDEX player_deletions_id_idx ON player_deletions (id);
+CREATE INDEX player_restorations_id_idx ON player_restorations (id);
+CREATE INDEX record_additions_id_idx ON record_additions (id);
+CREATE INDEX record_modifications_id_idx ON record_modifications (id);
+CREATE INDEX record_deletions_id_idx ON record_deletions (id);
+CREATE INDEX record_restorations_id_idx ON record_restorations (id);
+CREATE INDEX record_notes_additions_id_idx ON record_notes_additions (id);
+CREATE INDEX record_notes_modifications_id_idx ON record_notes_modifications (id);
+CREATE INDEX record_notes_deletions_id_idx ON record_notes_deletions (id);
+CREATE INDEX submitter_modifications_submitter_idx ON submitter_modifications (submitter);
+CREATE INDEX user_additions_id_idx ON user_additions (id);
+CREATE INDEX user_modifications_id_idx ON user_modifications (id);
+CREATE INDEX user_deletions_id_idx ON user_deletions (id);
@@ -0,0 +1,140 @@
+//! Module providing the fairing that keeps the [`ResponseCache`] and all [`Reloadable`] state in sync with the database
+
+use pointercrate_core::{
+    cache::{CacheRegion, ReloadRegistry, Reloadable, ResponseCache, INVALIDATION_CHANNEL},
+    pool::PointercratePool,
+};
+use rocket::{
+    fairing::{Fairing, Info, Kind},
+    tokio::{self, sync::oneshot, time::Duration},
+    Build, Rocket,
+};
+use sqlx::{postgres::PgListener, Pool, Postgres};
+
+/// Rocket fairing managing the [`ResponseCache`] of this instance
+///
+/// On ignite, starts a background task listening for notifications on the [`INVALIDATION_CHANNEL`], evicting the regions named in
+/// them and reloading the state registered for them via [`register_reloadable`]. The task ends once the rocket instance is dropped.
+pub struct ResponseCacheFairing;
+
+/// Registers the given state with the [`ReloadRegistry`] managed by the given rocket instance, so that it is reloaded whenever the
+/// given region is invalidated, on this and all other instances
+///
+/// If no registry is managed yet, a new one is created. Reloading requires the [`ResponseCacheFairing`] to be attached.
+pub fn register_reloadable(rocket: Rocket<Build>, region: CacheRegion, state: impl Reloadable + 'static) -> Rocket<Build> {
+    match rocket.state::<ReloadRegistry>() {
+        Some(registry) => {
+            registry.register(region, state);
+
+            rocket
+        },
+        None => {
+            let registry = ReloadRegistry::default();
+
+            registry.register(region, state);
+
+            rocket.manage(registry)
+        },
+    }
+}
+
+/// Keeps the invalidation listener alive for as long as it is managed by rocket
+struct InvalidationListener {
+    _shutdown: oneshot::Sender<()>,
+}
+
+#[rocket::async_trait]
+impl Fairing for ResponseCacheFairing {
+    fn info(&self) -> Info {
+        Info {
+            name: "Response Cache",
+            kind: Kind::Ignite | Kind::Singleton,
+        }
+    }
+
+    async fn on_ignite(&self, rocket: Rocket<Build>) -> rocket::fairing::Result {
+        let Some(pool) = rocket.state::<PointercratePool>() else {
+            log::error!("No connection pool managed, cannot listen for cache invalidations");
+
+            return Err(rocket);
+        };
+
+        let mut listener = match PgListener::connect_with(&pool.clone_inner()).await {
+            Ok(listener) => listener,
+            Err(err) => {
+                log::error!("Failed to connect cache invalidation listener: {}", err);
+
+                return Err(rocket);
+            },
+        };
+
+        if let Err(err) = listener.listen(INVALIDATION_CHANNEL).await {
+            log::error!("Failed to listen for cache invalidations: {}", err);
+
+            return Err(rocket);
+        }
+
+        let pool = pool.clone_inner();
+
+        // Fairings attached after this one might still register state, so the registry is shared with the listener instead of copied
+        let (rocket, registry) = match rocket.state::<ReloadRegistry>() {
+            Some(registry) => {
+                let registry = registry.clone();
+
+                (rocket, registry)
+            },
+            None => {
+                let registry = ReloadRegistry::default();
+
+                (rocket.manage(registry.clone()), registry)
+            },
+        };
+
+        let cache = ResponseCache::default();
+        let (shutdown, stopped) = oneshot::channel();
+
+        tokio::spawn(listen_for_invalidations(listener, cache.clone(), registry, pool, stopped));
+
+        Ok(rocket.manage(cache).manage(InvalidationListener { _shutdown: shutdown }))
+    }
+}
+
+async fn listen_for_invalidations(
+    mut listener: PgListener, cache: ResponseCache, registry: ReloadRegistry, pool: Pool<Postgres>, mut stopped: oneshot::Receiver<()>,
+) {
+    loop {
+        tokio::select! {
+            _ = &mut stopped => break,
+            notification = listener.try_recv() => match notification {
+                Ok(Some(notification)) => {
+                    cache.evict(notification.payload());
+                    reload(&registry, &pool, Some(notification.payload())).await;
+                },
+                // The connection was lost, and any notifications sent until it is re-established (on the next call) are lost
+                Ok(None) => {
+                    cache.evict_all();
+                    reload(&registry, &pool, None).await;
+                },
+                Err(err) => {
+                    log::error!("Failed to receive cache invalidations: {}", err);
+
+                    cache.evict_all();
+                    reload(&registry, &pool, None).await;
+                    tokio::time::sleep(Duration::from_secs(1)).await;
+                },
+            }
+        }
+    }
+}
+
+/// Reloads the state registered for the given region, or all registered state if no region is given
+async fn reload(registry: &ReloadRegistry, pool: &Pool<Postgres>, region: Option<&str>) {
+    let reloaded = match region {
+        Some(region) => registry.reload(region, pool).await,
+        None => registry.reload_all(pool).await,
+    };
+
+    if let Err(err) = reloaded {
+        log::error!("Failed to reload state after invalidation: {}", err);
+    }
+}
@@ -0,0 +1,56 @@
+//! Module providing a fairing checking the configuration against the rocket instance it is used for
+
+use pointercrate_core::{
+    config::{self, RatelimitConfig},
+    ratelimits::RatelimitRegistry,
+};
+use rocket::{
+    fairing::{AdHoc, Fairing, Info, Kind},
+    Build, Rocket,
+};
+
+/// Fairing making sure that the configuration was loaded, and that it only configures things that actually exist
+///
+/// In release builds, launching fails unless the [`CoreConfig`](config::CoreConfig) was loaded (see [`config::secret`]). Quotas
+/// configured for ratelimits that are not registered with the managed [`RatelimitRegistry`] fail launching as well, as they are
+/// most likely typos.
+pub struct ConfigFairing;
+
+#[rocket::async_trait]
+impl Fairing for ConfigFairing {
+    fn info(&self) -> Info {
+        Info {
+            name: "Configuration",
+            kind: Kind::Ignite | Kind::Singleton,
+        }
+    }
+
+    async fn on_ignite(&self, rocket: Rocket<Build>) -> rocket::fairing::Result {
+        if !cfg!(debug_assertions) && !config::secret_loaded() {
+            log::error!("The configuration needs to be loaded before launching");
+
+            return Err(rocket);
+        }
+
+        // Ratelimits may be registered by the ignite fairings of other components. Fairings attached during ignition run after all
+        // others, so by then all ratelimits are known
+        Ok(rocket.attach(AdHoc::try_on_ignite("Ratelimit configuration", |rocket| async {
+            let Some(config) = rocket.state::<RatelimitConfig>() else {
+                return Ok(rocket);
+            };
+
+            let unknown = match rocket.state::<RatelimitRegistry>() {
+                Some(registry) => registry.unknown_quotas(config),
+                None => config.quotas.keys().map(String::as_str).collect(),
+            };
+
+            if !unknown.is_empty() {
+                log::error!("Quotas configured for unknown ratelimits: {}", unknown.join(", "));
+
+                return Err(rocket);
+            }
+
+            Ok(rocket)
+        })))
+    }
+}
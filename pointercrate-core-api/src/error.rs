@@ -1,16 +1,17 @@
-use crate::localization::LOCALE_COOKIE_NAME;
-use crate::preferences::{ClientPreferences, PreferenceManager};
+use crate::localization::negotiate_locale;
+use crate::preferences::PreferenceManager;
 use crate::response::Page;
 use log::info;
 use pointercrate_core::error::PointercrateError;
-use pointercrate_core::localization::{LocaleConfiguration, LANGUAGE};
+use pointercrate_core::localization::LANGUAGE;
+use pointercrate_core::ratelimits::RatelimitStatus;
 use pointercrate_core_pages::error::ErrorFragment;
 use pointercrate_core_pages::PageFragment;
 use rocket::outcome::Outcome;
 use rocket::tokio::runtime::Handle;
 use rocket::tokio::task::block_in_place;
 use rocket::{
-    http::{MediaType, Status},
+    http::{Header, MediaType, Status},
     response::Responder,
     serde::json::Json,
     Request, Response,
@@ -26,10 +27,21 @@ pub struct ErrorResponder {
     #[serde(rename = "code")]
     error_code: u16,
     data: Value,
+    #[serde(skip)]
+    headers: Vec<(&'static str, String)>,
+}
+
+impl ErrorResponder {
+    /// Sets the `RateLimit-Limit`, `RateLimit-Remaining` and `RateLimit-Reset` headers, for errors reported after the request was
+    /// already counted against a ratelimit
+    pub fn with_ratelimit(mut self, status: RatelimitStatus) -> Self {
+        self.headers.extend(status.headers());
+        self
+    }
 }
 
 impl<'r> Responder<'r, 'static> for ErrorResponder {
-    fn respond_to(self, request: &'r Request<'_>) -> rocket::response::Result<'static> {
+    fn respond_to(mut self, request: &'r Request<'_>) -> rocket::response::Result<'static> {
         let accept = match request.accept() {
             None => {
                 info!("No ACCEPT header set, assuming application/json");
@@ -40,13 +52,11 @@ impl<'r> Responder<'r, 'static> for ErrorResponder {
         };
 
         let status = Status::from_code(self.error_code / 100).unwrap_or(Status::InternalServerError);
+        let headers = std::mem::take(&mut self.headers);
 
-        if *accept == MediaType::HTML {
+        let mut response = if *accept == MediaType::HTML {
             let preference_manager = request.rocket().state::<PreferenceManager>().ok_or(Status::InternalServerError)?;
-            let preferences = ClientPreferences::from_cookies(request.cookies(), preference_manager);
-
-            let language = preferences.get(LOCALE_COOKIE_NAME).ok_or(Status::InternalServerError)?;
-            let lang_id = LocaleConfiguration::get().by_code(language);
+            let lang_id = negotiate_locale(request, preference_manager).ok_or(Status::InternalServerError)?;
 
             let fragment = block_in_place(move || {
                 Handle::current().block_on(async {
@@ -62,10 +72,20 @@ impl<'r> Responder<'r, 'static> for ErrorResponder {
                 })
             });
 
-            Response::build_from(Page::new(fragment).respond_to(request)?).status(status).ok()
+            let mut response = Response::build_from(Page::new(fragment).respond_to(request)?);
+
+            // The error page is rendered in the locale negotiated above, which may have been taken from the Accept-Language header
+            response.header(Header::new("Vary", "Accept-Language"));
+            response
         } else {
-            Response::build_from(Json(self).respond_to(request)?).status(status).ok()
+            Response::build_from(Json(self).respond_to(request)?)
+        };
+
+        for (name, value) in headers {
+            response.header(Header::new(name, value));
         }
+
+        response.status(status).ok()
     }
 }
 
@@ -74,7 +94,8 @@ impl<E: PointercrateError> From<E> for ErrorResponder {
         ErrorResponder {
             message: error.to_string(),
             error_code: error.error_code(),
-            data: serde_json::to_value(error).expect("failed to serialize error to json"),
+            data: serde_json::to_value(&error).expect("failed to serialize error to json"),
+            headers: error.headers(),
         }
     }
 }
@@ -1,6 +1,18 @@
+//! Conditional request handling as described in [RFC 9110, Section 13](https://www.rfc-editor.org/rfc/rfc9110#section-13)
+//!
+//! Mutating requests (`PATCH`, `DELETE`) must carry either an `If-Match` or an `If-Unmodified-Since` header (see
+//! [`Precondition`]). If both are given, `If-Unmodified-Since` is ignored. `GET` requests can be made conditional via
+//! `If-None-Match`, both for single objects ([`Tagged`]) and for listings (see [`Response2`]). `If-Modified-Since` is not
+//! supported, as the representations of most objects include data about other objects (e.g. the records of a player), whose
+//! modifications are not reflected in the `Last-Modified` date.
+
 use crate::error::IntoOutcome2;
 use crate::response::Response2;
-use pointercrate_core::{error::CoreError, etag::Taggable};
+use chrono::{DateTime, Utc};
+use pointercrate_core::{
+    error::CoreError,
+    etag::{patch_part_of, Taggable},
+};
 use rocket::{
     http::{Method, Status},
     request::{FromRequest, Outcome},
@@ -11,22 +23,61 @@ use rocket::{
 
 pub struct Tagged<T: Taggable>(pub T);
 
-pub struct Precondition(Vec<String> /* ensure private constructor for type level proof of header */);
+/// The preconditions of a mutating request
+///
+/// Can only be constructed from a request carrying an `If-Match` or `If-Unmodified-Since` header, meaning that taking a
+/// [`Precondition`] as a request guard makes these headers mandatory (requests without them receive a `428 PRECONDITION
+/// REQUIRED` response).
+pub struct Precondition {
+    if_match: Option<IfMatch>,
+    if_unmodified_since: Option<DateTime<Utc>>,
+}
+
+enum IfMatch {
+    /// `If-Match: *`, which matches any current representation of the target object
+    Any,
+    Tags(Vec<String>),
+}
 
 impl Precondition {
+    /// Evaluates the preconditions of this request against the current state of the object it targets, without taking
+    /// modification dates into account
+    ///
+    /// Requests relying on `If-Unmodified-Since` alone are rejected with `428 PRECONDITION REQUIRED`, as RFC 9110 requires the
+    /// header to be ignored if the target has no modification date.
     pub fn require_etag_match<T: Taggable>(&self, taggable: &T) -> Result<(), CoreError> {
-        let patch_etag = taggable.patch_part().to_string();
-
-        if self
-            .0
-            .iter()
-            .filter_map(|if_match| if_match.split(';').next())
-            .filter(|etag_fragment| etag_fragment.len() > 3)
-            .any(|e| e[3..] == patch_etag)
-        {
-            Ok(())
-        } else {
-            Err(CoreError::PreconditionFailed)
+        self.require(taggable, None)
+    }
+
+    /// Evaluates the preconditions of this request against the current state of the object it targets, which was last modified
+    /// at the given time (if known)
+    ///
+    /// Returns `412 PRECONDITION FAILED` if the object does not match `If-Match`, or, in absence of that header, was modified
+    /// after the `If-Unmodified-Since` date.
+    pub fn require<T: Taggable>(&self, taggable: &T, last_modified: Option<DateTime<Utc>>) -> Result<(), CoreError> {
+        match self.if_match {
+            Some(IfMatch::Any) => Ok(()),
+            Some(IfMatch::Tags(ref tags)) => {
+                let patch_etag = taggable.patch_part().to_string();
+
+                if tags
+                    .iter()
+                    .filter_map(|if_match| patch_part_of(if_match))
+                    .any(|patch_part| patch_part == patch_etag)
+                {
+                    Ok(())
+                } else {
+                    Err(CoreError::PreconditionFailed)
+                }
+            },
+            None => match (self.if_unmodified_since, last_modified) {
+                // HTTP dates only have a precision of one second
+                (Some(if_unmodified_since), Some(last_modified)) if last_modified.timestamp() > if_unmodified_since.timestamp() => {
+                    Err(CoreError::PreconditionFailed)
+                },
+                (Some(_), Some(_)) => Ok(()),
+                _ => Err(CoreError::PreconditionRequired),
+            },
         }
     }
 }
@@ -36,35 +87,61 @@ impl<'r> FromRequest<'r> for Precondition {
     type Error = CoreError;
 
     async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
-        request
-            .headers()
-            .get_one("if-match")
-            .map(|if_match| Precondition(if_match.split(',').map(ToString::to_string).collect()))
-            .ok_or(CoreError::PreconditionFailed)
-            .into_outcome()
+        let if_match = request.headers().get_one("if-match").map(|if_match| match if_match.trim() {
+            "*" => IfMatch::Any,
+            tags => IfMatch::Tags(tags.split(',').map(ToString::to_string).collect()),
+        });
+
+        // RFC 9110 requires invalid dates to be ignored
+        let if_unmodified_since = request.headers().get_one("if-unmodified-since").and_then(parse_http_date);
+
+        if if_match.is_none() && if_unmodified_since.is_none() {
+            return CoreError::PreconditionRequired.into_outcome();
+        }
+
+        Outcome::Success(Precondition {
+            if_match,
+            if_unmodified_since,
+        })
+    }
+}
+
+/// Whether the given request is a `GET` (or `HEAD`) request whose `If-None-Match` header matches the given ETag, meaning it can
+/// be answered with `304 NOT MODIFIED`
+///
+/// Uses the weak comparison function, as all pointercrate ETags are weak.
+pub(crate) fn is_not_modified(request: &Request<'_>, etag: &str) -> bool {
+    if !matches!(request.method(), Method::Get | Method::Head) {
+        return false;
     }
+
+    let opaque_tag = etag.trim_start_matches("W/");
+
+    request
+        .headers()
+        .get("if-none-match")
+        .flat_map(|value| value.split(','))
+        .any(|candidate| {
+            let candidate = candidate.trim();
+
+            candidate == "*" || candidate.trim_start_matches("W/") == opaque_tag
+        })
+}
+
+pub(crate) fn parse_http_date(date: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc2822(date).ok().map(|date| date.with_timezone(&Utc))
+}
+
+pub(crate) fn format_http_date(date: DateTime<Utc>) -> String {
+    date.format("%a, %d %b %Y %H:%M:%S GMT").to_string()
 }
 
 impl<'r, T: Taggable> Responder<'r, 'static> for Tagged<T> {
     fn respond_to(self, request: &'r Request<'_>) -> rocket::response::Result<'static> {
         let response_etag = self.0.etag_string();
 
-        match request.method() {
-            Method::Get => {
-                if let Some(if_none_match) = request.headers().get_one("if-none-match") {
-                    if if_none_match.contains(&response_etag) {
-                        return Response::build().status(Status::NotModified).ok();
-                    }
-                }
-            },
-            Method::Patch | Method::Delete => {
-                if let Some(if_none_match) = request.headers().get_one("if-match") {
-                    if if_none_match.contains(&response_etag) {
-                        return Response::build().status(Status::NotModified).ok();
-                    }
-                }
-            },
-            _ => (),
+        if is_not_modified(request, &response_etag) {
+            return Response::build().status(Status::NotModified).raw_header("etag", response_etag).ok();
         }
 
         Response2::new(Json(serde_json::json! {{"data": self.0}}))
@@ -74,13 +151,28 @@ impl<'r, T: Taggable> Responder<'r, 'static> for Tagged<T> {
 }
 
 pub trait TaggableExt: Taggable {
-    fn require_match(self, precondition: Precondition) -> Result<Self, CoreError>
+    fn require_match(self, precondition: Precondition, last_modified: Option<DateTime<Utc>>) -> Result<Self, CoreError>
     where
         Self: Sized,
     {
-        precondition.require_etag_match(&self)?;
+        precondition.require(&self, last_modified)?;
         Ok(self)
     }
 }
 
 impl<T: Taggable> TaggableExt for T {}
+
+#[cfg(test)]
+mod tests {
+    use super::{format_http_date, parse_http_date};
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn test_http_dates() {
+        let date = Utc.with_ymd_and_hms(1994, 11, 6, 8, 49, 37).unwrap();
+
+        assert_eq!(format_http_date(date), "Sun, 06 Nov 1994 08:49:37 GMT");
+        assert_eq!(parse_http_date("Sun, 06 Nov 1994 08:49:37 GMT"), Some(date));
+        assert_eq!(parse_http_date("yesterday"), None);
+    }
+}
@@ -0,0 +1,153 @@
+use std::ops::DerefMut;
+
+use log::error;
+use pointercrate_core::{error::CoreError, export::Exportable, pagination::PaginationQuery};
+use rocket::{
+    futures::stream::{BoxStream, StreamExt},
+    http::{ContentType, MediaType},
+    request::{FromRequest, Outcome},
+    response::{
+        stream::{stream, TextStream},
+        Responder,
+    },
+    Request, Response,
+};
+use sqlx::PgConnection;
+
+/// The formats in which a listing can be exported, selected via the `Accept` header of the request
+///
+/// Requests that prefer `text/csv` get CSV, all other requests get newline delimited JSON.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    NdJson,
+    Csv,
+}
+
+impl ExportFormat {
+    fn content_type(self) -> ContentType {
+        match self {
+            ExportFormat::NdJson => ContentType::new("application", "x-ndjson"),
+            ExportFormat::Csv => ContentType::CSV,
+        }
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for ExportFormat {
+    type Error = CoreError;
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        match request.accept() {
+            Some(accept) if accept.preferred().media_type() == &MediaType::CSV => Outcome::Success(ExportFormat::Csv),
+            _ => Outcome::Success(ExportFormat::NdJson),
+        }
+    }
+}
+
+/// A response streaming an export of some listing line by line
+pub struct ExportResponse {
+    format: ExportFormat,
+    lines: BoxStream<'static, String>,
+}
+
+impl<'r> Responder<'r, 'r> for ExportResponse {
+    fn respond_to(self, request: &'r Request<'_>) -> rocket::response::Result<'r> {
+        Response::build_from(TextStream(self.lines).respond_to(request)?)
+            .header(self.format.content_type())
+            .ok()
+    }
+}
+
+/// Exports all objects matching the given query in the given format
+///
+/// The export is streamed from the database while the response is being sent, meaning the given connection is held until the client
+/// has received the entire export. Since the response status has already been sent at that point, errors during the export can only
+/// be logged, and result in a truncated response.
+pub fn export_response<Q, P, C>(query: Q, connection: C, format: ExportFormat) -> Result<ExportResponse, CoreError>
+where
+    Q: PaginationQuery + Send + Sync + 'static,
+    P: Exportable<Q> + Send,
+    C: DerefMut<Target = PgConnection> + Send + 'static,
+{
+    let parameters = query.parameters();
+
+    parameters.validate()?;
+
+    // Exports always start at the beginning of the listing, so a cursor into it is meaningless. For keyset paginated listings,
+    // the `before` and `after` parameters are meaningless as well (see `keyset_pagination_response`)
+    if parameters.cursor.is_some() || (P::keyset_paginated(&query) && (parameters.before.is_some() || parameters.after.is_some())) {
+        return Err(CoreError::InvalidPaginationCursor);
+    }
+
+    let lines = stream! {
+        let mut connection = connection;
+
+        if format == ExportFormat::Csv {
+            yield csv_line(P::CSV_HEADER);
+        }
+
+        let mut objects = P::export(&query, &mut connection);
+
+        while let Some(object) = objects.next().await {
+            let line = match object {
+                Ok(object) => match format {
+                    ExportFormat::NdJson => serde_json::to_string(&object).map(|json| json + "\n").map_err(|err| err.to_string()),
+                    ExportFormat::Csv => Ok(csv_line(&object.csv_record())),
+                },
+                Err(err) => Err(err.to_string()),
+            };
+
+            match line {
+                Ok(line) => yield line,
+                Err(err) => {
+                    error!("Aborting export for query {:?} due to error: {}", query, err);
+
+                    break
+                },
+            }
+        }
+    };
+
+    Ok(ExportResponse {
+        format,
+        lines: lines.boxed(),
+    })
+}
+
+/// Formats the given fields as a single CSV line, as per RFC 4180
+fn csv_line<S: AsRef<str>>(fields: &[S]) -> String {
+    let mut line = String::new();
+
+    for (idx, field) in fields.iter().enumerate() {
+        let field = field.as_ref();
+
+        if idx != 0 {
+            line.push(',');
+        }
+
+        if field.contains([',', '"', '\r', '\n']) {
+            line.push('"');
+            line.push_str(&field.replace('"', "\"\""));
+            line.push('"');
+        } else {
+            line.push_str(field);
+        }
+    }
+
+    line.push_str("\r\n");
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::csv_line;
+
+    #[test]
+    fn test_csv_line() {
+        assert_eq!(csv_line(&["1", "Bloodbath", ""]), "1,Bloodbath,\r\n");
+        assert_eq!(
+            csv_line(&["Riot, the verifier", "the \"best\" demon", "two\nlines"]),
+            "\"Riot, the verifier\",\"the \"\"best\"\" demon\",\"two\nlines\"\r\n"
+        );
+    }
+}
@@ -1,8 +1,12 @@
+pub mod cache;
+pub mod config;
 pub mod error;
 pub mod etag;
+pub mod export;
 pub mod localization;
 pub mod maintenance;
 pub mod pagination;
 pub mod preferences;
 pub mod query;
+pub mod ratelimits;
 pub mod response;
@@ -1,4 +1,4 @@
-use crate::preferences::{ClientPreferences, PreferenceManager};
+use crate::preferences::{preference_cookie_name, ClientPreferences, PreferenceManager};
 use crate::{tryo_result, tryo_state};
 use pointercrate_core::error::CoreError;
 use pointercrate_core::localization::LocaleConfiguration;
@@ -7,22 +7,43 @@ use rocket::{
     Request,
 };
 use unic_langid::subtags::Language;
+use unic_langid::LanguageIdentifier;
 
 pub const LOCALE_COOKIE_NAME: &str = "locale";
 
 pub struct ClientLocale(pub Language);
 
+/// Determines the locale in which to respond to the given request.
+///
+/// A locale explicitly chosen by the client (and stored in the locale preference cookie) always takes precedence.
+/// Otherwise, the locale is negotiated from the `Accept-Language` header, falling back to the default value of
+/// the locale preference if no available locale is acceptable.
+///
+/// Returns [`None`] if the locale preference was not registered with the given [`PreferenceManager`].
+pub fn negotiate_locale(request: &Request<'_>, preference_manager: &PreferenceManager) -> Option<&'static LanguageIdentifier> {
+    let config = LocaleConfiguration::get();
+
+    if request.cookies().get(&preference_cookie_name(LOCALE_COOKIE_NAME)).is_none() {
+        let accept_language = request.headers().get("Accept-Language").collect::<Vec<_>>().join(",");
+
+        if let Some(lang_id) = config.negotiate(&accept_language) {
+            return Some(lang_id);
+        }
+    }
+
+    let preferences = ClientPreferences::from_cookies(request.cookies(), preference_manager);
+
+    preferences.get(LOCALE_COOKIE_NAME).map(|language| config.by_code(language))
+}
+
 #[rocket::async_trait]
 impl<'r> FromRequest<'r> for ClientLocale {
     type Error = CoreError;
 
     async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
         let preference_manager = tryo_state!(request, PreferenceManager);
-        let preferences = ClientPreferences::from_cookies(request.cookies(), preference_manager);
-        let language = tryo_result!(preferences
-            .get(LOCALE_COOKIE_NAME)
+        let lang_id = tryo_result!(negotiate_locale(request, preference_manager)
             .ok_or_else(|| CoreError::internal_server_error("locale set not registered with preference manager")));
-        let lang_id = LocaleConfiguration::get().by_code(language);
 
         Outcome::Success(ClientLocale(lang_id.language))
     }
@@ -1,18 +1,110 @@
 //! Module providing a "maintenance mode" fairing (middleware)
 
-use crate::{error::Result, localization::ClientLocale};
-use pointercrate_core::{error::CoreError, localization::LANGUAGE};
+use crate::{cache::register_reloadable, error::Result, localization::ClientLocale};
+use chrono::Utc;
+use maud::{html, Markup};
+use pointercrate_core::{
+    error::CoreError,
+    localization::{format_datetime, task_lang, tr, LocaleConfiguration, LANGUAGE},
+    maintenance::{Maintenance, MAINTENANCE},
+    pool::PointercratePool,
+    trp,
+};
 use rocket::{
     fairing::{Fairing, Info, Kind},
     http::Method,
-    routes, uri, Build, Data, Request, Rocket,
+    routes, uri, Build, Data, Request, Rocket, State,
 };
+use std::sync::Mutex;
+
+/// The path at which the administrative maintenance API is mounted. Requests to it are exempt from maintenance mode, so that
+/// administrators can end maintenance early.
+pub const MAINTENANCE_API: &str = "/api/v1/maintenance/";
+
+/// A group of mutating routes implementing some feature of the website, which can be frozen independently of the rest of it
+///
+/// Routes are given as a method and a path. Dynamic segments in the path are written as `<_>`, and a trailing `<_..>` matches
+/// any number of remaining segments.
+#[derive(Debug, Clone, Copy)]
+pub struct RouteGroup {
+    text_id: &'static str,
+    routes: &'static [(Method, &'static str)],
+}
+
+impl RouteGroup {
+    pub const fn new(text_id: &'static str, routes: &'static [(Method, &'static str)]) -> Self {
+        RouteGroup { text_id, routes }
+    }
+
+    pub fn text_id(&self) -> &'static str {
+        self.text_id
+    }
+
+    pub fn matches(&self, method: Method, path: &str) -> bool {
+        self.routes
+            .iter()
+            .any(|&(route_method, pattern)| route_method == method && path_matches(pattern, path))
+    }
+}
+
+fn path_matches(pattern: &str, path: &str) -> bool {
+    let mut segments = path.split('/').filter(|segment| !segment.is_empty());
+
+    for pattern_segment in pattern.split('/').filter(|segment| !segment.is_empty()) {
+        if pattern_segment.starts_with('<') && pattern_segment.ends_with("..>") {
+            return true;
+        }
+
+        match segments.next() {
+            Some(segment) if segment == pattern_segment || pattern_segment.starts_with('<') => (),
+            _ => return false,
+        }
+    }
+
+    segments.next().is_none()
+}
+
+/// All [`RouteGroup`]s known to the maintenance fairing
+#[derive(Debug, Default)]
+pub struct RouteGroups(Mutex<Vec<RouteGroup>>);
+
+impl RouteGroups {
+    pub fn groups(&self) -> Vec<RouteGroup> {
+        self.0.lock().unwrap().clone()
+    }
+
+    pub fn find(&self, text_id: &str) -> Option<RouteGroup> {
+        self.groups().into_iter().find(|group| group.text_id == text_id)
+    }
+}
+
+/// Registers the given [`RouteGroup`]s with the [`RouteGroups`] managed by the given rocket instance, allowing administrators to
+/// freeze them
+///
+/// If no [`RouteGroups`] are managed yet, they are created.
+pub fn register_route_groups(rocket: Rocket<Build>, groups: &[RouteGroup]) -> Rocket<Build> {
+    match rocket.state::<RouteGroups>() {
+        Some(route_groups) => {
+            route_groups.0.lock().unwrap().extend_from_slice(groups);
 
-/// Rocket fairing that causes all mutating requests (aka non-GET requests) to return 503 SERVICE UNAVAILABLE if `.0` is `true`.
+            rocket
+        },
+        None => rocket.manage(RouteGroups(Mutex::new(groups.to_vec()))),
+    }
+}
+
+/// Rocket fairing that causes mutating requests (aka non-GET requests) to return 503 SERVICE UNAVAILABLE while maintenance
+/// mode is active.
+///
+/// Maintenance mode is active if `.0` is `true`, or if a
+/// [`MaintenanceWindow`](pointercrate_core::maintenance::MaintenanceWindow) scheduled by an administrator is currently ongoing.
+/// Depending on the window, either all mutating requests are rejected, or only those to the [`RouteGroup`]s it freezes. The
+/// fairing manages the [`Maintenance`] state, loading the scheduled window from the database on ignite, and reloading it whenever
+/// it is changed (which requires the [`ResponseCacheFairing`](crate::cache::ResponseCacheFairing) to be attached).
 ///
 /// Works in a very hacky way, as rocket does not allow fairing to terminate requests. Thus we instead rewrite the
-/// request on the fly to be a GET /maintenance, which is an endpoint that unconditionally returns a 503 response.
-/// This endpoint only exists when maintainence mode is active (it is dynamically mounted in `on_ignite`).
+/// request on the fly to be a GET /maintenance (or GET /maintenance/<route group>), which are endpoints that unconditionally
+/// return a 503 response.
 ///
 /// Idea taken from https://stackoverflow.com/questions/70011965/global-authentication-authorization-in-rocket-based-on-a-header
 #[derive(Default)]
@@ -33,25 +125,157 @@ impl Fairing for MaintenanceFairing {
         }
     }
 
-    async fn on_ignite(&self, mut rocket: Rocket<Build>) -> rocket::fairing::Result {
+    async fn on_ignite(&self, rocket: Rocket<Build>) -> rocket::fairing::Result {
+        let Some(pool) = rocket.state::<PointercratePool>() else {
+            log::error!("No connection pool managed, cannot load maintenance window");
+
+            return Err(rocket);
+        };
+
+        let maintenance = Maintenance::new(self.0);
+
+        let loaded = match pool.connection().await {
+            Ok(mut connection) => maintenance.reload(&mut connection).await,
+            Err(err) => Err(err),
+        };
+
+        if let Err(err) = loaded {
+            log::error!("Failed to load maintenance window from database: {}", err);
+
+            return Err(rocket);
+        }
+
         if self.0 {
             log::warn!("Maintenance mode activated! All non-GET requests will receive a 503 response!");
-            rocket = rocket.mount("/", routes![maintenance]);
         }
-        Ok(rocket)
+
+        Ok(register_reloadable(rocket.manage(maintenance.clone()), MAINTENANCE, maintenance).mount("/", routes![maintenance, frozen]))
     }
 
     async fn on_request(&self, request: &mut Request<'_>, _: &mut Data<'_>) {
-        if self.0 && request.method() != Method::Get {
+        let Some(maintenance) = request.rocket().state::<Maintenance>() else {
+            return;
+        };
+
+        if !maintenance.is_active() || request.method() == Method::Get || request.uri().path().starts_with(MAINTENANCE_API) {
+            return;
+        }
+
+        if maintenance.is_read_only() {
             request.set_uri(uri!("/maintenance"));
             request.set_method(Method::Get);
+        } else if let Some(route_groups) = request.rocket().state::<RouteGroups>() {
+            let frozen = route_groups
+                .groups()
+                .into_iter()
+                .find(|group| group.matches(request.method(), request.uri().path().as_str()) && maintenance.is_frozen(group.text_id));
+
+            if let Some(group) = frozen {
+                request.set_uri(uri!(frozen(group.text_id)));
+                request.set_method(Method::Get);
+            }
         }
     }
 }
 
+/// Renders a banner informing users about ongoing or upcoming maintenance, if any
+///
+/// Must be called inside a `LANGUAGE` scope.
+pub fn maintenance_banner(maintenance: &Maintenance) -> Option<Markup> {
+    let status = maintenance.status();
+    let now = Utc::now();
+    let window = status
+        .window
+        .filter(|window| window.is_active_at(now) || window.is_upcoming_at(now));
+
+    let text = if maintenance.is_read_only() {
+        match window.as_ref().and_then(|window| window.ends_at) {
+            Some(ends_at) if !status.forced => trp!("maintenance-banner-active-until", "end" = format_datetime(&ends_at)),
+            _ => tr("maintenance-banner-active"),
+        }
+    } else {
+        // Without read-only mode, the banner can only be about the scheduled window
+        let window = window.as_ref()?;
+        let features = window.frozen.iter().map(|text_id| tr(text_id)).collect::<Vec<_>>().join(", ");
+
+        match window.starts_at.filter(|_| window.is_upcoming_at(now)) {
+            None => trp!("maintenance-banner-frozen", "features" = features),
+            Some(starts_at) if !window.is_read_only() => trp!(
+                "maintenance-banner-scheduled-frozen",
+                "start" = format_datetime(&starts_at),
+                "features" = features
+            ),
+            Some(starts_at) => trp!("maintenance-banner-scheduled", "start" = format_datetime(&starts_at)),
+        }
+    };
+
+    let message = window
+        .as_ref()
+        .and_then(|window| window.message(task_lang(), LocaleConfiguration::get().fallback));
+
+    Some(html! {
+        div.info-yellow style="text-align: center; margin: 0px" {
+            b { (text) }
+            @if let Some(message) = message {
+                br;
+                (message)
+            }
+        }
+    })
+}
+
 // we can't use the #[localized] proc-macro here due to issues related to imports
 // (https://github.com/stadust/pointercrate/pull/232#discussion_r2118293806)
 #[rocket::get("/maintenance/")]
-async fn maintenance(locale: ClientLocale) -> Result<()> {
-    LANGUAGE.scope(locale.0, async { Err(CoreError::ReadOnlyMaintenance.into()) }).await
+async fn maintenance(locale: ClientLocale, maintenance: &State<Maintenance>) -> Result<()> {
+    let window = maintenance.window();
+    let error = CoreError::ReadOnlyMaintenance {
+        message: window
+            .as_ref()
+            .and_then(|window| window.message(locale.0, LocaleConfiguration::get().fallback))
+            .map(ToString::to_string),
+        ends_at: window.and_then(|window| window.ends_at),
+    };
+
+    LANGUAGE.scope(locale.0, async { Err(error.into()) }).await
+}
+
+#[rocket::get("/maintenance/<feature>/")]
+async fn frozen(locale: ClientLocale, maintenance: &State<Maintenance>, feature: &str) -> Result<()> {
+    let window = maintenance.window();
+    let error = CoreError::FeatureFrozen {
+        feature: feature.to_string(),
+        message: window
+            .as_ref()
+            .and_then(|window| window.message(locale.0, LocaleConfiguration::get().fallback))
+            .map(ToString::to_string),
+        ends_at: window.and_then(|window| window.ends_at),
+    };
+
+    LANGUAGE.scope(locale.0, async { Err(error.into()) }).await
+}
+
+#[cfg(test)]
+mod tests {
+    use rocket::http::Method;
+
+    use super::RouteGroup;
+
+    const CLAIMS: RouteGroup = RouteGroup::new(
+        "claims",
+        &[
+            (Method::Put, "/api/v1/players/<_>/claims/"),
+            (Method::Delete, "/api/v1/players/<_>/claims/<_..>"),
+        ],
+    );
+
+    #[test]
+    fn test_route_group_matching() {
+        assert!(CLAIMS.matches(Method::Put, "/api/v1/players/1/claims/"));
+        assert!(CLAIMS.matches(Method::Put, "/api/v1/players/1/claims"));
+        assert!(CLAIMS.matches(Method::Delete, "/api/v1/players/1/claims/2/"));
+        assert!(!CLAIMS.matches(Method::Patch, "/api/v1/players/1/claims/"));
+        assert!(!CLAIMS.matches(Method::Put, "/api/v1/players/1/"));
+        assert!(!CLAIMS.matches(Method::Put, "/api/v1/players/1/claims/2/"));
+    }
 }
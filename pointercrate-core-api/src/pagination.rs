@@ -2,7 +2,8 @@ use std::collections::BTreeMap;
 
 use pointercrate_core::{
     error::CoreError,
-    pagination::{Paginatable, PaginationParameters, PaginationQuery},
+    etag::{format_etag, stable_hash},
+    pagination::{Cursor, CursorDirection, Paginatable, PaginationParameters, PaginationQuery},
 };
 use rocket::serde::json::Json;
 use sqlx::PgConnection;
@@ -71,11 +72,23 @@ impl LinksBuilder {
         self
     }
 
+    /// Adds a link to the page described by the given cursor. If no cursor is given, the link points to the first page of the listing.
+    pub fn with_cursor(mut self, rel: &'static str, cursor: Option<Cursor>) -> Self {
+        self.rels.insert(
+            rel,
+            PaginationParameters {
+                cursor,
+                ..Default::default()
+            },
+        );
+        self
+    }
+
     pub fn generate<P: PaginationQuery>(&self, base: &P) -> Result<String, CoreError> {
         let mut buf = String::new();
         let mut is_first = true;
-        // The build functions set a default value for "limit" - copy the actual value from the given base here
-        let limit = base.parameters().limit;
+        // The build functions set a default value for "limit" and "count" - copy the actual values from the given base here
+        let PaginationParameters { limit, count, .. } = base.parameters();
 
         for (rel, param) in &self.rels {
             if !is_first {
@@ -83,13 +96,17 @@ impl LinksBuilder {
             }
             is_first = false;
 
-            let query_string =
-                serde_urlencoded::to_string(base.with_parameters(PaginationParameters { limit, ..*param })).map_err(|err| {
-                    CoreError::internal_server_error(format!(
-                        "Failed to serialize pagination query string: {:?}. Base: {:?}, Builder: {:?}, Current Rel: {}",
-                        err, base, self, rel
-                    ))
-                })?;
+            let query_string = serde_urlencoded::to_string(base.with_parameters(PaginationParameters {
+                limit,
+                count,
+                ..param.clone()
+            }))
+            .map_err(|err| {
+                CoreError::internal_server_error(format!(
+                    "Failed to serialize pagination query string: {:?}. Base: {:?}, Builder: {:?}, Current Rel: {}",
+                    err, base, self, rel
+                ))
+            })?;
 
             buf += &format!("<{}?{}>; rel={}", self.endpoint, query_string, rel);
         }
@@ -105,11 +122,19 @@ pub async fn pagination_response<Q: PaginationQuery, P: Paginatable<Q>>(
 
     parameters.validate()?;
 
+    if P::keyset_paginated(&query) {
+        return keyset_pagination_response(endpoint, query, connection).await;
+    }
+
+    if parameters.cursor.is_some() {
+        return Err(CoreError::InvalidPaginationCursor);
+    }
+
     let (objects, context) = P::page(&query, &mut *connection).await?;
 
     let mut links = LinksBuilder::new(endpoint);
 
-    if let Some((min_id, max_id)) = P::first_and_last(connection).await? {
+    if let Some((min_id, max_id)) = P::first_and_last(&mut *connection).await? {
         links = links.with_first(min_id - 1).with_last(max_id + 1);
     }
 
@@ -128,9 +153,8 @@ pub async fn pagination_response<Q: PaginationQuery, P: Paginatable<Q>>(
             },
         };
 
-        // TODO: Figure out the case where both `before` and `after` are set
-        // If `before` is set on this request, then we _could_ support one-way pagination up to `before` by preserving the "before" value here.
-        // Currently, this scenario cannot happen, as the documentation of `Pagination::page` we treat these pages as "standalone".
+        // If both `before` and `after` are set on this request, then we _could_ support one-way pagination up to `before` by preserving the
+        // "before" value here. However, the page context describes objects regardless of `before`, so the next page is not bounded by it either.
         links = links.with_next(after);
     }
 
@@ -152,22 +176,105 @@ pub async fn pagination_response<Q: PaginationQuery, P: Paginatable<Q>>(
         links = links.with_previous(before);
     };
 
-    Ok(Response2::json(objects).with_header("Links", links.generate(&query)?))
+    listing_response(objects, links, &query, connection).await
+}
+
+async fn keyset_pagination_response<Q: PaginationQuery, P: Paginatable<Q>>(
+    endpoint: &'static str, query: Q, connection: &mut PgConnection,
+) -> Result<Response2<Json<Vec<P>>>, CoreError> {
+    let parameters = query.parameters();
+
+    if parameters.before.is_some() || parameters.after.is_some() {
+        return Err(CoreError::InvalidPaginationCursor);
+    }
+
+    let binding = cursor_binding(endpoint, &query)?;
+
+    if let Some(ref cursor) = parameters.cursor {
+        cursor.verify(&binding)?;
+    }
+
+    let (objects, context) = P::page(&query, &mut *connection).await?;
+
+    let mut links = LinksBuilder::new(endpoint)
+        .with_cursor("first", None)
+        .with_cursor("last", Some(Cursor::new(CursorDirection::Before, None, &binding)));
+
+    // Unlike with ID based pagination, we cannot make up a key "just past" the current page if it is empty. Empty pages can only
+    // occur when paginating beyond either end of the listing though, at which point the "first" and "last" links are what clients want.
+    if context.has_next() {
+        if let Some(obj) = objects.last() {
+            links = links.with_cursor(
+                "next",
+                Some(Cursor::new(CursorDirection::After, Some(obj.sort_key(&query)), &binding)),
+            );
+        }
+    }
+
+    if context.has_previous() {
+        if let Some(obj) = objects.first() {
+            links = links.with_cursor(
+                "prev",
+                Some(Cursor::new(CursorDirection::Before, Some(obj.sort_key(&query)), &binding)),
+            );
+        }
+    }
+
+    listing_response(objects, links, &query, connection).await
+}
+
+/// Builds the response containing the given page of a listing
+///
+/// Adds the `Links` and `ETag` headers, as well as the `X-Total-Count` header if the client requested it via the `count`
+/// parameter. Since the links and the total count change independently of the objects on the page, they are part of the ETag.
+async fn listing_response<Q: PaginationQuery, P: Paginatable<Q>>(
+    objects: Vec<P>, links: LinksBuilder, query: &Q, connection: &mut PgConnection,
+) -> Result<Response2<Json<Vec<P>>>, CoreError> {
+    let links = links.generate(query)?;
+
+    let total_count = if query.parameters().count {
+        Some(P::count(query, connection).await?)
+    } else {
+        None
+    };
+
+    let body = serde_json::to_string(&objects)
+        .map_err(|err| CoreError::internal_server_error(format!("Failed to serialize page of {:?}: {:?}", query, err)))?;
+    let hash = stable_hash(&(body, &links, total_count));
+
+    let mut response = Response2::json(objects)
+        .with_header("Links", links)
+        .with_header("ETag", format_etag(hash, hash));
+
+    if let Some(total_count) = total_count {
+        response = response.with_header("X-Total-Count", total_count.to_string());
+    }
+
+    Ok(response)
+}
+
+/// Identifies the listing a [`Cursor`] is generated for - the endpoint together with all non-pagination query parameters
+fn cursor_binding<Q: PaginationQuery>(endpoint: &'static str, query: &Q) -> Result<String, CoreError> {
+    let query_string = serde_urlencoded::to_string(query.with_parameters(PaginationParameters::default())).map_err(|err| {
+        CoreError::internal_server_error(format!("Failed to serialize pagination query string: {:?}. Base: {:?}", err, query))
+    })?;
+
+    Ok(format!("{}?{}", endpoint, query_string))
 }
 
 #[cfg(test)]
 mod tests {
-    use pointercrate_core::pagination::{PaginationParameters, PaginationQuery};
+    use pointercrate_core::pagination::{Cursor, CursorDirection, PaginationParameters, PaginationQuery, SortKey};
     use serde::Serialize;
 
-    use super::LinksBuilder;
+    use super::{cursor_binding, LinksBuilder};
 
     #[derive(Debug, Default, Serialize)]
     struct DummyQuery(PaginationParameters);
 
     impl PaginationQuery for DummyQuery {
         fn parameters(&self) -> PaginationParameters {
-            self.0
+            self.0.clone()
         }
 
         fn with_parameters(&self, parameters: PaginationParameters) -> Self {
@@ -190,4 +297,33 @@ mod tests {
             "</dummies?after=0>; rel=first,</dummies?before=1971>; rel=last,</dummies?after=2>; rel=next,</dummies?before=100>; rel=prev"
         );
     }
+
+    #[test]
+    fn test_links_builder_cursor() {
+        let binding = cursor_binding("/dummies", &DummyQuery::default()).unwrap();
+        let cursor = Cursor::new(CursorDirection::After, Some(SortKey::new(&(75, 3))), &binding);
+
+        let links_header = LinksBuilder::new("/dummies")
+            .with_cursor("first", None)
+            .with_cursor("next", Some(cursor.clone()))
+            .generate(&DummyQuery::default())
+            .unwrap();
+
+        assert_eq!(
+            links_header,
+            format!("</dummies?>; rel=first,</dummies?cursor={}>; rel=next", cursor)
+        );
+    }
+
+    #[test]
+    fn test_cursor_binding() {
+        let binding = cursor_binding("/dummies", &DummyQuery::default()).unwrap();
+        let cursor = Cursor::new(CursorDirection::Before, Some(SortKey::new(&(75, 3))), &binding);
+        let parsed: Cursor = cursor.to_string().parse().unwrap();
+
+        assert_eq!(parsed.direction(), CursorDirection::Before);
+        assert_eq!(parsed.key::<(i16, i32)>().unwrap(), Some((75, 3)));
+        assert!(parsed.verify(&binding).is_ok());
+        assert!(parsed.verify("/other_dummies?").is_err());
+    }
 }
@@ -9,6 +9,11 @@ use rocket::{
     Request,
 };
 
+/// The name of the cookie storing the value of the preference with the given name
+pub(crate) fn preference_cookie_name(name: &str) -> String {
+    format!("preference-{}", name)
+}
+
 /// A request guard which stores the preferences sent from the client.
 pub struct ClientPreferences<'k, 'v>(HashMap<&'k str, &'v str>);
 
@@ -30,7 +35,7 @@ impl<'k: 'v, 'v> ClientPreferences<'k, 'v> {
                     (
                         name.as_ref(),
                         cookies
-                            .get(&format!("preference-{}", name))
+                            .get(&preference_cookie_name(name))
                             .map(|cookie| cookie.value())
                             .unwrap_or(default),
                     )
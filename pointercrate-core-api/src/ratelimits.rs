@@ -0,0 +1,40 @@
+use crate::cache::register_reloadable;
+use pointercrate_core::{
+    config::RatelimitConfig,
+    ratelimits::{RatelimitRegistry, RatelimitSet, RATELIMIT_QUOTAS},
+};
+use rocket::{Build, Rocket};
+
+/// Registers the given [`RatelimitSet`] with the [`RatelimitRegistry`] managed by the given rocket instance, making its limiters
+/// available to the ratelimit administration endpoints
+///
+/// If no registry is managed yet, a new one is created. The quotas configured in the managed [`RatelimitConfig`] (if any) are applied
+/// to the set, and its quota overrides are reloaded whenever another instance changes them.
+///
+/// # Panics
+///
+/// If the set declares a limiter of the same name as a limiter of an already registered set (see [`RatelimitRegistry::register`])
+pub fn register_ratelimits(rocket: Rocket<Build>, set: RatelimitSet) -> Rocket<Build> {
+    if let Some(config) = rocket.state::<RatelimitConfig>() {
+        set.configure(config);
+    }
+
+    let mut rocket = register_reloadable(rocket, RATELIMIT_QUOTAS, set.clone());
+
+    let registered = match rocket.state::<RatelimitRegistry>() {
+        Some(registry) => registry.register(set),
+        None => {
+            let registry = RatelimitRegistry::default();
+            let registered = registry.register(set);
+
+            rocket = rocket.manage(registry);
+            registered
+        },
+    };
+
+    if let Err(err) = registered {
+        panic!("Conflicting ratelimit definitions: {}", err);
+    }
+
+    rocket
+}
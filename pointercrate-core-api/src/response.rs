@@ -1,11 +1,12 @@
-use crate::localization::LOCALE_COOKIE_NAME;
+use crate::localization::negotiate_locale;
+use crate::maintenance::maintenance_banner;
 use crate::{
-    etag::Tagged,
-    preferences::{ClientPreferences, PreferenceManager},
+    etag::{format_http_date, is_not_modified, Tagged},
+    preferences::PreferenceManager,
 };
+use chrono::{DateTime, Utc};
 use maud::{html, Render, DOCTYPE};
-use pointercrate_core::localization::LocaleConfiguration;
-use pointercrate_core::{etag::Taggable, localization::LANGUAGE};
+use pointercrate_core::{error::CoreError, etag::Taggable, localization::LANGUAGE, maintenance::Maintenance, ratelimits::RatelimitStatus};
 use pointercrate_core_pages::{
     head::{Head, HeadLike},
     PageConfiguration, PageFragment,
@@ -14,7 +15,7 @@ use rocket::tokio::runtime::Handle;
 use rocket::tokio::task::block_in_place;
 use rocket::{
     http::{ContentType, Header, Status},
-    response::Responder,
+    response::{content::RawJson, Responder},
     serde::json::Json,
     Request, Response,
 };
@@ -38,12 +39,11 @@ impl HeadLike for Page {
 impl<'r, 'o: 'r> Responder<'r, 'o> for Page {
     fn respond_to(self, request: &'r Request<'_>) -> rocket::response::Result<'o> {
         let preference_manager = request.rocket().state::<PreferenceManager>().ok_or(Status::InternalServerError)?;
-        let preferences = ClientPreferences::from_cookies(request.cookies(), preference_manager);
+        let lang_id = negotiate_locale(request, preference_manager).ok_or(Status::InternalServerError)?;
 
-        let language = preferences.get(LOCALE_COOKIE_NAME).ok_or(Status::InternalServerError)?;
-        let lang_id = LocaleConfiguration::get().by_code(language);
+        let maintenance = request.rocket().state::<Maintenance>();
 
-        let (page_config, nav_bar, footer) = block_in_place(move || {
+        let (page_config, nav_bar, footer, banner) = block_in_place(move || {
             Handle::current().block_on(async {
                 LANGUAGE
                     .scope(lang_id.language, async {
@@ -54,8 +54,9 @@ impl<'r, 'o: 'r> Responder<'r, 'o> for Page {
 
                         let nav_bar = page_config.nav_bar.render();
                         let footer = page_config.footer.render();
+                        let banner = maintenance.and_then(maintenance_banner);
 
-                        Ok((page_config, nav_bar, footer))
+                        Ok((page_config, nav_bar, footer, banner))
                     })
                     .await
             })
@@ -75,6 +76,9 @@ impl<'r, 'o: 'r> Responder<'r, 'o> for Page {
                 body {
                     div.content {
                         (nav_bar)
+                        @if let Some(banner) = banner {
+                            (banner)
+                        }
                         @if is_uk {
                             nav.red style="height:auto; font-weight: bolder; position: relative; z-index: 100" {
                                 marquee scrolldelay = "60" {
@@ -94,6 +98,8 @@ impl<'r, 'o: 'r> Responder<'r, 'o> for Page {
         Response::build()
             .status(Status::Ok)
             .header(ContentType::HTML)
+            // The language of the page may have been negotiated via the Accept-Language header
+            .raw_header("Vary", "Accept-Language")
             .sized_body(rendered_fragment.len(), Cursor::new(rendered_fragment))
             .ok()
     }
@@ -109,6 +115,20 @@ impl<T: Serialize> Response2<Json<T>> {
     pub fn json(content: T) -> Self {
         Response2::new(Json(content))
     }
+
+    /// Serializes this response so that it can be stored in the
+    /// [`ResponseCache`](pointercrate_core::cache::ResponseCache)
+    ///
+    /// The status code is not preserved, only successful responses should be cached.
+    pub fn into_cached(self) -> Result<CachedResponse, CoreError> {
+        let body = serde_json::to_string(&self.content.0)
+            .map_err(|err| CoreError::internal_server_error(format!("Failed to serialize cached response: {:?}", err)))?;
+
+        Ok(CachedResponse {
+            body,
+            headers: self.headers,
+        })
+    }
 }
 
 impl<T: Taggable> Response2<Tagged<T>> {
@@ -131,6 +151,27 @@ impl<T> Response2<T> {
         self
     }
 
+    /// Sets the `RateLimit-Limit`, `RateLimit-Remaining` and `RateLimit-Reset` headers, informing the client about the ratelimit
+    /// applied to this request
+    pub fn with_ratelimit(mut self, status: RatelimitStatus) -> Self {
+        for (name, value) in status.headers() {
+            self = self.with_header(name, value);
+        }
+        self
+    }
+
+    /// Sets the `Last-Modified` header, if the modification date of the returned object is known
+    pub fn with_last_modified(self, last_modified: Option<DateTime<Utc>>) -> Self {
+        match last_modified {
+            // Browsers may heuristically cache responses with a `Last-Modified` date without revalidating their ETag, unless told
+            // otherwise
+            Some(last_modified) => self
+                .with_header("Last-Modified", format_http_date(last_modified))
+                .with_header("Cache-Control", "no-cache"),
+            None => self,
+        }
+    }
+
     pub fn status(mut self, status: Status) -> Self {
         self.status = status;
         self
@@ -139,8 +180,21 @@ impl<T> Response2<T> {
 
 impl<'r, 'o: 'r, T: Responder<'r, 'o>> Responder<'r, 'o> for Response2<T> {
     fn respond_to(self, request: &'r Request<'_>) -> rocket::response::Result<'o> {
-        let mut response_builder = Response::build_from(self.content.respond_to(request)?);
-        response_builder.status(self.status);
+        // Responses that set an ETag explicitly (e.g. listings) support conditional GET requests
+        if let Some(etag) = self.headers.iter().find(|header| header.name() == "etag") {
+            if is_not_modified(request, etag.value()) {
+                return Response::build().status(Status::NotModified).header(etag.clone()).ok();
+            }
+        }
+
+        let response = self.content.respond_to(request)?;
+        let not_modified = response.status() == Status::NotModified;
+        let mut response_builder = Response::build_from(response);
+
+        // The content itself might have already been answered with 304 NOT MODIFIED (see [`Tagged`])
+        if !not_modified {
+            response_builder.status(self.status);
+        }
 
         for header in self.headers {
             response_builder.header(header);
@@ -149,3 +203,21 @@ impl<'r, 'o: 'r, T: Responder<'r, 'o>> Responder<'r, 'o> for Response2<T> {
         response_builder.ok()
     }
 }
+
+/// A successful JSON response, as stored in the [`ResponseCache`](pointercrate_core::cache::ResponseCache)
+#[derive(Debug, Clone)]
+pub struct CachedResponse {
+    body: String,
+    headers: Vec<Header<'static>>,
+}
+
+impl<'r> Responder<'r, 'static> for CachedResponse {
+    fn respond_to(self, request: &'r Request<'_>) -> rocket::response::Result<'static> {
+        Response2 {
+            content: RawJson(self.body),
+            status: Status::Ok,
+            headers: self.headers,
+        }
+        .respond_to(request)
+    }
+}
@@ -15,15 +15,19 @@ error-core-payloadtoolarge = The data value transmitted exceeds the capacity lim
 error-core-unsupportedmediatype = The server does not support the media type transmitted in the request/no media type was specified. Expected one '{ $expected-type }'.
 error-core-unprocessableentity = The request was well-formed but was unable to be followed due to semantic errors.
 error-core-invalidpaginationlimit = Invalid value for the 'limit' parameter. It must be between 1 and 100.
+error-core-invalidpaginationcursor = Invalid value for the 'cursor' parameter. Cursors can only be obtained from the 'Links' header of the listing they belong to, and cannot be combined with 'before' or 'after'.
 error-core-invalidurlscheme = Invalid URL scheme. Only 'http' and 'https' are supported.
 error-core-urlauthenticated = The provided URL contains authentication information. For security reasons it has been rejected.
 error-core-invalidurlformat = The given URL does not lead to a video. The URL format for the given host has to be '{ $expected-format }'.
 error-core-aftersmallerbefore = The 'after' value provided for pagination is smaller than the 'before' value. This would result in an empty response and is most likely a bug.
 error-core-mutuallyexclusive = Your request contains mutually exclusive fields. Please restrict yourself to one of them.
-error-core-preconditionrequired = This request is required to be conditional; try using "If-Match".
+error-core-preconditionrequired = This request is required to be conditional; try using "If-Match" or "If-Unmodified-Since".
 error-core-ratelimited = { $message } Try again in { $remaining-duration }.
 error-core-internalservererror = The server encountered an internal error and was unable to complete your request. Either the server is overloaded or there is an error in the application. Please notify a server administrator and have them look at the server logs!
 error-core-databaseerror = Internally, an invalid database access has been made. Please notify a server administrator and have them look at the server logs!
 error-core-querytimeout = Internally, a database query timed out. This could be due to high server load, or because of a logic error resulting in a deadlock. If this issue persists after retrying, please notify a server administrator!
 error-core-databaseconnectionerror = Failed to retrieve connection to the database. The server might be temporarily overloaded.
 error-core-readonlymaintenance = The website is currently in read-only maintenance mode.
+error-core-readonlymaintenance-message = The website is currently in read-only maintenance mode: { $message }
+error-core-featurefrozen = { $feature } are temporarily disabled for maintenance.
+error-core-featurefrozen-message = { $feature } are temporarily disabled for maintenance: { $message }
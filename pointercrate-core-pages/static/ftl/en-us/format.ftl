@@ -0,0 +1,50 @@
+## Locale specific formatting of numbers and dates, see the format_* functions in pointercrate_core::localization
+
+format-number =
+    .decimal-separator = .
+    .group-separator = ,
+
+# .time is a chrono format string, see https://docs.rs/chrono/latest/chrono/format/strftime/index.html
+format-datetime = { $weekday ->
+        [1] Monday
+        [2] Tuesday
+        [3] Wednesday
+        [4] Thursday
+        [5] Friday
+        [6] Saturday
+       *[7] Sunday
+    }, { $month ->
+        [1] January
+        [2] February
+        [3] March
+        [4] April
+        [5] May
+        [6] June
+        [7] July
+        [8] August
+        [9] September
+        [10] October
+        [11] November
+       *[12] December
+    } { $day ->
+        [one] { $day }st
+        [two] { $day }nd
+        [few] { $day }rd
+       *[other] { $day }th
+    } { $year } at { $time } GMT{ $timezone }
+    .time = %l:%M:%S%P
+
+format-month-year = { $month ->
+        [1] Jan
+        [2] Feb
+        [3] Mar
+        [4] Apr
+        [5] May
+        [6] Jun
+        [7] Jul
+        [8] Aug
+        [9] Sep
+        [10] Oct
+        [11] Nov
+       *[12] Dec
+    } { $year }
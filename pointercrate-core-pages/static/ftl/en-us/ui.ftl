@@ -6,4 +6,10 @@ dropdown-placeholder = Click to select
 filtered-paginator-placeholder = Enter to search...
 
 paginator-previous = Previous
-paginator-next = Next
\ No newline at end of file
+paginator-next = Next
+
+maintenance-banner-scheduled = The website will enter read-only maintenance mode on { $start }.
+maintenance-banner-active = The website is currently in read-only maintenance mode, so no changes can be made.
+maintenance-banner-active-until = The website is in read-only maintenance mode until { $end }, so no changes can be made until then.
+maintenance-banner-frozen = The following features are temporarily disabled for maintenance: { $features }
+maintenance-banner-scheduled-frozen = The following features will be temporarily disabled for maintenance starting { $start }: { $features }
@@ -15,15 +15,19 @@ error-core-payloadtoolarge = Переданное значение превыш
 error-core-unsupportedmediatype = Сервер не поддерживает передаваемый в запросе тип медиа, либо тип медиа не был указан вовсе. Ожидался '{ $expected-type }'.
 error-core-unprocessableentity = Запрос был правильно оформлен, но не смог быть обработан из-за семантических ошибок.
 error-core-invalidpaginationlimit = Неверное значение параметра 'limit'. Он должен быть между 1 и 100.
+error-core-invalidpaginationcursor = Неверное значение параметра 'cursor'. Курсоры можно получить только из заголовка 'Links' того списка, к которому они относятся, и их нельзя совмещать с 'before' или 'after'.
 error-core-invalidurlscheme = Неверная схема URL. Поддерживаются только 'http' и 'https'.
 error-core-urlauthenticated = Переданная ссылка содержит учетные данные. В связи с безопасностью она была отклонена.
 error-core-invalidurlformat = Данная ссылка не перенаправляет на видео. Формат ссылки для данного хоста должен быть '{ $expected-format }'.
 error-core-aftersmallerbefore = Значение 'after', переданное для пагинации, меньше, чем значение 'before'. Это приведет к пустому запросу и, скорее всего, является багом.
 error-core-mutuallyexclusive = Ваш запрос содержит взаимоисключающие поля. Пожалуйста, используйте лишь одним из них.
-error-core-preconditionrequired = Этот запрос требует предварительного условия; попробуйте использовать "If-Match".
+error-core-preconditionrequired = Этот запрос требует предварительного условия; попробуйте использовать "If-Match" или "If-Unmodified-Since".
 error-core-ratelimited = { $message } Попробуйте еще раз через { $remaining-duration }.
 error-core-internalservererror = Сервер наткнулся на внутреннюю ошибку и не смог обработать ваш запрос. Либо сервер перегружен, либо в приложении содержится ошибка. Пожалуйста, свяжитесь с серверным администратором и попросите его просмотреть логи сервера!
 error-core-databaseerror = Был произведен внутренний запрос к базе данных. Пожалуйста, свяжитесь с серверным администратором и попросите его просмотреть логи сервера!
 error-core-querytimeout = Время ожидания внутреннего запроса к базе данных истекло. Это могло произойти из-за высокой нагрузки на сервер или логической ошибки, которая привела к тупику. Если проблема осталась после повторных попыток, свяжитесь с серверным администратором!
 error-core-databaseconnectionerror = Не удалось получить соединение с базой данных. Сервер может быть временно перегружен.
 error-core-readonlymaintenance = Сайт находится на техобслуживании и доступен только для чтения.
+error-core-readonlymaintenance-message = Сайт находится на техобслуживании и доступен только для чтения: { $message }
+error-core-featurefrozen = { $feature } — функция временно отключена на время техобслуживания.
+error-core-featurefrozen-message = { $feature } — функция временно отключена на время техобслуживания: { $message }
@@ -0,0 +1,45 @@
+## Locale specific formatting of numbers and dates, see the format_* functions in pointercrate_core::localization
+
+format-number =
+    .decimal-separator = ,
+    .group-separator = {" "}
+
+# .time is a chrono format string, see https://docs.rs/chrono/latest/chrono/format/strftime/index.html
+format-datetime = { $weekday ->
+        [1] понедельник
+        [2] вторник
+        [3] среда
+        [4] четверг
+        [5] пятница
+        [6] суббота
+       *[7] воскресенье
+    }, { $day } { $month ->
+        [1] января
+        [2] февраля
+        [3] марта
+        [4] апреля
+        [5] мая
+        [6] июня
+        [7] июля
+        [8] августа
+        [9] сентября
+        [10] октября
+        [11] ноября
+       *[12] декабря
+    } { $year } г. в { $time } GMT{ $timezone }
+    .time = %H:%M:%S
+
+format-month-year = { $month ->
+        [1] янв.
+        [2] февр.
+        [3] март
+        [4] апр.
+        [5] май
+        [6] июнь
+        [7] июль
+        [8] авг.
+        [9] сент.
+        [10] окт.
+        [11] нояб.
+       *[12] дек.
+    } { $year }
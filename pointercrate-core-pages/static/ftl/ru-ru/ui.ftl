@@ -6,4 +6,10 @@ dropdown-placeholder = Нажмите, чтобы выбрать
 filtered-paginator-placeholder = Поиск...
 
 paginator-previous = Предыдущая
-paginator-next = Следующая
\ No newline at end of file
+paginator-next = Следующая
+
+maintenance-banner-scheduled = Техобслуживание сайта (режим только для чтения) запланировано на { $start }.
+maintenance-banner-active = Сайт находится на техобслуживании и доступен только для чтения, поэтому вносить изменения сейчас нельзя.
+maintenance-banner-active-until = Сайт находится на техобслуживании и доступен только для чтения до { $end }, поэтому до этого времени вносить изменения нельзя.
+maintenance-banner-frozen = Следующие функции временно отключены на время техобслуживания: { $features }
+maintenance-banner-scheduled-frozen = Следующие функции будут временно отключены на время техобслуживания начиная с { $start }: { $features }
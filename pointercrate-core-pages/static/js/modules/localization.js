@@ -70,6 +70,20 @@ export function tr(category, resource, text_id) {
   return text_id;
 }
 
+/**
+ * Formats the given number with exactly `fractionDigits` decimal places according to the conventions of the page's language
+ *
+ * @param {number} value
+ * @param {number} fractionDigits
+ * @returns {string}
+ */
+export function formatNumber(value, fractionDigits) {
+  return value.toLocaleString(document.documentElement.lang, {
+    minimumFractionDigits: fractionDigits,
+    maximumFractionDigits: fractionDigits,
+  });
+}
+
 export function trp(category, resource, text_id, args) {
   loadResource(category, resource);
 
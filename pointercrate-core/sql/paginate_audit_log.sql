@@ -0,0 +1,12 @@
+SELECT audit_id, time, userid, members.name AS username, entity, entity_id, action, data::TEXT
+FROM audit_feed
+LEFT OUTER JOIN members ON members.member_id = userid
+WHERE (audit_id < $1 OR $1 IS NULL)
+  AND (audit_id > $2 OR $2 IS NULL)
+  AND (userid = $3 OR $3 IS NULL)
+  AND (entity = $4 OR $4 IS NULL)
+  AND (entity_id = $5 OR $5 IS NULL)
+  AND (time < $6 OR $6 IS NULL)
+  AND (time > $7 OR $7 IS NULL)
+ORDER BY audit_id {}
+LIMIT $8
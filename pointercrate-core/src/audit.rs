@@ -1,14 +1,87 @@
 //! Module containing some basic structures for dealing with audit logs
 
-use chrono::NaiveDateTime;
-use serde::Serialize;
+use crate::{
+    error::CoreError,
+    first_and_last,
+    pagination::{count_query, Paginatable, PaginationParameters, PaginationQuery},
+    util::non_nullable,
+};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use futures::StreamExt;
+use serde::{ser::SerializeStruct, Deserialize, Serialize, Serializer};
+use sqlx::{
+    postgres::{PgArguments, PgRow},
+    query::Query,
+    PgConnection, Postgres, Row,
+};
 
-#[derive(Serialize, Debug, Clone)]
+/// A non-human actor that changes to the database can be attributed to in the audit log
+///
+/// Service accounts have non-positive ids, so they never clash with the id of an actual user. They do not exist in the
+/// `members` table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ServiceAccount {
+    id: i32,
+    name: &'static str,
+}
+
+impl ServiceAccount {
+    /// Fallback for all changes that were not attributed to any more specific actor
+    pub const SYSTEM: ServiceAccount = ServiceAccount::new(0, "system");
+
+    /// Synchronization of level data with the Geometry Dash servers
+    pub const GD_SYNC: ServiceAccount = ServiceAccount::new(-1, "gd-sync");
+
+    /// Asynchronous validation of the videos of newly submitted records
+    pub const VIDEO_CHECKER: ServiceAccount = ServiceAccount::new(-2, "video-checker");
+
+    /// Maintenance performed through command line tools and scripts
+    pub const CLI: ServiceAccount = ServiceAccount::new(-3, "cli");
+
+    const ALL: [ServiceAccount; 4] = [Self::SYSTEM, Self::GD_SYNC, Self::VIDEO_CHECKER, Self::CLI];
+
+    const fn new(id: i32, name: &'static str) -> Self {
+        ServiceAccount { id, name }
+    }
+
+    pub fn by_id(id: i32) -> Option<ServiceAccount> {
+        Self::ALL.into_iter().find(|account| account.id == id)
+    }
+
+    pub fn id(&self) -> i32 {
+        self.id
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+/// Reference to the actor of some action, usually a user
+///
+/// If the id belongs to a [`ServiceAccount`], its name is taken from there and the serialized form has `service` set to
+/// `true`.
+#[derive(Debug, Clone)]
 pub struct NamedId {
     pub id: i32,
     pub name: Option<String>,
 }
 
+impl Serialize for NamedId {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let service_account = ServiceAccount::by_id(self.id);
+
+        let mut state = serializer.serialize_struct("NamedId", 3)?;
+        state.serialize_field("id", &self.id)?;
+        match service_account {
+            Some(account) => state.serialize_field("name", account.name())?,
+            None => state.serialize_field("name", &self.name)?,
+        }
+        state.serialize_field("service", &service_account.is_some())?;
+        state.end()
+    }
+}
+
 #[derive(Serialize, Debug)]
 pub struct AuditLogEntry<T> {
     pub time: NaiveDateTime,
@@ -24,3 +97,180 @@ pub enum AuditLogEntryType<T> {
     Modification(T),
     Deletion,
 }
+
+/// What happened to an entity in an [`AuditLogFeedEntry`]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    Addition,
+    Modification,
+    Deletion,
+
+    /// A previously deleted entity was restored from the audit log
+    Restoration,
+}
+
+/// Entry in the global audit log, which merges the audit logs of all entity types (demons, records, users, etc.)
+#[derive(Serialize, Debug)]
+pub struct AuditLogFeedEntry {
+    pub time: NaiveDateTime,
+    pub entry_id: i32,
+
+    /// The type of entity this entry is about, e.g. `"record"` or `"user"`
+    pub entity: String,
+
+    /// The id of the entity this entry is about
+    pub id: i32,
+
+    pub user: NamedId,
+    pub action: AuditAction,
+
+    /// For modifications, the fields that were changed, together with their values _before_ the modification. For restorations,
+    /// the id of the deletion entry that was undone. Otherwise usually [`None`], unless the entity is not fully identified by
+    /// its id (as is the case for creators).
+    pub data: Option<serde_json::Value>,
+}
+
+/// The time of the most recent audit log entry about the given entity, e.g. `("record", 1)`, which is the time it was last
+/// added, modified or restored
+///
+/// Returns [`None`] if the audit log contains no entries about the entity (which is the case for entities created before the
+/// audit log was introduced and never modified since).
+pub async fn last_modified(entity: &str, id: i32, connection: &mut PgConnection) -> Result<Option<DateTime<Utc>>, CoreError> {
+    let row = sqlx::query!(
+        r#"SELECT MAX(time) AS "last_modified" FROM audit_feed WHERE entity = $1 AND entity_id = $2"#,
+        entity,
+        id
+    )
+    .fetch_one(connection)
+    .await?;
+
+    // audit log timestamps are stored in UTC
+    Ok(row.last_modified.map(|time| time.and_utc()))
+}
+
+/// The time an entity was last modified after patching it, given the time it was last modified before (as returned by
+/// [`last_modified`]) and whether the patch changed it
+///
+/// Saves querying the audit log a second time. A patch that changed the entity was logged within the current transaction, which
+/// started no later than now.
+pub fn last_modified_after_patch(before: Option<DateTime<Utc>>, changed: bool) -> Option<DateTime<Utc>> {
+    if changed {
+        Some(Utc::now())
+    } else {
+        before
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, Serialize)]
+pub struct AuditLogPagination {
+    #[serde(flatten)]
+    pub params: PaginationParameters,
+
+    /// The id of the user that performed the logged action
+    #[serde(default, deserialize_with = "non_nullable")]
+    pub user: Option<i32>,
+
+    #[serde(default, deserialize_with = "non_nullable")]
+    pub entity: Option<String>,
+
+    #[serde(default, deserialize_with = "non_nullable")]
+    pub entity_id: Option<i32>,
+
+    #[serde(default, deserialize_with = "non_nullable")]
+    #[serde(rename = "time__lt")]
+    pub time_lt: Option<NaiveDateTime>,
+
+    #[serde(default, deserialize_with = "non_nullable")]
+    #[serde(rename = "time__gt")]
+    pub time_gt: Option<NaiveDateTime>,
+}
+
+impl PaginationQuery for AuditLogPagination {
+    fn parameters(&self) -> PaginationParameters {
+        self.params.clone()
+    }
+
+    fn with_parameters(&self, parameters: PaginationParameters) -> Self {
+        Self {
+            params: parameters,
+            ..self.clone()
+        }
+    }
+
+    fn bind_filters<'q>(&'q self, sql_query: Query<'q, Postgres, PgArguments>) -> Query<'q, Postgres, PgArguments> {
+        sql_query
+            .bind(self.user)
+            .bind(self.entity.as_deref())
+            .bind(self.entity_id)
+            .bind(self.time_lt)
+            .bind(self.time_gt)
+    }
+}
+
+impl Paginatable<AuditLogPagination> for AuditLogFeedEntry {
+    first_and_last!("audit_log2", "audit_id");
+
+    async fn count(query: &AuditLogPagination, connection: &mut PgConnection) -> Result<i64, sqlx::Error> {
+        let sql_query = count_query(&format!(include_str!("../sql/paginate_audit_log.sql"), "ASC"));
+
+        let sql_query = sqlx::query(&sql_query).bind(None::<i32>).bind(None::<i32>);
+
+        query
+            .bind_filters(sql_query)
+            .bind(None::<i32>)
+            .fetch_one(connection)
+            .await?
+            .try_get(0)
+    }
+
+    async fn fetch(query: &AuditLogPagination, connection: &mut PgConnection) -> Result<Vec<AuditLogFeedEntry>, sqlx::Error> {
+        let order = query.params.order();
+
+        let sql_query = format!(include_str!("../sql/paginate_audit_log.sql"), order);
+
+        let sql_query = sqlx::query(&sql_query).bind(query.params.before).bind(query.params.after);
+
+        let mut stream = query.bind_filters(sql_query).bind(query.params.limit + 1).fetch(connection);
+
+        let mut entries = Vec::new();
+
+        while let Some(row) = stream.next().await {
+            let row: PgRow = row?;
+
+            let action = match row.try_get::<&str, _>("action")? {
+                "addition" => AuditAction::Addition,
+                "modification" => AuditAction::Modification,
+                "restoration" => AuditAction::Restoration,
+                "deletion" => AuditAction::Deletion,
+                other => return Err(sqlx::Error::Decode(format!("unknown audit log action '{}'", other).into())),
+            };
+
+            // sqlx's JSON support is not enabled, so the data is retrieved as text
+            let data = row
+                .try_get::<Option<&str>, _>("data")?
+                .map(serde_json::from_str)
+                .transpose()
+                .map_err(|err| sqlx::Error::Decode(Box::new(err)))?;
+
+            entries.push(AuditLogFeedEntry {
+                time: row.try_get("time")?,
+                entry_id: row.try_get("audit_id")?,
+                entity: row.try_get("entity")?,
+                id: row.try_get("entity_id")?,
+                user: NamedId {
+                    id: row.try_get("userid")?,
+                    name: row.try_get("username")?,
+                },
+                action,
+                data,
+            })
+        }
+
+        Ok(entries)
+    }
+
+    fn pagination_id(&self) -> i32 {
+        self.entry_id
+    }
+}
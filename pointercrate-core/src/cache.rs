@@ -0,0 +1,333 @@
+//! In-process cache for responses of frequently requested, rarely changing endpoints
+//!
+//! Cached values are grouped into [`CacheRegion`]s, within which they are keyed by route and query string. Whenever data
+//! contained in a region changes, the mutating code calls [`invalidate`], which sends a notification on the
+//! [`INVALIDATION_CHANNEL`]. Since postgres only delivers notifications once the transaction sending them commits, cached
+//! values are never evicted before the change becomes visible to other connections (which would allow a concurrent request to
+//! re-cache the old state), and never evicted for changes that end up rolled back. It also means that all instances of
+//! pointercrate connected to the same database evict their caches.
+//!
+//! Values computed concurrently with an eviction are not stored, as they might have been computed from the old state.
+//!
+//! The same notifications keep [`Reloadable`] state, which is loaded from the database into memory, in sync between all instances.
+
+use crate::error::Result;
+use futures::future::BoxFuture;
+use log::{debug, warn};
+use serde::Serialize;
+use sqlx::{PgConnection, Pool, Postgres};
+use std::{
+    any::Any,
+    collections::HashMap,
+    future::Future,
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+/// The postgres notification channel on which the names of regions that need to be evicted are sent
+pub const INVALIDATION_CHANNEL: &str = "response_cache_invalidation";
+
+/// The maximal number of values cached per region. Since keys contain user-controlled query strings, the cache would otherwise
+/// grow without bounds.
+const MAX_ENTRIES_PER_REGION: usize = 512;
+
+/// A group of cached values that are all evicted together, since they are derived from the same data
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CacheRegion(&'static str);
+
+impl CacheRegion {
+    pub const fn new(name: &'static str) -> Self {
+        CacheRegion(name)
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.0
+    }
+}
+
+/// Evicts all values in the given region from the caches of all pointercrate instances, once the current transaction commits
+///
+/// Should be called by all code modifying data contained in the region. Postgres collapses duplicate notifications sent in the
+/// same transaction, so calling this multiple times is cheap.
+pub async fn invalidate(region: CacheRegion, connection: &mut PgConnection) -> Result<()> {
+    sqlx::query!("SELECT pg_notify($1, $2)", INVALIDATION_CHANNEL, region.name())
+        .execute(connection)
+        .await?;
+
+    Ok(())
+}
+
+/// State held in memory that is loaded from the database, and needs to be reloaded whenever the data it was loaded from changes
+///
+/// Given the connection pool instead of a connection, since not all state is loaded from the database directly.
+pub trait Reloadable: Send + Sync {
+    fn reload<'a>(&'a self, pool: &'a Pool<Postgres>) -> BoxFuture<'a, Result<()>>;
+}
+
+type RegisteredState = (CacheRegion, Arc<dyn Reloadable>);
+
+/// All [`Reloadable`] state of a pointercrate instance, together with the [`CacheRegion`] whose invalidation causes it to be reloaded
+///
+/// Cheap to clone, all clones share the same registrations.
+#[derive(Clone, Default)]
+pub struct ReloadRegistry {
+    states: Arc<Mutex<Vec<RegisteredState>>>,
+}
+
+impl ReloadRegistry {
+    pub fn register(&self, region: CacheRegion, state: impl Reloadable + 'static) {
+        self.states.lock().unwrap().push((region, Arc::new(state)));
+    }
+
+    /// Reloads all state registered for the region with the given name
+    ///
+    /// Called when receiving a notification on the [`INVALIDATION_CHANNEL`], code modifying data should use [`invalidate`] instead.
+    pub async fn reload(&self, region: &str, pool: &Pool<Postgres>) -> Result<()> {
+        self.reload_matching(|registered| registered.name() == region, pool).await
+    }
+
+    /// Reloads all registered state, for when invalidation notifications might have been missed
+    pub async fn reload_all(&self, pool: &Pool<Postgres>) -> Result<()> {
+        self.reload_matching(|_| true, pool).await
+    }
+
+    async fn reload_matching(&self, predicate: impl Fn(CacheRegion) -> bool, pool: &Pool<Postgres>) -> Result<()> {
+        let states = self
+            .states
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(region, _)| predicate(*region))
+            .map(|(_, state)| Arc::clone(state))
+            .collect::<Vec<_>>();
+
+        for state in states {
+            state.reload(pool).await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Hit/miss statistics of a single [`CacheRegion`], as reported to administrators
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+pub struct CacheStatistics {
+    pub region: String,
+
+    /// The number of values currently cached in this region
+    pub entries: usize,
+
+    pub hits: u64,
+    pub misses: u64,
+
+    /// How often this region was evicted since startup
+    pub invalidations: u64,
+}
+
+struct Entry {
+    value: Arc<dyn Any + Send + Sync>,
+    stored_at: Instant,
+}
+
+#[derive(Default)]
+struct Region {
+    /// Incremented on every eviction, used to detect evictions happening while a value is computed
+    generation: u64,
+    entries: HashMap<String, Entry>,
+    statistics: CacheStatistics,
+}
+
+/// The response cache of this pointercrate instance
+///
+/// Cheap to clone, all clones share the same cached values.
+#[derive(Clone, Default)]
+pub struct ResponseCache {
+    regions: Arc<Mutex<HashMap<&'static str, Region>>>,
+}
+
+impl ResponseCache {
+    /// Returns the value cached for the given key in the given region, or computes and caches it if there is none
+    ///
+    /// Errors are not cached.
+    pub async fn get_or_try_insert_with<T, E, F, Fut>(&self, region: CacheRegion, key: &str, compute: F) -> std::result::Result<Arc<T>, E>
+    where
+        T: Send + Sync + 'static,
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = std::result::Result<T, E>>,
+    {
+        let generation = {
+            let mut regions = self.regions.lock().unwrap();
+            let region = regions.entry(region.name()).or_default();
+
+            if let Some(value) = region.entries.get(key).and_then(|entry| entry.value.clone().downcast::<T>().ok()) {
+                region.statistics.hits += 1;
+
+                return Ok(value);
+            }
+
+            region.statistics.misses += 1;
+            region.generation
+        };
+
+        let value = Arc::new(compute().await?);
+
+        let mut regions = self.regions.lock().unwrap();
+        let region = regions.entry(region.name()).or_default();
+
+        if region.generation != generation {
+            debug!("Region evicted while computing value for {}, not caching it", key);
+        } else {
+            if region.entries.len() >= MAX_ENTRIES_PER_REGION && !region.entries.contains_key(key) {
+                let oldest = region
+                    .entries
+                    .iter()
+                    .min_by_key(|(_, entry)| entry.stored_at)
+                    .map(|(key, _)| key.clone());
+
+                if let Some(oldest) = oldest {
+                    region.entries.remove(&oldest);
+                }
+            }
+
+            region.entries.insert(
+                key.to_string(),
+                Entry {
+                    value: value.clone(),
+                    stored_at: Instant::now(),
+                },
+            );
+        }
+
+        Ok(value)
+    }
+
+    /// Evicts all values cached in the region with the given name
+    ///
+    /// Called when receiving a notification on the [`INVALIDATION_CHANNEL`], code modifying data should use [`invalidate`] instead.
+    pub fn evict(&self, region: &str) {
+        let mut regions = self.regions.lock().unwrap();
+
+        match regions.get_mut(region) {
+            Some(region) => {
+                region.generation += 1;
+                region.entries.clear();
+                region.statistics.invalidations += 1;
+            },
+            None => debug!("Received invalidation for region {} which has not been used yet", region),
+        }
+    }
+
+    /// Evicts all cached values, for when invalidation notifications might have been missed
+    pub fn evict_all(&self) {
+        warn!("Evicting all cached responses");
+
+        for region in self.regions.lock().unwrap().values_mut() {
+            region.generation += 1;
+            region.entries.clear();
+            region.statistics.invalidations += 1;
+        }
+    }
+
+    pub fn statistics(&self) -> Vec<CacheStatistics> {
+        let mut statistics = self
+            .regions
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(name, region)| CacheStatistics {
+                region: name.to_string(),
+                entries: region.entries.len(),
+                ..region.statistics.clone()
+            })
+            .collect::<Vec<_>>();
+
+        statistics.sort_by(|a, b| a.region.cmp(&b.region));
+        statistics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CacheRegion, ResponseCache, MAX_ENTRIES_PER_REGION};
+    use futures::executor::block_on;
+    use std::convert::Infallible;
+
+    const REGION: CacheRegion = CacheRegion::new("demons");
+
+    async fn get(cache: &ResponseCache, key: &str, value: i32) -> i32 {
+        *cache
+            .get_or_try_insert_with(REGION, key, || async { Ok::<_, Infallible>(value) })
+            .await
+            .unwrap()
+    }
+
+    #[test]
+    fn test_hits_and_eviction() {
+        block_on(async {
+            let cache = ResponseCache::default();
+
+            assert_eq!(get(&cache, "/listed/", 1).await, 1);
+            assert_eq!(get(&cache, "/listed/", 2).await, 1);
+            assert_eq!(get(&cache, "/listed/?limit=10", 3).await, 3);
+
+            cache.evict("ranking");
+            assert_eq!(get(&cache, "/listed/", 4).await, 1);
+
+            cache.evict("demons");
+            assert_eq!(get(&cache, "/listed/", 5).await, 5);
+
+            let statistics = cache.statistics();
+
+            assert_eq!(statistics.len(), 1);
+            assert_eq!(statistics[0].entries, 1);
+            assert_eq!(statistics[0].hits, 2);
+            assert_eq!(statistics[0].misses, 3);
+            assert_eq!(statistics[0].invalidations, 1);
+        })
+    }
+
+    #[test]
+    fn test_errors_not_cached() {
+        block_on(async {
+            let cache = ResponseCache::default();
+
+            assert!(cache
+                .get_or_try_insert_with(REGION, "/listed/", || async { Err::<i32, _>("database unavailable") })
+                .await
+                .is_err());
+            assert_eq!(get(&cache, "/listed/", 1).await, 1);
+        })
+    }
+
+    #[test]
+    fn test_concurrent_eviction() {
+        block_on(async {
+            let cache = ResponseCache::default();
+
+            let value = cache
+                .get_or_try_insert_with(REGION, "/listed/", || async {
+                    cache.evict("demons");
+
+                    Ok::<_, Infallible>(1)
+                })
+                .await
+                .unwrap();
+
+            assert_eq!(*value, 1);
+            assert_eq!(get(&cache, "/listed/", 2).await, 2);
+        })
+    }
+
+    #[test]
+    fn test_bounded_size() {
+        block_on(async {
+            let cache = ResponseCache::default();
+
+            for after in 0..=MAX_ENTRIES_PER_REGION {
+                get(&cache, &format!("/listed/?after={}", after), 0).await;
+            }
+
+            assert_eq!(cache.statistics()[0].entries, MAX_ENTRIES_PER_REGION);
+        })
+    }
+}
@@ -1,3 +1,556 @@
-pub fn database_url() -> String {
-    std::env::var("DATABASE_URL").expect("DATABASE_URL is not set")
+//! Typed configuration, loaded once at startup
+//!
+//! Configuration values are read from a TOML file (`pointercrate.toml`, or the file named by the `POINTERCRATE_CONFIG`
+//! environment variable), grouped into one table per [`ConfigSection`]. Every value can be overridden by an environment
+//! variable, which is also how values were configured before the configuration file existed:
+//!
+//! ```toml
+//! [core]
+//! database_url = "postgres://pointercrate@localhost/pointercrate"          # DATABASE_URL
+//! database_replica_url = "postgres://pointercrate@replica/pointercrate"    # DATABASE_REPLICA_URL
+//! database_max_connections = 20                                            # DATABASE_MAX_CONNECTIONS
+//! secret_file = ".secret"                                                  # SECRET_FILE
+//!
+//! [demonlist]
+//! list_size = 75           # LIST_SIZE
+//! extended_list_size = 150 # EXTENDED_LIST_SIZE
+//!
+//! [ratelimits]
+//! record_submission = "5 per 1200" # RATELIMIT_RECORD_SUBMISSION
+//! ```
+//!
+//! Sections are loaded from a [`ConfigSource`], which collects all problems it encounters instead of stopping at the first one,
+//! so that a misconfigured server reports everything that needs fixing at once (see [`ConfigSource::finish`]).
+
+use crate::ratelimits::Quota;
+use std::{collections::BTreeMap, fmt::Display, path::PathBuf, str::FromStr, sync::OnceLock, time::Duration};
+
+static SECRET: OnceLock<Vec<u8>> = OnceLock::new();
+
+/// The secret used by debug builds if no configuration was loaded
+const UNSECURE_SECRET: [u8; 64] = [0x0; 64];
+
+/// A problem with a single configuration value (or the configuration file itself)
+#[derive(thiserror::Error, Debug)]
+pub enum ConfigError {
+    #[error("Failed to read configuration file {0:?}: {1}")]
+    Io(PathBuf, std::io::Error),
+
+    #[error("Failed to parse configuration file {0:?}: {1}")]
+    Syntax(PathBuf, toml::de::Error),
+
+    #[error("{key} is not set (set it in the configuration file, or via the {env} environment variable)")]
+    Missing { key: &'static str, env: &'static str },
+
+    #[error("{key} has malformed value {value:?}: {reason}")]
+    Malformed { key: &'static str, value: String, reason: String },
+
+    #[error("{key} is invalid: {reason}")]
+    Invalid { key: &'static str, reason: String },
+}
+
+/// All problems found in the configuration
+#[derive(Debug)]
+pub struct ConfigErrors(pub Vec<ConfigError>);
+
+impl Display for ConfigErrors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Found {} problem(s) with the configuration:", self.0.len())?;
+
+        for error in &self.0 {
+            write!(f, "\n - {}", error)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for ConfigErrors {}
+
+/// The configuration of some part of pointercrate, stored in its own table of the configuration file
+pub trait ConfigSection: Sized {
+    /// Reads all values of this section, reporting every missing, malformed or invalid one to the given source
+    ///
+    /// Returns [`None`] if any value could not be read.
+    fn load(source: &mut ConfigSource) -> Option<Self>;
+}
+
+/// The configuration file and environment from which [`ConfigSection`]s are loaded
+#[derive(Debug, Default)]
+pub struct ConfigSource {
+    table: toml::Table,
+    errors: Vec<ConfigError>,
+}
+
+impl ConfigSource {
+    /// Reads the configuration file named by the `POINTERCRATE_CONFIG` environment variable, or `pointercrate.toml` if it is
+    /// not set
+    ///
+    /// The default file does not need to exist, in which case all configuration has to come from environment variables.
+    pub fn load() -> ConfigSource {
+        let (path, explicit) = match std::env::var("POINTERCRATE_CONFIG") {
+            Ok(path) => (PathBuf::from(path), true),
+            Err(_) => (PathBuf::from("pointercrate.toml"), false),
+        };
+
+        match std::fs::read_to_string(&path) {
+            Ok(content) => ConfigSource::from_toml(&content).map_err(|err| ConfigError::Syntax(path, err)),
+            Err(err) if !explicit && err.kind() == std::io::ErrorKind::NotFound => Ok(ConfigSource::default()),
+            Err(err) => Err(ConfigError::Io(path, err)),
+        }
+        .unwrap_or_else(|err| ConfigSource {
+            table: toml::Table::new(),
+            errors: vec![err],
+        })
+    }
+
+    pub fn from_toml(content: &str) -> Result<ConfigSource, toml::de::Error> {
+        Ok(ConfigSource {
+            table: content.parse()?,
+            errors: Vec::new(),
+        })
+    }
+
+    pub fn section<S: ConfigSection>(&mut self) -> Option<S> {
+        S::load(self)
+    }
+
+    /// Reads the value with the given key (of the form `<section>.<name>`), unless it is overridden by the given environment
+    /// variable
+    ///
+    /// Returns [`None`] if the value is not set or malformed, reporting an error in the latter case.
+    pub fn get<T: FromStr>(&mut self, key: &'static str, env: &'static str) -> Option<T>
+    where
+        T::Err: Display,
+    {
+        let value = match std::env::var(env) {
+            Ok(value) => value,
+            Err(_) => {
+                let (section, name) = key.split_once('.').unwrap_or(("", key));
+
+                let value = self.table.get(section).and_then(|section| section.get(name))?;
+
+                match scalar(value) {
+                    Some(value) => value,
+                    None => {
+                        self.errors.push(ConfigError::Malformed {
+                            key,
+                            value: value.to_string(),
+                            reason: "expected a string, number or boolean".to_string(),
+                        });
+
+                        return None;
+                    },
+                }
+            },
+        };
+
+        self.parse(key, value)
+    }
+
+    /// Reads all values of the given section, for sections whose names are not known in advance
+    ///
+    /// Every environment variable starting with the given prefix overrides (or adds) the value whose name is the lowercased
+    /// remainder of the variable name. Malformed values are reported under the name of the section and left out of the result.
+    pub fn get_all<T: FromStr>(&mut self, section: &'static str, env_prefix: &str) -> BTreeMap<String, T>
+    where
+        T::Err: Display,
+    {
+        let mut values = BTreeMap::new();
+
+        match self.table.get(section) {
+            Some(toml::Value::Table(table)) => {
+                for (name, value) in table {
+                    match scalar(value) {
+                        Some(value) => {
+                            values.insert(name.clone(), value);
+                        },
+                        None => self.errors.push(ConfigError::Malformed {
+                            key: section,
+                            value: format!("{} = {}", name, value),
+                            reason: "expected a string, number or boolean".to_string(),
+                        }),
+                    }
+                }
+            },
+            Some(value) => self.errors.push(ConfigError::Malformed {
+                key: section,
+                value: value.to_string(),
+                reason: "expected a table".to_string(),
+            }),
+            None => (),
+        }
+
+        for (variable, value) in std::env::vars() {
+            if let Some(name) = variable.strip_prefix(env_prefix) {
+                values.insert(name.to_lowercase(), value);
+            }
+        }
+
+        values
+            .into_iter()
+            .filter_map(|(name, value)| {
+                let parsed = value.parse().map_err(|err: T::Err| err.to_string());
+
+                match parsed {
+                    Ok(parsed) => Some((name, parsed)),
+                    Err(reason) => {
+                        self.errors.push(ConfigError::Malformed {
+                            key: section,
+                            value: format!("{} = {:?}", name, value),
+                            reason,
+                        });
+
+                        None
+                    },
+                }
+            })
+            .collect()
+    }
+
+    fn parse<T: FromStr>(&mut self, key: &'static str, value: String) -> Option<T>
+    where
+        T::Err: Display,
+    {
+        match value.parse() {
+            Ok(parsed) => Some(parsed),
+            Err(err) => {
+                self.errors.push(ConfigError::Malformed {
+                    key,
+                    value,
+                    reason: err.to_string(),
+                });
+
+                None
+            },
+        }
+    }
+
+    /// Like [`ConfigSource::get`], but additionally reports an error if the value is not set
+    pub fn require<T: FromStr>(&mut self, key: &'static str, env: &'static str) -> Option<T>
+    where
+        T::Err: Display,
+    {
+        if std::env::var(env).is_err() && !self.is_set(key) {
+            self.errors.push(ConfigError::Missing { key, env });
+
+            return None;
+        }
+
+        self.get(key, env)
+    }
+
+    /// Like [`ConfigSource::get`], but falls back to the given default if the value is not set
+    ///
+    /// Malformed values are still reported, in which case the returned default is never used, as [`ConfigSource::finish`] fails.
+    pub fn get_or<T: FromStr>(&mut self, key: &'static str, env: &'static str, default: T) -> T
+    where
+        T::Err: Display,
+    {
+        self.get(key, env).unwrap_or(default)
+    }
+
+    /// Reports a value that was read successfully, but violates some constraint
+    pub fn invalid(&mut self, key: &'static str, reason: impl Into<String>) {
+        self.errors.push(ConfigError::Invalid {
+            key,
+            reason: reason.into(),
+        })
+    }
+
+    fn is_set(&self, key: &str) -> bool {
+        let (section, name) = key.split_once('.').unwrap_or(("", key));
+
+        self.table.get(section).and_then(|section| section.get(name)).is_some()
+    }
+
+    /// Returns all problems encountered while loading sections from this source, if any
+    pub fn finish(self) -> Result<(), ConfigErrors> {
+        if self.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(ConfigErrors(self.errors))
+        }
+    }
+}
+
+/// The textual form of a value that can be parsed via [`FromStr`], if it is a string, number or boolean
+fn scalar(value: &toml::Value) -> Option<String> {
+    match value {
+        toml::Value::String(value) => Some(value.clone()),
+        toml::Value::Integer(_) | toml::Value::Float(_) | toml::Value::Boolean(_) => Some(value.to_string()),
+        _ => None,
+    }
+}
+
+/// The `[core]` section of the configuration
+#[derive(Debug, Clone)]
+pub struct CoreConfig {
+    pub database_url: String,
+
+    /// A read-only replica of the database at [`CoreConfig::database_url`], used for requests that only read data (see
+    /// [`PointercratePool::read_connection`](crate::pool::PointercratePool::read_connection)). Optional.
+    pub database_replica_url: Option<String>,
+
+    /// The maximal number of connections kept open to the database (and, separately, to its replica). Defaults to 20.
+    pub database_max_connections: u32,
+
+    /// How long to wait for a connection to become available before failing a request, configured in seconds. Defaults to 30.
+    pub database_acquire_timeout: Duration,
+
+    /// How long an unused connection is kept open, configured in seconds. Defaults to 600, with `0` meaning that idle connections
+    /// are never closed.
+    pub database_idle_timeout: Option<Duration>,
+
+    /// Whether localization files should be watched and reloaded on change. Meant for development only, defaults to `false`.
+    pub localization_hot_reload: bool,
+}
+
+impl ConfigSection for CoreConfig {
+    /// Also reads the secret used for signing tokens (see [`secret`]) from the file configured as `core.secret_file`
+    fn load(source: &mut ConfigSource) -> Option<Self> {
+        let database_url = source.require("core.database_url", "DATABASE_URL");
+        let database_replica_url = source.get("core.database_replica_url", "DATABASE_REPLICA_URL");
+        let database_max_connections = source.get_or("core.database_max_connections", "DATABASE_MAX_CONNECTIONS", 20);
+        let database_acquire_timeout = source.get_or("core.database_acquire_timeout", "DATABASE_ACQUIRE_TIMEOUT", 30);
+        let database_idle_timeout = source.get_or("core.database_idle_timeout", "DATABASE_IDLE_TIMEOUT", 600);
+        let localization_hot_reload = source.get_or("core.localization_hot_reload", "LOCALIZATION_HOT_RELOAD", false);
+        let secret_file: String = source.get_or("core.secret_file", "SECRET_FILE", ".secret".to_string());
+
+        let secret = match std::fs::read(&secret_file) {
+            Ok(secret) if secret.is_empty() => {
+                source.invalid("core.secret_file", format!("{} is empty", secret_file));
+
+                None
+            },
+            Ok(secret) => Some(secret),
+            Err(err) if cfg!(debug_assertions) => {
+                // needed for integration tests/CI
+                log::error!(
+                    "Failed to read secret, using an unsecure default since this is a debug build - {:?}",
+                    err
+                );
+
+                Some(UNSECURE_SECRET.to_vec())
+            },
+            Err(err) => {
+                source.invalid("core.secret_file", format!("failed to read {}: {}", secret_file, err));
+
+                None
+            },
+        };
+
+        if let Some(secret) = secret {
+            let _ = SECRET.set(secret);
+        }
+
+        if database_max_connections == 0 {
+            source.invalid("core.database_max_connections", "must be positive");
+        }
+
+        if database_acquire_timeout == 0 {
+            source.invalid("core.database_acquire_timeout", "must be positive");
+        }
+
+        Some(CoreConfig {
+            database_url: database_url?,
+            database_replica_url,
+            database_max_connections,
+            database_acquire_timeout: Duration::from_secs(database_acquire_timeout),
+            database_idle_timeout: Some(database_idle_timeout).filter(|&seconds| seconds != 0).map(Duration::from_secs),
+            localization_hot_reload,
+        })
+    }
+}
+
+/// The secret key this server uses to sign tokens
+///
+/// Unlike the rest of the configuration, the secret is needed deep inside the models (whenever a token or pagination cursor is
+/// signed or verified), where no managed state is available, so it is kept globally instead of being passed around. It is set when
+/// loading the [`CoreConfig`], without which release builds refuse to launch (see [`secret_loaded`]). Debug builds fall back to an
+/// unsecure default if no configuration was loaded, which integration tests rely on.
+pub fn secret() -> &'static [u8] {
+    SECRET.get().map(Vec::as_slice).unwrap_or(&UNSECURE_SECRET)
+}
+
+/// Whether the secret was set by loading the [`CoreConfig`]
+pub fn secret_loaded() -> bool {
+    SECRET.get().is_some()
+}
+
+/// The `[ratelimits]` section of the configuration
+///
+/// Maps the names of ratelimits (see [`ratelimits!`](crate::ratelimits!)) to quotas of the form `<capacity> per <seconds>`,
+/// overriding the defaults given where the ratelimits are declared. Each quota can also be set via the `RATELIMIT_<NAME>`
+/// environment variable. Which ratelimits exist is only known once all of them are registered, so names are not validated here
+/// (see [`RatelimitRegistry::unknown_quotas`](crate::ratelimits::RatelimitRegistry::unknown_quotas)).
+#[derive(Debug, Clone, Default)]
+pub struct RatelimitConfig {
+    pub quotas: BTreeMap<String, Quota>,
+}
+
+impl ConfigSection for RatelimitConfig {
+    fn load(source: &mut ConfigSource) -> Option<Self> {
+        Some(RatelimitConfig {
+            quotas: source.get_all("ratelimits", "RATELIMIT_"),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ConfigError, ConfigSource, CoreConfig};
+    use crate::ratelimits::Quota;
+    use std::time::Duration;
+
+    #[test]
+    fn test_values() {
+        let mut source = ConfigSource::from_toml(
+            r#"
+            [demonlist]
+            list_size = 75
+            name = "Demonlist"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(source.get::<i16>("demonlist.list_size", "POINTERCRATE_TEST_UNSET"), Some(75));
+        assert_eq!(
+            source.get::<String>("demonlist.list_size", "POINTERCRATE_TEST_UNSET"),
+            Some("75".to_string())
+        );
+        assert_eq!(
+            source.get::<String>("demonlist.name", "POINTERCRATE_TEST_UNSET"),
+            Some("Demonlist".to_string())
+        );
+        assert_eq!(source.get::<i16>("demonlist.extended_list_size", "POINTERCRATE_TEST_UNSET"), None);
+        assert_eq!(source.get_or::<i16>("user.list_size", "POINTERCRATE_TEST_UNSET", 50), 50);
+        assert!(source.finish().is_ok());
+    }
+
+    #[test]
+    fn test_all_errors_reported() {
+        let mut source = ConfigSource::from_toml(
+            r#"
+            [demonlist]
+            list_size = "seventy-five"
+            extended_list_size = [150]
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(source.get::<i16>("demonlist.list_size", "POINTERCRATE_TEST_UNSET"), None);
+        assert_eq!(source.get::<i16>("demonlist.extended_list_size", "POINTERCRATE_TEST_UNSET"), None);
+        assert_eq!(source.require::<String>("core.database_url", "POINTERCRATE_TEST_UNSET"), None);
+        source.invalid("demonlist.list_size", "must be positive");
+
+        let errors = source.finish().unwrap_err().0;
+
+        assert_eq!(errors.len(), 4);
+        assert!(matches!(
+            errors[0],
+            ConfigError::Malformed {
+                key: "demonlist.list_size",
+                ..
+            }
+        ));
+        assert!(matches!(
+            errors[1],
+            ConfigError::Malformed {
+                key: "demonlist.extended_list_size",
+                ..
+            }
+        ));
+        assert!(matches!(
+            errors[2],
+            ConfigError::Missing {
+                key: "core.database_url",
+                ..
+            }
+        ));
+        assert!(matches!(
+            errors[3],
+            ConfigError::Invalid {
+                key: "demonlist.list_size",
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_database_settings() {
+        let mut source = ConfigSource::from_toml(
+            r#"
+            [core]
+            database_url = "postgres://pointercrate@localhost/pointercrate"
+            database_acquire_timeout = 5
+            database_idle_timeout = 0
+            "#,
+        )
+        .unwrap();
+
+        let config = source.section::<CoreConfig>().unwrap();
+
+        assert!(source.finish().is_ok());
+        assert_eq!(config.database_acquire_timeout, Duration::from_secs(5));
+        assert_eq!(config.database_idle_timeout, None);
+    }
+
+    #[test]
+    fn test_database_settings_validated() {
+        let mut source = ConfigSource::from_toml(
+            r#"
+            [core]
+            database_url = "postgres://pointercrate@localhost/pointercrate"
+            database_max_connections = 0
+            database_acquire_timeout = -1
+            "#,
+        )
+        .unwrap();
+
+        source.section::<CoreConfig>();
+
+        let errors = source.finish().unwrap_err().0;
+
+        assert_eq!(errors.len(), 2);
+        assert!(matches!(
+            errors[0],
+            ConfigError::Malformed {
+                key: "core.database_acquire_timeout",
+                ..
+            }
+        ));
+        assert!(matches!(
+            errors[1],
+            ConfigError::Invalid {
+                key: "core.database_max_connections",
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_ratelimit_quotas() {
+        let mut source = ConfigSource::from_toml(
+            r#"
+            [ratelimits]
+            record_submission = "5 per 1200"
+            login_attempts = "3 every minute"
+            registrations = "0 per 60"
+            "#,
+        )
+        .unwrap();
+
+        let quotas = source.get_all::<Quota>("ratelimits", "POINTERCRATE_TEST_UNSET_");
+
+        assert_eq!(quotas.len(), 1);
+        assert_eq!(quotas["record_submission"], Quota::new(5, 1200));
+
+        let errors = source.finish().unwrap_err().0;
+
+        assert_eq!(errors.len(), 2);
+        assert!(errors
+            .iter()
+            .all(|error| matches!(error, ConfigError::Malformed { key: "ratelimits", .. })));
+    }
 }
@@ -1,4 +1,5 @@
-use crate::{localization::tr, permission::Permission, trp};
+use crate::{localization::tr, permission::Permission, ratelimits::RatelimitStatus, trp};
+use chrono::{DateTime, Utc};
 use log::error;
 use serde::Serialize;
 use std::{error::Error, fmt::Display, time::Duration};
@@ -10,6 +11,11 @@ pub trait PointercrateError: Error + Serialize + From<CoreError> {
     fn status_code(&self) -> u16 {
         self.error_code() / 100
     }
+
+    /// Additional headers to set on responses reporting this error
+    fn headers(&self) -> Vec<(&'static str, String)> {
+        Vec::new()
+    }
 }
 
 #[derive(Serialize, Debug, Eq, PartialEq, Clone)]
@@ -71,7 +77,7 @@ pub enum CoreError {
 
     /// `412 PRECONDITION FAILED`. This variant is returned if a `DELETE` or `PATCH` request is
     /// made, but the provided `If-Match` header doesn't match the hash of the object currently
-    /// in the database
+    /// in the database, or the object was modified after the date given in the `If-Unmodified-Since` header
     ///
     /// Error Code `41200`
     PreconditionFailed,
@@ -100,6 +106,13 @@ pub enum CoreError {
     /// Error Code `42207`
     InvalidPaginationLimit,
 
+    /// `422 UNPRECESSABLE ENTITY` variant returned if the `cursor` parameter provided for
+    /// pagination is malformed or was not issued for the requested listing, or if the listing does not support the
+    /// given kind of pagination parameters
+    ///
+    /// Error Code `42208`
+    InvalidPaginationCursor,
+
     /// `422 UNPROCESSABLE ENTITY` variant
     ///
     /// Error Code `42222`
@@ -128,7 +141,8 @@ pub enum CoreError {
     /// Error Code `42229`
     MutuallyExclusive,
 
-    /// `428 PRECONDITION REQUIRED`
+    /// `428 PRECONDITION REQUIRED`. This variant is returned if a `DELETE` or `PATCH` request is made without an `If-Match`
+    /// or `If-Unmodified-Since` header
     ///
     /// Error Code `42800`
     PreconditionRequired,
@@ -141,6 +155,9 @@ pub enum CoreError {
         message: String,
 
         remaining: Duration,
+
+        #[serde(skip)]
+        status: RatelimitStatus,
     },
 
     /// `500 INTERNAL SERVER ERROR`
@@ -165,7 +182,28 @@ pub enum CoreError {
     /// `503 SERVICE UNAVAILABLE` variant returned by all non-GET (e.g. all possible mutating) requests if the server is in maintenance mode.
     ///
     /// Error Core `50301`
-    ReadOnlyMaintenance,
+    ReadOnlyMaintenance {
+        /// The message administrators attached to the current maintenance window, if any
+        message: Option<String>,
+
+        /// The end of the current maintenance window, if known
+        ends_at: Option<DateTime<Utc>>,
+    },
+
+    /// `503 SERVICE UNAVAILABLE` variant returned by requests to routes belonging to a route group that was frozen for
+    /// maintenance, while the rest of the website remains available.
+    ///
+    /// Error Code `50302`
+    FeatureFrozen {
+        /// The text id of the frozen route group
+        feature: String,
+
+        /// The message administrators attached to the current maintenance window, if any
+        message: Option<String>,
+
+        /// The end of the current maintenance window, if known
+        ends_at: Option<DateTime<Utc>>,
+    },
 }
 
 impl CoreError {
@@ -203,6 +241,7 @@ impl PointercrateError for CoreError {
             CoreError::UnsupportedMediaType { .. } => 41500,
             CoreError::UnprocessableEntity => 42200,
             CoreError::InvalidPaginationLimit => 42207,
+            CoreError::InvalidPaginationCursor => 42208,
             CoreError::InvalidUrlScheme => 42222,
             CoreError::UrlAuthenticated => 42223,
             CoreError::InvalidUrlFormat { .. } => 42225,
@@ -214,7 +253,24 @@ impl PointercrateError for CoreError {
             CoreError::DatabaseError => 50003,
             CoreError::QueryTimeout => 50004,
             CoreError::DatabaseConnectionError => 50005,
-            CoreError::ReadOnlyMaintenance => 50301,
+            CoreError::ReadOnlyMaintenance { .. } => 50301,
+            CoreError::FeatureFrozen { .. } => 50302,
+        }
+    }
+
+    fn headers(&self) -> Vec<(&'static str, String)> {
+        match self {
+            CoreError::Ratelimited { remaining, status, .. } => {
+                let mut headers = status.headers();
+
+                headers.push(("Retry-After", (remaining.as_secs_f64().ceil() as u64).to_string()));
+                headers
+            },
+            CoreError::ReadOnlyMaintenance { ends_at, .. } | CoreError::FeatureFrozen { ends_at, .. } => ends_at
+                .iter()
+                .map(|ends_at| ("Retry-After", (*ends_at - Utc::now()).num_seconds().max(0).to_string()))
+                .collect(),
+            _ => Vec::new(),
         }
     }
 }
@@ -226,9 +282,9 @@ impl Display for CoreError {
             "{}",
             match self {
                 CoreError::BadRequest => tr("error-core-badrequest"),
-                CoreError::InvalidHeaderValue { header } => trp!("error-core-badrequest", "header" = header),
+                CoreError::InvalidHeaderValue { header } => trp!("error-core-invalidheadervalue", "header" = header),
                 CoreError::Unauthorized => tr("error-core-unauthorized"),
-                CoreError::Forbidden => tr("error-core-badrequest"),
+                CoreError::Forbidden => tr("error-core-forbidden"),
                 CoreError::MissingPermissions { required } =>
                     trp!("error-core-missingpermissions", "required-permission" = tr(required.text_id())),
                 CoreError::NotFound => tr("error-core-notfound"),
@@ -240,13 +296,14 @@ impl Display for CoreError {
                 CoreError::UnsupportedMediaType { expected } => trp!("error-core-unsupportedmediatype", "expected-type" = expected),
                 CoreError::UnprocessableEntity => tr("error-core-unprocessableentity"),
                 CoreError::InvalidPaginationLimit => tr("error-core-invalidpaginationlimit"),
+                CoreError::InvalidPaginationCursor => tr("error-core-invalidpaginationcursor"),
                 CoreError::InvalidUrlScheme => tr("error-core-invalidurlscheme"),
                 CoreError::UrlAuthenticated => tr("error-core-urlauthenticated"),
                 CoreError::InvalidUrlFormat { expected } => trp!("error-core-invalidurlformat", "expected-format" = expected),
                 CoreError::AfterSmallerBefore => tr("error-core-aftersmallerbefore"),
                 CoreError::MutuallyExclusive => tr("error-core-mutuallyexclusive"),
                 CoreError::PreconditionRequired => tr("error-core-preconditionrequired"),
-                CoreError::Ratelimited { message, remaining } => trp!(
+                CoreError::Ratelimited { message, remaining, .. } => trp!(
                     "error-core-ratelimited",
                     "message" = message,
                     "remaining-duration" = format!("{:.2?}", remaining)
@@ -255,7 +312,14 @@ impl Display for CoreError {
                 CoreError::DatabaseError => tr("error-core-databaseerror"),
                 CoreError::QueryTimeout => tr("error-core-querytimeout"),
                 CoreError::DatabaseConnectionError => tr("error-core-databaseconnectionerror"),
-                CoreError::ReadOnlyMaintenance => tr("error-core-readonlymaintenance"),
+                CoreError::ReadOnlyMaintenance { message, .. } => match message {
+                    Some(message) => trp!("error-core-readonlymaintenance-message", "message" = message),
+                    None => tr("error-core-readonlymaintenance"),
+                },
+                CoreError::FeatureFrozen { feature, message, .. } => match message {
+                    Some(message) => trp!("error-core-featurefrozen-message", "feature" = tr(feature), "message" = message),
+                    None => trp!("error-core-featurefrozen", "feature" = tr(feature)),
+                },
             }
         )
     }
@@ -6,7 +6,13 @@
 //! of all fields that can be modified via a direct `PATCH` request to the object represented, and a
 //! part relevant for `GET` requests, which is generally just a hash of the complete objects.
 //!
-//! These two parts are unsigned 64 bit integers separated by a semicolon (`;`)
+//! These two parts are unsigned 64 bit integers separated by a semicolon (`;`), and prefixed with the version of the ETag
+//! format (see [`ETAG_VERSION`]), e.g. `W/"v1:2166136261;1099511628211"`.
+//!
+//! Both parts are computed by [`stable_hash`], which feeds the JSON serialization of an object into [`StableHasher`], so that ETags
+//! stay valid across Rust releases. Neither the standard library's `DefaultHasher` nor the data its [`Hash`](std::hash::Hash)
+//! implementations feed into a hasher is guaranteed to stay the same between releases. Should the way ETags are computed ever have
+//! to change, [`ETAG_VERSION`] needs to be bumped, which causes all previously issued ETags to no longer match.
 //!
 //! The idea is that for `GET` requests only the second part of the ETag is used to determine if a
 //! 304 response should be generated, while for `PATCH` requests only the first part is used to
@@ -15,26 +21,191 @@
 //! The difference between `GET` and `PATCH` ETag is important for objects where specific subfields
 //! are not modifiable via `PATCH` (e.g. the record list of a player), so having changes to them
 //! cause a `412` is silly, yet for caching purposes, those parts are obviously important.
+//!
+//! Paginated listings cannot be modified via `PATCH`, so both parts of their ETags are the same hash of the entire page.
 
 use serde::Serialize;
-use std::{
-    collections::hash_map::DefaultHasher,
-    hash::{Hash, Hasher},
-};
+use std::{hash::Hasher, io};
+
+/// The version of the ETag format, prefixed to all ETags generated by [`Taggable::etag_string`]
+pub const ETAG_VERSION: &str = "v1";
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+/// [`Hasher`] implementing the 64 bit variant of the [FNV-1a](http://www.isthe.com/chongo/tech/comp/fnv/) hash function
+///
+/// Integers are always fed to the hash function in little endian byte order, and `usize`/`isize` are widened to 64 bits, so
+/// that hashes are the same across platforms.
+pub struct StableHasher(u64);
+
+impl Default for StableHasher {
+    fn default() -> Self {
+        StableHasher(FNV_OFFSET_BASIS)
+    }
+}
+
+impl Hasher for StableHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= *byte as u64;
+            self.0 = self.0.wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    fn write_u16(&mut self, i: u16) {
+        self.write(&i.to_le_bytes())
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.write(&i.to_le_bytes())
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.write(&i.to_le_bytes())
+    }
+
+    fn write_u128(&mut self, i: u128) {
+        self.write(&i.to_le_bytes())
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.write_u64(i as u64)
+    }
+
+    fn write_i16(&mut self, i: i16) {
+        self.write(&i.to_le_bytes())
+    }
+
+    fn write_i32(&mut self, i: i32) {
+        self.write(&i.to_le_bytes())
+    }
+
+    fn write_i64(&mut self, i: i64) {
+        self.write(&i.to_le_bytes())
+    }
+
+    fn write_i128(&mut self, i: i128) {
+        self.write(&i.to_le_bytes())
+    }
+
+    fn write_isize(&mut self, i: isize) {
+        self.write_i64(i as i64)
+    }
+}
+
+impl io::Write for StableHasher {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        Hasher::write(self, buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Hashes the JSON serialization of the given value using [`StableHasher`]
+pub fn stable_hash(value: &(impl Serialize + ?Sized)) -> u64 {
+    let mut hasher = StableHasher::default();
+    // Writing to the hasher cannot fail, and neither can serializing pointercrate's objects (all their maps have string keys)
+    let _ = serde_json::to_writer(&mut hasher, value);
+    hasher.finish()
+}
 
 /// Trait defining methods for producing the two parts of the pointercrate ETag format
-pub trait Taggable: Hash + Serialize {
+pub trait Taggable: Serialize {
     fn patch_part(&self) -> u64 {
         self.get_part()
     }
 
     fn get_part(&self) -> u64 {
-        let mut hasher = DefaultHasher::new();
-        self.hash(&mut hasher);
-        hasher.finish()
+        stable_hash(self)
     }
 
     fn etag_string(&self) -> String {
-        format!("W/\"{};{}\"", self.patch_part(), self.get_part())
+        format_etag(self.patch_part(), self.get_part())
+    }
+}
+
+/// Formats the given parts as a pointercrate ETag of the current version
+pub fn format_etag(patch_part: u64, get_part: u64) -> String {
+    format!("W/\"{}:{};{}\"", ETAG_VERSION, patch_part, get_part)
+}
+
+/// Extracts the part relevant for `PATCH` requests from the given ETag, if it is a pointercrate ETag of the current version
+pub fn patch_part_of(etag: &str) -> Option<&str> {
+    let (patch_part, _) = etag
+        .trim()
+        .strip_prefix("W/\"")?
+        .strip_prefix(ETAG_VERSION)?
+        .strip_prefix(':')?
+        .split_once(';')?;
+
+    Some(patch_part)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{patch_part_of, stable_hash, StableHasher, Taggable};
+    use serde::Serialize;
+    use std::hash::Hasher;
+
+    #[test]
+    fn test_fnv1a_test_vectors() {
+        let hash = |bytes: &[u8]| {
+            let mut hasher = StableHasher::default();
+            hasher.write(bytes);
+            hasher.finish()
+        };
+
+        assert_eq!(hash(b""), 0xcbf29ce484222325);
+        assert_eq!(hash(b"a"), 0xaf63dc4c8601ec8c);
+        assert_eq!(hash(b"foobar"), 0x85944171f73967e8);
+    }
+
+    #[test]
+    fn test_integers_are_platform_independent() {
+        assert_eq!(stable_hash(&1usize), stable_hash(&1u64));
+        assert_eq!(stable_hash(&-1isize), stable_hash(&-1i64));
+    }
+
+    #[test]
+    fn test_hashes_json() {
+        let mut hasher = StableHasher::default();
+        hasher.write(br#"{"id":1,"name":"dummy"}"#);
+
+        assert_eq!(
+            stable_hash(&Dummy {
+                id: 1,
+                name: "dummy".to_string()
+            }),
+            hasher.finish()
+        );
+    }
+
+    #[derive(Serialize)]
+    struct Dummy {
+        id: i32,
+        name: String,
+    }
+
+    impl Taggable for Dummy {}
+
+    #[test]
+    fn test_etag_format() {
+        let dummy = Dummy {
+            id: 1,
+            name: "dummy".to_string(),
+        };
+        let etag = dummy.etag_string();
+
+        assert_eq!(etag, "W/\"v1:12609892888794186802;12609892888794186802\"");
+        assert_eq!(patch_part_of(&etag), Some(dummy.patch_part().to_string().as_str()));
+        assert_eq!(patch_part_of("W/\"12609892888794186802;12609892888794186802\""), None);
     }
 }
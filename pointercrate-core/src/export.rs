@@ -0,0 +1,25 @@
+use crate::pagination::{Paginatable, PaginationQuery};
+use futures::stream::BoxStream;
+use sqlx::PgConnection;
+
+/// Trait for objects whose entire listing can be exported in bulk, in addition to being paginated
+///
+/// Exports are not subject to the `limit` of the [`PaginationParameters`](crate::pagination::PaginationParameters) of the query,
+/// and are meant for consumers that want to mirror or analyze a full listing without walking it page by page.
+pub trait Exportable<Q: PaginationQuery>: Paginatable<Q> {
+    /// The column names of the CSV representation of these objects, in the order in which
+    /// [`csv_record`](Exportable::csv_record) returns the corresponding fields
+    const CSV_HEADER: &'static [&'static str];
+
+    /// Streams all objects matching the query described by the given [`PaginationQuery`], including the `before` and `after`
+    /// bounds of its [`PaginationParameters`](crate::pagination::PaginationParameters), but ignoring its `limit`.
+    ///
+    /// The objects are produced in the same order in which they would appear when paginating through the listing from its first page.
+    /// Rows are streamed from the database as they are produced by the query, so implementations must not buffer the result set.
+    fn export<'a>(query: &'a Q, connection: &'a mut PgConnection) -> BoxStream<'a, Result<Self, sqlx::Error>>;
+
+    /// The fields of the CSV representation of this object
+    ///
+    /// Missing values are represented by the empty string.
+    fn csv_record(&self) -> Vec<String>;
+}
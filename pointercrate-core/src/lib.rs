@@ -1,8 +1,11 @@
 pub mod audit;
+pub mod cache;
 pub mod config;
 pub mod error;
 pub mod etag;
+pub mod export;
 pub mod localization;
+pub mod maintenance;
 pub mod pagination;
 pub mod permission;
 pub mod pool;
@@ -1,21 +1,41 @@
 use crate::error::log_internal_server_error;
+use chrono::{DateTime, Datelike, NaiveDate, TimeZone};
 pub use fluent::FluentValue;
-use fluent::{concurrent::FluentBundle, FluentArgs, FluentError, FluentMessage, FluentResource};
+use fluent::{
+    concurrent::FluentBundle,
+    types::{FluentNumber, FluentNumberOptions, FluentNumberType},
+    FluentArgs, FluentError, FluentMessage, FluentResource,
+};
 use fluent_syntax::parser::ParserError;
+use log::{error, info, warn};
 use std::collections::hash_map::Entry;
-use std::{collections::HashMap, fs::read_dir, path::Path, sync::OnceLock};
+use std::{
+    collections::HashMap,
+    fmt::{Display, Write},
+    fs::read_dir,
+    path::{Path, PathBuf},
+    sync::{Arc, OnceLock, RwLock},
+    time::{Duration, SystemTime},
+};
 use tokio::task_local;
 use unic_langid::subtags::Language;
 use unic_langid::{LanguageIdentifier, LanguageIdentifierError};
 
 static LOCALES: OnceLock<LocaleConfiguration> = OnceLock::new();
 
+type Bundles = HashMap<Language, FluentBundle<FluentResource>>;
+
 pub struct LocalesLoader {
-    bundles: HashMap<Language, FluentBundle<FluentResource>>,
+    bundles: Bundles,
+    resource_dirs: Vec<PathBuf>,
 }
 
 pub struct LocaleConfiguration {
-    bundles: HashMap<Language, FluentBundle<FluentResource>>,
+    /// The locales for which bundles were loaded at startup. Reloading can change the content of
+    /// the bundles, but not the set of available languages.
+    locales: HashMap<Language, LanguageIdentifier>,
+    bundles: RwLock<Arc<Bundles>>,
+    resource_dirs: Vec<PathBuf>,
     pub fallback: Language,
 }
 
@@ -25,100 +45,154 @@ pub enum LoaderError {
     Io(#[from] std::io::Error),
     #[error("Encountered directory whose name is not a language identifier: {0}")]
     LanguageIdentifier(#[from] LanguageIdentifierError),
-    #[error("Error(s) parsing fluent resource file: {0:?}")]
-    FluentParsing(Vec<ParserError>),
+    #[error("Error(s) parsing fluent resource file {0:?}: {1:?}")]
+    FluentParsing(PathBuf, Vec<ParserError>),
     #[error("Fluent Resource Conflict(s): {0:?}")]
     FluentConflict(Vec<FluentError>),
     #[error("The same language is registered with non-equal language identifiers (e.g. en-gb vs en-us)")]
     InconsistentLangIds,
+    #[error("The set of languages changed from {before:?} to {after:?}, which requires a restart")]
+    LanguagesChanged {
+        before: Vec<LanguageIdentifier>,
+        after: Vec<LanguageIdentifier>,
+    },
 }
 
 impl LocalesLoader {
     pub fn load(resource_dirs: &[impl AsRef<Path>]) -> Result<Self, LoaderError> {
         // Cannot use log::warn in this function, because it gets ran before rocket configures logging.
-        let mut bundles = HashMap::<Language, FluentBundle<FluentResource>>::new();
+        let resource_dirs = resource_dirs.iter().map(|dir| dir.as_ref().to_path_buf()).collect::<Vec<_>>();
+        let bundles = parse_bundles(&resource_dirs, |msg| eprintln!("{}", msg))?;
 
-        let mut text_ids = Vec::new();
+        Ok(LocalesLoader { bundles, resource_dirs })
+    }
 
-        for path in resource_dirs {
-            for dir_entry in read_dir(path)? {
-                let dir_entry = dir_entry?;
+    fn into_configuration(self, fallback: Language) -> LocaleConfiguration {
+        assert!(self.bundles.contains_key(&fallback));
 
-                if !dir_entry.path().is_dir() {
-                    eprintln!("Expected layout for localization directories is [...]/static/{{lang1,lang2,lang3}}/*.ftl. Unexpectedly found non-directory {:?}, ignoring", dir_entry.path());
-                    continue;
-                }
+        LocaleConfiguration {
+            locales: self
+                .bundles
+                .iter()
+                .map(|(language, bundle)| (*language, bundle.locales[0].clone()))
+                .collect(),
+            bundles: RwLock::new(Arc::new(self.bundles)),
+            resource_dirs: self.resource_dirs,
+            fallback,
+        }
+    }
+
+    /// Set the `LOCALES` [`OnceLock`] to use this set of loaded locales
+    pub fn commit(self, fallback: Language) {
+        LOCALES
+            .set(self.into_configuration(fallback))
+            .unwrap_or_else(|_| panic!("LOCALES OnceLock already initialized"));
+    }
+
+    /// Function setting up an empty [`LocaleConfiguration`] that will fail to localize
+    /// all keys. Mostly useful for integration tests.
+    pub fn empty() {
+        // Code assumes that the fallback has an entry in the hashmap, so create a dummy bundle
+        let mut bundles = HashMap::new();
+        let lang_id = LanguageIdentifier::default();
+        let lang = lang_id.language;
+        bundles.insert(lang, FluentBundle::new_concurrent(vec![lang_id]));
+        let empty = LocalesLoader {
+            bundles,
+            resource_dirs: Vec::new(),
+        };
+        _ = LOCALES.set(empty.into_configuration(lang))
+    }
+}
 
-                let lang_id = LanguageIdentifier::from_bytes(dir_entry.file_name().as_encoded_bytes())?;
+/// Parses all `.ftl` files in the given directories into one bundle per language, reporting
+/// keys missing from some languages via `report`
+fn parse_bundles(resource_dirs: &[PathBuf], report: impl Fn(String)) -> Result<Bundles, LoaderError> {
+    let mut bundles = Bundles::new();
 
-                let bundle = match bundles.entry(lang_id.language) {
-                    Entry::Occupied(bundle) => {
-                        if bundle.get().locales[0] != lang_id {
-                            return Err(LoaderError::InconsistentLangIds);
-                        }
+    let mut text_ids = Vec::new();
 
-                        bundle.into_mut()
-                    },
-                    Entry::Vacant(entry) => entry.insert(FluentBundle::new_concurrent(vec![lang_id])),
-                };
+    for path in resource_dirs {
+        for dir_entry in read_dir(path)? {
+            let dir_entry = dir_entry?;
 
-                for ftl_file in read_dir(dir_entry.path())? {
-                    let ftl_file = ftl_file?;
+            if !dir_entry.path().is_dir() {
+                report(format!("Expected layout for localization directories is [...]/static/{{lang1,lang2,lang3}}/*.ftl. Unexpectedly found non-directory {:?}, ignoring", dir_entry.path()));
+                continue;
+            }
+
+            let lang_id = LanguageIdentifier::from_bytes(dir_entry.file_name().as_encoded_bytes())?;
 
-                    if !ftl_file.path().is_file() {
-                        eprintln!("Expected layout for localization directories is [...]/static/{{lang1,lang2,lang3}}/*.ftl. Unexpectedly found non-file {:?}, ignoring", ftl_file.path());
-                        continue;
+            let bundle = match bundles.entry(lang_id.language) {
+                Entry::Occupied(bundle) => {
+                    if bundle.get().locales[0] != lang_id {
+                        return Err(LoaderError::InconsistentLangIds);
                     }
 
-                    let source = FluentResource::try_new(std::fs::read_to_string(ftl_file.path())?)
-                        .map_err(|(_, errors)| LoaderError::FluentParsing(errors))?;
+                    bundle.into_mut()
+                },
+                Entry::Vacant(entry) => entry.insert(FluentBundle::new_concurrent(vec![lang_id])),
+            };
 
-                    for entry in source.entries() {
-                        if let fluent_syntax::ast::Entry::Message(msg) = entry {
-                            text_ids.push(msg.id.name.to_string());
-                        }
-                    }
+            for ftl_file in read_dir(dir_entry.path())? {
+                let ftl_file = ftl_file?;
 
-                    bundle.add_resource(source).map_err(LoaderError::FluentConflict)?
+                if !ftl_file.path().is_file() {
+                    report(format!("Expected layout for localization directories is [...]/static/{{lang1,lang2,lang3}}/*.ftl. Unexpectedly found non-file {:?}, ignoring", ftl_file.path()));
+                    continue;
                 }
-            }
-        }
 
-        for bundle in bundles.values() {
-            for text_id in &text_ids {
-                if !bundle.has_message(text_id) {
-                    eprintln!("Localization Files for language {} are missing key {}!", bundle.locales[0], text_id);
+                let source = FluentResource::try_new(std::fs::read_to_string(ftl_file.path())?)
+                    .map_err(|(_, errors)| LoaderError::FluentParsing(ftl_file.path(), errors))?;
+
+                for entry in source.entries() {
+                    if let fluent_syntax::ast::Entry::Message(msg) = entry {
+                        text_ids.push(msg.id.name.to_string());
+                    }
                 }
+
+                bundle.add_resource(source).map_err(LoaderError::FluentConflict)?
             }
         }
+    }
 
-        Ok(LocalesLoader { bundles })
+    for bundle in bundles.values() {
+        for text_id in &text_ids {
+            if !bundle.has_message(text_id) {
+                report(format!(
+                    "Localization Files for language {} are missing key {}!",
+                    bundle.locales[0], text_id
+                ));
+            }
+        }
     }
 
-    /// Set the `LOCALES` [`OnceLock`] to use this set of loaded locales
-    pub fn commit(self, fallback: Language) {
-        assert!(self.bundles.contains_key(&fallback));
+    Ok(bundles)
+}
 
-        let config = LocaleConfiguration {
-            bundles: self.bundles,
-            fallback,
-        };
-        LOCALES
-            .set(config)
-            .unwrap_or_else(|_| panic!("LOCALES OnceLock already initialized"));
-    }
+/// The modification times of all files in the given localization directories, used to detect changes
+fn fingerprint(resource_dirs: &[PathBuf]) -> std::io::Result<Vec<(PathBuf, SystemTime)>> {
+    let mut fingerprint = Vec::new();
 
-    /// Function setting up an empty [`LocaleConfiguration`] that will fail to localize
-    /// all keys. Mostly useful for integration tests.
-    pub fn empty() {
-        // Code assumes that the fallback has an entry in the hashmap, so create a dummy bundle
-        let mut bundles = HashMap::new();
-        let lang_id = LanguageIdentifier::default();
-        let lang = lang_id.language;
-        bundles.insert(lang, FluentBundle::new_concurrent(vec![lang_id]));
-        let empty = LocaleConfiguration { bundles, fallback: lang };
-        _ = LOCALES.set(empty)
+    for path in resource_dirs {
+        for dir_entry in read_dir(path)? {
+            let dir_entry = dir_entry?;
+
+            fingerprint.push((dir_entry.path(), dir_entry.metadata()?.modified()?));
+
+            if dir_entry.path().is_dir() {
+                for ftl_file in read_dir(dir_entry.path())? {
+                    let ftl_file = ftl_file?;
+
+                    fingerprint.push((ftl_file.path(), ftl_file.metadata()?.modified()?));
+                }
+            }
+        }
     }
+
+    fingerprint.sort();
+
+    Ok(fingerprint)
 }
 
 impl LocaleConfiguration {
@@ -128,18 +202,77 @@ impl LocaleConfiguration {
             .expect("Locales were not properly initialized. Please ensure that the locales have been loaded correctly!")
     }
 
+    /// Re-parses all localization files from the directories this configuration was loaded from
+    /// and atomically swaps them in.
+    ///
+    /// On error, the previously loaded bundles stay in use. Adding or removing languages is not
+    /// supported and requires a restart.
+    pub fn reload(&self) -> Result<(), LoaderError> {
+        let bundles = parse_bundles(&self.resource_dirs, |msg| warn!("{}", msg))?;
+
+        if bundles.len() != self.locales.len()
+            || self
+                .locales
+                .iter()
+                .any(|(language, lang_id)| bundles.get(language).map(|bundle| &bundle.locales[0]) != Some(lang_id))
+        {
+            return Err(LoaderError::LanguagesChanged {
+                before: self.locales.values().cloned().collect(),
+                after: bundles.values().map(|bundle| bundle.locales[0].clone()).collect(),
+            });
+        }
+
+        *self.bundles.write().unwrap_or_else(|poisoned| poisoned.into_inner()) = Arc::new(bundles);
+
+        Ok(())
+    }
+
+    /// Spawns a background thread that checks the localization directories for changes every
+    /// `interval` and [reloads](LocaleConfiguration::reload) the bundles whenever a change is found.
+    ///
+    /// Intended for development only, so that translators do not have to restart the server after
+    /// each edit. Errors are logged, and the server keeps using the last bundles that loaded successfully.
+    pub fn watch(&'static self, interval: Duration) {
+        let mut last_seen = fingerprint(&self.resource_dirs).ok();
+
+        std::thread::spawn(move || loop {
+            std::thread::sleep(interval);
+
+            let current = match fingerprint(&self.resource_dirs) {
+                Ok(current) => Some(current),
+                Err(err) => {
+                    // Can happen transiently, e.g. while an editor replaces a file
+                    warn!("Failed to check localization files for changes: {}", err);
+                    continue;
+                },
+            };
+
+            if current == last_seen {
+                continue;
+            }
+
+            last_seen = current;
+
+            match self.reload() {
+                Ok(()) => info!("Reloaded localization files"),
+                Err(err) => error!("Failed to reload localization files, continuing to use previous versions: {}", err),
+            }
+        });
+    }
+
+    fn bundles(&self) -> Arc<Bundles> {
+        Arc::clone(&self.bundles.read().unwrap_or_else(|poisoned| poisoned.into_inner()))
+    }
+
     pub fn active_locale(&self) -> &LanguageIdentifier {
-        self.bundles
-            .get(&task_lang())
-            .map(|bundle| &bundle.locales[0])
-            .unwrap_or(&self.bundles[&self.fallback].locales[0])
+        self.locales.get(&task_lang()).unwrap_or(&self.locales[&self.fallback])
     }
 
     /// Returns a [`LanguageIdentifier`] whose string representation matches the given `code`.
     /// If one is not found, the [`LanguageIdentifier`] associated with the fallback language will be returned.
     pub fn by_code(&self, code: &str) -> &LanguageIdentifier {
         // Can unwrap, there's an assertion in `commit()` to assert the fallback language exists
-        let fallback_locale = &self.bundles[&self.fallback].locales[0];
+        let fallback_locale = &self.locales[&self.fallback];
 
         self.locales()
             .find(|lang_id| lang_id.to_string().eq_ignore_ascii_case(code))
@@ -147,13 +280,58 @@ impl LocaleConfiguration {
     }
 
     pub fn locales(&self) -> impl ExactSizeIterator<Item = &LanguageIdentifier> {
-        self.bundles.values().map(|bundle| &bundle.locales[0])
+        self.locales.values()
     }
 
-    fn get_message(&self, lang: &Language, text_id: &str) -> Option<(&FluentBundle<FluentResource>, FluentMessage<'_>)> {
-        // Can unwrap, there's an assertion in `commit()` to assert the fallback language exists
-        let fallback_bundle = &self.bundles[&self.fallback];
-        let bundle = self.bundles.get(lang).unwrap_or_else(|| {
+    /// Picks the locale best matching the language ranges in the given `Accept-Language` header value (see RFC 9110, section 12.5.4).
+    ///
+    /// Ranges are tried in order of descending quality. A range matches a locale if it is equal to, or a prefix of, the locale's
+    /// language tag (basic filtering as per RFC 4647, section 3.3.1), so `de` matches `de-DE`. If no locale matches a range exactly,
+    /// a locale of the same primary language is accepted instead, so that `de-AT` still matches `de-DE`. Ranges with a quality of
+    /// zero are ignored, and the wildcard range `*` matches the fallback locale.
+    ///
+    /// Returns [`None`] if no range matches any of the available locales.
+    pub fn negotiate(&self, accept_language: &str) -> Option<&LanguageIdentifier> {
+        let mut ranges = accept_language
+            .split(',')
+            .filter_map(|range| {
+                let mut parts = range.split(';').map(str::trim);
+                let tag = parts.next().filter(|tag| !tag.is_empty())?;
+                let quality = match parts.find_map(|param| param.strip_prefix("q=")) {
+                    Some(quality) => quality.parse::<f32>().ok()?,
+                    None => 1.0,
+                };
+
+                (quality > 0.0).then(|| (tag.to_ascii_lowercase(), quality))
+            })
+            .collect::<Vec<_>>();
+
+        // stable sort, so that ranges of equal quality retain the client's order
+        ranges.sort_by(|(_, q1), (_, q2)| q2.total_cmp(q1));
+
+        ranges.into_iter().find_map(|(range, _)| {
+            if range == "*" {
+                return Some(&self.locales[&self.fallback]);
+            }
+
+            let primary_language = range.split('-').next().unwrap_or(&range);
+
+            self.locales()
+                .find(|lang_id| {
+                    let tag = lang_id.to_string().to_ascii_lowercase();
+
+                    tag == range || tag.strip_prefix(&range).is_some_and(|rest| rest.starts_with('-'))
+                })
+                .or_else(|| self.locales().find(|lang_id| lang_id.language.as_str() == primary_language))
+        })
+    }
+
+    fn get_message<'b>(
+        &self, bundles: &'b Bundles, lang: &Language, text_id: &str,
+    ) -> Option<(&'b FluentBundle<FluentResource>, FluentMessage<'b>)> {
+        // Can unwrap, there's an assertion in `commit()` (and `reload()`) to assert the fallback language exists
+        let fallback_bundle = &bundles[&self.fallback];
+        let bundle = bundles.get(lang).unwrap_or_else(|| {
             log_internal_server_error(format!("Request for language that has no bundle associated with it: {}", lang));
 
             fallback_bundle
@@ -171,7 +349,9 @@ impl LocaleConfiguration {
             None => (text_id, None),
         };
 
-        let Some((bundle, message)) = self.get_message(lang, key) else {
+        let bundles = self.bundles();
+
+        let Some((bundle, message)) = self.get_message(&bundles, lang, key) else {
             #[cfg(not(test))]
             log_internal_server_error(format!("Invalid fluent key: {}", text_id));
 
@@ -263,3 +443,204 @@ macro_rules! trp {
         })
     }};
 }
+
+/// Formats the given number with exactly `fraction_digits` decimal places, using the decimal and digit group
+/// separators of the language of the current `LANGUAGE` scope (defined by the `format-number` message).
+///
+/// This function call must be nested inside a [`LocalKey`] scope.
+pub fn format_decimal(value: f64, fraction_digits: usize) -> String {
+    group_digits(
+        &format!("{:.*}", fraction_digits, value),
+        &tr("format-number.decimal-separator"),
+        &tr("format-number.group-separator"),
+    )
+}
+
+/// Replaces the decimal point of the given formatted number with `decimal_separator`, and inserts `group_separator`
+/// between each group of three digits of its integer part
+fn group_digits(formatted: &str, decimal_separator: &str, group_separator: &str) -> String {
+    let (sign, unsigned) = match formatted.strip_prefix('-') {
+        Some(unsigned) => ("-", unsigned),
+        None => ("", formatted),
+    };
+    let (integer, fraction) = match unsigned.split_once('.') {
+        Some((integer, fraction)) => (integer, Some(fraction)),
+        None => (unsigned, None),
+    };
+
+    let mut grouped = sign.to_string();
+
+    for (idx, digit) in integer.chars().enumerate() {
+        if idx > 0 && (integer.len() - idx) % 3 == 0 {
+            grouped.push_str(group_separator);
+        }
+
+        grouped.push(digit);
+    }
+
+    if let Some(fraction) = fraction {
+        grouped.push_str(decimal_separator);
+        grouped.push_str(fraction);
+    }
+
+    grouped
+}
+
+/// A [`FluentValue`] for the given number that selects variants according to the ordinal (instead of cardinal)
+/// plural rules of a language, e.g. `one` for 1, 21, 31, ... in english.
+pub fn ordinal(number: i64) -> FluentValue<'static> {
+    FluentValue::from(FluentNumber::new(
+        number as f64,
+        FluentNumberOptions {
+            r#type: FluentNumberType::Ordinal,
+            ..FluentNumberOptions::default()
+        },
+    ))
+}
+
+/// Formats the given point in time, including the weekday and the time zone, according to the language of the current
+/// `LANGUAGE` scope (defined by the `format-datetime` message).
+///
+/// This function call must be nested inside a [`LocalKey`] scope.
+pub fn format_datetime<Tz: TimeZone>(datetime: &DateTime<Tz>) -> String
+where
+    Tz::Offset: Display,
+{
+    // Time formats are a chrono format string, as there's no way to convert between 12 and 24 hour clocks in fluent
+    let mut time = String::new();
+
+    if write!(time, "{}", datetime.format(&tr("format-datetime.time"))).is_err() {
+        log_internal_server_error(format!("Invalid time format for language {}", task_lang()));
+
+        time = datetime.format("%H:%M:%S").to_string();
+    }
+
+    trp!(
+        "format-datetime",
+        "weekday" = datetime.weekday().number_from_monday(),
+        "day" = ordinal(datetime.day() as i64),
+        "month" = datetime.month(),
+        "year" = datetime.year(),
+        "time" = time,
+        "timezone" = datetime.format("%Z").to_string()
+    )
+}
+
+/// Formats the month and year of the given date in abbreviated form (e.g. "Jan 24" in english), according to the
+/// language of the current `LANGUAGE` scope (defined by the `format-month-year` message).
+///
+/// This function call must be nested inside a [`LocalKey`] scope.
+pub fn format_month_year(date: NaiveDate) -> String {
+    trp!("format-month-year", "month" = date.month(), "year" = date.format("%y").to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{group_digits, ordinal, LoaderError, LocaleConfiguration, LocalesLoader};
+    use fluent::concurrent::FluentBundle;
+    use unic_langid::{subtags::Language, LanguageIdentifier};
+
+    fn configuration(locales: &[&str]) -> LocaleConfiguration {
+        let bundles = locales
+            .iter()
+            .map(|locale| {
+                let lang_id: LanguageIdentifier = locale.parse().unwrap();
+
+                (lang_id.language, FluentBundle::new_concurrent(vec![lang_id]))
+            })
+            .collect();
+        let fallback = locales[0].parse::<LanguageIdentifier>().unwrap().language;
+
+        LocalesLoader {
+            bundles,
+            resource_dirs: Vec::new(),
+        }
+        .into_configuration(fallback)
+    }
+
+    #[test]
+    fn test_negotiate() {
+        let config = configuration(&["en-US", "ru-RU", "de-DE"]);
+        let negotiate = |header| config.negotiate(header).map(|lang_id| lang_id.to_string());
+
+        assert_eq!(negotiate("de-DE"), Some("de-DE".to_string()));
+        assert_eq!(negotiate("de"), Some("de-DE".to_string()));
+        assert_eq!(negotiate("DE-at"), Some("de-DE".to_string()));
+        assert_eq!(negotiate("fr-FR, ru;q=0.5, de;q=0.7"), Some("de-DE".to_string()));
+        assert_eq!(negotiate("ru;q=0.5, de;q=0.5"), Some("ru-RU".to_string()));
+        assert_eq!(negotiate("de;q=0, ru;q=0.1"), Some("ru-RU".to_string()));
+        assert_eq!(negotiate("fr, *;q=0.1"), Some("en-US".to_string()));
+        assert_eq!(negotiate("fr-FR, es"), None);
+        assert_eq!(negotiate("de;q=abc"), None);
+        assert_eq!(negotiate(""), None);
+    }
+
+    #[test]
+    fn test_reload() {
+        let dir = std::env::temp_dir().join(format!("pointercrate-ftl-{}", std::process::id()));
+        let en = dir.join("en-us");
+        let lang: Language = "en".parse().unwrap();
+
+        std::fs::create_dir_all(&en).unwrap();
+        std::fs::write(en.join("test.ftl"), "hello = Hello").unwrap();
+
+        let config = LocalesLoader::load(&[&dir]).unwrap().into_configuration(lang);
+
+        assert_eq!(config.lookup(&lang, "hello", None), "Hello");
+
+        // Parse errors leave the previously loaded bundles in place
+        std::fs::write(en.join("test.ftl"), "hello = {").unwrap();
+
+        assert!(matches!(config.reload(), Err(LoaderError::FluentParsing(..))));
+        assert_eq!(config.lookup(&lang, "hello", None), "Hello");
+
+        std::fs::write(en.join("test.ftl"), "hello = Hi").unwrap();
+
+        config.reload().unwrap();
+        assert_eq!(config.lookup(&lang, "hello", None), "Hi");
+
+        std::fs::create_dir_all(dir.join("ru-ru")).unwrap();
+
+        assert!(matches!(config.reload(), Err(LoaderError::LanguagesChanged { .. })));
+        assert_eq!(config.lookup(&lang, "hello", None), "Hi");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_group_digits() {
+        assert_eq!(group_digits("0.50", ".", ","), "0.50");
+        assert_eq!(group_digits("123.45", ",", "\u{a0}"), "123,45");
+        assert_eq!(group_digits("1234.00", ".", ","), "1,234.00");
+        assert_eq!(group_digits("-1234567", ".", ","), "-1,234,567");
+        assert_eq!(group_digits("-123456.7", ",", "."), "-123.456,7");
+    }
+
+    #[test]
+    fn test_ordinal_selection() {
+        let dir = std::env::temp_dir().join(format!("pointercrate-ordinal-{}", std::process::id()));
+        let en = dir.join("en-us");
+        let lang: Language = "en".parse().unwrap();
+
+        std::fs::create_dir_all(&en).unwrap();
+        std::fs::write(
+            en.join("format.ftl"),
+            "ordinal = { $number ->\n    [one] first\n    [two] second\n    [few] third\n   *[other] other\n}",
+        )
+        .unwrap();
+
+        let config = LocalesLoader::load(&[&dir]).unwrap().into_configuration(lang);
+        let lookup = |number| {
+            let args = [("number", ordinal(number))].into_iter().collect();
+
+            config.lookup(&lang, "ordinal", Some(&args))
+        };
+
+        assert_eq!(lookup(1), "first");
+        assert_eq!(lookup(11), "other");
+        assert_eq!(lookup(22), "second");
+        assert_eq!(lookup(103), "third");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,332 @@
+//! Read-only maintenance mode
+//!
+//! While maintenance is active, all mutating requests are rejected with a [`CoreError::ReadOnlyMaintenance`] error. Maintenance
+//! can either be forced at startup (e.g. for the duration of a database migration), or scheduled at runtime by administrators as a
+//! [`MaintenanceWindow`]. Windows are stored in the database, so they survive restarts.
+//!
+//! A window can also freeze only some features of the website (e.g. record submissions), identified by the text ids of the route
+//! groups implementing them. Requests to frozen routes are rejected with a [`CoreError::FeatureFrozen`] error instead.
+
+use crate::{
+    cache::{invalidate, CacheRegion, Reloadable},
+    error::{CoreError, Result},
+};
+use chrono::{DateTime, Utc};
+use futures::future::BoxFuture;
+use log::info;
+use serde::{Deserialize, Serialize};
+use sqlx::{PgConnection, Pool, Postgres};
+use std::{
+    collections::{BTreeSet, HashMap},
+    sync::{Arc, RwLock},
+};
+use unic_langid::subtags::Language;
+
+/// The region invalidated whenever the scheduled maintenance window changes, causing all instances to reload it
+pub const MAINTENANCE: CacheRegion = CacheRegion::new("maintenance");
+
+/// A (possibly open ended) period of time during which the website is in read-only maintenance mode
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MaintenanceWindow {
+    /// The start of the window, or [`None`] if the window started as soon as it was scheduled
+    #[serde(default)]
+    pub starts_at: Option<DateTime<Utc>>,
+
+    /// The end of the window, or [`None`] if the window lasts until it is removed
+    #[serde(default)]
+    pub ends_at: Option<DateTime<Utc>>,
+
+    /// The message displayed to users before and during the window, keyed by language (e.g. `en`)
+    #[serde(default)]
+    pub messages: HashMap<String, String>,
+
+    /// The text ids of the route groups frozen during this window. If empty, all mutating requests are rejected.
+    #[serde(default)]
+    pub frozen: BTreeSet<String>,
+}
+
+impl MaintenanceWindow {
+    pub fn is_active_at(&self, time: DateTime<Utc>) -> bool {
+        self.starts_at.is_none_or(|starts_at| starts_at <= time) && self.ends_at.is_none_or(|ends_at| time < ends_at)
+    }
+
+    /// Whether this window puts the entire website into read-only mode, instead of freezing only some features
+    pub fn is_read_only(&self) -> bool {
+        self.frozen.is_empty()
+    }
+
+    pub fn is_upcoming_at(&self, time: DateTime<Utc>) -> bool {
+        self.starts_at.is_some_and(|starts_at| time < starts_at)
+    }
+
+    /// The message for the given language, or for the given fallback language if no message in the former exists
+    ///
+    /// If neither language has a message, the message of the alphabetically first language is used, as some message is
+    /// still better than none.
+    pub fn message(&self, language: Language, fallback: Language) -> Option<&str> {
+        self.messages
+            .get(language.as_str())
+            .or_else(|| self.messages.get(fallback.as_str()))
+            .or_else(|| {
+                self.messages
+                    .iter()
+                    .min_by_key(|(language, _)| *language)
+                    .map(|(_, message)| message)
+            })
+            .map(String::as_str)
+    }
+
+    /// Ensures that this window ends in the future and after it starts, and that all messages are keyed by a valid language subtag
+    pub fn validate(&self) -> Result<()> {
+        if let Some(ends_at) = self.ends_at {
+            if ends_at <= Utc::now() || self.starts_at.is_some_and(|starts_at| starts_at >= ends_at) {
+                return Err(CoreError::UnprocessableEntity);
+            }
+        }
+
+        for (language, message) in &self.messages {
+            if language.parse::<Language>().map_or(true, |parsed| parsed.as_str() != language) || message.trim().is_empty() {
+                return Err(CoreError::UnprocessableEntity);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Loads the currently scheduled maintenance window from the database
+    pub async fn load(connection: &mut PgConnection) -> Result<Option<MaintenanceWindow>> {
+        let Some(row) = sqlx::query!("SELECT starts_at, ends_at, frozen FROM maintenance_window")
+            .fetch_optional(&mut *connection)
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        let messages = sqlx::query!("SELECT language, message FROM maintenance_messages")
+            .fetch_all(&mut *connection)
+            .await?
+            .into_iter()
+            .map(|row| (row.language, row.message))
+            .collect();
+
+        Ok(Some(MaintenanceWindow {
+            starts_at: row.starts_at,
+            ends_at: row.ends_at,
+            messages,
+            frozen: row.frozen.into_iter().collect(),
+        }))
+    }
+
+    /// Replaces the currently scheduled maintenance window (if any) with this one. Must be called inside a transaction.
+    pub async fn store(&self, connection: &mut PgConnection) -> Result<()> {
+        self.validate()?;
+
+        MaintenanceWindow::clear(&mut *connection).await?;
+
+        let frozen = self.frozen.iter().cloned().collect::<Vec<_>>();
+
+        sqlx::query!(
+            "INSERT INTO maintenance_window (starts_at, ends_at, frozen) VALUES ($1, $2, $3)",
+            self.starts_at,
+            self.ends_at,
+            &frozen
+        )
+        .execute(&mut *connection)
+        .await?;
+
+        let (languages, messages): (Vec<_>, Vec<_>) = self.messages.iter().map(|(l, m)| (l.clone(), m.clone())).unzip();
+
+        sqlx::query!(
+            "INSERT INTO maintenance_messages (language, message) SELECT * FROM UNNEST($1::TEXT[], $2::TEXT[])",
+            &languages,
+            &messages
+        )
+        .execute(&mut *connection)
+        .await?;
+
+        info!("Scheduled maintenance window from {:?} to {:?}", self.starts_at, self.ends_at);
+
+        Ok(())
+    }
+
+    /// Removes the currently scheduled maintenance window, returning whether there was one
+    pub async fn clear(connection: &mut PgConnection) -> Result<bool> {
+        sqlx::query!("DELETE FROM maintenance_messages").execute(&mut *connection).await?;
+
+        let removed = sqlx::query!("DELETE FROM maintenance_window").execute(&mut *connection).await?;
+
+        invalidate(MAINTENANCE, connection).await?;
+
+        Ok(removed.rows_affected() > 0)
+    }
+}
+
+/// The state of maintenance mode, as reported to administrators
+#[derive(Debug, Serialize)]
+pub struct MaintenanceStatus {
+    /// Whether maintenance mode was forced at startup, independently of any scheduled window
+    pub forced: bool,
+
+    /// Whether some or all mutating requests are currently rejected
+    pub active: bool,
+
+    pub window: Option<MaintenanceWindow>,
+}
+
+/// In-memory copy of the maintenance configuration, consulted on every request
+///
+/// Changes to the scheduled window only take effect after calling [`Maintenance::reload`], which happens on all instances once
+/// the [`MAINTENANCE`] region is invalidated. The window is shared between all clones.
+#[derive(Debug, Default, Clone)]
+pub struct Maintenance {
+    forced: bool,
+    window: Arc<RwLock<Option<MaintenanceWindow>>>,
+}
+
+impl Maintenance {
+    /// Creates a new maintenance state. If `forced` is true, maintenance mode is active regardless of the scheduled window.
+    pub fn new(forced: bool) -> Self {
+        Maintenance {
+            forced,
+            window: Arc::default(),
+        }
+    }
+
+    pub fn window(&self) -> Option<MaintenanceWindow> {
+        self.window.read().unwrap().clone()
+    }
+
+    /// Whether some or all mutating requests are currently rejected
+    pub fn is_active(&self) -> bool {
+        self.forced || self.active_window_is(|_| true)
+    }
+
+    /// Whether all mutating requests are currently rejected
+    pub fn is_read_only(&self) -> bool {
+        self.forced || self.active_window_is(MaintenanceWindow::is_read_only)
+    }
+
+    /// Whether requests to the route group with the given text id are currently rejected
+    pub fn is_frozen(&self, group: &str) -> bool {
+        self.forced || self.active_window_is(|window| window.is_read_only() || window.frozen.contains(group))
+    }
+
+    /// Whether there is a currently active window satisfying the given predicate
+    fn active_window_is(&self, predicate: impl FnOnce(&MaintenanceWindow) -> bool) -> bool {
+        self.window
+            .read()
+            .unwrap()
+            .as_ref()
+            .filter(|window| window.is_active_at(Utc::now()))
+            .is_some_and(predicate)
+    }
+
+    pub fn status(&self) -> MaintenanceStatus {
+        MaintenanceStatus {
+            forced: self.forced,
+            active: self.is_active(),
+            window: self.window(),
+        }
+    }
+
+    /// Reloads the scheduled maintenance window from the database
+    pub async fn reload(&self, connection: &mut PgConnection) -> Result<()> {
+        let window = MaintenanceWindow::load(connection).await?;
+
+        *self.window.write().unwrap() = window;
+
+        Ok(())
+    }
+}
+
+impl Reloadable for Maintenance {
+    fn reload<'a>(&'a self, pool: &'a Pool<Postgres>) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move { Maintenance::reload(self, &mut *pool.acquire().await?).await })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Maintenance, MaintenanceWindow};
+    use chrono::{Duration, Utc};
+    use std::{
+        collections::{BTreeSet, HashMap},
+        sync::{Arc, RwLock},
+    };
+    use unic_langid::subtags::Language;
+
+    #[test]
+    fn test_window_activity() {
+        let now = Utc::now();
+        let window = MaintenanceWindow {
+            starts_at: Some(now + Duration::hours(1)),
+            ends_at: Some(now + Duration::hours(2)),
+            ..Default::default()
+        };
+
+        assert!(!window.is_active_at(now));
+        assert!(window.is_upcoming_at(now));
+        assert!(window.is_active_at(now + Duration::hours(1)));
+        assert!(!window.is_active_at(now + Duration::hours(2)));
+        assert!(MaintenanceWindow::default().is_active_at(now));
+    }
+
+    #[test]
+    fn test_validate() {
+        let now = Utc::now();
+        let messages = HashMap::from([("en".to_string(), "Migrating the database".to_string())]);
+
+        assert!(MaintenanceWindow {
+            ends_at: Some(now - Duration::hours(1)),
+            ..Default::default()
+        }
+        .validate()
+        .is_err());
+        assert!(MaintenanceWindow {
+            starts_at: Some(now + Duration::hours(2)),
+            ends_at: Some(now + Duration::hours(1)),
+            ..Default::default()
+        }
+        .validate()
+        .is_err());
+        assert!(MaintenanceWindow {
+            messages: HashMap::from([("en-US".to_string(), "Migrating the database".to_string())]),
+            ..Default::default()
+        }
+        .validate()
+        .is_err());
+
+        let window = MaintenanceWindow {
+            ends_at: Some(now + Duration::hours(1)),
+            messages,
+            ..Default::default()
+        };
+
+        assert!(window.validate().is_ok());
+        assert_eq!(
+            window.message("ru".parse::<Language>().unwrap(), "en".parse().unwrap()),
+            Some("Migrating the database")
+        );
+    }
+
+    #[test]
+    fn test_freezes() {
+        let maintenance = Maintenance {
+            forced: false,
+            window: Arc::new(RwLock::new(Some(MaintenanceWindow {
+                frozen: BTreeSet::from(["route-group-claims".to_string()]),
+                ..Default::default()
+            }))),
+        };
+
+        assert!(maintenance.is_active());
+        assert!(!maintenance.is_read_only());
+        assert!(maintenance.is_frozen("route-group-claims"));
+        assert!(!maintenance.is_frozen("route-group-registrations"));
+
+        let forced = Maintenance::new(true);
+
+        assert!(forced.is_read_only());
+        assert!(forced.is_frozen("route-group-registrations"));
+    }
+}
@@ -1,8 +1,17 @@
-use std::fmt::{Debug, Display};
-
-use crate::{error::CoreError, util::non_nullable};
-use serde::{de::Error, Deserialize, Serialize};
-use sqlx::PgConnection;
+use std::{
+    fmt::{Debug, Display},
+    str::FromStr,
+};
+
+use crate::{config, error::CoreError, util::non_nullable};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use hmac::{Hmac, Mac};
+use serde::{
+    de::{DeserializeOwned, Error},
+    Deserialize, Serialize,
+};
+use sha2::Sha256;
+use sqlx::{postgres::PgArguments, query::Query, PgConnection, Postgres};
 
 /// The maximal number of entries that can be requested per page via the `limit` parameter.
 pub const ENTRIES_PER_PAGE: i32 = 100;
@@ -12,7 +21,7 @@ pub const ENTRIES_PER_PAGE: i32 = 100;
 /// Try not to directly rely on this constant, and instead use `PaginationParameters::default()`
 pub const DEFAULT_ENTRIES_PER_PAGE: i32 = 50;
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone, Copy)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
 pub struct PaginationParameters {
     #[serde(default, deserialize_with = "from_str_non_nullable")]
     pub before: Option<i32>,
@@ -20,12 +29,23 @@ pub struct PaginationParameters {
     #[serde(default, deserialize_with = "from_str_non_nullable")]
     pub after: Option<i32>,
 
+    /// Position inside a listing that is sorted by something other than [`Paginatable::pagination_id`].
+    ///
+    /// Mutually exclusive with `before` and `after`. See also [`Paginatable::keyset_paginated`].
+    #[serde(default, deserialize_with = "from_str_non_nullable")]
+    pub cursor: Option<Cursor>,
+
     #[serde(
         default = "default_limit",
         deserialize_with = "from_str",
         skip_serializing_if = "is_default_entries_per_page"
     )]
     pub limit: i32,
+
+    /// Whether the total number of objects matching the query (ignoring `before`, `after`, `cursor` and `limit`) should be reported
+    /// in the `X-Total-Count` header. Opt-in, as counting requires an additional, potentially expensive, query.
+    #[serde(default, deserialize_with = "from_str", skip_serializing_if = "is_false")]
+    pub count: bool,
 }
 
 impl Default for PaginationParameters {
@@ -33,7 +53,9 @@ impl Default for PaginationParameters {
         Self {
             before: None,
             after: None,
+            cursor: None,
             limit: DEFAULT_ENTRIES_PER_PAGE,
+            count: false,
         }
     }
 }
@@ -50,16 +72,185 @@ impl PaginationParameters {
             }
         }
 
+        if self.cursor.is_some() && (self.before.is_some() || self.after.is_some()) {
+            return Err(CoreError::MutuallyExclusive);
+        }
+
         Ok(())
     }
 
     pub fn order(&self) -> &'static str {
-        if self.after.is_none() && self.before.is_some() {
-            "DESC"
-        } else {
-            "ASC"
+        match self.cursor {
+            Some(ref cursor) if cursor.direction() == CursorDirection::Before => "DESC",
+            Some(_) => "ASC",
+            None if self.after.is_none() && self.before.is_some() => "DESC",
+            None => "ASC",
+        }
+    }
+
+    /// Computes the parameters with which to [fetch](Paginatable::fetch) a single object to determine whether objects exist beyond the
+    /// end and before the start of a page requested with these parameters (with "start" being the bound from which pagination
+    /// starts, e.g. `after` for ascending pages). A probe is only needed for bounds that are actually set, as a page starting at an unset
+    /// bound starts at the very beginning (or end) of the listing.
+    ///
+    /// The probe beyond the end is only needed if the page was not cut short by the `limit`, and there exists a bound
+    /// beyond it (e.g. if both `before` and `after` are set).
+    fn probes(&self) -> (Option<PaginationParameters>, Option<PaginationParameters>) {
+        let probe = |before, after, cursor| PaginationParameters {
+            before,
+            after,
+            cursor,
+            limit: 1,
+            count: false,
+        };
+
+        if let Some(ref cursor) = self.cursor {
+            // A keyless cursor describes the first or last page, for which no objects exist before its start
+            let beyond_start = cursor.key.as_ref().map(|_| probe(None, None, Some(cursor.reversed())));
+
+            return (None, beyond_start);
+        }
+
+        match (self.before, self.after) {
+            (None, None) => (None, None),
+            (Some(before), None) => (None, Some(probe(None, Some(before.saturating_sub(1)), None))),
+            (None, Some(after)) => (None, Some(probe(Some(after.saturating_add(1)), None, None))),
+            (Some(before), Some(after)) => (
+                Some(probe(None, Some(before.saturating_sub(1)), None)),
+                Some(probe(Some(after.saturating_add(1)), None, None)),
+            ),
+        }
+    }
+}
+
+/// The direction in which a [`Cursor`] continues a listing, relative to the [`SortKey`] it encodes.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum CursorDirection {
+    /// Objects sorted before the cursor's key are requested. If the cursor has no key, the last page is requested.
+    Before,
+
+    /// Objects sorted after the cursor's key are requested. If the cursor has no key, the first page is requested.
+    After,
+}
+
+/// The value of the (possibly composite) key by which an object is sorted, for example `(score, id)`.
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct SortKey(String);
+
+impl SortKey {
+    pub fn new<K: Serialize>(key: &K) -> Self {
+        // Sort keys are tuples of numbers and strings, which always serialize successfully
+        SortKey(serde_json::to_string(key).expect("sort key failed to serialize"))
+    }
+}
+
+/// Opaque token describing a position inside a listing that is paginated over a [`SortKey`] (so called "keyset pagination").
+///
+/// Cursors are only ever generated by the server (as part of the `Links` header), and are signed so that clients cannot forge
+/// them. Each cursor is bound to the listing it was generated for (e.g. the endpoint together with all filters), and is
+/// rejected by any other listing.
+///
+/// The textual representation is `<payload>.<signature>`, both encoded as URL-safe base64.
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct Cursor {
+    direction: CursorDirection,
+    key: Option<SortKey>,
+    payload: String,
+    signature: String,
+}
+
+impl Cursor {
+    /// Creates a new cursor pointing before/after the given key, valid only for the listing identified by `binding`
+    pub fn new(direction: CursorDirection, key: Option<SortKey>, binding: &str) -> Self {
+        let payload = format!(
+            "[{},{}]",
+            serde_json::to_string(&direction).expect("cursor direction failed to serialize"),
+            key.as_ref().map(|key| key.0.as_str()).unwrap_or("null")
+        );
+        let payload = URL_SAFE_NO_PAD.encode(payload);
+        let signature = URL_SAFE_NO_PAD.encode(Self::mac(&payload, binding).finalize().into_bytes());
+
+        Cursor {
+            direction,
+            key,
+            payload,
+            signature,
         }
     }
+
+    /// Checks that this cursor was generated by this server for the listing identified by `binding`
+    pub fn verify(&self, binding: &str) -> Result<(), CoreError> {
+        let signature = URL_SAFE_NO_PAD
+            .decode(&self.signature)
+            .map_err(|_| CoreError::InvalidPaginationCursor)?;
+
+        Self::mac(&self.payload, binding)
+            .verify_slice(&signature)
+            .map_err(|_| CoreError::InvalidPaginationCursor)
+    }
+
+    pub fn direction(&self) -> CursorDirection {
+        self.direction
+    }
+
+    /// Returns an (unsigned) cursor pointing to the other side of this cursor's key. Only meant for internal queries, as
+    /// clients will not be able to use it
+    fn reversed(&self) -> Cursor {
+        Cursor {
+            direction: match self.direction {
+                CursorDirection::Before => CursorDirection::After,
+                CursorDirection::After => CursorDirection::Before,
+            },
+            key: self.key.clone(),
+            payload: String::new(),
+            signature: String::new(),
+        }
+    }
+
+    /// Decodes the sort key this cursor points to. Only call this after [`Cursor::verify`] succeeded
+    pub fn key<K: DeserializeOwned>(&self) -> Result<Option<K>, serde_json::Error> {
+        self.key.as_ref().map(|key| serde_json::from_str(&key.0)).transpose()
+    }
+
+    fn mac(payload: &str, binding: &str) -> Hmac<Sha256> {
+        let mut mac = Hmac::<Sha256>::new_from_slice(config::secret()).expect("HMAC accepts keys of any length");
+
+        mac.update(binding.as_bytes());
+        mac.update(b"\0");
+        mac.update(payload.as_bytes());
+        mac
+    }
+}
+
+impl FromStr for Cursor {
+    type Err = CoreError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (payload, signature) = s.split_once('.').ok_or(CoreError::InvalidPaginationCursor)?;
+        let decoded = URL_SAFE_NO_PAD.decode(payload).map_err(|_| CoreError::InvalidPaginationCursor)?;
+        let (direction, key) = serde_json::from_slice::<(CursorDirection, Option<serde_json::Value>)>(&decoded)
+            .map_err(|_| CoreError::InvalidPaginationCursor)?;
+
+        Ok(Cursor {
+            direction,
+            key: key.as_ref().map(SortKey::new),
+            payload: payload.to_string(),
+            signature: signature.to_string(),
+        })
+    }
+}
+
+impl Display for Cursor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}", self.payload, self.signature)
+    }
+}
+
+impl Serialize for Cursor {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
 }
 
 /// Enum describing what is going on "around" a page returned by [`Pagination::page`].
@@ -111,89 +302,121 @@ impl PageContext {
 pub trait PaginationQuery: Serialize + Debug {
     fn parameters(&self) -> PaginationParameters;
     fn with_parameters(&self, parameters: PaginationParameters) -> Self;
+
+    /// Binds the filters of this query to the given SQL query, in the order in which the SQL query expects them.
+    ///
+    /// By convention, the SQL queries used by [`Paginatable::fetch`] and [`Paginatable::count`] take the two parameters describing the page's
+    /// bounds as `$1` and `$2`, followed by the filters starting at `$3`, and the limit as their last parameter. Implementing this method thus
+    /// allows both to share the same binding code. Queries without any filters can rely on the default implementation, which binds nothing.
+    fn bind_filters<'q>(&'q self, sql_query: Query<'q, Postgres, PgArguments>) -> Query<'q, Postgres, PgArguments> {
+        sql_query
+    }
 }
 
 #[allow(async_fn_in_trait)]
 pub trait Paginatable<Q: PaginationQuery>: Serialize + Sized {
-    /// Returns a page of objects matching the query described by tthe given [`PaginationQuery`].
+    /// Retrieves the objects matching the query described by the given [`PaginationQuery`], including the `before`
+    /// and `after` bounds of its [`PaginationParameters`].
     ///
     /// The returned list of objects must have the following properties:
-    /// - They are sorted in ascending order according to the value of [`pagination_id`].
+    /// - They are sorted according to the value of [`pagination_id`](Paginatable::pagination_id), in the order given by
+    ///   [`PaginationParameters::order`] (e.g. descending if only `before` is set).
     /// - Their ids are consecutive, meaning if the object at index `i` in the list has ID `a`, and
     ///   the object at index `i + 1` has id `b`, then there exists no object also matching all conditions
-    ///   of this `Pagination` in the _database_ with an ID `c` such that `a < c < b`.
-    /// - If the `after` parameter of the query's associated [`PaginationParameters`] is set, then the first
-    ///   object in the returned list must have the smallest ID out of all objects matching the given
-    ///   query greater than `after`.
-    /// - If the `after` parameter is not set, but `before` is, then the last object in the returned
-    ///   list must have the greatest ID out of all objects matching the given query smaller than
-    ///   `before`.
+    ///   of the query in the _database_ with an ID `c` between `a` and `b`.
+    /// - The first object is the one closest to the bound the list starts at, e.g. if only `before` is set, it must
+    ///   have the greatest ID out of all objects matching the given query smaller than `before`.
+    ///
+    /// The number of items in the returned `Vec` must not exceed [`PaginationParameters::limit`] plus one. The additional
+    /// object is used to detect whether there are further objects beyond the requested page.
+    async fn fetch(query: &Q, connection: &mut PgConnection) -> Result<Vec<Self>, sqlx::Error>;
+
+    /// Returns a page of objects matching the query described by the given [`PaginationQuery`], sorted in ascending order
+    /// according to the value of [`pagination_id`](Paginatable::pagination_id).
     ///
-    /// The returned [`PageContext`] should describe whether more pages surrounding this page exist which
-    /// match all conditions of this [`Pagination`] object, with the exception of the `before` and `after` fields!
-    /// HOWEVER, if both `before` and `after` are set, then it should be [`PageContext::Standalone`].
+    /// The returned [`PageContext`] describes whether more objects surrounding this page exist which
+    /// match all conditions of the query, with the exception of the `before` and `after` fields. It is determined by
+    /// [fetching](Paginatable::fetch) one additional object in the direction of pagination, and probing for an object
+    /// on each side of the page whose bounds this extra object does not already tell us about.
     ///
-    /// The number of items in the returned `Vec` must not exceed [`PaginationParameters::limit`].
-    async fn page(query: &Q, connection: &mut PgConnection) -> Result<(Vec<Self>, PageContext), sqlx::Error>;
+    /// The number of items in the returned `Vec` does not exceed [`PaginationParameters::limit`].
+    async fn page(query: &Q, connection: &mut PgConnection) -> Result<(Vec<Self>, PageContext), sqlx::Error> {
+        let params = query.parameters();
+        let descending = params.order() == "DESC";
 
-    async fn first_and_last(connection: &mut PgConnection) -> Result<Option<(i32, i32)>, sqlx::Error>;
+        let mut objects = Self::fetch(query, &mut *connection).await?;
 
-    fn pagination_id(&self) -> i32;
-}
+        let has_followup_page = objects.len() > params.limit as usize;
 
-/// Historically, pointercrate has been determining whether a new page exists by simply incrementing the "limit" parameter
-/// by one, and seeing if we can get one extra object from the database. This object was then popped from the results,
-/// and its presence indicated that "another page in the same direction" existed - e.g. if `after` was specified, it
-/// meant that a "next" should be generated, and if `before` was specified (but not after), it meant that a "prev" should
-/// be generated. While this logic is correct, it should never have leaked outside of the `page` implementation and into
-/// the actual pagination API.
-///
-/// Additionally, pointercrate assumes that a previous page exists whenever "after" is set, and that a next page exists
-/// whenever "previous" is set (but that we have a standalong page if _both_ are set). This is not sound (we should really be
-/// trying to find an "extra" object at the other end of the list), but fixing this would require a bigger refractor than I am
-/// willing to do at the time of writing this.
-///
-/// Lastly, pointercrate used to return the object list in reverse if `before` but not `after` was set, and left it up
-/// to the caller to reverse it. That, too, is an implementation detail that should never become API.
-///
-/// This compat function tries to fix these up as best as it can - it reverses the given list of objects if needed, and translates
-/// the "extra" object into a `PageContext`. It doesn't solve the second point though.
-#[doc(hidden)]
-pub fn __pagination_compat<T>(params: &PaginationParameters, mut objects: Vec<T>) -> (Vec<T>, PageContext) {
-    let has_followup_page = objects.len() > params.limit as usize;
+        if has_followup_page {
+            objects.pop();
+        }
+
+        if descending {
+            objects.reverse();
+        }
+
+        let (beyond_end, beyond_start) = params.probes();
 
-    if has_followup_page {
-        objects.pop();
+        let has_more_beyond_end = match beyond_end {
+            Some(probe) if !has_followup_page => !Self::fetch(&query.with_parameters(probe), &mut *connection).await?.is_empty(),
+            _ => has_followup_page,
+        };
+
+        let has_more_beyond_start = match beyond_start {
+            Some(probe) => !Self::fetch(&query.with_parameters(probe), &mut *connection).await?.is_empty(),
+            None => false,
+        };
+
+        let (has_previous, has_next) = if descending {
+            (has_more_beyond_end, has_more_beyond_start)
+        } else {
+            (has_more_beyond_start, has_more_beyond_end)
+        };
+
+        let ctx = match (has_previous, has_next) {
+            (false, false) => PageContext::Standalone,
+            (true, false) => PageContext::HasPrevious,
+            (false, true) => PageContext::HasNext,
+            (true, true) => PageContext::HasPreviousAndNext,
+        };
+
+        Ok((objects, ctx))
     }
 
-    let ctx = match (params.before, params.after) {
-        (Some(_), None) => {
-            objects.reverse();
+    /// Counts all objects matching the query described by the given [`PaginationQuery`], ignoring the `before`, `after`, `cursor`
+    /// and `limit` fields of its [`PaginationParameters`].
+    ///
+    /// Usually implemented by wrapping the SQL query used by [`fetch`](Paginatable::fetch) via [`count_query`].
+    async fn count(query: &Q, connection: &mut PgConnection) -> Result<i64, sqlx::Error>;
 
-            if has_followup_page {
-                PageContext::HasPreviousAndNext
-            } else {
-                PageContext::HasNext
-            }
-        },
-        (None, Some(_)) => {
-            if has_followup_page {
-                PageContext::HasPreviousAndNext
-            } else {
-                PageContext::HasPrevious
-            }
-        },
-        (Some(_), Some(_)) => PageContext::Standalone,
-        (None, None) => {
-            if has_followup_page {
-                PageContext::HasNext
-            } else {
-                PageContext::Standalone
-            }
-        },
-    };
+    async fn first_and_last(connection: &mut PgConnection) -> Result<Option<(i32, i32)>, sqlx::Error>;
 
-    (objects, ctx)
+    fn pagination_id(&self) -> i32;
+
+    /// Whether the given query sorts objects by something other than their [`pagination_id`](Paginatable::pagination_id).
+    ///
+    /// Such listings are paginated using the `cursor` parameter instead of `before` and `after`, with the cursors encoding the
+    /// [`SortKey`] returned by [`sort_key`](Paginatable::sort_key). In this case, all requirements listed on [`fetch`](Paginatable::fetch)
+    /// apply with "ID" replaced by "sort key", and with the cursor's [`CursorDirection`] taking the place of `before`/`after`.
+    fn keyset_paginated(_query: &Q) -> bool {
+        false
+    }
+
+    /// Returns the key by which this object is sorted under the given query. Only relevant if
+    /// [`keyset_paginated`](Paginatable::keyset_paginated) returns `true` for the query.
+    fn sort_key(&self, _query: &Q) -> SortKey {
+        SortKey::new(&self.pagination_id())
+    }
+}
+
+/// Turns the given SQL query, as used by an implementation of [`Paginatable::fetch`], into one counting all rows it matches.
+///
+/// When executing the resulting query, the parameters corresponding to `before`, `after` and the query's `LIMIT` should be bound to `NULL`
+/// (`LIMIT NULL` is equivalent to omitting the `LIMIT` clause).
+pub fn count_query(sql_query: &str) -> String {
+    // The newlines make sure that a trailing comment in the original query cannot comment out the closing parenthesis
+    format!("SELECT COUNT(*) FROM (\n{}\n) AS matching", sql_query)
 }
 
 #[macro_export]
@@ -224,6 +447,10 @@ const fn is_default_entries_per_page(limit: &i32) -> bool {
     *limit == DEFAULT_ENTRIES_PER_PAGE
 }
 
+const fn is_false(value: &bool) -> bool {
+    !*value
+}
+
 // Helper function needed because serde's flatten attribute does not work with non-self describing data formats (such as url-encoding) - it thinks everything is a string.
 // See also https://github.com/nox/serde_urlencoded/issues/33
 fn from_str<'de, D, S>(deserializer: D) -> Result<S, D::Error>
@@ -1,7 +1,18 @@
-use crate::error::CoreError;
+use crate::{
+    cache::{CacheRegion, Reloadable},
+    error::CoreError,
+};
 use derive_more::Display;
+use futures::future::BoxFuture;
 use serde::Serialize;
-use std::collections::{HashMap, HashSet};
+use sqlx::{PgConnection, Pool, Postgres};
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    sync::{Arc, Mutex, PoisonError, RwLock, RwLockReadGuard},
+};
+
+/// The region invalidated whenever roles, or the relations between them, change, causing all instances to reload their roles
+pub const ROLES: CacheRegion = CacheRegion::new("roles");
 
 #[derive(Serialize, Debug, Display, Eq, PartialEq, Clone, Copy, Hash)]
 #[serde(transparent)]
@@ -92,6 +103,21 @@ pub struct PermissionsManager {
     permissions: HashSet<Permission>,
     implication_map: HashMap<Permission, HashSet<Permission>>,
     assignable_map: HashMap<Permission, HashSet<Permission>>,
+
+    /// Roles (and relations between them) defined in the database in addition to the above. Shared between all clones, so that
+    /// changes made via [`PermissionsManager::reload`] are visible to every request.
+    roles: Arc<RwLock<DatabaseRoles>>,
+}
+
+/// Roles, and relations between roles, that are stored in the database instead of being hardcoded.
+///
+/// Relations stored here are in addition to the ones defined in code, meaning they can extend, but never restrict, the
+/// permission graph.
+#[derive(Default)]
+struct DatabaseRoles {
+    roles: HashSet<Permission>,
+    implication_map: HashMap<Permission, HashSet<Permission>>,
+    assignable_map: HashMap<Permission, HashSet<Permission>>,
 }
 
 impl PermissionsManager {
@@ -106,30 +132,41 @@ impl PermissionsManager {
             permissions: permission_set,
             implication_map: HashMap::new(),
             assignable_map: HashMap::new(),
+            roles: Arc::default(),
         }
     }
 
-    pub fn merge_with(&mut self, other: PermissionsManager) {
+    /// Merges the permissions, and relations between them, defined by another manager into this one
+    ///
+    /// Fails if the two managers define different permissions with the same bit or the same text id. Relations defined by both
+    /// managers for the same permission are combined.
+    pub fn merge_with(&mut self, other: PermissionsManager) -> Result<(), PermissionsError> {
         for new_permission in &other.permissions {
             if let Some(conflict) = self
                 .permissions
                 .iter()
-                .find(|&p| p.bit() == new_permission.bit() && p != new_permission)
+                .find(|&p| p != new_permission && (p.bit() == new_permission.bit() || p.text_id() == new_permission.text_id()))
             {
-                panic!(
-                    "Cannot merge permission managers, conflicting permissions {} and {}",
-                    conflict, new_permission
-                )
+                return Err(PermissionsError::Conflict {
+                    first: *conflict,
+                    second: *new_permission,
+                });
             }
         }
 
         self.permissions.extend(other.permissions);
-        self.implication_map.extend(other.implication_map);
-        self.assignable_map.extend(other.assignable_map);
+
+        for (perm, implied) in other.implication_map {
+            self.implication_map.entry(perm).or_default().extend(implied);
+        }
+
+        for (perm, assignable) in other.assignable_map {
+            self.assignable_map.entry(perm).or_default().extend(assignable);
+        }
+
+        Ok(())
     }
 
-    // we should probably verify that added permissions are all part of what was in
-    // the constructor but whatever
     pub fn assigns(mut self, perm1: Permission, perm2: Permission) -> Self {
         self.assignable_map.entry(perm1).or_default().insert(perm2);
         self
@@ -140,13 +177,132 @@ impl PermissionsManager {
         self
     }
 
+    /// Verifies that the permission graph defined in code is well-formed
+    ///
+    /// This means that all permissions used in implication or assignment relations were passed to
+    /// [`PermissionsManager::new`], and that no permission (transitively) implies itself.
+    pub fn validate(&self) -> Result<(), PermissionsError> {
+        for (perm, related) in self.implication_map.iter().chain(&self.assignable_map) {
+            for permission in std::iter::once(perm).chain(related) {
+                if !self.permissions.contains(permission) {
+                    return Err(PermissionsError::UnknownPermission { permission: *permission });
+                }
+            }
+        }
+
+        // Depth first search, where `path` is the chain of implications leading to the permission currently being visited
+        fn find_cycle(
+            manager: &PermissionsManager, perm: Permission, path: &mut Vec<Permission>, done: &mut HashSet<Permission>,
+        ) -> Option<Vec<Permission>> {
+            if let Some(start) = path.iter().position(|&p| p == perm) {
+                let mut cycle = path[start..].to_vec();
+                cycle.push(perm);
+
+                return Some(cycle);
+            }
+
+            if done.contains(&perm) {
+                return None;
+            }
+
+            path.push(perm);
+
+            for &implied in manager.implication_map.get(&perm).into_iter().flatten() {
+                if let Some(cycle) = find_cycle(manager, implied, path, done) {
+                    return Some(cycle);
+                }
+            }
+
+            path.pop();
+            done.insert(perm);
+
+            None
+        }
+
+        // Iterate in a fixed order, so that the same cycle is always reported the same way
+        let mut permissions = self.permissions.iter().copied().collect::<Vec<_>>();
+        let mut done = HashSet::new();
+
+        permissions.sort_unstable_by_key(Permission::bit);
+
+        for perm in permissions {
+            if let Some(cycle) = find_cycle(self, perm, &mut Vec::new(), &mut done) {
+                return Err(PermissionsError::ImplicationCycle { cycle });
+            }
+        }
+
+        Ok(())
+    }
+
+    fn database_roles(&self) -> RwLockReadGuard<'_, DatabaseRoles> {
+        self.roles.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// All permissions known to this manager, both the ones defined in code and the ones defined in the database
+    pub fn permissions(&self) -> HashSet<Permission> {
+        self.permissions.union(&self.database_roles().roles).copied().collect()
+    }
+
+    /// Looks up a permission (or database-defined role) by its text id
+    pub fn role(&self, name: &str) -> Option<Permission> {
+        self.permissions().into_iter().find(|perm| perm.text_id() == name)
+    }
+
+    /// Describes the full permission graph, including roles defined in the database, ordered by text id
+    pub fn describe(&self) -> Vec<PermissionDescription> {
+        let sorted = |permissions: HashSet<Permission>| {
+            let mut text_ids = permissions.into_iter().map(|perm| perm.text_id).collect::<Vec<_>>();
+            text_ids.sort_unstable();
+            text_ids
+        };
+
+        let mut descriptions = self
+            .permissions()
+            .into_iter()
+            .map(|perm| PermissionDescription {
+                text_id: perm.text_id,
+                bit: (perm.bit != 0).then_some(perm.bit),
+                implies: sorted(self.direct_implications(perm)),
+                assigns: sorted(self.direct_assignments(perm)),
+            })
+            .collect::<Vec<_>>();
+
+        descriptions.sort_unstable_by_key(|description| description.text_id);
+        descriptions
+    }
+
+    fn direct_implications(&self, permission: Permission) -> HashSet<Permission> {
+        let roles = self.database_roles();
+
+        self.implication_map
+            .get(&permission)
+            .into_iter()
+            .chain(roles.implication_map.get(&permission))
+            .flatten()
+            .copied()
+            .collect()
+    }
+
+    fn direct_assignments(&self, permission: Permission) -> HashSet<Permission> {
+        let roles = self.database_roles();
+
+        self.assignable_map
+            .get(&permission)
+            .into_iter()
+            .chain(roles.assignable_map.get(&permission))
+            .flatten()
+            .copied()
+            .collect()
+    }
+
     pub fn implied_by(&self, permission: Permission) -> HashSet<Permission> {
         let mut implied = HashSet::new();
-        implied.insert(permission);
+        let mut to_visit = vec![permission];
 
-        if let Some(set) = self.implication_map.get(&permission) {
-            for perm in set {
-                implied.extend(self.implied_by(*perm));
+        // Roles defined in the database can introduce cycles, so we must not visit a permission twice
+        while let Some(perm) = to_visit.pop() {
+            if implied.insert(perm) {
+                to_visit.extend(self.direct_implications(perm));
             }
         }
 
@@ -157,61 +313,224 @@ impl PermissionsManager {
         let mut assignable = HashSet::new();
 
         for perm in self.implied_by(permission) {
-            if let Some(set) = self.assignable_map.get(&perm) {
-                for perm in set {
-                    assignable.insert(*perm);
-                }
-            }
+            assignable.extend(self.direct_assignments(perm));
         }
 
         assignable
     }
 
     pub fn implied_by_bits(&self, permission_bits: u16) -> HashSet<Permission> {
-        let mut implied = HashSet::new();
+        self.implied_for(permission_bits)
+    }
+
+    pub fn assignable_by_bits(&self, permission_bits: u16) -> HashSet<Permission> {
+        self.assignable_for(permission_bits)
+    }
+
+    pub fn bits_to_permissions(&self, bits: u16) -> HashSet<Permission> {
+        let mut perms = HashSet::new();
 
-        for perm in self.bits_to_permissions(permission_bits) {
-            if perm.bit & permission_bits == perm.bit {
-                implied.extend(self.implied_by(perm));
+        for perm in self.permissions() {
+            // Roles defined only in the database have no bit, and are never granted via permission bits
+            if perm.bit() != 0 && perm.bit() & bits == perm.bit() {
+                perms.insert(perm);
             }
         }
 
+        perms
+    }
+
+    /// The permissions directly granted to the given holder, either via permission bits or via roles
+    pub fn granted_to(&self, holder: impl PermissionHolder) -> HashSet<Permission> {
+        let mut granted = self.bits_to_permissions(holder.permission_bits());
+
+        granted.extend(holder.roles().iter().filter_map(|role| self.role(role)));
+        granted
+    }
+
+    /// All permissions the given holder has, including implied ones
+    pub fn implied_for(&self, holder: impl PermissionHolder) -> HashSet<Permission> {
+        let mut implied = HashSet::new();
+
+        for perm in self.granted_to(holder) {
+            implied.extend(self.implied_by(perm));
+        }
+
         implied
     }
 
-    pub fn assignable_by_bits(&self, permission_bits: u16) -> HashSet<Permission> {
+    /// All permissions the given holder can assign to other users
+    pub fn assignable_for(&self, holder: impl PermissionHolder) -> HashSet<Permission> {
         let mut assignable = HashSet::new();
 
-        for perm in self.bits_to_permissions(permission_bits) {
+        for perm in self.granted_to(holder) {
             assignable.extend(self.assignable_by(perm));
         }
 
         assignable
     }
 
-    pub fn bits_to_permissions(&self, bits: u16) -> HashSet<Permission> {
-        let mut perms = HashSet::new();
+    pub fn require_permission(&self, permissions_we_have: impl PermissionHolder, permission_required: Permission) -> Result<(), CoreError> {
+        if !self.implied_for(permissions_we_have).contains(&permission_required) {
+            return Err(CoreError::MissingPermissions {
+                required: permission_required,
+            });
+        }
 
+        Ok(())
+    }
+
+    /// Ensures every permission defined in code has a role of the same name in the database, so that database-defined roles
+    /// can be related to them, and then loads all roles via [`PermissionsManager::reload`].
+    ///
+    /// Users holding these permissions continue to do so via their permission bits, so no further migration is needed. This also
+    /// means a permission cannot be renamed (or its bit reused by a different permission) while the old role still exists in
+    /// the database, as users holding the old role would silently be granted the new one.
+    pub async fn synchronize(&self, connection: &mut PgConnection) -> Result<(), PermissionsError> {
         for perm in &self.permissions {
-            if perm.bit() & bits == perm.bit() {
-                perms.insert(*perm);
+            let holder = sqlx::query_scalar!(
+                "SELECT name FROM roles WHERE bit = $1 AND name <> $2",
+                perm.bit as i32,
+                perm.text_id
+            )
+            .fetch_optional(&mut *connection)
+            .await
+            .map_err(CoreError::from)?;
+
+            if let Some(role) = holder {
+                return Err(PermissionsError::BitTaken { permission: *perm, role });
             }
+
+            sqlx::query!(
+                "INSERT INTO roles (name, bit) VALUES ($1, $2) ON CONFLICT (name) DO UPDATE SET bit = EXCLUDED.bit",
+                perm.text_id,
+                perm.bit as i32
+            )
+            .execute(&mut *connection)
+            .await
+            .map_err(CoreError::from)?;
         }
 
-        perms
+        Ok(self.reload(connection).await?)
     }
 
-    pub fn require_permission(&self, permissions_we_have: u16, permission_required: Permission) -> Result<(), CoreError> {
-        if !self.implied_by_bits(permissions_we_have).contains(&permission_required) {
-            return Err(CoreError::MissingPermissions {
-                required: permission_required,
-            });
+    /// (Re)loads all roles, and the relations between them, from the database
+    pub async fn reload(&self, connection: &mut PgConnection) -> Result<(), CoreError> {
+        let mut database_roles = DatabaseRoles::default();
+        let mut by_name = HashMap::new();
+
+        for row in sqlx::query!("SELECT name, bit FROM roles").fetch_all(&mut *connection).await? {
+            let role = match self.permissions.iter().find(|perm| perm.text_id == row.name) {
+                Some(perm) => *perm,
+                None => Permission::new(intern(&row.name), row.bit.unwrap_or(0) as u16),
+            };
+
+            database_roles.roles.insert(role);
+            by_name.insert(row.name, role);
+        }
+
+        for row in sqlx::query!("SELECT role, implies FROM role_implications")
+            .fetch_all(&mut *connection)
+            .await?
+        {
+            if let (Some(role), Some(implies)) = (by_name.get(&row.role), by_name.get(&row.implies)) {
+                database_roles.implication_map.entry(*role).or_default().insert(*implies);
+            }
+        }
+
+        for row in sqlx::query!("SELECT role, assigns FROM role_assignments")
+            .fetch_all(&mut *connection)
+            .await?
+        {
+            if let (Some(role), Some(assigns)) = (by_name.get(&row.role), by_name.get(&row.assigns)) {
+                database_roles.assignable_map.entry(*role).or_default().insert(*assigns);
+            }
         }
 
+        *self.roles.write().unwrap_or_else(PoisonError::into_inner) = database_roles;
+
         Ok(())
     }
 }
 
+impl Reloadable for PermissionsManager {
+    fn reload<'a>(&'a self, pool: &'a Pool<Postgres>) -> BoxFuture<'a, Result<(), CoreError>> {
+        Box::pin(async move { PermissionsManager::reload(self, &mut *pool.acquire().await?).await })
+    }
+}
+
+/// Error returned if the permission graph defined in code is malformed, or does not match the roles stored in the database.
+/// These are programming (or deployment) errors, and should cause startup to fail.
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+pub enum PermissionsError {
+    #[error("Permission {permission} is used in an implication or assignment, but not known to the permissions manager")]
+    UnknownPermission { permission: Permission },
+    #[error("Implication cycle between permissions: {}", cycle.iter().map(Permission::text_id).collect::<Vec<_>>().join(" -> "))]
+    ImplicationCycle { cycle: Vec<Permission> },
+    #[error("Conflicting permissions {first} (bit {:#x}) and {second} (bit {:#x})", first.bit, second.bit)]
+    Conflict { first: Permission, second: Permission },
+    #[error("Permission {permission} has bit {:#x}, which is already held by role '{role}' in the database. Permissions cannot be renamed, nor can their bits be reused, while the old role exists", permission.bit)]
+    BitTaken { permission: Permission, role: String },
+    #[error(transparent)]
+    Database(#[from] CoreError),
+}
+
+/// A [`Permission`] together with its direct relations to other permissions
+#[derive(Serialize, Debug)]
+pub struct PermissionDescription {
+    pub text_id: &'static str,
+
+    /// The bit via which this permission can be granted. [`None`] for roles defined only in the database
+    pub bit: Option<u16>,
+
+    pub implies: Vec<&'static str>,
+    pub assigns: Vec<&'static str>,
+}
+
+/// Something that can be granted [`Permission`]s, either via permission bits or via roles defined in the database
+pub trait PermissionHolder {
+    fn permission_bits(&self) -> u16;
+
+    /// The names of the database-defined roles held
+    fn roles(&self) -> &[String] {
+        &[]
+    }
+}
+
+impl PermissionHolder for u16 {
+    fn permission_bits(&self) -> u16 {
+        *self
+    }
+}
+
+impl<T: PermissionHolder + ?Sized> PermissionHolder for &T {
+    fn permission_bits(&self) -> u16 {
+        (**self).permission_bits()
+    }
+
+    fn roles(&self) -> &[String] {
+        (**self).roles()
+    }
+}
+
+/// Turns the name of a database-defined role into a `&'static str` usable as the text id of a [`Permission`].
+///
+/// Each distinct name is leaked only once, so memory usage is bounded by the number of roles ever created.
+fn intern(name: &str) -> &'static str {
+    static NAMES: Mutex<BTreeSet<&'static str>> = Mutex::new(BTreeSet::new());
+
+    let mut names = NAMES.lock().unwrap_or_else(PoisonError::into_inner);
+
+    if let Some(interned) = names.get(name) {
+        return interned;
+    }
+
+    let interned: &'static str = Box::leak(name.to_string().into_boxed_str());
+
+    names.insert(interned);
+    interned
+}
+
 #[cfg(test)]
 mod test {
     // copied from https://riptutorial.com/rust/example/4149/create-a-hashset-macro because im lazy as fuck
@@ -227,7 +546,7 @@ mod test {
         };
     }
 
-    use crate::permission::{Permission, PermissionsManager};
+    use crate::permission::{Permission, PermissionsError, PermissionsManager};
     use std::collections::HashSet;
 
     const PERM1: Permission = Permission::new("1", 0x1);
@@ -259,8 +578,58 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_implication_cycle() {
+        let manager = permission_manager().implies(PERM3, PERM1);
+
+        assert_eq!(manager.implied_by(PERM2), set![PERM1, PERM2, PERM3]);
+        assert_eq!(manager.implied_by_bits(0x4), set![PERM1, PERM2, PERM3]);
+    }
+
     #[test]
     fn test_assignment() {
         assert_eq!(permission_manager().assignable_by(PERM4), set![PERM2, PERM5, PERM6]);
     }
+
+    #[test]
+    fn test_validation() {
+        assert_eq!(
+            permission_manager().validate(),
+            Err(PermissionsError::UnknownPermission { permission: PERM6 })
+        );
+
+        let manager = PermissionsManager::new(vec![PERM1, PERM2, PERM3])
+            .implies(PERM1, PERM2)
+            .implies(PERM2, PERM3)
+            .assigns(PERM1, PERM3);
+
+        assert_eq!(manager.validate(), Ok(()));
+        assert_eq!(
+            manager.implies(PERM3, PERM2).validate(),
+            Err(PermissionsError::ImplicationCycle {
+                cycle: vec![PERM2, PERM3, PERM2]
+            })
+        );
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut manager = PermissionsManager::new(vec![PERM1, PERM2]).assigns(PERM1, PERM2);
+
+        manager
+            .merge_with(PermissionsManager::new(vec![PERM1, PERM3]).assigns(PERM1, PERM3))
+            .unwrap();
+
+        assert_eq!(manager.assignable_by(PERM1), set![PERM2, PERM3]);
+
+        let conflicting = Permission::new("conflict", PERM2.bit());
+
+        assert_eq!(
+            manager.merge_with(PermissionsManager::new(vec![conflicting])),
+            Err(PermissionsError::Conflict {
+                first: PERM2,
+                second: conflicting
+            })
+        );
+    }
 }
@@ -1,23 +1,45 @@
-use crate::{config, error::Result};
+use crate::{audit::ServiceAccount, config::CoreConfig, error::Result};
 use log::trace;
 use sqlx::{pool::PoolConnection, postgres::PgPoolOptions, PgConnection, Pool, Postgres, Transaction};
 
 pub struct PointercratePool {
     connection_pool: Pool<Postgres>,
+
+    /// Pool of connections to a read-only replica of the database, if one is configured
+    replica_pool: Option<Pool<Postgres>>,
 }
 
 impl PointercratePool {
+    /// A clone of the pool of connections to the primary database
     pub fn clone_inner(&self) -> Pool<Postgres> {
         self.connection_pool.clone()
     }
 
-    pub async fn init() -> Self {
+    pub async fn init(config: &CoreConfig) -> Self {
+        let options = PgPoolOptions::default()
+            .max_connections(config.database_max_connections)
+            .acquire_timeout(config.database_acquire_timeout)
+            .idle_timeout(config.database_idle_timeout);
+
+        let connection_pool = options
+            .clone()
+            .connect(&config.database_url)
+            .await
+            .expect("Failed to connect to pointercrate database");
+
+        let replica_pool = match config.database_replica_url {
+            Some(ref replica_url) => Some(
+                options
+                    .connect(replica_url)
+                    .await
+                    .expect("Failed to connect to pointercrate database replica"),
+            ),
+            None => None,
+        };
+
         let pool = PointercratePool {
-            connection_pool: PgPoolOptions::default()
-                .max_connections(20)
-                .connect(&config::database_url())
-                .await
-                .expect("Failed to connect to pointercrate database"),
+            connection_pool,
+            replica_pool,
         };
 
         pool.run_migrations().await;
@@ -55,18 +77,43 @@ AND NOT EXISTS (
     }
 
     /// Gets a connection from the connection pool
+    ///
+    /// Changes made through this connection are attributed to [`ServiceAccount::SYSTEM`]
     pub async fn connection(&self) -> Result<PoolConnection<Postgres>> {
+        self.connection_as(ServiceAccount::SYSTEM).await
+    }
+
+    /// Gets a connection from the connection pool, attributing all changes made through it to the given [`ServiceAccount`]
+    pub async fn connection_as(&self, account: ServiceAccount) -> Result<PoolConnection<Postgres>> {
         let mut connection = self.connection_pool.acquire().await?;
 
-        audit_connection(&mut connection, 0).await?;
+        audit_connection(&mut connection, account.id()).await?;
 
         Ok(connection)
     }
 
+    /// Gets a connection for read-only use, from the replica if one is configured and from the primary database otherwise
+    ///
+    /// Since the replica might lag behind the primary database, data read through this connection is not guaranteed to reflect
+    /// changes committed just before. It must thus not be used to fill the [`ResponseCache`](crate::cache::ResponseCache), as
+    /// the stale data would stay cached until the next invalidation. Connections to the replica cannot be written through, and
+    /// as such are not attributed to any [`ServiceAccount`].
+    pub async fn read_connection(&self) -> Result<PoolConnection<Postgres>> {
+        match self.replica_pool {
+            Some(ref replica_pool) => Ok(replica_pool.acquire().await?),
+            None => self.connection().await,
+        }
+    }
+
     pub async fn transaction(&self) -> Result<Transaction<'static, Postgres>> {
+        self.transaction_as(ServiceAccount::SYSTEM).await
+    }
+
+    /// Starts a transaction, attributing all changes made in it to the given [`ServiceAccount`]
+    pub async fn transaction_as(&self, account: ServiceAccount) -> Result<Transaction<'static, Postgres>> {
         let mut connection = self.connection_pool.begin().await?;
 
-        audit_connection(&mut connection, 0).await?;
+        audit_connection(&mut connection, account.id()).await?;
 
         Ok(connection)
     }
@@ -75,10 +122,17 @@ AND NOT EXISTS (
 // Used for integration tests, when sqlx::test sets up a pool for us
 impl From<Pool<Postgres>> for PointercratePool {
     fn from(connection_pool: Pool<Postgres>) -> Self {
-        PointercratePool { connection_pool }
+        PointercratePool {
+            connection_pool,
+            replica_pool: None,
+        }
     }
 }
 
+/// Attributes all changes made through the given connection to the user (or [`ServiceAccount`]) with the given id
+///
+/// Note that this setting persists when a pooled connection is returned to the pool, so it needs to be set each time a
+/// connection is acquired.
 pub async fn audit_connection(connection: &mut PgConnection, user_id: i32) -> Result<()> {
     trace!(
         "Creating connection of which usage will be attributed to user {} in audit logs",
@@ -1,85 +1,873 @@
-#[macro_export]
-macro_rules! ratelimits {
-    ($struct_name: ident {$($tokens:tt)*}) => {
-        ratelimits!(@struct@ $struct_name [] $($tokens)*);
+//! Ratelimiting based on the generic cell rate algorithm (GCRA)
+//!
+//! Ratelimits are declared via the [`ratelimits!`] macro, which generates a struct with one method per limiter. The state of all
+//! limiters of such a struct is kept in a [`RatelimitStore`], which is in-memory by default. A [`PostgresRatelimitStore`] can be used
+//! to have ratelimits survive restarts and to share them between multiple instances of pointercrate running against the same database.
+//!
+//! The quotas given in the macro invocation are defaults. They can be overridden at startup via the `[ratelimits]` section of the
+//! configuration (see [`RatelimitConfig`] and [`RatelimitSet::configure`]), and at runtime via [`RatelimitSet::set_quota_override`].
+//! Since quotas are configured by the name of a limiter alone (without the name of the struct declaring it), limiter names have to be
+//! unique across all sets registered with a [`RatelimitRegistry`].
 
-        impl $struct_name {
-            ratelimits!(@method@  $($tokens)*);
+use crate::{
+    cache::{invalidate, CacheRegion, Reloadable},
+    config::RatelimitConfig,
+    error::CoreError,
+    util::nullable,
+};
+use futures::future::BoxFuture;
+use log::debug;
+use serde::{Deserialize, Serialize};
+use sqlx::{Pool, Postgres};
+use std::{
+    collections::HashMap,
+    fmt::{Display, Formatter},
+    str::FromStr,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex, RwLock,
+    },
+    time::{Duration, Instant},
+};
+
+/// How many checks a store performs between removing state that no longer affects any ratelimit
+const PRUNE_INTERVAL: u64 = 1000;
+
+/// The region invalidated whenever a quota override changes, causing all instances to reload their overrides
+pub const RATELIMIT_QUOTAS: CacheRegion = CacheRegion::new("ratelimit-quotas");
+
+/// A quota of requests allowed per time period, with up to `capacity` requests allowed in a burst
+///
+/// In configuration, quotas are written as `<capacity> per <seconds>`, e.g. `3 per 1200`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Quota {
+    pub capacity: u32,
+    pub seconds: u64,
+}
+
+impl Quota {
+    pub const fn new(capacity: u32, seconds: u64) -> Self {
+        Quota { capacity, seconds }
+    }
+
+    pub fn period(&self) -> Duration {
+        Duration::from_secs(self.seconds)
+    }
+
+    /// The time it takes for a single request to become available again after being used up
+    pub fn replenish_interval(&self) -> Duration {
+        self.period() / self.capacity.max(1)
+    }
+
+    pub fn validate(&self) -> Result<(), CoreError> {
+        if self.capacity == 0 || self.seconds == 0 {
+            return Err(CoreError::UnprocessableEntity);
         }
-    };
 
-    // Accumulates the fields of the struct as a comma separated list of the form "name: type | initializer_expression"
-    (@struct@ $struct_name: ident [$($field: ident: $type: ty | $init: expr),*] $name: ident[$capacity: tt per $seconds: tt] => $message: expr, $($remaining: tt)*) => {
-        ratelimits!(@struct@
-            $struct_name [
-                $($field: $type | $init,)*  // already processed fields
-                $name: governor::RateLimiter<governor::state::direct::NotKeyed, governor::state::InMemoryState, governor::clock::DefaultClock> | governor::RateLimiter::direct(governor::Quota::new(nonzero_ext::nonzero!($capacity), std::time::Duration::from_secs($seconds)).unwrap())  // new field
-            ] $($remaining)*);  // remaining, unprocessed fields as token stream
-    };
+        Ok(())
+    }
+}
 
-    (@struct@ $struct_name: ident [$($field: ident: $type: ty | $init: expr),*] $name: ident[$capacity: tt per $seconds: tt per $key_type: ty] => $message: expr, $($remaining: tt)*) => {
-        ratelimits!(@struct@
-            $struct_name [
-                $($field: $type | $init,)*
-                $name: governor::RateLimiter<$key_type, governor::state::keyed::DefaultKeyedStateStore<$key_type>, governor::clock::DefaultClock> | governor::RateLimiter::keyed(governor::Quota::new(nonzero_ext::nonzero!($capacity), std::time::Duration::from_secs($seconds)).unwrap())
-            ] $($remaining)*);
-    };
+impl Display for Quota {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} per {}", self.capacity, self.seconds)
+    }
+}
 
-    (@method@ $name: ident[$capacity: tt per $seconds: tt] => $message: expr, $($remaining: tt)*) => {
-        pub(crate) fn $name(&self) -> Result<(), pointercrate_core::error::CoreError> {
-            use governor::clock::{Clock, Reference};
+impl FromStr for Quota {
+    type Err = String;
 
-            let now = governor::clock::DefaultClock::default().now();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (capacity, seconds) = s
+            .split_once(" per ")
+            .ok_or_else(|| format!("quota '{}' is not of the form '<capacity> per <seconds>'", s))?;
 
-            self.$name.check().map_err(|too_early| {
-                let remaining: std::time::Duration = too_early.earliest_possible().duration_since(now).into();
-                log::debug!("Triggered ratelimit '{}'. Cooldown: {}s", stringify!($name), remaining.as_secs());
+        let quota = Quota {
+            capacity: capacity
+                .trim()
+                .parse()
+                .map_err(|err| format!("invalid capacity in quota '{}': {}", s, err))?,
+            seconds: seconds
+                .trim()
+                .parse()
+                .map_err(|err| format!("invalid period in quota '{}': {}", s, err))?,
+        };
 
-                pointercrate_core::error::CoreError::Ratelimited {
-                    message: $message.to_string(),
-                    remaining,
-                }
-            })
+        quota
+            .validate()
+            .map_err(|_| format!("quota '{}' must have a non-zero capacity and period", s))?;
+
+        Ok(quota)
+    }
+}
+
+/// The result of checking a request against a [`Quota`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RatelimitDecision {
+    /// The request was allowed, and the limiter will have fully replenished after `reset_after`
+    Allowed { reset_after: Duration },
+
+    /// The request was denied, and the next request will be allowed after `remaining`
+    Denied { remaining: Duration },
+}
+
+/// The state of a limiter for some key after checking a request against it, as reported to clients via the `RateLimit-*` headers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RatelimitStatus {
+    /// The number of requests allowed in a burst
+    pub limit: u32,
+
+    /// The number of requests currently allowed before the limiter starts denying them
+    pub remaining: u32,
+
+    /// The time until the limiter has fully replenished
+    pub reset_after: Duration,
+}
+
+impl RatelimitStatus {
+    /// Computes the status of a limiter with the given quota that fully replenishes after `reset_after`
+    pub fn new(quota: Quota, reset_after: Duration) -> Self {
+        // A request is allowed as long as the theoretical arrival time after performing it is at most one period in the future,
+        // so n more requests are allowed if reset_after + n * replenish_interval <= period
+        let remaining = quota.period().saturating_sub(reset_after).as_secs_f64() / quota.replenish_interval().as_secs_f64();
+
+        RatelimitStatus {
+            limit: quota.capacity,
+            remaining: (remaining.floor() as u32).min(quota.capacity),
+            reset_after,
         }
-        ratelimits!(@method@  $($remaining)*);
-    };
+    }
+
+    /// Of two limiters applying to the same request, returns the status of the one that will start denying requests first
+    pub fn most_restrictive(self, other: RatelimitStatus) -> RatelimitStatus {
+        if (other.remaining, std::cmp::Reverse(other.reset_after)) < (self.remaining, std::cmp::Reverse(self.reset_after)) {
+            other
+        } else {
+            self
+        }
+    }
+
+    /// The `RateLimit-Limit`, `RateLimit-Remaining` and `RateLimit-Reset` headers describing this status
+    pub fn headers(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("RateLimit-Limit", self.limit.to_string()),
+            ("RateLimit-Remaining", self.remaining.to_string()),
+            ("RateLimit-Reset", (self.reset_after.as_secs_f64().ceil() as u64).to_string()),
+        ]
+    }
+}
+
+/// Persists the state of ratelimits, keyed by the name of the limiter and (for keyed limiters) the key a request is checked against
+///
+/// Additionally persists the quota overrides set at runtime, so that they apply to everything sharing the store.
+pub trait RatelimitStore: Send + Sync {
+    /// Checks whether a request is allowed under the given quota, and if so, atomically records it
+    ///
+    /// Non-keyed limiters use the empty string as key.
+    fn check<'a>(&'a self, limiter: &'static str, key: String, quota: Quota) -> BoxFuture<'a, Result<RatelimitDecision, CoreError>>;
+
+    /// Returns the time until the given limiter has fully replenished for the given key, or `None` if it already has
+    fn inspect<'a>(&'a self, limiter: &'static str, key: String) -> BoxFuture<'a, Result<Option<Duration>, CoreError>>;
+
+    /// Removes all state of the given limiter for the given key, fully replenishing it
+    fn clear<'a>(&'a self, limiter: &'static str, key: String) -> BoxFuture<'a, Result<(), CoreError>>;
+
+    /// Returns all quota overrides in this store, keyed by the name of the limiter they apply to
+    fn quota_overrides(&self) -> BoxFuture<'_, Result<HashMap<String, Quota>, CoreError>>;
+
+    /// Overrides the quota of the given limiter, or removes an existing override if `None` is given
+    fn set_quota_override<'a>(&'a self, limiter: &'static str, quota: Option<Quota>) -> BoxFuture<'a, Result<(), CoreError>>;
+}
+
+/// [`RatelimitStore`] keeping state in the memory of the current process
+///
+/// This state is lost on restart, and not shared between multiple processes.
+#[derive(Debug, Default)]
+pub struct InMemoryRatelimitStore {
+    /// The theoretical arrival time of the next request for each limiter/key pair, as defined by GCRA
+    arrivals: Mutex<HashMap<(&'static str, String), Instant>>,
+    overrides: Mutex<HashMap<&'static str, Quota>>,
+    checks: AtomicU64,
+}
+
+impl RatelimitStore for InMemoryRatelimitStore {
+    fn check<'a>(&'a self, limiter: &'static str, key: String, quota: Quota) -> BoxFuture<'a, Result<RatelimitDecision, CoreError>> {
+        let now = Instant::now();
+
+        let mut arrivals = self.arrivals.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        if self.checks.fetch_add(1, Ordering::Relaxed).is_multiple_of(PRUNE_INTERVAL) {
+            arrivals.retain(|_, arrival| *arrival > now);
+        }
+
+        let arrival = arrivals.get(&(limiter, key.clone())).copied().unwrap_or(now).max(now) + quota.replenish_interval();
+
+        let decision = if arrival > now + quota.period() {
+            RatelimitDecision::Denied {
+                remaining: arrival - quota.period() - now,
+            }
+        } else {
+            arrivals.insert((limiter, key), arrival);
+
+            RatelimitDecision::Allowed {
+                reset_after: arrival - now,
+            }
+        };
+
+        Box::pin(async move { Ok(decision) })
+    }
+
+    fn inspect<'a>(&'a self, limiter: &'static str, key: String) -> BoxFuture<'a, Result<Option<Duration>, CoreError>> {
+        let now = Instant::now();
+
+        let reset_after = self
+            .arrivals
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .get(&(limiter, key))
+            .filter(|&&arrival| arrival > now)
+            .map(|&arrival| arrival - now);
+
+        Box::pin(async move { Ok(reset_after) })
+    }
+
+    fn clear<'a>(&'a self, limiter: &'static str, key: String) -> BoxFuture<'a, Result<(), CoreError>> {
+        self.arrivals
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .remove(&(limiter, key));
+
+        Box::pin(async move { Ok(()) })
+    }
+
+    fn quota_overrides(&self) -> BoxFuture<'_, Result<HashMap<String, Quota>, CoreError>> {
+        let overrides = self
+            .overrides
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .iter()
+            .map(|(&limiter, &quota)| (limiter.to_string(), quota))
+            .collect();
+
+        Box::pin(async move { Ok(overrides) })
+    }
+
+    fn set_quota_override<'a>(&'a self, limiter: &'static str, quota: Option<Quota>) -> BoxFuture<'a, Result<(), CoreError>> {
+        let mut overrides = self.overrides.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        match quota {
+            Some(quota) => overrides.insert(limiter, quota),
+            None => overrides.remove(limiter),
+        };
+
+        Box::pin(async move { Ok(()) })
+    }
+}
+
+/// [`RatelimitStore`] keeping state in the `ratelimits` and `ratelimit_quotas` tables of the pointercrate database
+///
+/// All timestamps are computed by the database, so that instances sharing the database agree on the state of all ratelimits.
+pub struct PostgresRatelimitStore {
+    pool: Pool<Postgres>,
+    checks: AtomicU64,
+}
+
+impl PostgresRatelimitStore {
+    pub fn new(pool: Pool<Postgres>) -> Self {
+        PostgresRatelimitStore {
+            pool,
+            checks: AtomicU64::new(0),
+        }
+    }
+
+    async fn check_in_database(&self, limiter: &str, key: String, quota: Quota) -> Result<RatelimitDecision, sqlx::Error> {
+        let mut connection = self.pool.acquire().await?;
+
+        if self.checks.fetch_add(1, Ordering::Relaxed).is_multiple_of(PRUNE_INTERVAL) {
+            sqlx::query!("DELETE FROM ratelimits WHERE theoretical_arrival < NOW()")
+                .execute(&mut *connection)
+                .await?;
+        }
+
+        let replenish_interval = quota.replenish_interval().as_secs_f64();
+        let period = quota.period().as_secs_f64();
+
+        // Only updates (or inserts) the state if the request is allowed. Since a limiter always allows at least one request in a burst,
+        // inserting new state for a previously unseen key is always allowed.
+        let reset_after = sqlx::query!(
+            r#"INSERT INTO ratelimits (limiter, key, theoretical_arrival) VALUES ($1, $2, NOW() + $3::FLOAT8 * INTERVAL '1 second')
+               ON CONFLICT (limiter, key) DO UPDATE
+               SET theoretical_arrival = GREATEST(ratelimits.theoretical_arrival, NOW()) + $3::FLOAT8 * INTERVAL '1 second'
+               WHERE GREATEST(ratelimits.theoretical_arrival, NOW()) + $3::FLOAT8 * INTERVAL '1 second' <= NOW() + $4::FLOAT8 * INTERVAL '1 second'
+               RETURNING EXTRACT(EPOCH FROM theoretical_arrival - NOW())::FLOAT8 AS "reset_after!""#,
+            limiter,
+            key,
+            replenish_interval,
+            period
+        )
+        .fetch_optional(&mut *connection)
+        .await?
+        .map(|row| row.reset_after);
+
+        if let Some(reset_after) = reset_after {
+            return Ok(RatelimitDecision::Allowed {
+                reset_after: Duration::from_secs_f64(reset_after.max(0.0)),
+            });
+        }
+
+        let remaining = sqlx::query!(
+            r#"SELECT EXTRACT(EPOCH FROM GREATEST(theoretical_arrival, NOW()) + ($3::FLOAT8 - $4::FLOAT8) * INTERVAL '1 second' - NOW())::FLOAT8 AS "remaining!"
+               FROM ratelimits WHERE limiter = $1 AND key = $2"#,
+            limiter,
+            key,
+            replenish_interval,
+            period
+        )
+        .fetch_optional(&mut *connection)
+        .await?
+        .map(|row| row.remaining)
+        .unwrap_or_default();
+
+        Ok(RatelimitDecision::Denied {
+            remaining: Duration::from_secs_f64(remaining.max(0.0)),
+        })
+    }
+}
+
+impl RatelimitStore for PostgresRatelimitStore {
+    fn check<'a>(&'a self, limiter: &'static str, key: String, quota: Quota) -> BoxFuture<'a, Result<RatelimitDecision, CoreError>> {
+        Box::pin(async move { Ok(self.check_in_database(limiter, key, quota).await?) })
+    }
+
+    fn inspect<'a>(&'a self, limiter: &'static str, key: String) -> BoxFuture<'a, Result<Option<Duration>, CoreError>> {
+        Box::pin(async move {
+            let reset_after = sqlx::query!(
+                r#"SELECT EXTRACT(EPOCH FROM theoretical_arrival - NOW())::FLOAT8 AS "reset_after!" FROM ratelimits WHERE limiter = $1 AND key = $2"#,
+                limiter,
+                key
+            )
+            .fetch_optional(&self.pool)
+            .await?
+            .map(|row| row.reset_after)
+            .filter(|&reset_after| reset_after > 0.0);
+
+            Ok(reset_after.map(Duration::from_secs_f64))
+        })
+    }
+
+    fn clear<'a>(&'a self, limiter: &'static str, key: String) -> BoxFuture<'a, Result<(), CoreError>> {
+        Box::pin(async move {
+            sqlx::query!("DELETE FROM ratelimits WHERE limiter = $1 AND key = $2", limiter, key)
+                .execute(&self.pool)
+                .await?;
+
+            Ok(())
+        })
+    }
+
+    fn quota_overrides(&self) -> BoxFuture<'_, Result<HashMap<String, Quota>, CoreError>> {
+        Box::pin(async move {
+            let overrides = sqlx::query!("SELECT limiter, capacity, seconds FROM ratelimit_quotas")
+                .fetch_all(&self.pool)
+                .await?
+                .into_iter()
+                .map(|row| (row.limiter, Quota::new(row.capacity as u32, row.seconds as u64)))
+                .collect();
+
+            Ok(overrides)
+        })
+    }
+
+    fn set_quota_override<'a>(&'a self, limiter: &'static str, quota: Option<Quota>) -> BoxFuture<'a, Result<(), CoreError>> {
+        Box::pin(async move {
+            let mut transaction = self.pool.begin().await?;
+
+            match quota {
+                Some(quota) => sqlx::query!(
+                    "INSERT INTO ratelimit_quotas (limiter, capacity, seconds) VALUES ($1, $2, $3) ON CONFLICT (limiter) DO UPDATE SET capacity = \
+                     EXCLUDED.capacity, seconds = EXCLUDED.seconds",
+                    limiter,
+                    quota.capacity as i32,
+                    quota.seconds as i64
+                )
+                .execute(&mut *transaction)
+                .await?,
+                None => sqlx::query!("DELETE FROM ratelimit_quotas WHERE limiter = $1", limiter)
+                    .execute(&mut *transaction)
+                    .await?,
+            };
+
+            // Other instances sharing this store keep the overrides in memory as well
+            invalidate(RATELIMIT_QUOTAS, &mut transaction).await?;
+
+            transaction.commit().await?;
+
+            Ok(())
+        })
+    }
+}
+
+/// A single limiter declared via the [`ratelimits!`] macro
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Limiter {
+    /// The name of this limiter, qualified with the name of the struct it was declared in (e.g. `DemonlistRatelimits::new_submitters`)
+    pub name: &'static str,
+
+    /// The name under which a quota for this limiter can be configured (see [`RatelimitConfig`]), e.g. `new_submitters`
+    pub config_name: &'static str,
+
+    /// Whether this limiter tracks requests separately per key (e.g. per IP address)
+    pub keyed: bool,
+
+    /// The quota given in the macro invocation
+    pub default: Quota,
+}
 
-    (@method@ $name: ident[$capacity: tt per $seconds: tt per $key_type: ty] => $message: expr, $($remaining: tt)*) => {
-        pub(crate) fn $name(&self, ip: $key_type) -> Result<(), pointercrate_core::error::CoreError> {
-            use governor::clock::{Clock, Reference};
+impl Limiter {
+    pub const fn new(name: &'static str, config_name: &'static str, keyed: bool, default: Quota) -> Self {
+        Limiter {
+            name,
+            config_name,
+            keyed,
+            default,
+        }
+    }
+}
+
+/// Overview of a limiter, as returned by the ratelimit administration endpoints
+#[derive(Debug, Serialize)]
+pub struct LimiterInfo {
+    pub name: &'static str,
+    pub keyed: bool,
+    pub default: Quota,
+    pub configured: Quota,
+    #[serde(rename = "override")]
+    pub quota_override: Option<Quota>,
+
+    /// The quota currently in effect
+    pub quota: Quota,
+}
+
+/// The state of a limiter for a specific key, as returned by the ratelimit administration endpoints
+#[derive(Debug, Serialize)]
+pub struct LimiterState {
+    pub limiter: &'static str,
+    pub key: String,
+
+    /// The number of requests currently allowed for this key before the limiter starts denying them
+    pub remaining: u32,
+
+    /// The number of seconds until the limiter has fully replenished for this key
+    pub reset_after: u64,
+}
+
+/// Changes to a limiter made via the ratelimit administration endpoints
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PatchLimiter {
+    /// The quota overriding the configured one, or `null` to remove an existing override
+    #[serde(default, deserialize_with = "nullable")]
+    pub quota: Option<Option<Quota>>,
+}
+
+/// The limiters declared in a single invocation of the [`ratelimits!`] macro, together with the store holding their state
+///
+/// Cheap to clone, all clones share the same state.
+#[derive(Clone)]
+pub struct RatelimitSet {
+    store: Arc<dyn RatelimitStore>,
+    limiters: Arc<Vec<Limiter>>,
 
-            let now = governor::clock::DefaultClock::default().now();
+    /// The quotas configured at startup for this set's limiters, see [`RatelimitSet::configure`]
+    configured: Arc<RwLock<HashMap<&'static str, Quota>>>,
 
-            self.$name.check_key(&ip).map_err(|too_early| {
-                let remaining: std::time::Duration = too_early.earliest_possible().duration_since(now).into();
-                log::debug!("Triggered ratelimit '{}' on key '{}'. Cooldown: {}s", stringify!($name), ip, remaining.as_secs());
+    /// The quota overrides of this set's limiters, as last loaded from the store. `None` until first loaded.
+    ///
+    /// Checked on every request, so they are only reloaded when they change (see [`RATELIMIT_QUOTAS`]).
+    overrides: Arc<RwLock<Option<HashMap<&'static str, Quota>>>>,
+}
+
+impl RatelimitSet {
+    pub fn new(store: impl RatelimitStore + 'static, limiters: Vec<Limiter>) -> Self {
+        RatelimitSet {
+            store: Arc::new(store),
+            limiters: Arc::new(limiters),
+            configured: Arc::default(),
+            overrides: Arc::default(),
+        }
+    }
+
+    /// Applies the quotas the given configuration sets for this set's limiters
+    ///
+    /// Ratelimits are declared without access to the configuration, so this happens when registering them with a rocket instance.
+    pub fn configure(&self, config: &RatelimitConfig) {
+        let configured = self
+            .limiters
+            .iter()
+            .filter_map(|limiter| config.quotas.get(limiter.config_name).map(|&quota| (limiter.name, quota)))
+            .collect();
+
+        *self.configured.write().unwrap_or_else(|poisoned| poisoned.into_inner()) = configured;
+    }
+
+    /// The quota configured at startup for the given limiter, falling back to its default if none is configured
+    pub fn configured_quota(&self, limiter: &Limiter) -> Quota {
+        self.configured
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .get(limiter.name)
+            .copied()
+            .unwrap_or(limiter.default)
+    }
 
-                pointercrate_core::error::CoreError::Ratelimited {
-                    message: $message.to_string(),
+    pub fn limiters(&self) -> &[Limiter] {
+        &self.limiters
+    }
+
+    pub fn limiter(&self, name: &str) -> Option<&Limiter> {
+        self.limiters.iter().find(|limiter| limiter.name == name)
+    }
+
+    /// Checks (and records) a request against the limiter of the given name, returning [`CoreError::Ratelimited`] with the given
+    /// message if it is denied
+    pub async fn check(&self, limiter: &'static str, key: String, message: impl FnOnce() -> String) -> Result<RatelimitStatus, CoreError> {
+        let quota = self.quota(limiter).await?;
+
+        match self.store.check(limiter, key.clone(), quota).await? {
+            RatelimitDecision::Allowed { reset_after } => Ok(RatelimitStatus::new(quota, reset_after)),
+            RatelimitDecision::Denied { remaining } => {
+                debug!(
+                    "Triggered ratelimit '{}' on key '{}'. Cooldown: {}s",
+                    limiter,
+                    key,
+                    remaining.as_secs()
+                );
+
+                Err(CoreError::Ratelimited {
+                    message: message(),
                     remaining,
-                }
-            })
+                    // The state of a limiter does not change when denying a request, so it fully replenishes one replenish interval
+                    // after the point in time at which the next request is allowed
+                    status: RatelimitStatus {
+                        limit: quota.capacity,
+                        remaining: 0,
+                        reset_after: remaining + quota.period() - quota.replenish_interval(),
+                    },
+                })
+            },
         }
-        ratelimits!(@method@  $($remaining)*);
-    };
+    }
+
+    /// The quota currently in effect for the limiter of the given name
+    ///
+    /// Quotas overridden at runtime take precedence over quotas configured at startup, which take precedence over the defaults.
+    pub async fn quota(&self, limiter: &'static str) -> Result<Quota, CoreError> {
+        if let Some(quota) = self.quota_override(limiter).await? {
+            return Ok(quota);
+        }
+
+        self.limiter(limiter)
+            .map(|limiter| self.configured_quota(limiter))
+            .ok_or_else(|| CoreError::internal_server_error(format!("Unknown ratelimit '{}'", limiter)))
+    }
+
+    async fn quota_override(&self, limiter: &str) -> Result<Option<Quota>, CoreError> {
+        let loaded = self
+            .overrides
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .as_ref()
+            .map(|overrides| overrides.get(limiter).copied());
+
+        match loaded {
+            Some(quota) => Ok(quota),
+            None => {
+                self.reload_overrides().await?;
+
+                Ok(self
+                    .overrides
+                    .read()
+                    .unwrap_or_else(|poisoned| poisoned.into_inner())
+                    .as_ref()
+                    .and_then(|overrides| overrides.get(limiter).copied()))
+            },
+        }
+    }
+
+    /// Reloads the quota overrides of this set's limiters from its [`RatelimitStore`]
+    pub async fn reload_overrides(&self) -> Result<(), CoreError> {
+        let stored = self.store.quota_overrides().await?;
+        let overrides = self
+            .limiters
+            .iter()
+            .filter_map(|limiter| stored.get(limiter.name).map(|&quota| (limiter.name, quota)))
+            .collect();
+
+        *self.overrides.write().unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(overrides);
+
+        Ok(())
+    }
+
+    pub async fn info(&self, limiter: &Limiter) -> Result<LimiterInfo, CoreError> {
+        let quota_override = self.quota_override(limiter.name).await?;
+        let configured = self.configured_quota(limiter);
+
+        Ok(LimiterInfo {
+            name: limiter.name,
+            keyed: limiter.keyed,
+            default: limiter.default,
+            configured,
+            quota_override,
+            quota: quota_override.unwrap_or(configured),
+        })
+    }
+
+    /// Overrides the quota of the given limiter, or removes an existing override if `None` is given
+    ///
+    /// The override is persisted in the [`RatelimitStore`] of this set, meaning it applies to all instances sharing the store.
+    pub async fn set_quota_override(&self, limiter: &Limiter, quota: Option<Quota>) -> Result<(), CoreError> {
+        if let Some(quota) = quota {
+            quota.validate()?;
+        }
+
+        self.store.set_quota_override(limiter.name, quota).await?;
+
+        // If the overrides were not loaded yet, they will be (including this change) on first use
+        if let Some(overrides) = self.overrides.write().unwrap_or_else(|poisoned| poisoned.into_inner()).as_mut() {
+            match quota {
+                Some(quota) => overrides.insert(limiter.name, quota),
+                None => overrides.remove(limiter.name),
+            };
+        }
+
+        Ok(())
+    }
+
+    pub async fn state(&self, limiter: &Limiter, key: String) -> Result<LimiterState, CoreError> {
+        let quota = self.quota(limiter.name).await?;
+        let reset_after = self.store.inspect(limiter.name, key.clone()).await?.unwrap_or_default();
+        let status = RatelimitStatus::new(quota, reset_after);
+
+        Ok(LimiterState {
+            limiter: limiter.name,
+            key,
+            remaining: status.remaining,
+            reset_after: reset_after.as_secs_f64().ceil() as u64,
+        })
+    }
+
+    pub async fn clear(&self, limiter: &Limiter, key: String) -> Result<(), CoreError> {
+        self.store.clear(limiter.name, key).await
+    }
+}
+
+impl Reloadable for RatelimitSet {
+    fn reload<'a>(&'a self, _: &'a Pool<Postgres>) -> BoxFuture<'a, Result<(), CoreError>> {
+        // The overrides live in the store, which is not necessarily the database
+        Box::pin(self.reload_overrides())
+    }
+}
 
-    (@struct@ $struct_name: ident [$($field: ident: $type: ty | $init: expr),*]) => {
-        pub struct $struct_name {
-            $(
-                $field: $type,
-            )*
+/// Error returned when registering a [`RatelimitSet`] with a [`RatelimitRegistry`] that already knows a limiter of the same
+/// [configuration name](Limiter::config_name)
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+#[error("Ratelimits {limiter} and {existing} cannot be configured independently, as their names are the same")]
+pub struct DuplicateLimiter {
+    pub limiter: &'static str,
+    pub existing: &'static str,
+}
+
+/// All [`RatelimitSet`]s of a pointercrate instance, allowing them to be managed at runtime
+#[derive(Default)]
+pub struct RatelimitRegistry {
+    sets: RwLock<Vec<RatelimitSet>>,
+}
+
+impl RatelimitRegistry {
+    /// Registers the given set, unless it declares a limiter under a configuration name already used by a registered set
+    ///
+    /// Quotas are configured by [`Limiter::config_name`] alone, so such limiters could not be configured independently.
+    pub fn register(&self, set: RatelimitSet) -> Result<(), DuplicateLimiter> {
+        let mut sets = self.sets.write().unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        for limiter in set.limiters() {
+            let existing = sets
+                .iter()
+                .flat_map(|set| set.limiters())
+                .find(|existing| existing.config_name == limiter.config_name);
+
+            if let Some(existing) = existing {
+                return Err(DuplicateLimiter {
+                    limiter: limiter.name,
+                    existing: existing.name,
+                });
+            }
         }
 
+        sets.push(set);
+
+        Ok(())
+    }
+
+    pub fn sets(&self) -> Vec<RatelimitSet> {
+        self.sets.read().unwrap_or_else(|poisoned| poisoned.into_inner()).clone()
+    }
+
+    /// Finds the limiter of the given (qualified) name, together with the set it belongs to
+    pub fn find(&self, name: &str) -> Result<(RatelimitSet, Limiter), CoreError> {
+        self.sets()
+            .into_iter()
+            .find_map(|set| set.limiter(name).copied().map(|limiter| (set, limiter)))
+            .ok_or(CoreError::NotFound)
+    }
+
+    /// The names of all ratelimits the given configuration sets a quota for, but which are not declared by any registered set
+    pub fn unknown_quotas<'a>(&self, config: &'a RatelimitConfig) -> Vec<&'a str> {
+        let sets = self.sets();
+
+        config
+            .quotas
+            .keys()
+            .map(String::as_str)
+            .filter(|&name| {
+                !sets
+                    .iter()
+                    .any(|set| set.limiters().iter().any(|limiter| limiter.config_name == name))
+            })
+            .collect()
+    }
+}
+
+/// Declares a struct holding a set of ratelimits
+///
+/// Each ratelimit is given as `name[capacity per seconds] => message` or, for ratelimits tracked separately per key (e.g. per IP
+/// address), as `name[capacity per seconds per KeyType] => message`. For each of them, the struct gets an async method of the same
+/// name checking (and recording) a request. It returns the [`RatelimitStatus`] of the ratelimit after recording the request, or
+/// [`CoreError::Ratelimited`] with the given message if the ratelimit is exceeded.
+/// The given quotas are defaults, see the [module level documentation](self) for how to override them.
+///
+/// The struct is constructed via `new()`, which keeps ratelimit state in memory, or via `with_store()`, which accepts any
+/// [`RatelimitStore`]. Its limiters can be made available to the ratelimit administration endpoints by registering `set()` with
+/// a [`RatelimitRegistry`].
+#[macro_export]
+macro_rules! ratelimits {
+    ($struct_name: ident {$($tokens:tt)*}) => {
+        pub struct $struct_name($crate::ratelimits::RatelimitSet);
+
         impl $struct_name {
+            #[allow(dead_code)]
             pub(crate) fn new() -> Self {
-                #[allow(deprecated)] // the governor API mentions that using Quota::new() is fine since our ratelimits are given as "burst per duration"
-                $struct_name {
-                    $(
-                        $field: $init,
-                    )*
-                }
+                Self::with_store($crate::ratelimits::InMemoryRatelimitStore::default())
+            }
+
+            #[allow(dead_code)]
+            pub(crate) fn with_store(store: impl $crate::ratelimits::RatelimitStore + 'static) -> Self {
+                $struct_name($crate::ratelimits::RatelimitSet::new(
+                    store,
+                    $crate::ratelimits!(@limiters@ $struct_name [] $($tokens)*),
+                ))
             }
+
+            #[allow(dead_code)]
+            pub(crate) fn set(&self) -> &$crate::ratelimits::RatelimitSet {
+                &self.0
+            }
+
+            $crate::ratelimits!(@method@ $struct_name $($tokens)*);
         }
     };
-    (@method@) => {};
+
+    (@limiters@ $struct_name: ident [$($limiter: expr),*] $name: ident[$capacity: tt per $seconds: tt] => $message: expr, $($remaining: tt)*) => {
+        $crate::ratelimits!(@limiters@ $struct_name [$($limiter,)* $crate::ratelimits::Limiter::new(
+            concat!(stringify!($struct_name), "::", stringify!($name)),
+            stringify!($name),
+            false,
+            $crate::ratelimits::Quota::new($capacity, $seconds),
+        )] $($remaining)*)
+    };
+
+    (@limiters@ $struct_name: ident [$($limiter: expr),*] $name: ident[$capacity: tt per $seconds: tt per $key_type: ty] => $message: expr, $($remaining: tt)*) => {
+        $crate::ratelimits!(@limiters@ $struct_name [$($limiter,)* $crate::ratelimits::Limiter::new(
+            concat!(stringify!($struct_name), "::", stringify!($name)),
+            stringify!($name),
+            true,
+            $crate::ratelimits::Quota::new($capacity, $seconds),
+        )] $($remaining)*)
+    };
+
+    (@limiters@ $struct_name: ident [$($limiter: expr),*]) => {
+        vec![$($limiter),*]
+    };
+
+    (@method@ $struct_name: ident $name: ident[$capacity: tt per $seconds: tt] => $message: expr, $($remaining: tt)*) => {
+        pub(crate) async fn $name(&self) -> Result<$crate::ratelimits::RatelimitStatus, $crate::error::CoreError> {
+            self.0
+                .check(concat!(stringify!($struct_name), "::", stringify!($name)), String::new(), || $message.to_string())
+                .await
+        }
+        $crate::ratelimits!(@method@ $struct_name $($remaining)*);
+    };
+
+    (@method@ $struct_name: ident $name: ident[$capacity: tt per $seconds: tt per $key_type: ty] => $message: expr, $($remaining: tt)*) => {
+        pub(crate) async fn $name(&self, key: $key_type) -> Result<$crate::ratelimits::RatelimitStatus, $crate::error::CoreError> {
+            self.0
+                .check(concat!(stringify!($struct_name), "::", stringify!($name)), key.to_string(), || $message.to_string())
+                .await
+        }
+        $crate::ratelimits!(@method@ $struct_name $($remaining)*);
+    };
+
+    (@method@ $struct_name: ident) => {};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DuplicateLimiter, InMemoryRatelimitStore, Limiter, Quota, RatelimitRegistry, RatelimitSet, RatelimitStatus};
+    use std::time::Duration;
+
+    #[test]
+    fn test_parse_quota() {
+        assert_eq!("3 per 1200".parse(), Ok(Quota::new(3, 1200)));
+        assert_eq!(Quota::new(3, 1200).to_string(), "3 per 1200");
+
+        assert!("3/1200".parse::<Quota>().is_err());
+        assert!("0 per 1200".parse::<Quota>().is_err());
+        assert!("3 per -1".parse::<Quota>().is_err());
+    }
+
+    #[test]
+    fn test_ratelimit_status() {
+        let quota = Quota::new(3, 1800);
+
+        assert_eq!(RatelimitStatus::new(quota, Duration::ZERO).remaining, 3);
+        assert_eq!(RatelimitStatus::new(quota, Duration::from_secs(599)).remaining, 2);
+        assert_eq!(RatelimitStatus::new(quota, Duration::from_secs(1799)).remaining, 0);
+
+        let burst = RatelimitStatus::new(quota, Duration::from_secs(1200));
+        let exhausted = RatelimitStatus::new(Quota::new(1, 60), Duration::from_secs(60));
+
+        assert_eq!(burst.most_restrictive(exhausted), exhausted);
+        assert_eq!(exhausted.most_restrictive(burst), exhausted);
+    }
+
+    #[test]
+    fn test_duplicate_limiters_rejected() {
+        let set = |limiter| {
+            RatelimitSet::new(
+                InMemoryRatelimitStore::default(),
+                vec![Limiter::new(limiter, "record_submission", true, Quota::new(3, 1200))],
+            )
+        };
+        let registry = RatelimitRegistry::default();
+
+        assert_eq!(registry.register(set("DemonlistRatelimits::record_submission")), Ok(()));
+        assert_eq!(
+            registry.register(set("OtherRatelimits::record_submission")),
+            Err(DuplicateLimiter {
+                limiter: "OtherRatelimits::record_submission",
+                existing: "DemonlistRatelimits::record_submission"
+            })
+        );
+        assert_eq!(registry.sets().len(), 1);
+    }
 }
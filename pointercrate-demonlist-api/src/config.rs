@@ -1,7 +1,34 @@
-pub fn submission_webhook() -> Option<String> {
-    std::env::var("DISCORD_WEBHOOK").ok()
+use pointercrate_core::config::{ConfigSection, ConfigSource};
+
+/// The parts of the `[demonlist]` section of the configuration only relevant to the API
+#[derive(Debug, Clone, Default)]
+pub struct DemonlistApiConfig {
+    /// The discord webhook notified about new submissions. If not set, no notifications are sent.
+    pub submission_webhook: Option<String>,
+
+    /// The endpoint of the Geometry Dash servers to use instead of the official one
+    pub gd_connector_endpoint: Option<String>,
 }
 
-pub fn gd_connector_endpoint() -> Option<String> {
-    std::env::var("GD_CONNECTOR_ENDPOINT").ok()
+impl ConfigSection for DemonlistApiConfig {
+    fn load(source: &mut ConfigSource) -> Option<Self> {
+        let submission_webhook: Option<String> = source.get("demonlist.submission_webhook", "DISCORD_WEBHOOK");
+        let gd_connector_endpoint: Option<String> = source.get("demonlist.gd_connector_endpoint", "GD_CONNECTOR_ENDPOINT");
+
+        for (key, url) in [
+            ("demonlist.submission_webhook", &submission_webhook),
+            ("demonlist.gd_connector_endpoint", &gd_connector_endpoint),
+        ] {
+            if let Some(url) = url {
+                if !url.starts_with("http://") && !url.starts_with("https://") {
+                    source.invalid(key, format!("{:?} is not an http(s) URL", url));
+                }
+            }
+        }
+
+        Some(DemonlistApiConfig {
+            submission_webhook,
+            gd_connector_endpoint,
+        })
+    }
 }
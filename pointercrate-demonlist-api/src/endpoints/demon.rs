@@ -1,11 +1,17 @@
 use crate::ratelimits::DemonlistRatelimits;
-use pointercrate_core::{audit::AuditLogEntry, pool::PointercratePool};
+use pointercrate_core::{
+    audit::{last_modified, last_modified_after_patch, AuditLogEntry},
+    cache::ResponseCache,
+    etag::Taggable,
+    pool::PointercratePool,
+};
 use pointercrate_core_api::{
     error::Result,
     etag::{Precondition, TaggableExt, Tagged},
+    export::{export_response, ExportFormat, ExportResponse},
     pagination::pagination_response,
     query::Query,
-    response::Response2,
+    response::{CachedResponse, Response2},
 };
 use pointercrate_core_macros::localized;
 use pointercrate_demonlist::{
@@ -16,30 +22,61 @@ use pointercrate_demonlist::{
     },
     error::DemonlistError,
     player::DatabasePlayer,
-    LIST_ADMINISTRATOR, LIST_MODERATOR,
+    LISTED_DEMONS, LIST_ADMINISTRATOR, LIST_MODERATOR,
 };
 use pointercrate_user::auth::ApiToken;
 use pointercrate_user_api::auth::Auth;
-use rocket::{http::Status, serde::json::Json, State};
+use rocket::{
+    http::{uri::Origin, Status},
+    serde::json::Json,
+    State,
+};
 
 #[localized]
 #[rocket::get("/")]
 pub async fn paginate(pool: &State<PointercratePool>, pagination: Query<DemonIdPagination>) -> Result<Response2<Json<Vec<Demon>>>> {
-    Ok(pagination_response("/api/v2/demons/", pagination.0, &mut *pool.connection().await?).await?)
+    Ok(pagination_response("/api/v2/demons/", pagination.0, &mut *pool.read_connection().await?).await?)
 }
 
 #[localized]
 #[rocket::get("/listed/")]
 pub async fn paginate_listed(
-    pool: &State<PointercratePool>, pagination: Query<DemonPositionPagination>,
-) -> Result<Response2<Json<Vec<Demon>>>> {
-    Ok(pagination_response("/api/v2/demons/listed/", pagination.0, &mut *pool.connection().await?).await?)
+    pool: &State<PointercratePool>, cache: &State<ResponseCache>, uri: &Origin<'_>, pagination: Query<DemonPositionPagination>,
+) -> Result<CachedResponse> {
+    let response = cache
+        .get_or_try_insert_with(LISTED_DEMONS, &uri.to_string(), || async {
+            pagination_response::<_, Demon>("/api/v2/demons/listed/", pagination.0, &mut *pool.connection().await?)
+                .await?
+                .into_cached()
+        })
+        .await?;
+
+    Ok(CachedResponse::clone(&response))
+}
+
+#[localized]
+#[rocket::get("/export/")]
+pub async fn export(pool: &State<PointercratePool>, pagination: Query<DemonIdPagination>, format: ExportFormat) -> Result<ExportResponse> {
+    Ok(export_response::<_, Demon, _>(pagination.0, pool.read_connection().await?, format)?)
+}
+
+#[localized]
+#[rocket::get("/listed/export/")]
+pub async fn export_listed(
+    pool: &State<PointercratePool>, pagination: Query<DemonPositionPagination>, format: ExportFormat,
+) -> Result<ExportResponse> {
+    Ok(export_response::<_, Demon, _>(pagination.0, pool.read_connection().await?, format)?)
 }
 
 #[localized]
 #[rocket::get("/<demon_id>/")]
-pub async fn get(demon_id: i32, pool: &State<PointercratePool>) -> Result<Tagged<FullDemon>> {
-    Ok(Tagged(FullDemon::by_id(demon_id, &mut *pool.connection().await?).await?))
+pub async fn get(demon_id: i32, pool: &State<PointercratePool>) -> Result<Response2<Tagged<FullDemon>>> {
+    let mut connection = pool.read_connection().await?;
+
+    let demon = FullDemon::by_id(demon_id, &mut connection).await?;
+    let last_modified = last_modified("demon", demon_id, &mut connection).await?;
+
+    Ok(Response2::tagged(demon).with_last_modified(last_modified))
 }
 
 #[localized]
@@ -59,7 +96,7 @@ pub async fn audit(demon_id: i32, mut auth: Auth<ApiToken>) -> Result<Json<Vec<A
 #[localized]
 #[rocket::get("/<demon_id>/audit/movement/")]
 pub async fn movement_log(demon_id: i32, pool: &State<PointercratePool>) -> Result<Json<Vec<MovementLogEntry>>> {
-    let log = pointercrate_demonlist::demon::audit::movement_log_for_demon(demon_id, &mut *pool.connection().await?).await?;
+    let log = pointercrate_demonlist::demon::audit::movement_log_for_demon(demon_id, &mut *pool.read_connection().await?).await?;
 
     if log.is_empty() {
         return Err(DemonlistError::DemonNotFound { demon_id }.into());
@@ -75,7 +112,7 @@ pub async fn post(
 ) -> Result<Response2<Tagged<FullDemon>>> {
     auth.require_permission(LIST_MODERATOR)?;
 
-    ratelimits.add_demon()?;
+    ratelimits.add_demon().await?;
 
     let demon = FullDemon::create_from(data.0, &mut auth.connection).await?;
 
@@ -92,18 +129,21 @@ pub async fn post(
 #[rocket::patch("/<demon_id>/", data = "<patch>")]
 pub async fn patch(
     demon_id: i32, mut auth: Auth<ApiToken>, precondition: Precondition, patch: Json<PatchDemon>,
-) -> Result<Tagged<FullDemon>> {
+) -> Result<Response2<Tagged<FullDemon>>> {
     auth.require_permission(LIST_MODERATOR)?;
 
+    let last_modified = last_modified("demon", demon_id, &mut auth.connection).await?;
     let demon = FullDemon::by_id(demon_id, &mut auth.connection)
         .await?
-        .require_match(precondition)?
-        .apply_patch(patch.0, &mut auth.connection)
-        .await?;
+        .require_match(precondition, last_modified)?;
+    let etag = demon.etag_string();
+
+    let demon = demon.apply_patch(patch.0, &mut auth.connection).await?;
+    let last_modified = last_modified_after_patch(last_modified, demon.etag_string() != etag);
 
     auth.commit().await?;
 
-    Ok(Tagged(demon))
+    Ok(Response2::tagged(demon).with_last_modified(last_modified))
 }
 
 #[localized]
@@ -141,3 +181,21 @@ pub async fn delete_creator(demon_id: i32, player_id: i32, mut auth: Auth<ApiTok
 
     Ok(Status::NoContent)
 }
+
+/// Restores a deleted creator entry from the audit log
+#[localized]
+#[rocket::post("/<demon_id>/creators/<player_id>/restore/")]
+pub async fn restore_creator(demon_id: i32, player_id: i32, mut auth: Auth<ApiToken>) -> Result<Response2<Json<()>>> {
+    auth.require_permission(LIST_ADMINISTRATOR)?;
+
+    let demon = Demon::by_id(demon_id, &mut auth.connection).await?;
+    let player = DatabasePlayer::by_id(player_id, &mut auth.connection).await?;
+
+    Creator::restore(&demon.base, &player, &mut auth.connection).await?;
+
+    auth.commit().await?;
+
+    Ok(Response2::json(())
+        .status(Status::Created)
+        .with_header("Location", format!("/api/v2/demons/{}/creators/{}/", demon.base.id, player.id)))
+}
@@ -1,13 +1,13 @@
-use pointercrate_demonlist::config;
-use rocket::serde::json::Json;
+use pointercrate_demonlist::config::DemonlistConfig;
+use rocket::{serde::json::Json, State};
 use serde_json::{json, Value};
 
 #[rocket::get("/")]
-pub fn list_information() -> Json<Value> {
+pub fn list_information(config: &State<DemonlistConfig>) -> Json<Value> {
     let data = json! {
         {
-            "list_size": config::list_size(),
-            "extended_list_size": config::extended_list_size()
+            "list_size": config.list_size,
+            "extended_list_size": config.extended_list_size
         }
     };
 
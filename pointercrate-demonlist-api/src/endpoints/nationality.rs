@@ -1,13 +1,16 @@
 use pointercrate_core::pool::PointercratePool;
 use pointercrate_core_api::{error::Result, etag::Tagged, query::Query};
 use pointercrate_core_macros::localized;
-use pointercrate_demonlist::nationality::{Nationality, NationalityRankingPagination, NationalityRecord, RankedNation, Subdivision};
+use pointercrate_demonlist::{
+    config::DemonlistConfig,
+    nationality::{Nationality, NationalityRankingPagination, NationalityRecord, RankedNation, Subdivision},
+};
 use rocket::{serde::json::Json, State};
 
 #[localized]
 #[rocket::get("/<iso_code>/subdivisions/")]
 pub async fn subdivisions(pool: &State<PointercratePool>, iso_code: String) -> Result<Json<Vec<Subdivision>>> {
-    let mut connection = pool.connection().await?;
+    let mut connection = pool.read_connection().await?;
 
     // good code
     let nationality = Nationality::by_country_code_or_name(iso_code.to_uppercase().as_ref(), &mut connection).await?;
@@ -18,16 +21,18 @@ pub async fn subdivisions(pool: &State<PointercratePool>, iso_code: String) -> R
 #[localized]
 #[rocket::get("/ranking/")]
 pub async fn ranking(pool: &State<PointercratePool>, pagination: Query<NationalityRankingPagination>) -> Result<Json<Vec<RankedNation>>> {
-    Ok(Json(pagination.0.page(&mut *pool.connection().await?).await?))
+    Ok(Json(pagination.0.page(&mut *pool.read_connection().await?).await?))
 }
 
 #[localized]
 #[rocket::get("/<iso_code>/")]
-pub async fn nation(pool: &State<PointercratePool>, iso_code: String) -> Result<Tagged<NationalityRecord>> {
-    let mut connection = pool.connection().await?;
+pub async fn nation(
+    pool: &State<PointercratePool>, config: &State<DemonlistConfig>, iso_code: String,
+) -> Result<Tagged<NationalityRecord>> {
+    let mut connection = pool.read_connection().await?;
 
     // good code
     let nationality = Nationality::by_country_code_or_name(iso_code.to_uppercase().as_ref(), &mut connection).await?;
 
-    Ok(Tagged(nationality.upgrade(&mut connection).await?))
+    Ok(Tagged(nationality.upgrade(config, &mut connection).await?))
 }
@@ -1,24 +1,35 @@
 use crate::claims::AuthWithClaim;
-use pointercrate_core::pool::PointercratePool;
+use pointercrate_core::{
+    audit::{last_modified, last_modified_after_patch, AuditLogEntry},
+    cache::ResponseCache,
+    etag::Taggable,
+    pool::PointercratePool,
+};
 use pointercrate_core_api::{
     error::Result,
     etag::{Precondition, TaggableExt, Tagged},
+    export::{export_response, ExportFormat, ExportResponse},
     pagination::pagination_response,
     query::Query,
-    response::Response2,
+    response::{CachedResponse, Response2},
 };
 use pointercrate_core_macros::localized;
 use pointercrate_demonlist::{
     error::DemonlistError,
     player::{
+        audit::PlayerModificationData,
         claim::{ListedClaim, PatchPlayerClaim, PlayerClaim, PlayerClaimPagination},
         DatabasePlayer, FullPlayer, PatchPlayer, Player, PlayerPagination, RankedPlayer, RankingPagination,
     },
-    LIST_HELPER,
+    LIST_ADMINISTRATOR, LIST_HELPER, PLAYER_RANKING,
 };
 use pointercrate_user::{auth::ApiToken, MODERATOR};
 use pointercrate_user_api::auth::Auth;
-use rocket::{http::Status, serde::json::Json, State};
+use rocket::{
+    http::{uri::Origin, Status},
+    serde::json::Json,
+    State,
+};
 
 #[localized]
 #[rocket::get("/")]
@@ -35,52 +46,108 @@ pub async fn paginate(
         pagination.banned = Some(false);
     }
 
-    Ok(pagination_response("/api/v1/players/", pagination, &mut *pool.connection().await?).await?)
+    Ok(pagination_response("/api/v1/players/", pagination, &mut *pool.read_connection().await?).await?)
+}
+
+#[localized]
+#[rocket::get("/export/")]
+pub async fn export(
+    pool: &State<PointercratePool>, query: Query<PlayerPagination>, auth: Option<Auth<ApiToken>>, format: ExportFormat,
+) -> Result<ExportResponse> {
+    let mut pagination = query.0;
+
+    if !auth.is_some_and(|auth| auth.has_permission(LIST_HELPER)) {
+        pagination.banned = Some(false);
+    }
+
+    Ok(export_response::<_, Player, _>(pagination, pool.read_connection().await?, format)?)
 }
 
 #[localized]
 #[rocket::get("/ranking/")]
-pub async fn ranking(pool: &State<PointercratePool>, query: Query<RankingPagination>) -> Result<Response2<Json<Vec<RankedPlayer>>>> {
-    Ok(pagination_response("/api/v1/players/ranking/", query.0, &mut *pool.connection().await?).await?)
+pub async fn ranking(
+    pool: &State<PointercratePool>, cache: &State<ResponseCache>, uri: &Origin<'_>, query: Query<RankingPagination>,
+) -> Result<CachedResponse> {
+    let response = cache
+        .get_or_try_insert_with(PLAYER_RANKING, &uri.to_string(), || async {
+            pagination_response::<_, RankedPlayer>("/api/v1/players/ranking/", query.0, &mut *pool.connection().await?)
+                .await?
+                .into_cached()
+        })
+        .await?;
+
+    Ok(CachedResponse::clone(&response))
 }
 
 #[localized]
 #[rocket::get("/me/", rank = 0)]
-pub async fn get_me(auth: AuthWithClaim<ApiToken, false>) -> Result<Tagged<FullPlayer>> {
+pub async fn get_me(auth: AuthWithClaim<ApiToken, false>) -> Result<Response2<Tagged<FullPlayer>>> {
     let AuthWithClaim(mut auth, claim) = auth;
 
     let player = Player::by_id(claim.player.id, &mut auth.connection).await?;
     let full_player = player.upgrade(&mut auth.connection).await?;
+    let last_modified = last_modified("player", claim.player.id, &mut auth.connection).await?;
 
-    Ok(Tagged(full_player))
+    Ok(Response2::tagged(full_player).with_last_modified(last_modified))
 }
 
 #[localized]
 #[rocket::get("/<player_id>/")]
-pub async fn get(player_id: i32, pool: &State<PointercratePool>) -> Result<Tagged<FullPlayer>> {
-    let mut connection = pool.connection().await?;
+pub async fn get(player_id: i32, pool: &State<PointercratePool>) -> Result<Response2<Tagged<FullPlayer>>> {
+    let mut connection = pool.read_connection().await?;
 
-    Ok(Tagged(
-        Player::by_id(player_id, &mut connection).await?.upgrade(&mut connection).await?,
-    ))
+    let player = Player::by_id(player_id, &mut connection).await?.upgrade(&mut connection).await?;
+    let last_modified = last_modified("player", player_id, &mut connection).await?;
+
+    Ok(Response2::tagged(player).with_last_modified(last_modified))
+}
+
+#[localized]
+#[rocket::get("/<player_id>/audit/")]
+pub async fn audit(player_id: i32, mut auth: Auth<ApiToken>) -> Result<Json<Vec<AuditLogEntry<PlayerModificationData>>>> {
+    auth.require_permission(LIST_ADMINISTRATOR)?;
+
+    let log = pointercrate_demonlist::player::audit::audit_log_for_player(player_id, &mut auth.connection).await?;
+
+    if log.is_empty() {
+        return Err(DemonlistError::PlayerNotFound { player_id }.into());
+    }
+
+    Ok(Json(log))
 }
 
 #[localized]
 #[rocket::patch("/<player_id>/", data = "<patch>")]
 pub async fn patch(
     player_id: i32, mut auth: Auth<ApiToken>, precondition: Precondition, patch: Json<PatchPlayer>,
-) -> Result<Tagged<FullPlayer>> {
+) -> Result<Response2<Tagged<FullPlayer>>> {
+    let last_modified = last_modified("player", player_id, &mut auth.connection).await?;
     let player = Player::by_id(player_id, &mut auth.connection)
         .await?
         .upgrade(&mut auth.connection)
         .await?
-        .require_match(precondition)?
-        .apply_patch(patch.0, &mut auth.connection)
-        .await?;
+        .require_match(precondition, last_modified)?;
+    let etag = player.etag_string();
+
+    let player = player.apply_patch(patch.0, &mut auth.connection).await?;
+    let last_modified = last_modified_after_patch(last_modified, player.etag_string() != etag);
+
+    auth.commit().await?;
+
+    Ok(Response2::tagged(player).with_last_modified(last_modified))
+}
+
+/// Restores a player that was deleted (due to being merged into another player) from the audit log
+#[localized]
+#[rocket::post("/<player_id>/restore/")]
+pub async fn restore(player_id: i32, mut auth: Auth<ApiToken>) -> Result<Response2<Tagged<FullPlayer>>> {
+    auth.require_permission(LIST_ADMINISTRATOR)?;
+
+    let player = DatabasePlayer::restore(player_id, &mut auth.connection).await?;
 
     auth.commit().await?;
 
-    Ok(Tagged(player))
+    Ok(Response2::tagged(player).status(Status::Created))
 }
 
 #[localized]
@@ -1,20 +1,27 @@
-use crate::ratelimits::DemonlistRatelimits;
+use crate::{config::DemonlistApiConfig, ratelimits::DemonlistRatelimits};
 use log::{debug, error, warn};
-use pointercrate_core::{audit::AuditLogEntry, error::CoreError, pool::PointercratePool};
+use pointercrate_core::{
+    audit::{last_modified, last_modified_after_patch, AuditLogEntry, ServiceAccount},
+    error::CoreError,
+    etag::Taggable,
+    pool::PointercratePool,
+};
 use pointercrate_core_api::{
     error::Result,
     etag::{Precondition, TaggableExt, Tagged},
+    export::{export_response, ExportFormat, ExportResponse},
     pagination::pagination_response,
     query::Query,
     response::Response2,
 };
 use pointercrate_core_macros::localized;
 use pointercrate_demonlist::{
+    config::DemonlistConfig,
     error::DemonlistError,
     player::claim::PlayerClaim,
     record::{
         audit::RecordModificationData,
-        note::{notes_on, NewNote, Note, PatchNote},
+        note::{audit::NoteModificationData, notes_on, NewNote, Note, PatchNote},
         submission_count, FullRecord, MinimalRecordPD, PatchRecord, RecordPagination, RecordStatus, Submission,
     },
     submitter::Submitter,
@@ -40,6 +47,55 @@ use std::net::IpAddr;
 pub async fn paginate(mut auth: Auth<ApiToken>, query: Query<RecordPagination>) -> Result<Response2<Json<Vec<MinimalRecordPD>>>> {
     let mut pagination = query.0;
 
+    restrict_authed_query(&mut pagination, &mut auth).await?;
+
+    Ok(pagination_response("/api/v1/records/", pagination, &mut auth.connection).await?)
+}
+
+#[localized]
+#[rocket::get("/", rank = 1)]
+pub async fn unauthed_pagination(
+    pool: &State<PointercratePool>, query: Query<RecordPagination>,
+) -> Result<Response2<Json<Vec<MinimalRecordPD>>>> {
+    let mut connection = pool.read_connection().await?;
+    let mut pagination = query.0;
+
+    restrict_unauthed_query(&mut pagination)?;
+
+    Ok(pagination_response("/api/v1/records/", pagination, &mut connection).await?)
+}
+
+/// Export endpoint for records in case authentication is provided
+///
+/// Subject to the same constraints as the [pagination endpoint](paginate)
+#[localized]
+#[rocket::get("/export/")]
+pub async fn export(mut auth: Auth<ApiToken>, query: Query<RecordPagination>, format: ExportFormat) -> Result<ExportResponse> {
+    let mut pagination = query.0;
+
+    restrict_authed_query(&mut pagination, &mut auth).await?;
+
+    Ok(export_response::<_, MinimalRecordPD, _>(pagination, auth.connection, format)?)
+}
+
+#[localized]
+#[rocket::get("/export/", rank = 1)]
+pub async fn unauthed_export(
+    pool: &State<PointercratePool>, query: Query<RecordPagination>, format: ExportFormat,
+) -> Result<ExportResponse> {
+    let mut pagination = query.0;
+
+    restrict_unauthed_query(&mut pagination)?;
+
+    Ok(export_response::<_, MinimalRecordPD, _>(
+        pagination,
+        pool.read_connection().await?,
+        format,
+    )?)
+}
+
+/// Applies the constraints documented on [`paginate`] to the given query
+async fn restrict_authed_query(pagination: &mut RecordPagination, auth: &mut Auth<ApiToken>) -> Result<()> {
     if pagination.submitter.is_some() {
         auth.require_permission(LIST_MODERATOR)?;
     }
@@ -56,17 +112,11 @@ pub async fn paginate(mut auth: Auth<ApiToken>, query: Query<RecordPagination>)
         pagination.status = Some(RecordStatus::Approved);
     }
 
-    Ok(pagination_response("/api/v1/records/", pagination, &mut auth.connection).await?)
+    Ok(())
 }
 
-#[localized]
-#[rocket::get("/", rank = 1)]
-pub async fn unauthed_pagination(
-    pool: &State<PointercratePool>, query: Query<RecordPagination>,
-) -> Result<Response2<Json<Vec<MinimalRecordPD>>>> {
-    let mut connection = pool.connection().await?;
-    let mut pagination = query.0;
-
+/// Restricts the given query to approved records, and rejects it if it filters by submitter
+fn restrict_unauthed_query(pagination: &mut RecordPagination) -> Result<()> {
     if pagination.submitter.is_some() {
         return Err(CoreError::Unauthorized.into());
     }
@@ -77,14 +127,14 @@ pub async fn unauthed_pagination(
 
     pagination.status = Some(RecordStatus::Approved);
 
-    Ok(pagination_response("/api/v1/records/", pagination, &mut connection).await?)
+    Ok(())
 }
 
 #[localized]
 #[rocket::post("/", data = "<submission>")]
 pub async fn submit(
     ip: IpAddr, auth: Option<Auth<ApiToken>>, submission: Json<Submission>, pool: &State<PointercratePool>,
-    ratelimits: &State<DemonlistRatelimits>,
+    ratelimits: &State<DemonlistRatelimits>, config: &State<DemonlistConfig>, api_config: &State<DemonlistApiConfig>,
 ) -> Result<Response2<Tagged<FullRecord>>> {
     let submission = submission.0;
     let status_is_submitted = submission.status() == RecordStatus::Submitted;
@@ -105,10 +155,13 @@ pub async fn submit(
         None => pool.transaction().await?,
     };
 
+    // The status of the most restrictive ratelimit applied to this request, reported to the client via the RateLimit headers
+    let mut ratelimit = None;
+
     let submitter = match Submitter::by_ip(ip, &mut connection).await? {
         Some(submitter) => submitter,
         None => {
-            ratelimits.new_submitters()?;
+            ratelimit = Some(ratelimits.new_submitters().await?);
 
             Submitter::create_submitter(ip, &mut connection).await?
         },
@@ -131,15 +184,21 @@ pub async fn submit(
         }
     }
 
-    let validated = normalized.validate(&mut connection).await?;
+    let validated = normalized.validate(config, &mut connection).await?;
 
     if !is_team_member {
         // Check ratelimits before any change is made to the database so that the transaction rollback is
         // easier.
 
         // Also check the local ratelimit first since that one expires earlier
-        ratelimits.record_submission(ip)?;
-        ratelimits.record_submission_global()?;
+        let local = ratelimits.record_submission(ip).await?;
+        let global = ratelimits.record_submission_global().await?;
+
+        ratelimit = Some(
+            ratelimit
+                .map_or(local, |status| status.most_restrictive(local))
+                .most_restrictive(global),
+        );
     }
 
     let mut record = validated.create(submitter, &mut connection).await?;
@@ -153,7 +212,8 @@ pub async fn submit(
                 record.id,
                 video.to_string(),
                 webhook_embed(&record),
-                pool.connection().await?,
+                api_config.submission_webhook.clone(),
+                pool.connection_as(ServiceAccount::VIDEO_CHECKER).await?,
             ));
         }
     }
@@ -164,6 +224,10 @@ pub async fn submit(
 
     let mut response = Response2::tagged(record);
 
+    if let Some(ratelimit) = ratelimit {
+        response = response.with_ratelimit(ratelimit);
+    }
+
     if status_is_submitted {
         response = response.with_header(
             "X-SUBMISSION-COUNT",
@@ -176,7 +240,7 @@ pub async fn submit(
 
 #[localized]
 #[rocket::get("/<record_id>/")]
-pub async fn get(record_id: i32, auth: Option<Auth<ApiToken>>, pool: &State<PointercratePool>) -> Result<Tagged<FullRecord>> {
+pub async fn get(record_id: i32, auth: Option<Auth<ApiToken>>, pool: &State<PointercratePool>) -> Result<Response2<Tagged<FullRecord>>> {
     let is_helper = auth.as_ref().is_some_and(|auth| auth.has_permission(LIST_HELPER));
 
     let mut connection = match auth {
@@ -195,7 +259,9 @@ pub async fn get(record_id: i32, auth: Option<Auth<ApiToken>>, pool: &State<Poin
         record.raw_footage = None;
     }
 
-    Ok(Tagged(record))
+    let last_modified = last_modified("record", record_id, &mut connection).await?;
+
+    Ok(Response2::tagged(record).with_last_modified(last_modified))
 }
 
 #[localized]
@@ -215,24 +281,26 @@ pub async fn audit(record_id: i32, mut auth: Auth<ApiToken>) -> Result<Json<Vec<
 #[localized]
 #[rocket::patch("/<record_id>/", data = "<patch>")]
 pub async fn patch(
-    record_id: i32, mut auth: Auth<ApiToken>, precondition: Precondition, patch: Json<PatchRecord>,
-) -> Result<Tagged<FullRecord>> {
+    record_id: i32, mut auth: Auth<ApiToken>, precondition: Precondition, patch: Json<PatchRecord>, config: &State<DemonlistConfig>,
+) -> Result<Response2<Tagged<FullRecord>>> {
     let record = FullRecord::by_id(record_id, &mut auth.connection).await?;
 
-    if record.demon.position > pointercrate_demonlist::config::extended_list_size() {
+    if record.demon.position > config.extended_list_size {
         auth.require_permission(LIST_MODERATOR)?;
     } else {
         auth.require_permission(LIST_HELPER)?;
     }
 
-    let record = record
-        .require_match(precondition)?
-        .apply_patch(patch.0, &mut auth.connection)
-        .await?;
+    let last_modified = last_modified("record", record_id, &mut auth.connection).await?;
+    let record = record.require_match(precondition, last_modified)?;
+    let etag = record.etag_string();
+
+    let record = record.apply_patch(patch.0, &mut auth.connection).await?;
+    let last_modified = last_modified_after_patch(last_modified, record.etag_string() != etag);
 
     auth.commit().await?;
 
-    Ok(Tagged(record))
+    Ok(Response2::tagged(record).with_last_modified(last_modified))
 }
 
 #[localized]
@@ -246,7 +314,7 @@ pub async fn delete(record_id: i32, mut auth: Auth<ApiToken>, precondition: Prec
         auth.require_permission(LIST_MODERATOR)?;
     }
 
-    precondition.require_etag_match(&record)?;
+    precondition.require(&record, last_modified("record", record_id, &mut auth.connection).await?)?;
 
     record.delete(&mut auth.connection).await?;
     auth.commit().await?;
@@ -254,6 +322,19 @@ pub async fn delete(record_id: i32, mut auth: Auth<ApiToken>, precondition: Prec
     Ok(Status::NoContent)
 }
 
+/// Restores a deleted record (and the notes that were deleted with it) from the audit log
+#[localized]
+#[rocket::post("/<record_id>/restore/")]
+pub async fn restore(record_id: i32, mut auth: Auth<ApiToken>) -> Result<Response2<Tagged<FullRecord>>> {
+    auth.require_permission(LIST_ADMINISTRATOR)?;
+
+    let record = FullRecord::restore(record_id, &mut auth.connection).await?;
+
+    auth.commit().await?;
+
+    Ok(Response2::tagged(record).status(Status::Created))
+}
+
 #[localized]
 #[rocket::get("/<record_id>/notes/")]
 pub async fn get_notes(record_id: i32, mut auth: Auth<ApiToken>) -> Result<Response2<Json<Vec<Note>>>> {
@@ -320,6 +401,20 @@ pub async fn patch_note(record_id: i32, note_id: i32, mut auth: Auth<ApiToken>,
     Ok(Tagged(note))
 }
 
+#[localized]
+#[rocket::get("/<record_id>/notes/<note_id>/audit/")]
+pub async fn note_audit(record_id: i32, note_id: i32, mut auth: Auth<ApiToken>) -> Result<Json<Vec<AuditLogEntry<NoteModificationData>>>> {
+    auth.require_permission(LIST_ADMINISTRATOR)?;
+
+    let log = pointercrate_demonlist::record::note::audit::audit_log_for_note(record_id, note_id, &mut auth.connection).await?;
+
+    if log.is_empty() {
+        return Err(DemonlistError::NoteNotFound { note_id, record_id }.into());
+    }
+
+    Ok(Json(log))
+}
+
 #[localized]
 #[rocket::delete("/<record_id>/notes/<note_id>/")]
 pub async fn delete_note(record_id: i32, note_id: i32, mut auth: Auth<ApiToken>) -> Result<Status> {
@@ -338,7 +433,9 @@ pub async fn delete_note(record_id: i32, note_id: i32, mut auth: Auth<ApiToken>)
     Ok(Status::NoContent)
 }
 
-async fn validate(record_id: i32, video: String, body: serde_json::Value, mut connection: PoolConnection<Postgres>) {
+async fn validate(
+    record_id: i32, video: String, body: serde_json::Value, webhook_url: Option<String>, mut connection: PoolConnection<Postgres>,
+) {
     debug!("Verifying that submission {} with video {} actually is valid", record_id, video);
 
     match reqwest::get(&video).await {
@@ -348,7 +445,7 @@ async fn validate(record_id: i32, video: String, body: serde_json::Value, mut co
             if (200..400).contains(&status) {
                 debug!("GET request yielded some sort of successful response, executing webhook");
 
-                execute_webhook(body).await;
+                execute_webhook(body, webhook_url).await;
             } else {
                 warn!("Server response to 'GET {}' was {:?}, deleting submission!", video, response);
 
@@ -372,8 +469,8 @@ async fn validate(record_id: i32, video: String, body: serde_json::Value, mut co
     }
 }
 
-async fn execute_webhook(body: serde_json::Value) {
-    if let Some(ref webhook_url) = crate::config::submission_webhook() {
+async fn execute_webhook(body: serde_json::Value, webhook_url: Option<String>) {
+    if let Some(ref webhook_url) = webhook_url {
         match reqwest::Client::new()
             .post(webhook_url)
             .header("Content-Type", "application/json")
@@ -1,3 +1,7 @@
+use pointercrate_core::{
+    audit::{last_modified, last_modified_after_patch, AuditLogEntry},
+    etag::Taggable,
+};
 use pointercrate_core_api::{
     error::Result,
     etag::{Precondition, TaggableExt, Tagged},
@@ -7,8 +11,8 @@ use pointercrate_core_api::{
 };
 use pointercrate_core_macros::localized;
 use pointercrate_demonlist::{
-    submitter::{PatchSubmitter, Submitter, SubmitterPagination},
-    LIST_MODERATOR,
+    submitter::{audit::SubmitterModificationData, PatchSubmitter, Submitter, SubmitterPagination},
+    LIST_ADMINISTRATOR, LIST_MODERATOR,
 };
 use pointercrate_user::auth::ApiToken;
 use pointercrate_user_api::auth::Auth;
@@ -24,26 +28,47 @@ pub async fn paginate(mut auth: Auth<ApiToken>, pagination: Query<SubmitterPagin
 
 #[localized]
 #[rocket::get("/<submitter_id>/")]
-pub async fn get(submitter_id: i32, mut auth: Auth<ApiToken>) -> Result<Tagged<Submitter>> {
+pub async fn get(submitter_id: i32, mut auth: Auth<ApiToken>) -> Result<Response2<Tagged<Submitter>>> {
     auth.require_permission(LIST_MODERATOR)?;
 
-    Ok(Tagged(Submitter::by_id(submitter_id, &mut auth.connection).await?))
+    let submitter = Submitter::by_id(submitter_id, &mut auth.connection).await?;
+    let last_modified = last_modified("submitter", submitter_id, &mut auth.connection).await?;
+
+    Ok(Response2::tagged(submitter).with_last_modified(last_modified))
+}
+
+#[localized]
+#[rocket::get("/<submitter_id>/audit/")]
+pub async fn audit(submitter_id: i32, mut auth: Auth<ApiToken>) -> Result<Json<Vec<AuditLogEntry<SubmitterModificationData>>>> {
+    auth.require_permission(LIST_ADMINISTRATOR)?;
+
+    let log = pointercrate_demonlist::submitter::audit::audit_log_for_submitter(submitter_id, &mut auth.connection).await?;
+
+    // Submitters that were never modified have no audit log entries at all
+    if log.is_empty() {
+        Submitter::by_id(submitter_id, &mut auth.connection).await?;
+    }
+
+    Ok(Json(log))
 }
 
 #[localized]
 #[rocket::patch("/<submitter_id>/", data = "<patch>")]
 pub async fn patch(
     submitter_id: i32, precondition: Precondition, mut auth: Auth<ApiToken>, patch: Json<PatchSubmitter>,
-) -> Result<Tagged<Submitter>> {
+) -> Result<Response2<Tagged<Submitter>>> {
     auth.require_permission(LIST_MODERATOR)?;
 
+    let last_modified = last_modified("submitter", submitter_id, &mut auth.connection).await?;
     let submitter = Submitter::by_id(submitter_id, &mut auth.connection)
         .await?
-        .require_match(precondition)?
-        .apply_patch(patch.0, &mut auth.connection)
-        .await?;
+        .require_match(precondition, last_modified)?;
+    let etag = submitter.etag_string();
+
+    let submitter = submitter.apply_patch(patch.0, &mut auth.connection).await?;
+    let last_modified = last_modified_after_patch(last_modified, submitter.etag_string() != etag);
 
     auth.commit().await?;
 
-    Ok(Tagged(submitter))
+    Ok(Response2::tagged(submitter).with_last_modified(last_modified))
 }
@@ -1,33 +1,43 @@
-use crate::{endpoints::misc, ratelimits::DemonlistRatelimits};
-use pointercrate_core::pool::PointercratePool;
+use crate::{config::DemonlistApiConfig, endpoints::misc, ratelimits::DemonlistRatelimits};
+use pointercrate_core::{pool::PointercratePool, ratelimits::PostgresRatelimitStore};
+use pointercrate_core_api::{maintenance::register_route_groups, ratelimits::register_ratelimits};
 use pointercrate_integrate::gd::GeometryDashConnector;
 use rocket::{Build, Rocket};
 
 pub(crate) mod claims;
-pub(crate) mod config;
+pub mod config;
 mod endpoints;
 #[cfg(feature = "geolocation")]
 mod geolocate;
 pub(crate) mod pages;
 pub(crate) mod ratelimits;
+pub mod route_groups;
 
 #[cfg(feature = "geolocation")]
 pub use geolocate::GeolocationProvider;
 
 pub fn setup(rocket: Rocket<Build>) -> Rocket<Build> {
-    let ratelimits = DemonlistRatelimits::new();
-    let dash_rs = GeometryDashConnector::new(rocket.state::<PointercratePool>().unwrap().clone_inner());
+    let pool = rocket.state::<PointercratePool>().unwrap().clone_inner();
+    let ratelimits = DemonlistRatelimits::with_store(PostgresRatelimitStore::new(pool.clone()));
+    let dash_rs = GeometryDashConnector::new(pool);
 
-    if let Some(endpoint) = config::gd_connector_endpoint() {
-        pointercrate_integrate::set_gd_connector_endpoint(endpoint);
+    let config = rocket
+        .state::<DemonlistApiConfig>()
+        .expect("DemonlistApiConfig needs to be managed before setting up the demonlist API");
+
+    if let Some(ref endpoint) = config.gd_connector_endpoint {
+        pointercrate_integrate::set_gd_connector_endpoint(endpoint.clone());
     }
 
     #[cfg_attr(not(feature = "geolocation"), allow(unused_mut))]
     let mut player_routes = rocket::routes![
         endpoints::player::get,
+        endpoints::player::audit,
         endpoints::player::get_me,
         endpoints::player::paginate,
+        endpoints::player::export,
         endpoints::player::patch,
+        endpoints::player::restore,
         endpoints::player::ranking,
         endpoints::player::put_claim,
         endpoints::player::patch_claim,
@@ -38,6 +48,13 @@ pub fn setup(rocket: Rocket<Build>) -> Rocket<Build> {
     #[cfg(feature = "geolocation")]
     player_routes.extend(rocket::routes![endpoints::player::geolocate_nationality]);
 
+    let rocket = register_ratelimits(rocket, ratelimits.set().clone());
+    let rocket = register_ratelimits(rocket, dash_rs.ratelimits().clone());
+    let rocket = register_route_groups(
+        rocket,
+        &[route_groups::RECORD_SUBMISSIONS, route_groups::DEMON_EDITS, route_groups::CLAIMS],
+    );
+
     rocket
         .manage(ratelimits)
         .manage(dash_rs)
@@ -47,6 +64,7 @@ pub fn setup(rocket: Rocket<Build>) -> Rocket<Build> {
             rocket::routes![
                 endpoints::submitter::paginate,
                 endpoints::submitter::get,
+                endpoints::submitter::audit,
                 endpoints::submitter::patch
             ],
         )
@@ -54,6 +72,7 @@ pub fn setup(rocket: Rocket<Build>) -> Rocket<Build> {
             "/api/v1/records/",
             rocket::routes![
                 endpoints::record::get_notes,
+                endpoints::record::note_audit,
                 endpoints::record::add_note,
                 endpoints::record::audit,
                 endpoints::record::delete,
@@ -61,8 +80,11 @@ pub fn setup(rocket: Rocket<Build>) -> Rocket<Build> {
                 endpoints::record::get,
                 endpoints::record::paginate,
                 endpoints::record::unauthed_pagination,
+                endpoints::record::export,
+                endpoints::record::unauthed_export,
                 endpoints::record::patch,
                 endpoints::record::patch_note,
+                endpoints::record::restore,
                 endpoints::record::submit
             ],
         )
@@ -81,12 +103,15 @@ pub fn setup(rocket: Rocket<Build>) -> Rocket<Build> {
                 endpoints::demon::get,
                 endpoints::demon::paginate,
                 endpoints::demon::paginate_listed,
+                endpoints::demon::export,
+                endpoints::demon::export_listed,
                 endpoints::demon::audit,
                 endpoints::demon::movement_log,
                 endpoints::demon::patch,
                 endpoints::demon::post,
                 endpoints::demon::post_creator,
-                endpoints::demon::delete_creator
+                endpoints::demon::delete_creator,
+                endpoints::demon::restore_creator
             ],
         )
         .mount(
@@ -4,7 +4,7 @@ use pointercrate_core_macros::localized;
 use rocket::{response::Redirect, State};
 
 use chrono::{DateTime, Datelike, FixedOffset, NaiveDate, Utc};
-use pointercrate_core::{audit::AuditLogEntryType, pool::PointercratePool};
+use pointercrate_core::{audit::AuditLogEntryType, cache::ResponseCache, pool::PointercratePool};
 use pointercrate_core_api::{
     error::Result,
     response::{Page, Response2},
@@ -12,10 +12,11 @@ use pointercrate_core_api::{
 use pointercrate_demonlist::player::claim::PlayerClaim;
 use pointercrate_demonlist::player::{FullPlayer, Player};
 use pointercrate_demonlist::{
+    config::DemonlistConfig,
     demon::{audit::audit_log_for_demon, current_list, list_at, FullDemon, MinimalDemon},
     error::DemonlistError,
     nationality::Nationality,
-    LIST_ADMINISTRATOR, LIST_HELPER, LIST_MODERATOR,
+    LISTED_DEMONS, LIST_ADMINISTRATOR, LIST_HELPER, LIST_MODERATOR,
 };
 use pointercrate_demonlist_pages::{
     components::{team::Team, time_machine::Tardis},
@@ -34,15 +35,21 @@ use sqlx::PgConnection;
 #[localized]
 #[rocket::get("/?<timemachine>&<submitter>")]
 pub async fn overview(
-    pool: &State<PointercratePool>, timemachine: Option<bool>, submitter: Option<bool>, cookies: &CookieJar<'_>,
-    auth: Option<Auth<NonMutating>>,
+    pool: &State<PointercratePool>, cache: &State<ResponseCache>, config: &State<DemonlistConfig>, timemachine: Option<bool>,
+    submitter: Option<bool>, cookies: &CookieJar<'_>, auth: Option<Auth<NonMutating>>,
 ) -> Result<Page> {
     // A few months before pointercrate first went live - definitely the oldest data we have
     let beginning_of_time = NaiveDate::from_ymd_opt(2017, 1, 4).unwrap().and_hms_opt(0, 0, 0).unwrap();
 
-    let mut connection = pool.connection().await?;
+    let mut connection = pool.read_connection().await?;
 
-    let demonlist = current_list(&mut connection).await?;
+    // Everything else on this page depends on the user (or their time machine settings). The cached list is read from the
+    // primary database, as a lagging replica could get stale data cached.
+    let demonlist = cache
+        .get_or_try_insert_with(LISTED_DEMONS, "/demonlist/", || async {
+            current_list(&mut *pool.connection().await.map_err(DemonlistError::from)?).await
+        })
+        .await?;
 
     let mut specified_when = cookies
         .get("when")
@@ -82,13 +89,14 @@ pub async fn overview(
             moderators: User::by_permission(LIST_MODERATOR, &mut connection).await?,
             helpers: User::by_permission(LIST_HELPER, &mut connection).await?,
         },
-        demonlist,
+        demonlist: Vec::clone(&demonlist),
         time_machine: tardis,
         submitter_initially_visible: submitter.unwrap_or(false),
         claimed_player: match auth {
             Some(auth) => claimed_full_player(auth.user.user(), &mut connection).await,
             None => None,
         },
+        list_config: **config,
     }))
 }
 
@@ -101,7 +109,7 @@ async fn claimed_full_player(user: &User, connection: &mut PgConnection) -> Opti
 
 #[rocket::get("/permalink/<demon_id>/")]
 pub async fn demon_permalink(demon_id: i32, pool: &State<PointercratePool>) -> Result<Redirect> {
-    let mut connection = pool.connection().await?;
+    let mut connection = pool.read_connection().await?;
 
     let position = MinimalDemon::by_id(demon_id, &mut connection).await?.position;
 
@@ -110,8 +118,10 @@ pub async fn demon_permalink(demon_id: i32, pool: &State<PointercratePool>) -> R
 
 #[localized]
 #[rocket::get("/<position>/")]
-pub async fn demon_page(position: i16, pool: &State<PointercratePool>, gd: &State<GeometryDashConnector>) -> Result<Page> {
-    let mut connection = pool.connection().await?;
+pub async fn demon_page(
+    position: i16, pool: &State<PointercratePool>, gd: &State<GeometryDashConnector>, config: &State<DemonlistConfig>,
+) -> Result<Page> {
+    let mut connection = pool.read_connection().await?;
 
     let full_demon = FullDemon::by_position(position, &mut connection).await?;
 
@@ -161,13 +171,14 @@ pub async fn demon_page(position: i16, pool: &State<PointercratePool>, gd: &Stat
         movements: modifications,
         integration: gd.load_level_for_demon(&full_demon.demon).await,
         data: full_demon,
+        list_config: **config,
     }))
 }
 
 #[localized]
 #[rocket::get("/statsviewer/")]
 pub async fn stats_viewer(pool: &State<PointercratePool>) -> Result<Page> {
-    let mut connection = pool.connection().await?;
+    let mut connection = pool.read_connection().await?;
 
     Ok(Page::new(IndividualStatsViewer {
         nationalities_in_use: Nationality::used(&mut connection).await?,
@@ -183,7 +194,7 @@ pub async fn nation_stats_viewer() -> Page {
 #[localized]
 #[rocket::get("/statsviewer/heatmap.css")]
 pub async fn heatmap_css(pool: &State<PointercratePool>) -> Result<Response2<String>> {
-    let mut connection = pool.connection().await?;
+    let mut connection = pool.read_connection().await?;
     let mut css = String::new();
 
     let mut nation_scores = HashMap::new();
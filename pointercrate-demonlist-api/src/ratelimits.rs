@@ -4,7 +4,7 @@ use std::net::IpAddr;
 
 ratelimits! {
     DemonlistRatelimits {
-        record_submission[3u32 per 1200 per IpAddr] => tr("error-demonlist-ratelimit-submit"),
+        record_submission[3u32 per 1200 per IpAddr] => tr("error-demonlist-ratelimit-record-submit"),
 
         record_submission_global[20u32 per 3600] => tr("error-demonlist-ratelimit-record-submit-global"),
 
@@ -19,14 +19,14 @@ mod test {
     use crate::ratelimits::DemonlistRatelimits;
     use pointercrate_core::error::CoreError;
 
-    #[test]
-    fn test_non_burst_ratelimit() {
+    #[rocket::async_test]
+    async fn test_non_burst_ratelimit() {
         let ratelimits = DemonlistRatelimits::new();
-        let pass = ratelimits.add_demon();
+        let pass = ratelimits.add_demon().await;
 
         assert!(pass.is_ok());
 
-        let fail = ratelimits.add_demon();
+        let fail = ratelimits.add_demon().await;
 
         assert!(fail.is_err());
 
@@ -40,15 +40,15 @@ mod test {
         }
     }
 
-    #[test]
-    fn test_burst_ratelimits() {
+    #[rocket::async_test]
+    async fn test_burst_ratelimits() {
         let ratelimits = DemonlistRatelimits::new();
 
         for _ in 1..=7 {
-            assert!(ratelimits.new_submitters().is_ok());
+            assert!(ratelimits.new_submitters().await.is_ok());
         }
 
-        let fail = ratelimits.new_submitters();
+        let fail = ratelimits.new_submitters().await;
 
         assert!(fail.is_err());
 
@@ -0,0 +1,28 @@
+//! [`RouteGroup`]s of the demonlist that administrators can freeze for maintenance
+
+use pointercrate_core_api::maintenance::RouteGroup;
+use rocket::http::Method;
+
+/// Submission of new records. Since moderators add records via the same endpoint, this also prevents them from doing so.
+pub const RECORD_SUBMISSIONS: RouteGroup = RouteGroup::new("route-group-record-submissions", &[(Method::Post, "/api/v1/records/")]);
+
+/// Adding and modifying demons, including their creators
+pub const DEMON_EDITS: RouteGroup = RouteGroup::new(
+    "route-group-demon-edits",
+    &[
+        (Method::Post, "/api/v2/demons/<_..>"),
+        (Method::Patch, "/api/v2/demons/<_..>"),
+        (Method::Delete, "/api/v2/demons/<_..>"),
+    ],
+);
+
+/// Initiating, verifying and removing player claims, as well as claim geolocation
+pub const CLAIMS: RouteGroup = RouteGroup::new(
+    "route-group-claims",
+    &[
+        (Method::Put, "/api/v1/players/<_>/claims/"),
+        (Method::Patch, "/api/v1/players/<_>/claims/<_>/"),
+        (Method::Delete, "/api/v1/players/<_>/claims/<_>/"),
+        (Method::Post, "/api/v1/players/me/geolocate/"),
+    ],
+);
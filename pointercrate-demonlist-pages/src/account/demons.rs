@@ -4,7 +4,10 @@ use pointercrate_core::{localization::tr, permission::PermissionsManager};
 use pointercrate_core_pages::trp_html;
 use pointercrate_core_pages::util::filtered_paginator;
 use pointercrate_demonlist::LIST_MODERATOR;
-use pointercrate_user::auth::{AuthenticatedUser, NonMutating};
+use pointercrate_user::{
+    auth::{AuthenticatedUser, NonMutating},
+    User,
+};
 use pointercrate_user_pages::account::AccountPageTab;
 use sqlx::PgConnection;
 
@@ -12,8 +15,8 @@ pub struct DemonsTab;
 
 #[async_trait::async_trait]
 impl AccountPageTab for DemonsTab {
-    fn should_display_for(&self, permissions_we_have: u16, permissions: &PermissionsManager) -> bool {
-        permissions.require_permission(permissions_we_have, LIST_MODERATOR).is_ok()
+    fn should_display_for(&self, user: &User, permissions: &PermissionsManager) -> bool {
+        permissions.require_permission(user, LIST_MODERATOR).is_ok()
     }
 
     fn initialization_script(&self) -> String {
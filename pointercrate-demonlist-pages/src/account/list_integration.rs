@@ -10,7 +10,7 @@ use pointercrate_core_pages::{
 use pointercrate_demonlist::player::claim::PlayerClaim;
 use pointercrate_user::{
     auth::{AuthenticatedUser, NonMutating},
-    MODERATOR,
+    User, MODERATOR,
 };
 use pointercrate_user_pages::account::AccountPageTab;
 use sqlx::PgConnection;
@@ -19,7 +19,7 @@ pub struct ListIntegrationTab(#[doc = "discord invite url"] pub &'static str);
 
 #[async_trait::async_trait]
 impl AccountPageTab for ListIntegrationTab {
-    fn should_display_for(&self, _permissions_we_have: u16, _permissions: &PermissionsManager) -> bool {
+    fn should_display_for(&self, _user: &User, _permissions: &PermissionsManager) -> bool {
         true
     }
 
@@ -57,7 +57,7 @@ impl AccountPageTab for ListIntegrationTab {
                 .body();
             },
         };
-        let is_moderator = permissions.require_permission(user.user().permissions, MODERATOR).is_ok();
+        let is_moderator = permissions.require_permission(user.user(), MODERATOR).is_ok();
 
         html! {
             div.left {
@@ -9,19 +9,23 @@ use pointercrate_core_pages::{
     util::{dropdown, paginator},
 };
 use pointercrate_demonlist::{
+    config::DemonlistConfig,
     demon::{current_list, Demon},
     LIST_HELPER,
 };
-use pointercrate_user::auth::{AuthenticatedUser, NonMutating};
+use pointercrate_user::{
+    auth::{AuthenticatedUser, NonMutating},
+    User,
+};
 use pointercrate_user_pages::account::AccountPageTab;
 use sqlx::PgConnection;
 
-pub struct RecordsPage;
+pub struct RecordsPage(pub DemonlistConfig);
 
 #[async_trait::async_trait]
 impl AccountPageTab for RecordsPage {
-    fn should_display_for(&self, permissions_we_have: u16, permissions: &PermissionsManager) -> bool {
-        permissions.require_permission(permissions_we_have, LIST_HELPER).is_ok()
+    fn should_display_for(&self, user: &User, permissions: &PermissionsManager) -> bool {
+        permissions.require_permission(user, LIST_HELPER).is_ok()
     }
 
     fn initialization_script(&self) -> String {
@@ -59,7 +63,7 @@ impl AccountPageTab for RecordsPage {
 
         html! {
             div.left {
-                (RecordSubmitter::new(false, &demons[..]))
+                (RecordSubmitter::new(false, &demons[..], &self.0))
                 (record_manager(&demons[..]))
                 (note_adder())
                 div.panel.fade #record-notes-container style = "display:none" {
@@ -2,7 +2,10 @@ use maud::{html, Markup, PreEscaped};
 use pointercrate_core::{localization::tr, permission::PermissionsManager};
 use pointercrate_core_pages::util::paginator;
 use pointercrate_demonlist::LIST_MODERATOR;
-use pointercrate_user::auth::{AuthenticatedUser, NonMutating};
+use pointercrate_user::{
+    auth::{AuthenticatedUser, NonMutating},
+    User,
+};
 use pointercrate_user_pages::account::AccountPageTab;
 use sqlx::PgConnection;
 
@@ -10,8 +13,8 @@ pub struct SubmittersPage;
 
 #[async_trait::async_trait]
 impl AccountPageTab for SubmittersPage {
-    fn should_display_for(&self, permissions_we_have: u16, permissions: &PermissionsManager) -> bool {
-        permissions.require_permission(permissions_we_have, LIST_MODERATOR).is_ok()
+    fn should_display_for(&self, user: &User, permissions: &PermissionsManager) -> bool {
+        permissions.require_permission(user, LIST_MODERATOR).is_ok()
     }
 
     fn initialization_script(&self) -> String {
@@ -2,18 +2,20 @@ use crate::components::{demon_dropdown, player_selection_dropdown};
 use maud::{html, Markup, Render};
 use pointercrate_core::{localization::tr, trp};
 use pointercrate_core_pages::trp_html;
-use pointercrate_demonlist::{config, demon::Demon};
+use pointercrate_demonlist::{config::DemonlistConfig, demon::Demon};
 
 pub struct RecordSubmitter<'a> {
     initially_visible: bool,
     demons: &'a [Demon],
+    list_config: &'a DemonlistConfig,
 }
 
 impl<'a> RecordSubmitter<'a> {
-    pub fn new(visible: bool, demons: &'a [Demon]) -> RecordSubmitter<'a> {
+    pub fn new(visible: bool, demons: &'a [Demon], list_config: &'a DemonlistConfig) -> RecordSubmitter<'a> {
         RecordSubmitter {
             initially_visible: visible,
             demons,
+            list_config,
         }
     }
 }
@@ -33,10 +35,10 @@ impl Render for RecordSubmitter<'_> {
                         (tr("record-submission.demon"))
                     }
                     p {
-                        (trp!("record-submission.demon-info", "list-size" = config::extended_list_size()))
+                        (trp!("record-submission.demon-info", "list-size" = self.list_config.extended_list_size))
                     }
                     span.form-input data-type = "dropdown" {
-                        (demon_dropdown("id_demon", self.demons.iter().filter(|demon| demon.base.position <= config::extended_list_size())))
+                        (demon_dropdown("id_demon", self.demons.iter().filter(|demon| demon.base.position <= self.list_config.extended_list_size)))
                         p.error {}
                     }
                     h3 {
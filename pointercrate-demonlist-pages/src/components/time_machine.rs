@@ -1,6 +1,6 @@
-use chrono::{DateTime, Datelike, FixedOffset};
+use chrono::{DateTime, FixedOffset};
 use maud::{html, Markup, Render};
-use pointercrate_core::localization::tr;
+use pointercrate_core::localization::{format_datetime, tr};
 use pointercrate_demonlist::demon::TimeShiftedDemon;
 
 pub enum Tardis {
@@ -55,12 +55,7 @@ impl Render for Tardis {
                             (tr("time-machine.active-info"))
                             br;
                             b {
-                                @match destination.day() {
-                                   1 | 21 | 31 => (destination.format("%A, %B %est %Y at %l:%M:%S%P GMT%Z")),
-                                   2 | 22 => (destination.format("%A, %B %end %Y at %l:%M:%S%P GMT%Z")),
-                                   3 | 23 => (destination.format("%A, %B %erd %Y at %l:%M:%S%P GMT%Z")),
-                                   _ => (destination.format("%A, %B %eth %Y at %l:%M:%S%P GMT%Z"))
-                                }
+                                (format_datetime(destination))
                             }
                         }
                         a.white.button href = "/demonlist/" onclick=r#"document.cookie = "when=""# style = "margin-left: 15px"{ b{ (tr("time-machine.return")) }}
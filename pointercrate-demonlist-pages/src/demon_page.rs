@@ -8,10 +8,13 @@ use crate::{
 };
 use chrono::NaiveDateTime;
 use maud::{html, Markup, PreEscaped};
-use pointercrate_core::{localization::tr, trp};
+use pointercrate_core::{
+    localization::{format_decimal, format_month_year, tr},
+    trp,
+};
 use pointercrate_core_pages::{head::HeadLike, trp_html, PageFragment};
 use pointercrate_demonlist::{
-    config::{self as list_config, extended_list_size},
+    config::DemonlistConfig,
     demon::{Demon, FullDemon},
 };
 use pointercrate_integrate::gd::{DemonRating, IntegrationLevel, LevelRating, Thunk};
@@ -29,6 +32,7 @@ pub struct DemonPage {
     pub data: FullDemon,
     pub movements: Vec<DemonMovement>,
     pub integration: Option<IntegrationLevel>,
+    pub list_config: DemonlistConfig,
 }
 
 impl From<DemonPage> for PageFragment {
@@ -53,7 +57,7 @@ impl DemonPage {
             self.data.demon.base.name // FIXME: flatten the structs, holy shit
         );
 
-        if self.data.demon.base.position <= extended_list_size() {
+        if self.data.demon.base.position <= self.list_config.extended_list_size {
             title = format!("#{} - {}", self.data.demon.base.position, title);
         }
 
@@ -112,13 +116,17 @@ impl DemonPage {
                     window.list_length = {0};
                     window.extended_list_length = {1};
                     window.demon_id = {2};
-                </script>", list_config::list_size(), list_config::extended_list_size(), self.data.demon.base.id
+                </script>", self.list_config.list_size, self.list_config.extended_list_size, self.data.demon.base.id
             )))
         }
     }
 
     fn body(&self) -> Markup {
-        let dropdowns = super::dropdowns(&self.demonlist.iter().collect::<Vec<_>>()[..], Some(&self.data.demon));
+        let dropdowns = super::dropdowns(
+            &self.demonlist.iter().collect::<Vec<_>>()[..],
+            Some(&self.data.demon),
+            &self.list_config,
+        );
 
         let mut labels = Vec::new();
 
@@ -129,7 +137,7 @@ impl DemonPage {
             let would_be_label = if year_only {
                 movement.at.date().format("%Y").to_string()
             } else {
-                movement.at.date().format("%b %y").to_string()
+                format_month_year(movement.at.date())
             };
 
             match last_label {
@@ -137,7 +145,7 @@ impl DemonPage {
                 _ => {
                     last_label = Some(would_be_label.clone());
                     if labels.is_empty() {
-                        labels.push(format!("Added ({})", would_be_label))
+                        labels.push(trp!("movements.added-label", "date" = would_be_label))
                     } else {
                         labels.push(would_be_label)
                     }
@@ -150,7 +158,7 @@ impl DemonPage {
 
             div.flex.m-center.container {
                 main.left {
-                    (RecordSubmitter::new(false, &self.demonlist))
+                    (RecordSubmitter::new(false, &self.demonlist, &self.list_config))
                     (self.demon_panel())
                     div.panel.fade.js-scroll-anim.js-collapse data-anim = "fade" {
                         h2.underlined.pad {
@@ -398,22 +406,22 @@ impl DemonPage {
                             }
                         }
                     }
-                    @if position <= list_config::extended_list_size() {
+                    @if position <= self.list_config.extended_list_size {
                         span {
                             b {
                                 (trp!("demon-score", "percent" = 100.0))
                             }
                             br;
-                            (format!("{:.2}", score100))
+                            (format_decimal(score100, 2))
                         }
                     }
-                    @if position <= list_config::list_size(){
+                    @if position <= self.list_config.list_size{
                         span {
                             b {
                                 (trp!("demon-score", "percent" = self.data.demon.requirement))
                             }
                             br;
-                            (format!("{:.2}", score_requirement))
+                            (format_decimal(score_requirement, 2))
                         }
                     }
                 }
@@ -426,18 +434,18 @@ impl DemonPage {
         let _name = &self.data.demon.base.name;
 
         html! {
-            @if !self.data.records.is_empty() || position <= list_config::extended_list_size() {
+            @if !self.data.records.is_empty() || position <= self.list_config.extended_list_size {
                 section.records.panel.fade.js-scroll-anim data-anim = "fade" {
                     div.underlined.pad {
                         h2 {
                             (tr("demon-records"))
                         }
-                        @if position <= list_config::list_size() {
+                        @if position <= self.list_config.list_size {
                             h3 {
                                 (trp!("demon-records-qualify", "percent" = self.data.demon.requirement))
                             }
                         }
-                        @else if position <= list_config::extended_list_size() {
+                        @else if position <= self.list_config.extended_list_size {
                             h3 {
                                 (trp!("demon-records-qualify", "percent" = 100.0))
                             }
@@ -451,7 +459,7 @@ impl DemonPage {
                     }
                     @if self.data.records.is_empty() {
                         h3 {
-                            @if position > list_config::extended_list_size() {
+                            @if position > self.list_config.extended_list_size {
                                 (tr("demon-records.none"))
                             }
                             @else {
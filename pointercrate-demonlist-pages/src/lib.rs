@@ -1,7 +1,7 @@
 use maud::{html, Markup};
 
 use pointercrate_core::localization::tr;
-use pointercrate_demonlist::{config, demon::Demon};
+use pointercrate_demonlist::{config::DemonlistConfig, demon::Demon};
 
 pub mod account;
 pub mod components;
@@ -16,20 +16,20 @@ struct ListSection {
     numbered: bool,
 }
 
-fn dropdowns(all_demons: &[&Demon], current: Option<&Demon>) -> Markup {
-    let (main, extended, legacy) = if all_demons.len() < config::list_size() as usize {
+fn dropdowns(all_demons: &[&Demon], current: Option<&Demon>, config: &DemonlistConfig) -> Markup {
+    let (main, extended, legacy) = if all_demons.len() < config.list_size as usize {
         (all_demons, Default::default(), Default::default())
     } else {
-        let (extended, legacy) = if all_demons.len() < config::extended_list_size() as usize {
-            (&all_demons[config::list_size() as usize..], Default::default())
+        let (extended, legacy) = if all_demons.len() < config.extended_list_size as usize {
+            (&all_demons[config.list_size as usize..], Default::default())
         } else {
             (
-                &all_demons[config::list_size() as usize..config::extended_list_size() as usize],
-                &all_demons[config::extended_list_size() as usize..],
+                &all_demons[config.list_size as usize..config.extended_list_size as usize],
+                &all_demons[config.extended_list_size as usize..],
             )
         };
 
-        (&all_demons[..config::list_size() as usize], extended, legacy)
+        (&all_demons[..config.list_size as usize], extended, legacy)
     };
 
     html! {
@@ -8,11 +8,14 @@ use crate::{
     statsviewer::stats_viewer_panel,
 };
 use maud::{html, Markup, PreEscaped};
-use pointercrate_core::{localization::tr, trp};
+use pointercrate_core::{
+    localization::{format_decimal, tr},
+    trp,
+};
 use pointercrate_core_pages::{head::HeadLike, trp_html, PageFragment};
 use pointercrate_demonlist::player::FullPlayer;
 use pointercrate_demonlist::{
-    config as list_config, config,
+    config::DemonlistConfig,
     demon::{Demon, TimeShiftedDemon},
 };
 
@@ -22,6 +25,7 @@ pub struct OverviewPage {
     pub time_machine: Tardis,
     pub submitter_initially_visible: bool,
     pub claimed_player: Option<FullPlayer>,
+    pub list_config: DemonlistConfig,
 }
 
 impl From<OverviewPage> for PageFragment {
@@ -76,7 +80,7 @@ impl OverviewPage {
                 <script>
                     window.list_length = {0};
                     window.extended_list_length = {1}
-                </script>", list_config::list_size(), list_config::extended_list_size())
+                </script>", self.list_config.list_size, self.list_config.extended_list_size)
             ))
             // FIXME: abstract away
             link ref = "canonical" href = "https://pointercrate.com/demonlist/";
@@ -89,7 +93,7 @@ impl OverviewPage {
             _ => self.demonlist.iter().collect(),
         };
 
-        let dropdowns = super::dropdowns(&demons_for_dropdown[..], None);
+        let dropdowns = super::dropdowns(&demons_for_dropdown[..], None, &self.list_config);
 
         html! {
             (dropdowns)
@@ -97,19 +101,19 @@ impl OverviewPage {
             div.flex.m-center.container {
                 main.left {
                     (self.time_machine)
-                    (RecordSubmitter::new(self.submitter_initially_visible, &self.demonlist))
+                    (RecordSubmitter::new(self.submitter_initially_visible, &self.demonlist, &self.list_config))
 
                     @match &self.time_machine {
                         Tardis::Activated { demons, ..} => {
                             @for TimeShiftedDemon {current_demon, position_now} in demons {
-                                @if current_demon.base.position <= list_config::extended_list_size() {
+                                @if current_demon.base.position <= self.list_config.extended_list_size {
                                     (self.demon_panel(current_demon, Some(*position_now)))
                                 }
                             }
                         },
                         _ => {
                             @for demon in &self.demonlist {
-                                @if demon.base.position <= list_config::extended_list_size() {
+                                @if demon.base.position <= self.list_config.extended_list_size {
                                     (self.demon_panel(demon, None))
                                 }
                             }
@@ -143,9 +147,9 @@ impl OverviewPage {
             })
             .unwrap_or_default();
 
-        let total_score = format!("{:.2}", demon.score(100));
-        let progress_score = format!("{:.2}", demon.score(progress));
-        let minimal_score = format!("{:.2}", demon.score(demon.requirement));
+        let total_score = format_decimal(demon.score(100), 2);
+        let progress_score = format_decimal(demon.score(progress), 2);
+        let minimal_score = format_decimal(demon.score(demon.requirement), 2);
 
         html! {
              section.panel.fade.flex.mobile-col.completed[progress==100] style="overflow:hidden" {
@@ -165,7 +169,7 @@ impl OverviewPage {
                          }
                          div style="text-align: left; font-size: 0.8em" {
                             @if let Some(current_position) = current_position {
-                                 @if current_position > list_config::extended_list_size() {
+                                 @if current_position > self.list_config.extended_list_size {
                                      (tr("time-machine.active-position-legacy"))
                                  }
                                  @else {
@@ -176,7 +180,7 @@ impl OverviewPage {
                                  }
                             }
                             @else {
-                                @if demon.base.position > config::list_size() {
+                                @if demon.base.position > self.list_config.list_size {
                                     (trp!(
                                         "demon-info.score-short",
                                         "score" = total_score
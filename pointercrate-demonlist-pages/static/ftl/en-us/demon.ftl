@@ -76,6 +76,7 @@ demon-headline = by { $creator }
 movements = Position History
     .date = Date
     .change = Change
+    .added-label = Added ({ $date })
 
 movements-newposition = New Position
     .legacy = Legacy
@@ -185,4 +186,6 @@ demon-creator-dialog = Add creator
     .info = Type the creator to add to this demon into the text field below. If the player already exists, it will appear as a suggestion below the text field. Then click the button below.
     .submit = Add Creator
 
-    .edit-success = Successfully added creator!
\ No newline at end of file
+    .edit-success = Successfully added creator!
+
+route-group-demon-edits = Demon edits
@@ -20,6 +20,8 @@ error-demonlist-creatorexists = This player is already registered as a creator o
 error-demonlist-duplicatevideo = This video is already used by record #{ $record-id }
 error-demonlist-nonationset = Attempt to set subdivision without nation
 error-demonlist-conflictingclaims = The players '{ $player-1 }' and '{ $player-2 }' have verified claims by different pointercrate users
+error-demonlist-notdeleted = This object has not been deleted
+error-demonlist-duplicateplayername = Another player with this name already exists (player #{ $player-id })
 error-demonlist-invalidrequirement = Record requirement needs to be greater than -1 and smaller than 101
 error-demonlist-invalidposition = Demon position needs to be greater than or equal to 1 and smaller than or equal to { $maximal }
 error-demonlist-invalidprogress = Record progress must lie between { $requirement } and 100%!
@@ -34,6 +36,7 @@ error-demonlist-alreadyclaimed = This player already has a verified claim associ
 error-demonlist-rawrequired = Raw footage much be provided to submit this record
 error-demonlist-malformedrawurl = Raw footage needs to be a valid URL
 error-demonlist-invalidlevelid = Level ID needs to be positive
+error-demonlist-incompleteaudittrail = The audit log does not contain enough information to restore this object
 
 error-demonlist-ratelimit-record-submit = You're submitting too many records too fast!
 error-demonlist-ratelimit-record-submit-global = Too many records are being submitted right now!
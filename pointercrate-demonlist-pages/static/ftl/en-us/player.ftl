@@ -89,4 +89,6 @@ claim-info-panel = Claiming 101
     .info-discord = this discord server
 
 claim-video-panel = Record video
-    .info = Clicking a claim in the 'Manage Claims' panel will pull up a random video of an approved record by the claimed player.
\ No newline at end of file
+    .info = Clicking a claim in the 'Manage Claims' panel will pull up a random video of an approved record by the claimed player.
+
+route-group-claims = Player claims
@@ -76,6 +76,7 @@ demon-headline = от { $creator }
 movements = История позиции
     .date = Дата
     .change = Изменение
+    .added-label = Добавлен ({ $date })
 
 movements-newposition = Новая позиция
     .legacy = Legacy
@@ -89,6 +90,8 @@ movements-reason = Причина
 
 ## Records table
 demon-records = Рекорды
+    .none = Рекордов нет!
+    .none-yet = Рекордов пока нет! Станьте первым!
 
 demon-records-qualify = {$percent}% { $percent ->
     [100] требуется для квалификации
@@ -187,4 +190,6 @@ demon-creator-dialog = Добавление креатора
     .info = Здесь проходит добавление нового креатора для этого демона через поле ниже. Если такой игрок уже существует, его имя появится в качестве предложения ниже поля ввода. После этого нажмите на кнопку ниже.
     .submit = Добавить креатора
 
-    .edit-success = Креатор добавлен успешно!
\ No newline at end of file
+    .edit-success = Креатор добавлен успешно!
+
+route-group-demon-edits = Редактирование демонов
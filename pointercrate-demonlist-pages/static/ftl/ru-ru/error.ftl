@@ -1,3 +1,4 @@
+error-demonlist-geolocationfailed = Не удалось определить геолокацию!
 error-demonlist-malformedvideourl = Неправильная ссылка на видео
 error-demonlist-bannedfromsubmissions = Вы забанены в демонлисте!
 error-demonlist-claimunverified = Ваш запрос на присвоение профиля не подтвержден
@@ -19,6 +20,8 @@ error-demonlist-creatorexists = Этот игрок уже указан как 
 error-demonlist-duplicatevideo = Это видео уже используется рекордом #{ $record-id }
 error-demonlist-nonationset = Попытка установить регион без страны
 error-demonlist-conflictingclaims = Игроки '{ $player-1 }' и '{ $player-2 }' имеют подтвержденные присвоения разными пользователями pointercrate
+error-demonlist-notdeleted = Этот объект не был удален
+error-demonlist-duplicateplayername = Другой игрок с таким именем уже существует (игрок #{ $player-id })
 error-demonlist-invalidrequirement = Требование к рекорду должно быть больше -1 и меньше 101
 error-demonlist-invalidposition = Позиция демона должна быть между 1 и { $maximal }
 error-demonlist-invalidprogress = Прогресс на рекорде должен находиться между { $requirement } и 100%!
@@ -33,6 +36,7 @@ error-demonlist-alreadyclaimed = Этот игрок уже имеет связ
 error-demonlist-rawrequired = Для отправки рекорда необходимо предоставить необработанную запись
 error-demonlist-malformedrawurl = Необработанная запись должна быть в виде правильно оформленной ссылки
 error-demonlist-invalidlevelid = ID уровня должен быть положительным
+error-demonlist-incompleteaudittrail = Журнал аудита не содержит достаточно информации для восстановления этого объекта
 
 error-demonlist-ratelimit-record-submit = Вы отправляете слишком много рекордов слишком часто!
 error-demonlist-ratelimit-record-submit-global = Слишком много рекордов отправляется на данный момент!
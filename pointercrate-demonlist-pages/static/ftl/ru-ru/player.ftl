@@ -46,6 +46,8 @@ claimed-player = Присвоенный профиль
 # .info-api-link is turned into a clickable link to the geolocation API
 # pointercrate uses, and replaces { $info-api-link }
 claim-geolocate = Указать флаг для панели статистики по геолокации
+    .info = Нажав на кнопку выше, вы можете указать флаг вашего игрока в панели статистики с помощью геолокации по IP. Для этого ваш IP-адрес может быть передан сторонним сервисам. Подробнее см. политику конфиденциальности!
+
     .submit = Найти
 
     .edit-success = Страна указана как { $nationality }
@@ -87,4 +89,6 @@ claim-info-panel = Присвоение 101
     .info-discord = этом Discord-сервере
 
 claim-video-panel = Видео рекорда
-    .info = Нажатие на запрос в панели 'Менеджер присвоения' выведет случайное видео из принятого рекорда присваиваемым игроком.
\ No newline at end of file
+    .info = Нажатие на запрос в панели 'Менеджер присвоения' выведет случайное видео из принятого рекорда присваиваемым игроком.
+
+route-group-claims = Присвоение профилей
@@ -120,4 +120,6 @@ record-manager-help = Работа с рекордами
 
     .note-a = Если игрок забанен, им запрещено иметь рекорды со статусом { record-approved } либо { record-submitted } в листе. Все рекорды, помеченные как '{ record-submitted }' будут удалены, все остальные поменяют статус на '{ record-rejected }'.
 
-    .note-b = Бан отправителя приведет к удалению всех их рекордов со статусом '{ record-submitted }'. Отправленные ими рекорды, которые уже поменяли статус на { record-approved } либо { record-rejected } не будут затронуты.
\ No newline at end of file
+    .note-b = Бан отправителя приведет к удалению всех их рекордов со статусом '{ record-submitted }'. Отправленные ими рекорды, которые уже поменяли статус на { record-approved } либо { record-rejected } не будут затронуты.
+
+route-group-record-submissions = Отправка рекордов
@@ -88,7 +88,7 @@ export class DemonManager extends FilteredPaginator {
 
     requirementForm.addValidators({
       "demon-requirement-edit": {
-        [tr("demonlist", "demon", "demon-requirement.validator-underflow")]:
+        [tr("demonlist", "demon", "demon-requirement.validator-rangeunderflow")]:
           rangeUnderflow,
         [tr("demonlist", "demon", "demon-requirement.validator-rangeoverflow")]:
           rangeOverflow,
@@ -288,7 +288,7 @@ function setupDemonAdditionForm() {
         valueMissing,
       [tr("demonlist", "demon", "demon-requirement.validator-rangeunderflow")]:
         rangeUnderflow,
-      [tr("demonlist", "demon", "demon-requirement.validator-overflow")]:
+      [tr("demonlist", "demon", "demon-requirement.validator-rangeoverflow")]:
         rangeOverflow,
       [tr("demonlist", "demon", "demon-requirement.validator-badinput")]:
         badInput,
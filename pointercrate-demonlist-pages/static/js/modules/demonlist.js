@@ -209,7 +209,7 @@ export function initializeRecordSubmitter(submitApproved = false) {
             trp(
               "demonlist",
               "submitter",
-              "record-submission.submission-success.queue",
+              "record-submission.submission-success-queue",
               {
                 ["queue-position"]: queue_position,
               }
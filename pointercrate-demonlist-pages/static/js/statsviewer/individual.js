@@ -1,4 +1,4 @@
-import { tr } from "/static/core/js/modules/localization.js";
+import { formatNumber, tr } from "/static/core/js/modules/localization.js";
 import { displayError, Dropdown, get } from "/static/core/js/modules/form.js";
 import {
   getCountryFlag,
@@ -25,7 +25,7 @@ class IndividualStatsViewer extends StatsViewer {
     var playerData = response.data.data;
 
     this._rank.innerText = playerData.rank || "-";
-    this._score.innerText = playerData.score.toFixed(2);
+    this._score.innerText = formatNumber(playerData.score, 2);
 
     this.setName(playerData.name, playerData.nationality);
 
@@ -270,7 +270,7 @@ function generateStatsViewerPlayer(player) {
   li.dataset.id = player.id;
 
   b.appendChild(document.createTextNode("#" + player.rank + " "));
-  i.appendChild(document.createTextNode(player.score.toFixed(2)));
+  i.appendChild(document.createTextNode(formatNumber(player.score, 2)));
 
   if (player.nationality) {
     li.appendChild(
@@ -5,7 +5,7 @@ import {
 } from "/static/demonlist/js/modules/statsviewer.js";
 import { Dropdown } from "/static/core/js/modules/form.js";
 import { getCountryFlag } from "/static/demonlist/js/modules/demonlist.js";
-import { tr, trp } from "/static/core/js/modules/localization.js";
+import { formatNumber, tr, trp } from "/static/core/js/modules/localization.js";
 
 class NationStatsViewer extends StatsViewer {
   constructor(html) {
@@ -344,7 +344,7 @@ function generateStatsViewerNation(nation) {
   li.dataset.rank = nation.rank;
 
   b.appendChild(document.createTextNode("#" + nation.rank + " "));
-  i.appendChild(document.createTextNode(nation.score.toFixed(2)));
+  i.appendChild(document.createTextNode(formatNumber(nation.score, 2)));
 
   li.appendChild(getCountryFlag(nation.nation, nation.country_code));
   li.appendChild(document.createTextNode(" "));
@@ -1,9 +1,39 @@
-use pointercrate_core::util::from_env_or_default;
+use pointercrate_core::config::{ConfigSection, ConfigSource};
 
-pub fn list_size() -> i16 {
-    from_env_or_default("LIST_SIZE", 50)
+/// The `[demonlist]` section of the configuration
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DemonlistConfig {
+    /// The number of demons on the main list. Only 100% records can be submitted for demons below it.
+    pub list_size: i16,
+
+    /// The number of demons on the main and extended list combined. No records can be submitted for demons below it.
+    pub extended_list_size: i16,
+}
+
+impl Default for DemonlistConfig {
+    fn default() -> Self {
+        DemonlistConfig {
+            list_size: 50,
+            extended_list_size: 100,
+        }
+    }
 }
 
-pub fn extended_list_size() -> i16 {
-    from_env_or_default("EXTENDED_LIST_SIZE", 100)
+impl ConfigSection for DemonlistConfig {
+    fn load(source: &mut ConfigSource) -> Option<Self> {
+        let default = DemonlistConfig::default();
+        let list_size = source.get_or("demonlist.list_size", "LIST_SIZE", default.list_size);
+        let extended_list_size = source.get_or("demonlist.extended_list_size", "EXTENDED_LIST_SIZE", default.extended_list_size);
+
+        if list_size < 1 {
+            source.invalid("demonlist.list_size", "must be positive");
+        } else if extended_list_size < list_size {
+            source.invalid("demonlist.extended_list_size", "must not be smaller than demonlist.list_size");
+        }
+
+        Some(DemonlistConfig {
+            list_size,
+            extended_list_size,
+        })
+    }
 }
@@ -6,6 +6,7 @@ pub use post::PostCreator;
 mod delete;
 mod get;
 mod post;
+mod restore;
 
 #[derive(Debug, Display, Hash)]
 #[display("creator with id {} on demon {}", creator, demon)]
@@ -0,0 +1,51 @@
+use crate::{
+    creator::Creator,
+    demon::MinimalDemon,
+    error::{DemonlistError, Result},
+    player::DatabasePlayer,
+};
+use log::info;
+use sqlx::PgConnection;
+
+impl Creator {
+    /// Restores the most recently deleted creator entry of the given player on the given demon
+    pub async fn restore(demon: &MinimalDemon, player: &DatabasePlayer, connection: &mut PgConnection) -> Result<Creator> {
+        match Creator::get(demon, player, connection).await {
+            Ok(_) => return Err(DemonlistError::CreatorExists),
+            Err(DemonlistError::CreatorNotFound { .. }) => (),
+            Err(err) => return Err(err),
+        }
+
+        let deletion = sqlx::query!(
+            "SELECT audit_id FROM creator_deletions WHERE creator = $1 AND demon = $2 ORDER BY audit_id DESC LIMIT 1",
+            player.id,
+            demon.id
+        )
+        .fetch_optional(&mut *connection)
+        .await?
+        .ok_or(DemonlistError::CreatorNotFound {
+            demon_id: demon.id,
+            player_id: player.id,
+        })?;
+
+        sqlx::query!("INSERT INTO creators (creator, demon) VALUES ($1, $2)", player.id, demon.id)
+            .execute(&mut *connection)
+            .await?;
+
+        sqlx::query!(
+            "INSERT INTO creator_restorations (userid, creator, demon, deletion) (SELECT id, $1, $2, $3 FROM active_user LIMIT 1)",
+            player.id,
+            demon.id,
+            deletion.audit_id
+        )
+        .execute(connection)
+        .await?;
+
+        info!("Restored creator {} on demon {}", player, demon);
+
+        Ok(Creator {
+            demon: demon.id,
+            creator: player.id,
+        })
+    }
+}
@@ -11,13 +11,10 @@ use crate::{
 };
 use derive_more::Display;
 use log::info;
-use pointercrate_core::etag::Taggable;
+use pointercrate_core::etag::{stable_hash, Taggable};
 use serde::{Deserialize, Serialize};
 use sqlx::PgConnection;
-use std::{
-    collections::hash_map::DefaultHasher,
-    hash::{Hash, Hasher},
-};
+use std::hash::Hash;
 
 #[macro_use]
 mod get;
@@ -32,7 +29,7 @@ pub struct TimeShiftedDemon {
 }
 
 /// Struct modelling a demon. These objects are returned from the paginating `/demons/` endpoint
-#[derive(Debug, Deserialize, Serialize, Hash, Display, Eq, PartialEq)]
+#[derive(Debug, Deserialize, Serialize, Hash, Display, Eq, PartialEq, Clone)]
 #[display("{}", base)]
 pub struct Demon {
     #[serde(flatten)]
@@ -92,9 +89,7 @@ pub struct FullDemon {
 
 impl Taggable for FullDemon {
     fn patch_part(&self) -> u64 {
-        let mut hasher = DefaultHasher::new();
-        self.demon.hash(&mut hasher);
-        hasher.finish()
+        stable_hash(&self.demon)
     }
 }
 
@@ -193,3 +188,52 @@ impl Demon {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        demon::{Demon, FullDemon, MinimalDemon},
+        player::DatabasePlayer,
+        record::{MinimalRecordP, RecordStatus},
+    };
+    use pointercrate_core::etag::Taggable;
+
+    #[test]
+    fn test_pinned_etag() {
+        let verifier = DatabasePlayer {
+            id: 1,
+            name: "Riot".to_string(),
+            banned: false,
+        };
+        let demon = FullDemon {
+            demon: Demon {
+                base: MinimalDemon {
+                    id: 2,
+                    position: 1,
+                    name: "Bloodbath".to_string(),
+                },
+                requirement: 54,
+                video: Some("https://www.youtube.com/watch?v=dQw4w9WgXcQ".to_string()),
+                thumbnail: "https://i.ytimg.com/vi/dQw4w9WgXcQ/mqdefault.jpg".to_string(),
+                publisher: verifier.clone(),
+                verifier: verifier.clone(),
+                level_id: Some(10565740),
+            },
+            creators: vec![verifier],
+            records: vec![MinimalRecordP {
+                id: 3,
+                progress: 100,
+                video: None,
+                status: RecordStatus::Approved,
+                player: DatabasePlayer {
+                    id: 4,
+                    name: "stardust1971".to_string(),
+                    banned: false,
+                },
+                nationality: None,
+            }],
+        };
+
+        assert_eq!(demon.etag_string(), "W/\"v1:18312379792019820322;592627023084420063\"");
+    }
+}
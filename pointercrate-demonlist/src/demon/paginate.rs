@@ -2,14 +2,20 @@ use crate::{
     demon::{Demon, MinimalDemon},
     player::DatabasePlayer,
 };
-use futures::stream::StreamExt;
+use async_stream::try_stream;
+use futures::stream::{BoxStream, StreamExt};
 use pointercrate_core::{
+    export::Exportable,
     first_and_last,
-    pagination::{PageContext, Paginatable, PaginationParameters, PaginationQuery, __pagination_compat},
+    pagination::{count_query, Paginatable, PaginationParameters, PaginationQuery},
     util::non_nullable,
 };
 use serde::{Deserialize, Serialize};
-use sqlx::{PgConnection, Row};
+use sqlx::{
+    postgres::{PgArguments, PgRow},
+    query::Query,
+    PgConnection, Postgres, Row,
+};
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct DemonIdPagination {
@@ -49,7 +55,7 @@ pub struct DemonIdPagination {
 
 impl PaginationQuery for DemonIdPagination {
     fn parameters(&self) -> PaginationParameters {
-        self.params
+        self.params.clone()
     }
 
     fn with_parameters(&self, parameters: PaginationParameters) -> Self {
@@ -58,64 +64,110 @@ impl PaginationQuery for DemonIdPagination {
             ..self.clone()
         }
     }
+
+    fn bind_filters<'q>(&'q self, sql_query: Query<'q, Postgres, PgArguments>) -> Query<'q, Postgres, PgArguments> {
+        sql_query
+            .bind(self.name.as_deref())
+            .bind(self.requirement)
+            .bind(self.requirement_lt)
+            .bind(self.requirement_gt)
+            .bind(self.verifier_id)
+            .bind(self.verifier_name.as_deref())
+            .bind(self.publisher_id)
+            .bind(self.publisher_name.as_deref())
+            .bind(self.name_contains.as_deref())
+            .bind(self.level_id)
+    }
+}
+
+fn demon_from_row(row: PgRow) -> Demon {
+    Demon {
+        base: MinimalDemon {
+            id: row.get("demon_id"),
+            name: row.get("demon_name"),
+            position: row.get("position"),
+        },
+        requirement: row.get("requirement"),
+        video: row.get("video"),
+        thumbnail: row.get("thumbnail"),
+        publisher: DatabasePlayer {
+            id: row.get("publisher_id"),
+            name: row.get("publisher_name"),
+            banned: row.get("publisher_banned"),
+        },
+        verifier: DatabasePlayer {
+            id: row.get("verifier_id"),
+            name: row.get("verifier_name"),
+            banned: row.get("verifier_banned"),
+        },
+        level_id: row.get::<Option<i64>, _>("level_id").map(|id| id as u64),
+    }
+}
+
+/// The CSV columns of a [`Demon`], shared between all demon exports
+const DEMON_CSV_HEADER: &[&str] = &[
+    "id",
+    "position",
+    "name",
+    "requirement",
+    "video",
+    "thumbnail",
+    "level_id",
+    "publisher_id",
+    "publisher_name",
+    "verifier_id",
+    "verifier_name",
+];
+
+fn demon_csv_record(demon: &Demon) -> Vec<String> {
+    vec![
+        demon.base.id.to_string(),
+        demon.base.position.to_string(),
+        demon.base.name.clone(),
+        demon.requirement.to_string(),
+        demon.video.clone().unwrap_or_default(),
+        demon.thumbnail.clone(),
+        demon.level_id.map(|id| id.to_string()).unwrap_or_default(),
+        demon.publisher.id.to_string(),
+        demon.publisher.name.clone(),
+        demon.verifier.id.to_string(),
+        demon.verifier.name.clone(),
+    ]
 }
 
 impl Paginatable<DemonIdPagination> for Demon {
     first_and_last!("demons");
 
-    async fn page(query: &DemonIdPagination, connection: &mut PgConnection) -> Result<(Vec<Demon>, PageContext), sqlx::Error> {
+    async fn count(query: &DemonIdPagination, connection: &mut PgConnection) -> Result<i64, sqlx::Error> {
+        let sql_query = count_query(&format!(include_str!("../../sql/paginate_demons_by_id.sql"), "ASC"));
+
+        let sql_query = sqlx::query(&sql_query).bind(None::<i32>).bind(None::<i32>);
+
+        query
+            .bind_filters(sql_query)
+            .bind(None::<i32>)
+            .fetch_one(connection)
+            .await?
+            .try_get(0)
+    }
+
+    async fn fetch(query: &DemonIdPagination, connection: &mut PgConnection) -> Result<Vec<Demon>, sqlx::Error> {
         let order = query.params.order();
 
         let sql_query = format!(include_str!("../../sql/paginate_demons_by_id.sql"), order);
 
         // FIXME(sqlx) once CITEXT is supported
-        let mut stream = sqlx::query(&sql_query)
-            .bind(query.params.before)
-            .bind(query.params.after)
-            .bind(query.name.as_deref())
-            .bind(query.requirement)
-            .bind(query.requirement_lt)
-            .bind(query.requirement_gt)
-            .bind(query.verifier_id)
-            .bind(query.verifier_name.as_deref())
-            .bind(query.publisher_id)
-            .bind(query.publisher_name.as_deref())
-            .bind(query.name_contains.as_deref())
-            .bind(query.level_id)
-            .bind(query.params.limit + 1)
-            .fetch(connection);
+        let sql_query = sqlx::query(&sql_query).bind(query.params.before).bind(query.params.after);
+
+        let mut stream = query.bind_filters(sql_query).bind(query.params.limit + 1).fetch(connection);
 
         let mut demons = Vec::new();
 
         while let Some(row) = stream.next().await {
-            let row = row?;
-
-            let video: Option<String> = row.get("video");
-
-            demons.push(Demon {
-                base: MinimalDemon {
-                    id: row.get("demon_id"),
-                    name: row.get("demon_name"),
-                    position: row.get("position"),
-                },
-                requirement: row.get("requirement"),
-                video,
-                thumbnail: row.get("thumbnail"),
-                publisher: DatabasePlayer {
-                    id: row.get("publisher_id"),
-                    name: row.get("publisher_name"),
-                    banned: row.get("publisher_banned"),
-                },
-                verifier: DatabasePlayer {
-                    id: row.get("verifier_id"),
-                    name: row.get("verifier_name"),
-                    banned: row.get("verifier_banned"),
-                },
-                level_id: row.get::<Option<i64>, _>("level_id").map(|id| id as u64),
-            })
+            demons.push(demon_from_row(row?))
         }
 
-        Ok(__pagination_compat(&query.params, demons))
+        Ok(demons)
     }
 
     fn pagination_id(&self) -> i32 {
@@ -123,6 +175,27 @@ impl Paginatable<DemonIdPagination> for Demon {
     }
 }
 
+impl Exportable<DemonIdPagination> for Demon {
+    const CSV_HEADER: &'static [&'static str] = DEMON_CSV_HEADER;
+
+    fn export<'a>(query: &'a DemonIdPagination, connection: &'a mut PgConnection) -> BoxStream<'a, Result<Demon, sqlx::Error>> {
+        Box::pin(try_stream! {
+            let sql_query = format!(include_str!("../../sql/paginate_demons_by_id.sql"), "ASC");
+            let sql_query = sqlx::query(&sql_query).bind(query.params.before).bind(query.params.after);
+
+            let mut stream = query.bind_filters(sql_query).bind(None::<i32>).fetch(connection);
+
+            while let Some(row) = stream.next().await {
+                yield demon_from_row(row?);
+            }
+        })
+    }
+
+    fn csv_record(&self) -> Vec<String> {
+        demon_csv_record(self)
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, Default)]
 pub struct DemonPositionPagination {
     #[serde(flatten)]
@@ -161,7 +234,7 @@ pub struct DemonPositionPagination {
 
 impl PaginationQuery for DemonPositionPagination {
     fn parameters(&self) -> PaginationParameters {
-        self.params
+        self.params.clone()
     }
 
     fn with_parameters(&self, parameters: PaginationParameters) -> Self {
@@ -170,67 +243,79 @@ impl PaginationQuery for DemonPositionPagination {
             ..self.clone()
         }
     }
+
+    fn bind_filters<'q>(&'q self, sql_query: Query<'q, Postgres, PgArguments>) -> Query<'q, Postgres, PgArguments> {
+        sql_query
+            .bind(self.name.as_deref())
+            .bind(self.requirement)
+            .bind(self.requirement_lt)
+            .bind(self.requirement_gt)
+            .bind(self.verifier_id)
+            .bind(self.verifier_name.as_deref())
+            .bind(self.publisher_id)
+            .bind(self.publisher_name.as_deref())
+            .bind(self.name_contains.as_deref())
+            .bind(self.level_id)
+    }
 }
 
 impl Paginatable<DemonPositionPagination> for Demon {
     first_and_last!("demons", "position");
 
-    async fn page(query: &DemonPositionPagination, connection: &mut PgConnection) -> Result<(Vec<Demon>, PageContext), sqlx::Error> {
+    async fn count(query: &DemonPositionPagination, connection: &mut PgConnection) -> Result<i64, sqlx::Error> {
+        let sql_query = count_query(&format!(include_str!("../../sql/paginate_demons_by_position.sql"), "ASC"));
+
+        let sql_query = sqlx::query(&sql_query).bind(None::<i32>).bind(None::<i32>);
+
+        query
+            .bind_filters(sql_query)
+            .bind(None::<i32>)
+            .fetch_one(connection)
+            .await?
+            .try_get(0)
+    }
+
+    async fn fetch(query: &DemonPositionPagination, connection: &mut PgConnection) -> Result<Vec<Demon>, sqlx::Error> {
         let order = query.params.order();
 
         let sql_query = format!(include_str!("../../sql/paginate_demons_by_position.sql"), order);
 
         // FIXME(sqlx) once CITEXT is supported
-        let mut stream = sqlx::query(&sql_query)
-            .bind(query.params.before)
-            .bind(query.params.after)
-            .bind(query.name.as_deref())
-            .bind(query.requirement)
-            .bind(query.requirement_lt)
-            .bind(query.requirement_gt)
-            .bind(query.verifier_id)
-            .bind(query.verifier_name.as_deref())
-            .bind(query.publisher_id)
-            .bind(query.publisher_name.as_deref())
-            .bind(query.name_contains.as_deref())
-            .bind(query.level_id)
-            .bind(query.params.limit + 1)
-            .fetch(connection);
+        let sql_query = sqlx::query(&sql_query).bind(query.params.before).bind(query.params.after);
+
+        let mut stream = query.bind_filters(sql_query).bind(query.params.limit + 1).fetch(connection);
 
         let mut demons = Vec::new();
 
         while let Some(row) = stream.next().await {
-            let row = row?;
-
-            let video: Option<String> = row.get("video");
-
-            demons.push(Demon {
-                base: MinimalDemon {
-                    id: row.get("demon_id"),
-                    name: row.get("demon_name"),
-                    position: row.get("position"),
-                },
-                requirement: row.get("requirement"),
-                video,
-                thumbnail: row.get("thumbnail"),
-                publisher: DatabasePlayer {
-                    id: row.get("publisher_id"),
-                    name: row.get("publisher_name"),
-                    banned: row.get("publisher_banned"),
-                },
-                verifier: DatabasePlayer {
-                    id: row.get("verifier_id"),
-                    name: row.get("verifier_name"),
-                    banned: row.get("verifier_banned"),
-                },
-                level_id: row.get::<Option<i64>, _>("level_id").map(|id| id as u64),
-            })
+            demons.push(demon_from_row(row?))
         }
 
-        Ok(__pagination_compat(&query.params, demons))
+        Ok(demons)
     }
 
     fn pagination_id(&self) -> i32 {
         self.base.position as i32
     }
 }
+
+impl Exportable<DemonPositionPagination> for Demon {
+    const CSV_HEADER: &'static [&'static str] = DEMON_CSV_HEADER;
+
+    fn export<'a>(query: &'a DemonPositionPagination, connection: &'a mut PgConnection) -> BoxStream<'a, Result<Demon, sqlx::Error>> {
+        Box::pin(try_stream! {
+            let sql_query = format!(include_str!("../../sql/paginate_demons_by_position.sql"), "ASC");
+            let sql_query = sqlx::query(&sql_query).bind(query.params.before).bind(query.params.after);
+
+            let mut stream = query.bind_filters(sql_query).bind(None::<i32>).fetch(connection);
+
+            while let Some(row) = stream.next().await {
+                yield demon_from_row(row?);
+            }
+        })
+    }
+
+    fn csv_record(&self) -> Vec<String> {
+        demon_csv_record(self)
+    }
+}
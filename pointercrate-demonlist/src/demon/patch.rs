@@ -2,9 +2,13 @@ use crate::{
     demon::{Demon, FullDemon, MinimalDemon},
     error::{DemonlistError, Result},
     player::{recompute_scores, DatabasePlayer},
+    LISTED_DEMONS,
 };
 use log::{debug, info, warn};
-use pointercrate_core::util::{non_nullable, nullable};
+use pointercrate_core::{
+    cache::invalidate,
+    util::{non_nullable, nullable},
+};
 use serde::Deserialize;
 use sqlx::PgConnection;
 
@@ -89,6 +93,8 @@ impl Demon {
             self.set_requirement(requirement, connection).await?;
         }
 
+        invalidate(LISTED_DEMONS, connection).await?;
+
         Ok(self)
     }
 
@@ -247,7 +253,8 @@ impl MinimalDemon {
 
         self.position = to;
 
-        recompute_scores(connection).await?;
+        recompute_scores(&mut *connection).await?;
+        invalidate(LISTED_DEMONS, connection).await?;
 
         Ok(())
     }
@@ -3,8 +3,10 @@ use crate::{
     demon::{Demon, FullDemon, MinimalDemon},
     error::Result,
     player::{recompute_scores, DatabasePlayer},
+    LISTED_DEMONS,
 };
 use log::info;
+use pointercrate_core::cache::invalidate;
 use serde::Deserialize;
 use sqlx::PgConnection;
 
@@ -77,7 +79,8 @@ impl FullDemon {
             creators.push(player);
         }
 
-        recompute_scores(connection).await?;
+        recompute_scores(&mut *connection).await?;
+        invalidate(LISTED_DEMONS, connection).await?;
 
         Ok(FullDemon {
             demon,
@@ -114,6 +114,19 @@ pub enum DemonlistError {
         player2: String,
     },
 
+    /// `409 CONFLICT` variant returned when trying to restore an object that still exists
+    ///
+    /// Error Code `40910`
+    NotDeleted,
+
+    /// `409 CONFLICT` variant returned when trying to restore a player whose name has since been
+    /// taken by another player
+    ///
+    /// Error Code `40911`
+    DuplicatePlayerName {
+        player_id: i32,
+    },
+
     /// `422 UNPROCESSABLE ENTITY` variant returned if attempted to create a demon with a record
     /// requirements outside of [0, 100]
     ///
@@ -198,6 +211,12 @@ pub enum DemonlistError {
     ///
     /// Error Code `42235`
     InvalidLevelId,
+
+    /// `422 UNPROCESSABLE ENTITY` variant returned when trying to restore an object whose deletion
+    /// was not recorded in enough detail to reconstruct it
+    ///
+    /// Error Code `42239`
+    IncompleteAuditTrail,
 }
 
 impl std::error::Error for DemonlistError {}
@@ -233,6 +252,8 @@ impl PointercrateError for DemonlistError {
             DuplicateVideo { .. } => 40906,
             NoNationSet => 40907,
             ConflictingClaims { .. } => 40908,
+            NotDeleted => 40910,
+            DuplicatePlayerName { .. } => 40911,
             InvalidProgress { .. } => 42215,
             SubmissionExists { .. } => 42217,
             PlayerBanned => 42218,
@@ -244,6 +265,14 @@ impl PointercrateError for DemonlistError {
             RawRequired => 42232,
             MalformedRawUrl => 42233,
             InvalidLevelId => 42235,
+            IncompleteAuditTrail => 42239,
+        }
+    }
+
+    fn headers(&self) -> Vec<(&'static str, String)> {
+        match self {
+            DemonlistError::Core(core) => core.headers(),
+            _ => Vec::new(),
         }
     }
 }
@@ -294,6 +323,8 @@ impl Display for DemonlistError {
                 DemonlistError::NoNationSet => tr("error-demonlist-nonationset"),
                 DemonlistError::ConflictingClaims { player1, player2 } =>
                     trp!("error-demonlist-conflictingclaims", "player-1" = player1, "player-2" = player2),
+                DemonlistError::NotDeleted => tr("error-demonlist-notdeleted"),
+                DemonlistError::DuplicatePlayerName { player_id } => trp!("error-demonlist-duplicateplayername", "player-id" = player_id),
                 DemonlistError::InvalidRequirement => tr("error-demonlist-invalidrequirement"),
                 DemonlistError::InvalidPosition { maximal } => trp!("error-demonlist-invalidposition", "maximal" = maximal),
                 DemonlistError::InvalidProgress { requirement } => trp!("error-demonlist-invalidprogress", "requirement" = requirement),
@@ -312,6 +343,7 @@ impl Display for DemonlistError {
                 DemonlistError::RawRequired => tr("error-demonlist-rawrequired"),
                 DemonlistError::MalformedRawUrl => tr("error-demonlist-malformedrawurl"),
                 DemonlistError::InvalidLevelId => tr("error-demonlist-invalidlevelid"),
+                DemonlistError::IncompleteAuditTrail => tr("error-demonlist-incompleteaudittrail"),
             }
         )
     }
@@ -1,4 +1,7 @@
-use pointercrate_core::permission::{Permission, PermissionsManager};
+use pointercrate_core::{
+    cache::CacheRegion,
+    permission::{Permission, PermissionsManager},
+};
 use pointercrate_user::ADMINISTRATOR;
 
 #[macro_use]
@@ -16,6 +19,12 @@ pub const LIST_HELPER: Permission = Permission::new("user-permissions.list-helpe
 pub const LIST_MODERATOR: Permission = Permission::new("user-permissions.list-moderator", 0x4);
 pub const LIST_ADMINISTRATOR: Permission = Permission::new("user-permissions.list-administrator", 0x8);
 
+/// Cached responses containing the list of (main- and extended-) list demons, including their publishers and verifiers
+pub const LISTED_DEMONS: CacheRegion = CacheRegion::new("listed-demons");
+
+/// Cached pages of the stats viewer's player ranking
+pub const PLAYER_RANKING: CacheRegion = CacheRegion::new("player-ranking");
+
 pub fn default_permissions_manager() -> PermissionsManager {
     PermissionsManager::new(vec![ADMINISTRATOR, LIST_HELPER, LIST_MODERATOR, LIST_ADMINISTRATOR])
         .assigns(ADMINISTRATOR, LIST_ADMINISTRATOR)
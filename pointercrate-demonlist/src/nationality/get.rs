@@ -1,4 +1,5 @@
 use crate::{
+    config::DemonlistConfig,
     demon::MinimalDemon,
     error::{DemonlistError, Result},
     nationality::{BestRecord, MiniDemonWithPlayers, Nationality, NationalityRecord, Subdivision},
@@ -113,23 +114,23 @@ impl Nationality {
         Ok(nationalities)
     }
 
-    pub async fn upgrade(self, connection: &mut PgConnection) -> Result<NationalityRecord> {
+    pub async fn upgrade(self, config: &DemonlistConfig, connection: &mut PgConnection) -> Result<NationalityRecord> {
         Ok(NationalityRecord {
             best_records: best_records_in(&self, connection).await?,
             created: created_in(&self, connection).await?,
             verified: verified_in(&self, connection).await?,
             published: published_in(&self, connection).await?,
-            unbeaten: unbeaten_in(&self, connection).await?,
+            unbeaten: unbeaten_in(&self, config, connection).await?,
             nation: self,
         })
     }
 }
 
-pub async fn unbeaten_in(nation: &Nationality, connection: &mut PgConnection) -> Result<Vec<MinimalDemon>> {
+pub async fn unbeaten_in(nation: &Nationality, config: &DemonlistConfig, connection: &mut PgConnection) -> Result<Vec<MinimalDemon>> {
     let mut stream = sqlx::query!(
         r#"select name::text as "name!", id as "id!", position as "position!" from demons where position <= $1 except (select demons.name, demons.id, position from records inner join players on 
          players.id=records.player inner join demons on demons.id=records.demon where status_='APPROVED' and nationality=$2 and progress=100 union select demons.name, demons.id, demons.position from demons inner join players on players.id=verifier where players.nationality=$2)"#,
-        crate::config::extended_list_size(),
+        config.extended_list_size,
         nation.iso_country_code
     )
     .fetch(connection);
@@ -0,0 +1,106 @@
+use crate::error::Result;
+
+use futures::StreamExt;
+use pointercrate_core::audit::{AuditLogEntry, AuditLogEntryType, NamedId};
+use serde::Serialize;
+use sqlx::PgConnection;
+
+#[derive(Serialize)]
+pub struct PlayerModificationData {
+    name: Option<String>,
+    banned: Option<bool>,
+    nationality: Option<String>,
+    subdivision: Option<String>,
+}
+
+/// Gets all audit log entries for the given player, in chronological order
+pub async fn audit_log_for_player(player_id: i32, connection: &mut PgConnection) -> Result<Vec<AuditLogEntry<PlayerModificationData>>> {
+    let mut entries = Vec::new();
+
+    let addition_row = sqlx::query!(
+        r#"SELECT time, audit_id, 
+                  userid,
+                  members.name AS "name?"
+                  FROM player_additions LEFT OUTER JOIN members ON members.member_id = userid WHERE id = $1"#,
+        player_id
+    )
+    .fetch_optional(&mut *connection)
+    .await?;
+
+    if let Some(addition) = addition_row {
+        entries.push(AuditLogEntry {
+            time: addition.time,
+            entry_id: addition.audit_id,
+            id: player_id,
+            user: NamedId {
+                name: addition.name,
+                id: addition.userid,
+            },
+            r#type: AuditLogEntryType::Addition,
+        });
+    }
+
+    {
+        let mut modification_stream = sqlx::query!(
+            r#"SELECT time, 
+                  audit_id,
+                  members.name AS "username?",
+                  userid,
+                  player_modifications.name::TEXT,
+                  player_modifications.banned,
+                  player_modifications.nationality::TEXT,
+                  player_modifications.subdivision::TEXT
+                  FROM player_modifications 
+                  LEFT OUTER JOIN members ON members.member_id = userid
+                  WHERE player_modifications.id = $1
+                  ORDER BY time"#,
+            player_id
+        )
+        .fetch(&mut *connection);
+
+        while let Some(modification) = modification_stream.next().await {
+            let modification = modification?;
+
+            entries.push(AuditLogEntry {
+                time: modification.time,
+                entry_id: modification.audit_id,
+                id: player_id,
+                r#type: AuditLogEntryType::Modification(PlayerModificationData {
+                    name: modification.name,
+                    banned: modification.banned,
+                    nationality: modification.nationality,
+                    subdivision: modification.subdivision,
+                }),
+                user: NamedId {
+                    name: modification.username,
+                    id: modification.userid,
+                },
+            })
+        }
+    }
+
+    let deletion_row = sqlx::query!(
+        r#"SELECT time, audit_id, 
+                  userid,
+                  members.name AS "name?"
+                  FROM player_deletions LEFT OUTER JOIN members ON members.member_id = userid WHERE id = $1"#,
+        player_id
+    )
+    .fetch_optional(&mut *connection)
+    .await?;
+
+    if let Some(deletion) = deletion_row {
+        entries.push(AuditLogEntry {
+            time: deletion.time,
+            entry_id: deletion.audit_id,
+            id: player_id,
+            user: NamedId {
+                name: deletion.name,
+                id: deletion.userid,
+            },
+            r#type: AuditLogEntryType::Deletion,
+        });
+    }
+
+    Ok(entries)
+}
@@ -2,7 +2,7 @@ use futures::StreamExt;
 use pointercrate_core::{
     audit::NamedId,
     first_and_last,
-    pagination::{PageContext, Paginatable, PaginationParameters, PaginationQuery, __pagination_compat},
+    pagination::{count_query, Paginatable, PaginationParameters, PaginationQuery},
     util::non_nullable,
 };
 use serde::{Deserialize, Serialize};
@@ -31,7 +31,7 @@ pub struct ListedClaim {
 
 impl PaginationQuery for PlayerClaimPagination {
     fn parameters(&self) -> PaginationParameters {
-        self.params
+        self.params.clone()
     }
 
     fn with_parameters(&self, parameters: PaginationParameters) -> Self {
@@ -45,7 +45,21 @@ impl PaginationQuery for PlayerClaimPagination {
 impl Paginatable<PlayerClaimPagination> for ListedClaim {
     first_and_last!("player_claims");
 
-    async fn page(query: &PlayerClaimPagination, connection: &mut PgConnection) -> Result<(Vec<ListedClaim>, PageContext), sqlx::Error> {
+    async fn count(query: &PlayerClaimPagination, connection: &mut PgConnection) -> Result<i64, sqlx::Error> {
+        let sql_query = count_query(&format!(include_str!("../../../sql/paginate_claims.sql"), "ASC"));
+
+        sqlx::query(&sql_query)
+            .bind(None::<i32>)
+            .bind(None::<i32>)
+            .bind(query.any_name_contains.as_ref())
+            .bind(query.verified)
+            .bind(None::<i32>)
+            .fetch_one(connection)
+            .await?
+            .try_get(0)
+    }
+
+    async fn fetch(query: &PlayerClaimPagination, connection: &mut PgConnection) -> Result<Vec<ListedClaim>, sqlx::Error> {
         let order = query.params.order();
 
         let sql_query = format!(include_str!("../../../sql/paginate_claims.sql"), order);
@@ -77,7 +91,7 @@ impl Paginatable<PlayerClaimPagination> for ListedClaim {
             })
         }
 
-        Ok(__pagination_compat(&query.params, claims))
+        Ok(claims)
     }
 
     fn pagination_id(&self) -> i32 {
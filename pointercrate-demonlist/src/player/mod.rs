@@ -1,21 +1,24 @@
 pub use self::{
-    paginate::{PlayerPagination, RankedPlayer, RankingPagination},
+    paginate::{PlayerPagination, RankedPlayer, RankingPagination, RankingSorting},
     patch::PatchPlayer,
 };
-use crate::{demon::MinimalDemon, nationality::Nationality, record::MinimalRecordD};
+use crate::{demon::MinimalDemon, nationality::Nationality, record::MinimalRecordD, PLAYER_RANKING};
 use derive_more::Display;
-use pointercrate_core::{error::CoreError, etag::Taggable};
+use pointercrate_core::{
+    cache::invalidate,
+    error::CoreError,
+    etag::{stable_hash, Taggable},
+};
 use serde::{Deserialize, Serialize};
 use sqlx::PgConnection;
-use std::{
-    collections::hash_map::DefaultHasher,
-    hash::{Hash, Hasher},
-};
+use std::hash::{Hash, Hasher};
 
+pub mod audit;
 pub mod claim;
 mod get;
 mod paginate;
 mod patch;
+mod restore;
 
 #[derive(Debug, Hash, Eq, PartialEq, Serialize, Display, Clone, Deserialize)]
 #[display("{} (ID: {})", name, id)]
@@ -75,9 +78,7 @@ impl Hash for Player {
 
 impl Taggable for FullPlayer {
     fn patch_part(&self) -> u64 {
-        let mut hasher = DefaultHasher::new();
-        self.player.hash(&mut hasher);
-        hasher.finish()
+        stable_hash(&self.player)
     }
 }
 
@@ -98,6 +99,8 @@ impl DatabasePlayer {
             .execute(&mut *connection)
             .await?;
 
+        invalidate(PLAYER_RANKING, connection).await?;
+
         Ok(new_score.score)
     }
 }
@@ -111,5 +114,54 @@ pub async fn recompute_scores(connection: &mut PgConnection) -> Result<(), CoreE
     sqlx::query!("REFRESH MATERIALIZED VIEW CONCURRENTLY player_ranks;")
         .execute(&mut *connection)
         .await?;
+    invalidate(PLAYER_RANKING, connection).await?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        demon::MinimalDemon,
+        nationality::Nationality,
+        player::{DatabasePlayer, FullPlayer, Player},
+        record::{MinimalRecordD, RecordStatus},
+    };
+    use pointercrate_core::etag::Taggable;
+
+    #[test]
+    fn test_pinned_etag() {
+        let bloodbath = MinimalDemon {
+            id: 2,
+            position: 1,
+            name: "Bloodbath".to_string(),
+        };
+        let player = FullPlayer {
+            player: Player {
+                base: DatabasePlayer {
+                    id: 1,
+                    name: "stardust1971".to_string(),
+                    banned: false,
+                },
+                score: 250.25,
+                rank: Some(1),
+                nationality: Some(Nationality {
+                    iso_country_code: "DE".to_string(),
+                    nation: "Germany".to_string(),
+                    subdivision: None,
+                }),
+            },
+            records: vec![MinimalRecordD {
+                id: 3,
+                progress: 100,
+                video: None,
+                status: RecordStatus::Approved,
+                demon: bloodbath.clone(),
+            }],
+            created: vec![bloodbath.clone()],
+            verified: vec![bloodbath.clone()],
+            published: vec![bloodbath],
+        };
+
+        assert_eq!(player.etag_string(), "W/\"v1:9300629947665312153;13716866666574793013\"");
+    }
+}
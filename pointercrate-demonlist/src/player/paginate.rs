@@ -2,14 +2,20 @@ use crate::{
     nationality::{Continent, Nationality, Subdivision},
     player::{DatabasePlayer, Player},
 };
-use futures::StreamExt;
+use async_stream::try_stream;
+use futures::{stream::BoxStream, StreamExt};
 use pointercrate_core::{
+    export::Exportable,
     first_and_last,
-    pagination::{PageContext, Paginatable, PaginationParameters, PaginationQuery, __pagination_compat},
+    pagination::{count_query, Paginatable, PaginationParameters, PaginationQuery, SortKey},
     util::{non_nullable, nullable},
 };
 use serde::{Deserialize, Serialize};
-use sqlx::{postgres::PgConnection, Row};
+use sqlx::{
+    postgres::{PgArguments, PgConnection, PgRow},
+    query::Query,
+    Postgres, Row,
+};
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct PlayerPagination {
@@ -34,7 +40,7 @@ pub struct PlayerPagination {
 
 impl PaginationQuery for PlayerPagination {
     fn parameters(&self) -> PaginationParameters {
-        self.params
+        self.params.clone()
     }
 
     fn with_parameters(&self, parameters: PaginationParameters) -> Self {
@@ -43,69 +49,86 @@ impl PaginationQuery for PlayerPagination {
             ..self.clone()
         }
     }
+
+    fn bind_filters<'q>(&'q self, sql_query: Query<'q, Postgres, PgArguments>) -> Query<'q, Postgres, PgArguments> {
+        sql_query
+            .bind(self.name.as_deref())
+            .bind(self.name_contains.as_deref())
+            .bind(self.banned)
+            .bind(&self.nation)
+            .bind(self.nation == Some(None))
+            .bind(&self.subdivision)
+    }
+}
+
+fn player_from_row(row: PgRow) -> Player {
+    let nationality = match (
+        row.get("nation"),
+        row.get("iso_country_code"),
+        row.get("iso_code"),
+        row.get("subdivision_name"),
+    ) {
+        (Some(nation), Some(country_code), Some(iso_code), Some(subdivision_name)) => Some(Nationality {
+            iso_country_code: country_code,
+            nation,
+            subdivision: Some(Subdivision {
+                iso_code,
+                name: subdivision_name,
+            }),
+        }),
+        (Some(nation), Some(country_code), None, None) => Some(Nationality {
+            iso_country_code: country_code,
+            nation,
+            subdivision: None,
+        }),
+        _ => None,
+    };
+
+    Player {
+        base: DatabasePlayer {
+            id: row.get("id"),
+            name: row.get("name"),
+            banned: row.get("banned"),
+        },
+        score: row.get("score"),
+        rank: row.get("rank"),
+        nationality,
+    }
 }
 
 impl Paginatable<PlayerPagination> for Player {
     first_and_last!("players");
 
-    async fn page(query: &PlayerPagination, connection: &mut PgConnection) -> Result<(Vec<Player>, PageContext), sqlx::Error> {
+    async fn count(query: &PlayerPagination, connection: &mut PgConnection) -> Result<i64, sqlx::Error> {
+        let sql_query = count_query(&format!(include_str!("../../sql/paginate_players_by_id.sql"), "ASC"));
+
+        let sql_query = sqlx::query(&sql_query).bind(None::<i32>).bind(None::<i32>);
+
+        query
+            .bind_filters(sql_query)
+            .bind(None::<i32>)
+            .fetch_one(connection)
+            .await?
+            .try_get(0)
+    }
+
+    async fn fetch(query: &PlayerPagination, connection: &mut PgConnection) -> Result<Vec<Player>, sqlx::Error> {
         let order = query.params.order();
 
         let sql_query = format!(include_str!("../../sql/paginate_players_by_id.sql"), order);
 
         // FIXME(sqlx) once CITEXT is supported
-        let mut stream = sqlx::query(&sql_query)
-            .bind(query.params.before)
-            .bind(query.params.after)
-            .bind(query.name.as_deref())
-            .bind(query.name_contains.as_deref())
-            .bind(query.banned)
-            .bind(&query.nation)
-            .bind(query.nation == Some(None))
-            .bind(&query.subdivision)
-            .bind(query.params.limit + 1)
-            .fetch(connection);
+        let sql_query = sqlx::query(&sql_query).bind(query.params.before).bind(query.params.after);
+
+        let mut stream = query.bind_filters(sql_query).bind(query.params.limit + 1).fetch(connection);
 
         let mut players = Vec::new();
 
         while let Some(row) = stream.next().await {
-            let row = row?;
-
-            let nationality = match (
-                row.get("nation"),
-                row.get("iso_country_code"),
-                row.get("iso_code"),
-                row.get("subdivision_name"),
-            ) {
-                (Some(nation), Some(country_code), Some(iso_code), Some(subdivision_name)) => Some(Nationality {
-                    iso_country_code: country_code,
-                    nation,
-                    subdivision: Some(Subdivision {
-                        iso_code,
-                        name: subdivision_name,
-                    }),
-                }),
-                (Some(nation), Some(country_code), None, None) => Some(Nationality {
-                    iso_country_code: country_code,
-                    nation,
-                    subdivision: None,
-                }),
-                _ => None,
-            };
-
-            players.push(Player {
-                base: DatabasePlayer {
-                    id: row.get("id"),
-                    name: row.get("name"),
-                    banned: row.get("banned"),
-                },
-                score: row.get("score"),
-                rank: row.get("rank"),
-                nationality,
-            })
+            players.push(player_from_row(row?))
         }
 
-        Ok(__pagination_compat(&query.params, players))
+        Ok(players)
     }
 
     fn pagination_id(&self) -> i32 {
@@ -113,11 +136,60 @@ impl Paginatable<PlayerPagination> for Player {
     }
 }
 
+impl Exportable<PlayerPagination> for Player {
+    const CSV_HEADER: &'static [&'static str] = &["id", "name", "banned", "score", "rank", "nation", "country_code", "subdivision"];
+
+    fn export<'a>(query: &'a PlayerPagination, connection: &'a mut PgConnection) -> BoxStream<'a, Result<Player, sqlx::Error>> {
+        Box::pin(try_stream! {
+            let sql_query = format!(include_str!("../../sql/paginate_players_by_id.sql"), "ASC");
+            let sql_query = sqlx::query(&sql_query).bind(query.params.before).bind(query.params.after);
+
+            let mut stream = query.bind_filters(sql_query).bind(None::<i32>).fetch(connection);
+
+            while let Some(row) = stream.next().await {
+                yield player_from_row(row?);
+            }
+        })
+    }
+
+    fn csv_record(&self) -> Vec<String> {
+        let nationality = self.nationality.as_ref();
+
+        vec![
+            self.base.id.to_string(),
+            self.base.name.clone(),
+            self.base.banned.to_string(),
+            self.score.to_string(),
+            self.rank.map(|rank| rank.to_string()).unwrap_or_default(),
+            nationality.map(|n| n.nation.clone()).unwrap_or_default(),
+            nationality.map(|n| n.iso_country_code.clone()).unwrap_or_default(),
+            nationality
+                .and_then(|n| n.subdivision.as_ref())
+                .map(|s| s.iso_code.clone())
+                .unwrap_or_default(),
+        ]
+    }
+}
+
+/// The orders in which the ranking can be listed
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RankingSorting {
+    /// By position in the (unfiltered) ranking. Paginated using the `before` and `after` parameters.
+    Rank,
+
+    /// Descending by score, with ties broken by ascending player ID. Paginated using cursors.
+    Score,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct RankingPagination {
     #[serde(flatten)]
     pub params: PaginationParameters,
 
+    #[serde(default, deserialize_with = "non_nullable")]
+    pub sort: Option<RankingSorting>,
+
     #[serde(default, deserialize_with = "nullable")]
     nation: Option<Option<String>>,
 
@@ -133,7 +205,7 @@ pub struct RankingPagination {
 
 impl PaginationQuery for RankingPagination {
     fn parameters(&self) -> PaginationParameters {
-        self.params
+        self.params.clone()
     }
 
     fn with_parameters(&self, parameters: PaginationParameters) -> Self {
@@ -142,8 +214,18 @@ impl PaginationQuery for RankingPagination {
             ..self.clone()
         }
     }
+
+    fn bind_filters<'q>(&'q self, sql_query: Query<'q, Postgres, PgArguments>) -> Query<'q, Postgres, PgArguments> {
+        sql_query
+            .bind(self.name_contains.as_deref())
+            .bind(&self.nation)
+            .bind(self.nation == Some(None))
+            .bind(self.continent.as_ref().map(|c| c.to_sql()))
+            .bind(&self.subdivision)
+    }
 }
 
+/// A player as listed in the ranking, which is sorted by descending score, with ties broken by ascending player ID
 #[derive(Debug, Serialize)]
 pub struct RankedPlayer {
     #[serde(skip)]
@@ -153,6 +235,23 @@ pub struct RankedPlayer {
 }
 
 impl Paginatable<RankingPagination> for RankedPlayer {
+    async fn count(query: &RankingPagination, connection: &mut PgConnection) -> Result<i64, sqlx::Error> {
+        let sql_query = count_query(&format!(include_str!("../../sql/paginate_player_ranking.sql"), ">", "ASC"));
+
+        let sql_query = sqlx::query(&sql_query)
+            .bind(None::<i32>)
+            .bind(None::<i32>)
+            .bind(None::<f64>)
+            .bind(None::<i32>);
+
+        query
+            .bind_filters(sql_query)
+            .bind(None::<i32>)
+            .fetch_one(connection)
+            .await?
+            .try_get(0)
+    }
+
     async fn first_and_last(connection: &mut PgConnection) -> Result<Option<(i32, i32)>, sqlx::Error> {
         Ok(sqlx::query!("SELECT COUNT(*) FROM players WHERE NOT banned AND score > 0.0")
             .fetch_one(connection)
@@ -161,21 +260,27 @@ impl Paginatable<RankingPagination> for RankedPlayer {
             .map(|max| (1, max as i32)))
     }
 
-    async fn page(query: &RankingPagination, connection: &mut PgConnection) -> Result<(Vec<RankedPlayer>, PageContext), sqlx::Error> {
+    async fn fetch(query: &RankingPagination, connection: &mut PgConnection) -> Result<Vec<RankedPlayer>, sqlx::Error> {
         let order = query.params.order();
 
-        let sql_query = format!(include_str!("../../sql/paginate_player_ranking.sql"), order);
+        let sql_query = format!(
+            include_str!("../../sql/paginate_player_ranking.sql"),
+            if order == "ASC" { ">" } else { "<" },
+            order
+        );
+
+        let key = match query.params.cursor {
+            Some(ref cursor) => cursor.key::<(f64, i32)>().map_err(|err| sqlx::Error::Decode(err.into()))?,
+            None => None,
+        };
 
-        let mut stream = sqlx::query(&sql_query)
+        let sql_query = sqlx::query(&sql_query)
             .bind(query.params.before)
             .bind(query.params.after)
-            .bind(query.name_contains.as_deref())
-            .bind(&query.nation)
-            .bind(query.nation == Some(None))
-            .bind(query.continent.as_ref().map(|c| c.to_sql()))
-            .bind(&query.subdivision)
-            .bind(query.params.limit + 1)
-            .fetch(connection);
+            .bind(key.map(|(score, _)| score))
+            .bind(key.map(|(_, id)| id));
+
+        let mut stream = query.bind_filters(sql_query).bind(query.params.limit + 1).fetch(connection);
 
         let mut players = Vec::new();
 
@@ -208,10 +313,18 @@ impl Paginatable<RankingPagination> for RankedPlayer {
             })
         }
 
-        Ok(__pagination_compat(&query.params, players))
+        Ok(players)
     }
 
     fn pagination_id(&self) -> i32 {
         self.index as i32
     }
+
+    fn keyset_paginated(query: &RankingPagination) -> bool {
+        query.sort == Some(RankingSorting::Score)
+    }
+
+    fn sort_key(&self, _query: &RankingPagination) -> SortKey {
+        SortKey::new(&(self.player.score, self.player.base.id))
+    }
 }
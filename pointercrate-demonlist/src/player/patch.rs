@@ -3,9 +3,13 @@ use crate::{
     nationality::Nationality,
     player::{claim::PlayerClaim, DatabasePlayer, FullPlayer, Player},
     record::{approved_records_by, FullRecord},
+    LISTED_DEMONS,
 };
 use log::info;
-use pointercrate_core::util::{non_nullable, nullable};
+use pointercrate_core::{
+    cache::invalidate,
+    util::{non_nullable, nullable},
+};
 use serde::Deserialize;
 use sqlx::PgConnection;
 
@@ -74,6 +78,9 @@ impl FullPlayer {
 
         self.player.score = self.player.base.update_score(connection).await?;
 
+        // Players appear in the listed demons as their publishers and verifiers
+        invalidate(LISTED_DEMONS, connection).await?;
+
         Ok(self)
     }
 
@@ -0,0 +1,79 @@
+use crate::{
+    error::{DemonlistError, Result},
+    player::{DatabasePlayer, FullPlayer, Player},
+};
+use log::info;
+use sqlx::PgConnection;
+
+impl DatabasePlayer {
+    /// Restores the player with the given id from its audit trail
+    ///
+    /// Note that players are only ever deleted when being merged into another player, in which case
+    /// their records, creator entries, etc. were transferred to that player. These are not moved back.
+    pub async fn restore(player_id: i32, connection: &mut PgConnection) -> Result<FullPlayer> {
+        if sqlx::query!("SELECT id FROM players WHERE id = $1", player_id)
+            .fetch_optional(&mut *connection)
+            .await?
+            .is_some()
+        {
+            return Err(DemonlistError::NotDeleted);
+        }
+
+        let deletion = sqlx::query!(
+            "SELECT audit_id FROM player_deletions WHERE id = $1 ORDER BY audit_id DESC LIMIT 1",
+            player_id
+        )
+        .fetch_optional(&mut *connection)
+        .await?
+        .ok_or(DemonlistError::PlayerNotFound { player_id })?;
+
+        // The deletion trigger stores the state of the player right before its deletion as a modification
+        let snapshot = sqlx::query!(
+            "SELECT name::TEXT, banned, nationality::TEXT, subdivision::TEXT FROM player_modifications WHERE id = $1 AND audit_id < $2 \
+             ORDER BY audit_id DESC LIMIT 1",
+            player_id,
+            deletion.audit_id
+        )
+        .fetch_optional(&mut *connection)
+        .await?
+        .ok_or(DemonlistError::IncompleteAuditTrail)?;
+
+        let (Some(name), Some(banned)) = (snapshot.name, snapshot.banned) else {
+            return Err(DemonlistError::IncompleteAuditTrail);
+        };
+
+        if let Some(row) = sqlx::query!("SELECT id FROM players WHERE name = $1::TEXT", name)
+            .fetch_optional(&mut *connection)
+            .await?
+        {
+            return Err(DemonlistError::DuplicatePlayerName { player_id: row.id });
+        }
+
+        sqlx::query!(
+            "INSERT INTO players (id, name, banned, nationality, subdivision) VALUES ($1, $2::TEXT, $3, $4, $5)",
+            player_id,
+            name,
+            banned,
+            snapshot.nationality,
+            snapshot.subdivision
+        )
+        .execute(&mut *connection)
+        .await?;
+
+        sqlx::query!(
+            "INSERT INTO player_restorations (userid, id, deletion) (SELECT id, $1, $2 FROM active_user LIMIT 1)",
+            player_id,
+            deletion.audit_id
+        )
+        .execute(&mut *connection)
+        .await?;
+
+        let player = DatabasePlayer::by_id(player_id, &mut *connection).await?;
+
+        info!("Restored player {}", player);
+
+        player.update_score(&mut *connection).await?;
+
+        Player::by_id(player_id, &mut *connection).await?.upgrade(connection).await
+    }
+}
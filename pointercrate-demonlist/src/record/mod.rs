@@ -23,19 +23,18 @@
 
 pub use self::{
     get::{approved_records_by, approved_records_on, submission_count},
-    paginate::RecordPagination,
+    paginate::{RecordPagination, RecordSorting},
     patch::PatchRecord,
     post::Submission,
 };
 use crate::{demon::MinimalDemon, error::Result, nationality::Nationality, player::DatabasePlayer, submitter::Submitter};
 use derive_more::Display;
-use pointercrate_core::etag::Taggable;
+use pointercrate_core::etag::{stable_hash, Taggable};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use sqlx::PgConnection;
 use std::{
-    collections::hash_map::DefaultHasher,
     fmt::{Display, Formatter},
-    hash::{Hash, Hasher},
+    hash::Hash,
 };
 
 pub mod audit;
@@ -45,6 +44,7 @@ pub mod note;
 mod paginate;
 mod patch;
 mod post;
+mod restore;
 
 #[derive(Debug, Eq, PartialEq, Clone, Copy, Hash, Default)]
 pub enum RecordStatus {
@@ -132,17 +132,10 @@ pub struct FullRecord {
 
 impl Taggable for FullRecord {
     fn patch_part(&self) -> u64 {
-        let mut hasher = DefaultHasher::new();
-        self.id.hash(&mut hasher);
-        self.progress.hash(&mut hasher);
-        self.video.hash(&mut hasher);
-        self.status.hash(&mut hasher);
-        self.player.id.hash(&mut hasher);
-        self.demon.id.hash(&mut hasher);
         // notes have sub-endpoint -> no hash
         // submitter cannot be patched -> no hash
         // raw footage cannot be patched -> no hash
-        hasher.finish()
+        stable_hash(&(self.id, self.progress, &self.video, self.status, self.player.id, self.demon.id))
     }
 }
 
@@ -189,3 +182,38 @@ impl FullRecord {
         .was_modified)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        demon::MinimalDemon,
+        player::DatabasePlayer,
+        record::{FullRecord, RecordStatus},
+        submitter::Submitter,
+    };
+    use pointercrate_core::etag::Taggable;
+
+    #[test]
+    fn test_pinned_etag() {
+        let record = FullRecord {
+            id: 1,
+            progress: 100,
+            video: Some("https://www.youtube.com/watch?v=dQw4w9WgXcQ".to_string()),
+            status: RecordStatus::Approved,
+            player: DatabasePlayer {
+                id: 2,
+                name: "stardust1971".to_string(),
+                banned: false,
+            },
+            demon: MinimalDemon {
+                id: 3,
+                position: 1,
+                name: "Bloodbath".to_string(),
+            },
+            submitter: Some(Submitter { id: 4, banned: false }),
+            raw_footage: None,
+        };
+
+        assert_eq!(record.etag_string(), "W/\"v1:18367366934076205127;1837349545687480324\"");
+    }
+}
@@ -0,0 +1,119 @@
+use crate::error::{DemonlistError, Result};
+
+use futures::StreamExt;
+use pointercrate_core::audit::{AuditLogEntry, AuditLogEntryType, NamedId};
+use serde::Serialize;
+use sqlx::PgConnection;
+
+#[derive(Serialize)]
+pub struct NoteModificationData {
+    /// The record this note was on before it was transferred to a different one
+    record: Option<i32>,
+    content: Option<String>,
+}
+
+/// Gets all audit log entries for the given note, in chronological order
+///
+/// Fails with [`DemonlistError::NoteNotFound`] if the note still exists, but is attached to a record other than the given one
+/// (notes that were deleted are no longer attached to any record).
+pub async fn audit_log_for_note(
+    record_id: i32, note_id: i32, connection: &mut PgConnection,
+) -> Result<Vec<AuditLogEntry<NoteModificationData>>> {
+    let on_other_record = sqlx::query!(
+        r#"SELECT EXISTS(SELECT 1 FROM record_notes WHERE id = $1 AND record <> $2) AS "exists!""#,
+        note_id,
+        record_id
+    )
+    .fetch_one(&mut *connection)
+    .await?
+    .exists;
+
+    if on_other_record {
+        return Err(DemonlistError::NoteNotFound { note_id, record_id });
+    }
+
+    let mut entries = Vec::new();
+
+    let addition_row = sqlx::query!(
+        r#"SELECT time, audit_id, 
+                  userid,
+                  members.name AS "name?"
+                  FROM record_notes_additions LEFT OUTER JOIN members ON members.member_id = userid WHERE id = $1"#,
+        note_id
+    )
+    .fetch_optional(&mut *connection)
+    .await?;
+
+    if let Some(addition) = addition_row {
+        entries.push(AuditLogEntry {
+            time: addition.time,
+            entry_id: addition.audit_id,
+            id: note_id,
+            user: NamedId {
+                name: addition.name,
+                id: addition.userid,
+            },
+            r#type: AuditLogEntryType::Addition,
+        });
+    }
+
+    {
+        let mut modification_stream = sqlx::query!(
+            r#"SELECT time, 
+                  audit_id,
+                  members.name AS "username?",
+                  userid,
+                  record,
+                  content
+                  FROM record_notes_modifications 
+                  LEFT OUTER JOIN members ON members.member_id = userid
+                  WHERE record_notes_modifications.id = $1
+                  ORDER BY time"#,
+            note_id
+        )
+        .fetch(&mut *connection);
+
+        while let Some(modification) = modification_stream.next().await {
+            let modification = modification?;
+
+            entries.push(AuditLogEntry {
+                time: modification.time,
+                entry_id: modification.audit_id,
+                id: note_id,
+                r#type: AuditLogEntryType::Modification(NoteModificationData {
+                    record: modification.record,
+                    content: modification.content,
+                }),
+                user: NamedId {
+                    name: modification.username,
+                    id: modification.userid,
+                },
+            })
+        }
+    }
+
+    let deletion_row = sqlx::query!(
+        r#"SELECT time, audit_id, 
+                  userid,
+                  members.name AS "name?"
+                  FROM record_notes_deletions LEFT OUTER JOIN members ON members.member_id = userid WHERE id = $1"#,
+        note_id
+    )
+    .fetch_optional(&mut *connection)
+    .await?;
+
+    if let Some(deletion) = deletion_row {
+        entries.push(AuditLogEntry {
+            time: deletion.time,
+            entry_id: deletion.audit_id,
+            id: note_id,
+            user: NamedId {
+                name: deletion.name,
+                id: deletion.userid,
+            },
+            r#type: AuditLogEntryType::Deletion,
+        });
+    }
+
+    Ok(entries)
+}
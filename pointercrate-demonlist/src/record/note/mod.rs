@@ -1,16 +1,14 @@
+pub mod audit;
 mod delete;
 mod get;
 mod patch;
 mod post;
 
 pub use self::{get::notes_on, patch::PatchNote, post::NewNote};
-use pointercrate_core::etag::Taggable;
+use pointercrate_core::etag::{stable_hash, Taggable};
 use serde::Deserialize;
 use serde::Serialize;
-use std::{
-    collections::hash_map::DefaultHasher,
-    hash::{Hash, Hasher},
-};
+use std::hash::Hash;
 
 #[derive(Serialize, Deserialize, Debug, Hash)]
 pub struct Note {
@@ -40,8 +38,27 @@ pub struct Note {
 
 impl Taggable for Note {
     fn patch_part(&self) -> u64 {
-        let mut hasher = DefaultHasher::new();
-        self.content.hash(&mut hasher);
-        hasher.finish()
+        stable_hash(&self.content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::record::note::Note;
+    use pointercrate_core::etag::Taggable;
+
+    #[test]
+    fn test_pinned_etag() {
+        let note = Note {
+            id: 1,
+            record: 2,
+            content: "Verified via raw footage".to_string(),
+            is_public: false,
+            transferred: false,
+            author: Some("stadust".to_string()),
+            editors: vec!["Aquatias".to_string()],
+        };
+
+        assert_eq!(note.etag_string(), "W/\"v1:10830349369429823190;14872433405039476297\"");
     }
 }
@@ -3,20 +3,40 @@ use crate::{
     player::DatabasePlayer,
     record::{MinimalRecordPD, RecordStatus},
 };
-use futures::StreamExt;
+use async_stream::try_stream;
+use futures::{stream::BoxStream, StreamExt};
 use pointercrate_core::{
+    export::Exportable,
     first_and_last,
-    pagination::{PageContext, Paginatable, PaginationParameters, PaginationQuery, __pagination_compat},
+    pagination::{count_query, Paginatable, PaginationParameters, PaginationQuery, SortKey},
     util::{non_nullable, nullable},
 };
 use serde::{Deserialize, Serialize};
-use sqlx::{postgres::PgRow, PgConnection, Row};
+use sqlx::{
+    postgres::{PgArguments, PgRow},
+    query::Query,
+    PgConnection, Postgres, Row,
+};
+
+/// The orders in which records can be listed
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RecordSorting {
+    /// Ascending by record ID. Paginated using the `before` and `after` parameters.
+    Id,
+
+    /// Descending by progress, with ties broken by ascending record ID. Paginated using cursors.
+    Progress,
+}
 
 #[derive(Clone, Debug, Serialize, Deserialize, Default)]
 pub struct RecordPagination {
     #[serde(flatten)]
     pub params: PaginationParameters,
 
+    #[serde(default, deserialize_with = "non_nullable")]
+    pub sort: Option<RecordSorting>,
+
     progress: Option<i16>,
 
     #[serde(default, deserialize_with = "non_nullable")]
@@ -58,7 +78,7 @@ pub struct RecordPagination {
 
 impl PaginationQuery for RecordPagination {
     fn parameters(&self) -> PaginationParameters {
-        self.params
+        self.params.clone()
     }
 
     fn with_parameters(&self, parameters: PaginationParameters) -> Self {
@@ -67,62 +87,164 @@ impl PaginationQuery for RecordPagination {
             ..self.clone()
         }
     }
+
+    fn bind_filters<'q>(&'q self, sql_query: Query<'q, Postgres, PgArguments>) -> Query<'q, Postgres, PgArguments> {
+        sql_query
+            .bind(self.progress)
+            .bind(self.progress_lt)
+            .bind(self.progress_gt)
+            .bind(self.demon_position)
+            .bind(self.demon_position_lt)
+            .bind(self.demon_position_gt)
+            .bind(self.status.map(|s| s.to_sql()))
+            .bind(self.demon.as_deref())
+            .bind(self.demon_id)
+            .bind(&self.video)
+            .bind(self.video == Some(None))
+            .bind(self.player)
+            .bind(self.submitter)
+    }
+}
+
+impl RecordPagination {
+    /// The SQL query retrieving the records matching this query, ordered as requested by `sort` in the given direction
+    fn sql(&self, order: &str) -> String {
+        match self.sort {
+            Some(RecordSorting::Progress) => format!(
+                include_str!("../../sql/paginate_records_by_progress.sql"),
+                if order == "ASC" { ">" } else { "<" },
+                order
+            ),
+            _ => format!(include_str!("../../sql/paginate_records.sql"), order),
+        }
+    }
+}
+
+fn record_from_row(row: PgRow) -> Result<MinimalRecordPD, sqlx::Error> {
+    Ok(MinimalRecordPD {
+        id: row.try_get("id")?,
+        progress: row.try_get("progress")?,
+        video: row.try_get("video")?,
+        status: RecordStatus::from_sql(&row.try_get::<String, _>("status")?),
+        player: DatabasePlayer {
+            id: row.try_get("player_id")?,
+            name: row.try_get("player_name")?,
+            banned: row.try_get("player_banned")?,
+        },
+        demon: MinimalDemon {
+            id: row.try_get("demon_id")?,
+            position: row.try_get("position")?,
+            name: row.try_get("demon_name")?,
+        },
+    })
 }
 
 impl Paginatable<RecordPagination> for MinimalRecordPD {
     first_and_last!("records");
 
-    async fn page(query: &RecordPagination, connection: &mut PgConnection) -> Result<(Vec<MinimalRecordPD>, PageContext), sqlx::Error> {
-        let order = query.params.order();
-
-        let sql_query = format!(include_str!("../../sql/paginate_records.sql"), order);
-
-        let mut stream = sqlx::query(&sql_query)
-            .bind(query.params.before)
-            .bind(query.params.after)
-            .bind(query.progress)
-            .bind(query.progress_lt)
-            .bind(query.progress_gt)
-            .bind(query.demon_position)
-            .bind(query.demon_position_lt)
-            .bind(query.demon_position_gt)
-            .bind(query.status.map(|s| s.to_sql()))
-            .bind(query.demon.as_deref())
-            .bind(query.demon_id)
-            .bind(&query.video)
-            .bind(query.video == Some(None))
-            .bind(query.player)
-            .bind(query.submitter)
-            .bind(query.params.limit + 1)
-            .fetch(&mut *connection);
+    async fn count(query: &RecordPagination, connection: &mut PgConnection) -> Result<i64, sqlx::Error> {
+        // The sort order does not affect which records match the query
+        let sql_query = count_query(&format!(include_str!("../../sql/paginate_records.sql"), "ASC"));
+
+        let sql_query = sqlx::query(&sql_query).bind(None::<i32>).bind(None::<i32>);
+
+        query
+            .bind_filters(sql_query)
+            .bind(None::<i32>)
+            .fetch_one(connection)
+            .await?
+            .try_get(0)
+    }
+
+    async fn fetch(query: &RecordPagination, connection: &mut PgConnection) -> Result<Vec<MinimalRecordPD>, sqlx::Error> {
+        let sql_query = query.sql(query.params.order());
+
+        let mut sql_query = sqlx::query(&sql_query);
+
+        sql_query = match query.sort {
+            Some(RecordSorting::Progress) => {
+                let key = match query.params.cursor {
+                    Some(ref cursor) => cursor.key::<(i16, i32)>().map_err(|err| sqlx::Error::Decode(err.into()))?,
+                    None => None,
+                };
+
+                sql_query.bind(key.map(|(progress, _)| progress)).bind(key.map(|(_, id)| id))
+            },
+            _ => sql_query.bind(query.params.before).bind(query.params.after),
+        };
+
+        let mut stream = query.bind_filters(sql_query).bind(query.params.limit + 1).fetch(&mut *connection);
 
         let mut records = Vec::new();
 
         while let Some(row) = stream.next().await {
-            let row: PgRow = row?;
-
-            records.push(MinimalRecordPD {
-                id: row.try_get("id")?,
-                progress: row.try_get("progress")?,
-                video: row.try_get("video")?,
-                status: RecordStatus::from_sql(&row.try_get::<String, _>("status")?),
-                player: DatabasePlayer {
-                    id: row.try_get("player_id")?,
-                    name: row.try_get("player_name")?,
-                    banned: row.try_get("player_banned")?,
-                },
-                demon: MinimalDemon {
-                    id: row.try_get("demon_id")?,
-                    position: row.try_get("position")?,
-                    name: row.try_get("demon_name")?,
-                },
-            })
+            records.push(record_from_row(row?)?)
         }
 
-        Ok(__pagination_compat(&query.params, records))
+        Ok(records)
     }
 
     fn pagination_id(&self) -> i32 {
         self.id
     }
+
+    fn keyset_paginated(query: &RecordPagination) -> bool {
+        query.sort == Some(RecordSorting::Progress)
+    }
+
+    fn sort_key(&self, query: &RecordPagination) -> SortKey {
+        match query.sort {
+            Some(RecordSorting::Progress) => SortKey::new(&(self.progress, self.id)),
+            _ => SortKey::new(&self.id),
+        }
+    }
+}
+
+impl Exportable<RecordPagination> for MinimalRecordPD {
+    const CSV_HEADER: &'static [&'static str] = &[
+        "id",
+        "progress",
+        "video",
+        "status",
+        "player_id",
+        "player_name",
+        "player_banned",
+        "demon_id",
+        "demon_name",
+        "demon_position",
+    ];
+
+    fn export<'a>(query: &'a RecordPagination, connection: &'a mut PgConnection) -> BoxStream<'a, Result<MinimalRecordPD, sqlx::Error>> {
+        Box::pin(try_stream! {
+            let sql_query = query.sql("ASC");
+            let sql_query = sqlx::query(&sql_query);
+
+            // Exports of keyset paginated listings always start at the beginning of the listing
+            let sql_query = match query.sort {
+                Some(RecordSorting::Progress) => sql_query.bind(None::<i16>).bind(None::<i32>),
+                _ => sql_query.bind(query.params.before).bind(query.params.after),
+            };
+
+            let mut stream = query.bind_filters(sql_query).bind(None::<i32>).fetch(connection);
+
+            while let Some(row) = stream.next().await {
+                yield record_from_row(row?)?;
+            }
+        })
+    }
+
+    fn csv_record(&self) -> Vec<String> {
+        vec![
+            self.id.to_string(),
+            self.progress.to_string(),
+            self.video.clone().unwrap_or_default(),
+            self.status.to_string(),
+            self.player.id.to_string(),
+            self.player.name.clone(),
+            self.player.banned.to_string(),
+            self.demon.id.to_string(),
+            self.demon.name.clone(),
+            self.demon.position.to_string(),
+        ]
+    }
 }
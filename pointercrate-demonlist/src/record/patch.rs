@@ -3,9 +3,11 @@ use crate::{
     error::{DemonlistError, Result},
     player::DatabasePlayer,
     record::{FullRecord, RecordStatus},
+    PLAYER_RANKING,
 };
 use log::{info, warn};
 use pointercrate_core::{
+    cache::invalidate,
     error::CoreError,
     util::{non_nullable, nullable},
 };
@@ -338,9 +340,12 @@ impl FullRecord {
             status.to_sql().to_string(),
             self.id
         )
-        .execute(connection)
+        .execute(&mut *connection)
         .await?;
 
+        // Approving or un-approving a record changes the player's score
+        invalidate(PLAYER_RANKING, connection).await?;
+
         self.status = status;
 
         Ok(())
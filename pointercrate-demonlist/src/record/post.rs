@@ -1,4 +1,5 @@
 use crate::{
+    config::DemonlistConfig,
     demon::MinimalDemon,
     error::{DemonlistError, Result},
     player::{claim::PlayerClaim, DatabasePlayer},
@@ -89,20 +90,20 @@ impl NormalizedSubmission {
         PlayerClaim::verified_claim_on(self.player.id, connection).await
     }
 
-    pub async fn validate(self, connection: &mut PgConnection) -> Result<ValidatedSubmission> {
+    pub async fn validate(self, config: &DemonlistConfig, connection: &mut PgConnection) -> Result<ValidatedSubmission> {
         // Banned player can't have records on the list
         if self.player.banned {
             return Err(DemonlistError::PlayerBanned);
         }
 
         // Cannot submit records for the legacy list (it is possible to directly add them for list mods)
-        if self.demon.position > crate::config::extended_list_size() && self.status == RecordStatus::Submitted {
+        if self.demon.position > config.extended_list_size && self.status == RecordStatus::Submitted {
             return Err(DemonlistError::SubmitLegacy);
         }
 
         // Can only submit 100% records for the extended list (it is possible to directly add them for list
         // mods)
-        if self.demon.position > crate::config::list_size() && self.progress != 100 && self.status == RecordStatus::Submitted {
+        if self.demon.position > config.list_size && self.progress != 100 && self.status == RecordStatus::Submitted {
             return Err(DemonlistError::Non100Extended);
         }
 
@@ -221,6 +222,7 @@ impl ValidatedSubmission {
 #[cfg(test)]
 mod tests {
     use crate::{
+        config::DemonlistConfig,
         demon::MinimalDemon,
         error::DemonlistError,
         player::DatabasePlayer,
@@ -247,7 +249,7 @@ mod tests {
             raw_footage: None,
             note: None,
         }
-        .validate(&mut conn)
+        .validate(&DemonlistConfig::default(), &mut conn)
         .await;
 
         assert!(result.is_err());
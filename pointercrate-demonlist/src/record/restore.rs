@@ -0,0 +1,138 @@
+use crate::{
+    demon::MinimalDemon,
+    error::{DemonlistError, Result},
+    player::DatabasePlayer,
+    record::{FullRecord, RecordStatus},
+    submitter::Submitter,
+};
+use log::info;
+use sqlx::PgConnection;
+
+impl FullRecord {
+    /// Restores the record with the given id from its audit trail, together with all notes that
+    /// were deleted alongside it
+    ///
+    /// Fails if the record was never deleted, or if restoring it would violate the uniqueness
+    /// invariants described in the [module level documentation](crate::record). Must be called
+    /// inside a transaction.
+    pub async fn restore(record_id: i32, connection: &mut PgConnection) -> Result<FullRecord> {
+        if sqlx::query!("SELECT id FROM records WHERE id = $1", record_id)
+            .fetch_optional(&mut *connection)
+            .await?
+            .is_some()
+        {
+            return Err(DemonlistError::NotDeleted);
+        }
+
+        let deletion = sqlx::query!(
+            "SELECT audit_id, time FROM record_deletions WHERE id = $1 ORDER BY audit_id DESC LIMIT 1",
+            record_id
+        )
+        .fetch_optional(&mut *connection)
+        .await?
+        .ok_or(DemonlistError::RecordNotFound { record_id })?;
+
+        // The deletion trigger stores the state of the record right before its deletion as a modification
+        let snapshot = sqlx::query!(
+            r#"SELECT progress, video::TEXT, status_::TEXT AS status, player, demon, submitter, raw_footage FROM record_modifications
+             WHERE id = $1 AND audit_id < $2 ORDER BY audit_id DESC LIMIT 1"#,
+            record_id,
+            deletion.audit_id
+        )
+        .fetch_optional(&mut *connection)
+        .await?
+        .ok_or(DemonlistError::IncompleteAuditTrail)?;
+
+        // Records deleted before the submitter was included in the snapshot cannot be restored
+        let (Some(progress), Some(status), Some(player), Some(demon), Some(submitter)) = (
+            snapshot.progress,
+            snapshot.status,
+            snapshot.player,
+            snapshot.demon,
+            snapshot.submitter,
+        ) else {
+            return Err(DemonlistError::IncompleteAuditTrail);
+        };
+
+        let status = RecordStatus::from_sql(&status);
+        let player = DatabasePlayer::by_id(player, &mut *connection).await?;
+        let demon = MinimalDemon::by_id(demon, &mut *connection).await?;
+        let submitter = Submitter::by_id(submitter, &mut *connection).await?;
+
+        if player.banned && status != RecordStatus::Rejected {
+            return Err(DemonlistError::PlayerBanned);
+        }
+
+        if let Some(ref video) = snapshot.video {
+            if let Some(row) = sqlx::query!("SELECT id FROM records WHERE video = $1", video)
+                .fetch_optional(&mut *connection)
+                .await?
+            {
+                return Err(DemonlistError::DuplicateVideo { id: row.id });
+            }
+        }
+
+        // Records that would have been deleted (or would have prevented the submission of this record) had this record never been
+        // deleted. We do not resolve such conflicts automatically, as that would mean deleting records the list team may have
+        // already dealt with.
+        let existing = sqlx::query!(
+            r#"SELECT id, status_::text as "status_!: String" FROM records WHERE demon = $1 AND player = $2 AND ($3 = 'REJECTED' OR
+             status_ = 'REJECTED' OR ($3 = 'APPROVED' AND (status_ = 'APPROVED' OR progress <= $4)) OR ($3 <> 'APPROVED' AND (status_ =
+             'UNDER_CONSIDERATION' OR (status_ = 'APPROVED' AND progress >= $4)))) LIMIT 1"#,
+            demon.id,
+            player.id,
+            status.to_sql(),
+            progress
+        )
+        .fetch_optional(&mut *connection)
+        .await?;
+
+        if let Some(row) = existing {
+            return Err(DemonlistError::SubmissionExists {
+                existing: row.id,
+                status: RecordStatus::from_sql(&row.status_),
+            });
+        }
+
+        sqlx::query!(
+            "INSERT INTO records (id, progress, video, status_, player, submitter, demon, raw_footage) VALUES ($1, $2, $3::TEXT, cast($4::text \
+             as record_status), $5, $6, $7, $8)",
+            record_id,
+            progress,
+            snapshot.video,
+            status.to_sql(),
+            player.id,
+            submitter.id,
+            demon.id,
+            snapshot.raw_footage
+        )
+        .execute(&mut *connection)
+        .await?;
+
+        // Notes are deleted via ON DELETE CASCADE, meaning in the same transaction as the record itself
+        let notes_restored = sqlx::query!(
+            "INSERT INTO record_notes (id, record, content, is_public) SELECT id, record, content, coalesce(is_public, FALSE) FROM \
+             record_notes_deletions WHERE record = $1 AND time = $2 AND content IS NOT NULL",
+            record_id,
+            deletion.time
+        )
+        .execute(&mut *connection)
+        .await?;
+
+        sqlx::query!(
+            "INSERT INTO record_restorations (userid, id, deletion) (SELECT id, $1, $2 FROM active_user LIMIT 1)",
+            record_id,
+            deletion.audit_id
+        )
+        .execute(&mut *connection)
+        .await?;
+
+        let record = FullRecord::by_id(record_id, &mut *connection).await?;
+
+        info!("Restored record {} and {} of its notes", record, notes_restored.rows_affected());
+
+        record.player.update_score(connection).await?;
+
+        Ok(record)
+    }
+}
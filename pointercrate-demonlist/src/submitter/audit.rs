@@ -0,0 +1,53 @@
+use crate::error::Result;
+
+use futures::StreamExt;
+use pointercrate_core::audit::{AuditLogEntry, AuditLogEntryType, NamedId};
+use serde::Serialize;
+use sqlx::PgConnection;
+
+#[derive(Serialize)]
+pub struct SubmitterModificationData {
+    banned: Option<bool>,
+}
+
+/// Gets all audit log entries for the given submitter, in chronological order
+///
+/// Submitters are created implicitly and never deleted, so only modifications are logged for them.
+pub async fn audit_log_for_submitter(
+    submitter_id: i32, connection: &mut PgConnection,
+) -> Result<Vec<AuditLogEntry<SubmitterModificationData>>> {
+    let mut entries = Vec::new();
+
+    let mut modification_stream = sqlx::query!(
+        r#"SELECT time, 
+                  audit_id,
+                  members.name AS "username?",
+                  userid,
+                  banned
+                  FROM submitter_modifications 
+                  LEFT OUTER JOIN members ON members.member_id = userid
+                  WHERE submitter = $1
+                  ORDER BY time"#,
+        submitter_id
+    )
+    .fetch(&mut *connection);
+
+    while let Some(modification) = modification_stream.next().await {
+        let modification = modification?;
+
+        entries.push(AuditLogEntry {
+            time: modification.time,
+            entry_id: modification.audit_id,
+            id: submitter_id,
+            r#type: AuditLogEntryType::Modification(SubmitterModificationData {
+                banned: modification.banned,
+            }),
+            user: NamedId {
+                name: modification.username,
+                id: modification.userid,
+            },
+        })
+    }
+
+    Ok(entries)
+}
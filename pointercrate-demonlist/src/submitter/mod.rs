@@ -6,6 +6,7 @@ pub use paginate::SubmitterPagination;
 pub use patch::PatchSubmitter;
 use pointercrate_core::etag::Taggable;
 
+pub mod audit;
 mod get;
 mod paginate;
 mod patch;
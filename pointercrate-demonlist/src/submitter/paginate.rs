@@ -2,13 +2,13 @@ use crate::submitter::Submitter;
 use futures::StreamExt;
 use pointercrate_core::{
     first_and_last,
-    pagination::{PageContext, Paginatable, PaginationParameters, PaginationQuery, __pagination_compat},
+    pagination::{Paginatable, PaginationParameters, PaginationQuery},
     util::non_nullable,
 };
 use serde::{Deserialize, Serialize};
 use sqlx::{PgConnection, Row};
 
-#[derive(Deserialize, Debug, Clone, Copy, Serialize)]
+#[derive(Deserialize, Debug, Clone, Serialize)]
 pub struct SubmitterPagination {
     #[serde(flatten)]
     pub params: PaginationParameters,
@@ -19,13 +19,13 @@ pub struct SubmitterPagination {
 
 impl PaginationQuery for SubmitterPagination {
     fn parameters(&self) -> PaginationParameters {
-        self.params
+        self.params.clone()
     }
 
     fn with_parameters(&self, parameters: PaginationParameters) -> Self {
         Self {
             params: parameters,
-            ..*self
+            ..self.clone()
         }
     }
 }
@@ -33,7 +33,17 @@ impl PaginationQuery for SubmitterPagination {
 impl Paginatable<SubmitterPagination> for Submitter {
     first_and_last!("submitters", "submitter_id");
 
-    async fn page(query: &SubmitterPagination, connection: &mut PgConnection) -> Result<(Vec<Submitter>, PageContext), sqlx::Error> {
+    async fn count(query: &SubmitterPagination, connection: &mut PgConnection) -> Result<i64, sqlx::Error> {
+        Ok(sqlx::query!(
+            r#"SELECT COUNT(*) AS "count!" FROM submitters WHERE banned = $1 OR $1 IS NULL"#,
+            query.banned
+        )
+        .fetch_one(connection)
+        .await?
+        .count)
+    }
+
+    async fn fetch(query: &SubmitterPagination, connection: &mut PgConnection) -> Result<Vec<Submitter>, sqlx::Error> {
         let order = query.params.order();
 
         let sql_query = format!("SELECT submitter_id, banned FROM submitters WHERE (submitter_id < $1 OR $1 IS NULL) AND (submitter_id > $2 OR $2 IS NULL) AND (banned = $3 OR $3 IS NULL) ORDER BY submitter_id {} LIMIT $4", order);
@@ -56,7 +66,7 @@ impl Paginatable<SubmitterPagination> for Submitter {
             })
         }
 
-        Ok(__pagination_compat(&query.params, submitters))
+        Ok(submitters)
     }
 
     fn pagination_id(&self) -> i32 {
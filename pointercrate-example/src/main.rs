@@ -1,23 +1,27 @@
 use maud::html;
-use pointercrate_core::localization::LocalesLoader;
+use pointercrate_core::config::{ConfigSource, CoreConfig, RatelimitConfig};
+use pointercrate_core::localization::{LocaleConfiguration, LocalesLoader};
 use pointercrate_core::pool::PointercratePool;
 use pointercrate_core::{error::CoreError, localization::tr};
-use pointercrate_core_api::{error::ErrorResponder, maintenance::MaintenanceFairing, preferences::PreferenceManager};
+use pointercrate_core_api::{
+    cache::ResponseCacheFairing, config::ConfigFairing, error::ErrorResponder, maintenance::MaintenanceFairing,
+    preferences::PreferenceManager,
+};
 use pointercrate_core_macros::localized_catcher;
 use pointercrate_core_pages::{
     footer::{Footer, FooterColumn, Link},
     navigation::{NavigationBar, TopLevelNavigationBarItem},
     PageConfiguration,
 };
-use pointercrate_demonlist::LIST_ADMINISTRATOR;
-use pointercrate_demonlist_api::GeolocationProvider;
+use pointercrate_demonlist::{config::DemonlistConfig, LIST_ADMINISTRATOR};
+use pointercrate_demonlist_api::{config::DemonlistApiConfig, GeolocationProvider};
 use pointercrate_demonlist_pages::account::{
     demons::DemonsTab, list_integration::ListIntegrationTab, players::PlayersPage, records::RecordsPage,
 };
-use pointercrate_user::MODERATOR;
+use pointercrate_user::{config::UserConfig, MODERATOR};
 use pointercrate_user_pages::account::{profile::ProfileTab, users::UsersTab, AccountPageConfig};
 use rocket::{async_trait, fs::FileServer, response::Redirect, serde, uri, Request};
-use std::net::IpAddr;
+use std::{net::IpAddr, sync::OnceLock, time::Duration};
 use unic_langid::lang;
 use unic_langid::subtags::Language;
 
@@ -59,6 +63,9 @@ fn home() -> Redirect {
 
 const DEFAULT_LOCALE: Language = lang!("en");
 
+/// The demonlist configuration, needed by [`page_configuration`] to link to the extended and legacy lists
+static LIST_CONFIG: OnceLock<DemonlistConfig> = OnceLock::new();
+
 /// A very simplistic geolocation provider based on https://ipwho.is/
 ///
 /// Note that ipwho.is is only free for testing, non-commercial use-cases, and
@@ -93,6 +100,33 @@ async fn rocket() -> _ {
     // Load the configuration from your .env file
     dotenv::dotenv().unwrap();
 
+    // Load the configuration of all pointercrate components. Values are read from `pointercrate.toml` (or the file
+    // specified via the POINTERCRATE_CONFIG environment variable), and can be overridden via environment variables
+    // (such as the ones from your .env file). If anything is missing or malformed, all problems are reported at once
+    // and the server does not start.
+    let mut config_source = ConfigSource::load();
+    let core_config = config_source.section::<CoreConfig>();
+    let list_config = config_source.section::<DemonlistConfig>();
+    let list_api_config = config_source.section::<DemonlistApiConfig>();
+    let user_config = config_source.section::<UserConfig>();
+    let ratelimit_config = config_source.section::<RatelimitConfig>();
+
+    if let Err(errors) = config_source.finish() {
+        eprintln!("{}", errors);
+        std::process::exit(1);
+    }
+
+    // Sections are only missing if loading them reported an error
+    let (core_config, list_config, list_api_config, user_config, ratelimit_config) = (
+        core_config.unwrap(),
+        list_config.unwrap(),
+        list_api_config.unwrap(),
+        user_config.unwrap(),
+        ratelimit_config.unwrap(),
+    );
+
+    let _ = LIST_CONFIG.set(list_config);
+
     // Load the translation files
     LocalesLoader::load(&[
         "pointercrate-core-pages/static/ftl/",
@@ -103,14 +137,25 @@ async fn rocket() -> _ {
     .expect("Failed to load localization files")
     .commit(DEFAULT_LOCALE);
 
-    // Initialize a database connection pool to the database specified by the
-    // DATABASE_URL environment variable
-    let pool = PointercratePool::init().await;
+    // During development, pick up changes to the translation files without needing a restart
+    if core_config.localization_hot_reload {
+        LocaleConfiguration::get().watch(Duration::from_secs(1));
+    }
+
+    // Initialize a database connection pool to the configured database
+    let pool = PointercratePool::init(&core_config).await;
 
     // Set up the HTTP server
     let rocket = rocket::build()
         // Tell it about the connection pool to use (individual handlers can get hold of this pool by declaring an argument of type `&State<PointercratePool>`)
         .manage(pool)
+        // Make each component's configuration available to its request handlers
+        .manage(list_config)
+        .manage(list_api_config)
+        .manage(user_config.clone())
+        .manage(ratelimit_config)
+        // Refuse to launch if the configuration was not loaded, or configures ratelimits that do not exist
+        .attach(ConfigFairing)
         // Tell pointercrate's core components about navigation bar and footers, so that it knows how to render the website
         // We are passing is as a function pointer so the page can load it in a different language each time a page is rendered
         .manage(page_configuration as fn() -> PageConfiguration)
@@ -127,7 +172,9 @@ async fn rocket() -> _ {
     // `LIST_HELPER`. For more information on pointercrate' permissions system, see
     // the documentation of the [`PermissionsManager`] structure.
     let mut permissions_manager = pointercrate_user::default_permissions_manager();
-    permissions_manager.merge_with(pointercrate_demonlist::default_permissions_manager());
+    permissions_manager
+        .merge_with(pointercrate_demonlist::default_permissions_manager())
+        .expect("Conflicting permission definitions");
 
     let rocket = rocket.manage(permissions_manager);
 
@@ -146,7 +193,7 @@ async fn rocket() -> _ {
     // [`AccountPageTab::should_display_for`] returns `true`.
     let account_page_config = AccountPageConfig::default()
         // Tab where users can modify their own accounts
-        .with_page(ProfileTab)
+        .with_page(ProfileTab(user_config))
         // Tab where users can initiate player claims and manage their claimed players
         .with_page(ListIntegrationTab("https://discord.gg/tMBzYP77ag"))
         // Tab where website moderators can manage permissions. 
@@ -157,13 +204,18 @@ async fn rocket() -> _ {
         // Tab where list helpers can manage players
         .with_page(PlayersPage)
         // Tab where list helpers can manage records
-        .with_page(RecordsPage);
+        .with_page(RecordsPage(list_config));
 
     let rocket = rocket.manage(account_page_config);
 
     // Changing `false` to `true` here will put your website into "maintenance mode", which will disable all mutating request handlers and always return 503 SERVICE UNAVAILABLE responses for non-GET requests.
+    // Administrators can additionally enable maintenance mode at runtime, schedule it in advance, or freeze only some features (such as record submissions), via the `/api/v1/maintenance/` endpoint.
     let rocket = rocket.attach(MaintenanceFairing::new(false));
 
+    // Cache responses of frequently requested endpoints (such as the demonlist itself). Cached responses are evicted whenever
+    // the data they contain changes. Administrators can inspect the cache's hit rate via the `/api/v1/cache/` endpoint.
+    let rocket = rocket.attach(ResponseCacheFairing);
+
     // Register all the endpoints related to the demonlist to our server (this is
     // optional, but without registering the demonlist related endpoint your website
     // will just be User Account Simulator 2024).
@@ -191,6 +243,8 @@ async fn rocket() -> _ {
 /// links to include) and various metadata without you needing to worry (much)
 /// about styling and layout.
 fn page_configuration() -> PageConfiguration {
+    let list_config = LIST_CONFIG.get().copied().unwrap_or_default();
+
     // Define a navigation bar with only two items, a link to the user account page,
     // and a link to your demonlist.
     let nav_bar = NavigationBar::new("/static/images/path/to/your/logo.png")
@@ -236,11 +290,11 @@ fn page_configuration() -> PageConfiguration {
         links: vec![
             Link::new("/demonlist/1/", tr("footer-demonlist.top-demon")),
             Link::new(
-                format!("/demonlist/{}/", pointercrate_demonlist::config::list_size() + 1),
+                format!("/demonlist/{}/", list_config.list_size + 1),
                 tr("footer-demonlist.extended-list"),
             ),
             Link::new(
-                format!("/demonlist/{}/", pointercrate_demonlist::config::extended_list_size() + 1),
+                format!("/demonlist/{}/", list_config.extended_list_size + 1),
                 tr("footer-demonlist.legacy-list"),
             ),
         ],
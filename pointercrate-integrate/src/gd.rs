@@ -10,7 +10,9 @@ use dash_rs::{
 };
 use log::{debug, error, trace, warn};
 use pointercrate_core::ratelimits;
-use pointercrate_demonlist::demon::Demon;
+use pointercrate_core::ratelimits::{PostgresRatelimitStore, RatelimitSet};
+use pointercrate_core::{audit::ServiceAccount, cache::invalidate, pool::audit_connection};
+use pointercrate_demonlist::{demon::Demon, LISTED_DEMONS};
 use reqwest::{header::CONTENT_TYPE, Client};
 use sqlx::{Pool, Postgres};
 use std::{borrow::Cow, sync::Arc};
@@ -38,9 +40,9 @@ impl GeometryDashConnector {
     /// If the last time the data for this demon was sought on the Geometry Dash servers was over 24h ago,
     /// re-query them for updated data.
     pub async fn load_level_for_demon(&self, demon: &Demon) -> Option<IntegrationLevel> {
-        if self.ratelimits.throttle_throttle(demon.base.id).is_ok()
-            && self.ratelimits.throttle().is_ok()
-            && self.ratelimits.demon_refresh(demon.base.id).is_ok()
+        if self.ratelimits.throttle_throttle(demon.base.id).await.is_ok()
+            && self.ratelimits.throttle().await.is_ok()
+            && self.ratelimits.demon_refresh(demon.base.id).await.is_ok()
         {
             tokio::spawn(
                 self.clone()
@@ -122,9 +124,20 @@ impl GeometryDashConnector {
         self.store_level(&level, level.creator, level.custom_song).await;
         self.store_level_data(level.level_id, &mut level.level_data).await;
 
-        let _ = sqlx::query!("UPDATE demons SET level_id = $1 WHERE id = $2", level.level_id as i64, demon_id)
-            .execute(&self.pool)
+        let Ok(mut connection) = self.pool.acquire().await else { return };
+
+        if audit_connection(&mut connection, ServiceAccount::GD_SYNC.id()).await.is_err() {
+            return;
+        }
+
+        let updated = sqlx::query!("UPDATE demons SET level_id = $1 WHERE id = $2", level.level_id as i64, demon_id)
+            .execute(&mut *connection)
             .await;
+
+        // Listings include the level id, and this update bypasses the demonlist models
+        if updated.is_ok() {
+            let _ = invalidate(LISTED_DEMONS, &mut *connection).await;
+        }
     }
 
     async fn make_request(&self, url: String, body: String) -> Result<String, reqwest::Error> {
@@ -158,12 +171,17 @@ pub struct GeometryDashConnector {
 impl GeometryDashConnector {
     pub fn new(pool: Pool<Postgres>) -> Self {
         GeometryDashConnector {
+            ratelimits: Arc::new(IntegrationRatelimits::with_store(PostgresRatelimitStore::new(pool.clone()))),
             pool,
             http_client: Client::new(),
-            ratelimits: Arc::new(IntegrationRatelimits::new()),
         }
     }
 
+    /// The ratelimits applied to requests made to the Geometry Dash servers
+    pub fn ratelimits(&self) -> &RatelimitSet {
+        self.ratelimits.set()
+    }
+
     pub async fn lookup_creator(&self, user_id: u64) -> Option<Creator<'static>> {
         let mut connection = self.pool.acquire().await.ok()?;
 
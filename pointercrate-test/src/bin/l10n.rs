@@ -0,0 +1,54 @@
+//! Command line interface to the localization utilities in [`pointercrate_test::localization`].
+//!
+//! Usage:
+//! * `cargo run -p pointercrate-test --bin l10n -- lint` reports undefined, unused, missing and inconsistent text ids
+//! * `cargo run -p pointercrate-test --bin l10n -- pseudo [source-language]` generates the pseudo-locale from the given
+//!   language (`en-us` by default)
+
+use pointercrate_test::localization::{generate_pseudo_locale, lint, workspace_root, PSEUDO_LOCALE};
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    let root = workspace_root();
+
+    match args.iter().map(String::as_str).collect::<Vec<_>>()[..] {
+        ["lint"] => match lint(&root) {
+            Ok(report) => {
+                print!("{}", report);
+
+                if report.is_clean() {
+                    ExitCode::SUCCESS
+                } else {
+                    ExitCode::FAILURE
+                }
+            },
+            Err(err) => {
+                eprintln!("{}", err);
+
+                ExitCode::FAILURE
+            },
+        },
+        ["pseudo", ref source_language @ ..] if source_language.len() <= 1 => {
+            let source_language = source_language.first().copied().unwrap_or("en-us");
+
+            match generate_pseudo_locale(&root, source_language) {
+                Ok(()) => {
+                    println!("Generated pseudo-locale {} from {}", PSEUDO_LOCALE, source_language);
+
+                    ExitCode::SUCCESS
+                },
+                Err(err) => {
+                    eprintln!("{}", err);
+
+                    ExitCode::FAILURE
+                },
+            }
+        },
+        _ => {
+            eprintln!("Usage: l10n lint | l10n pseudo [source-language]");
+
+            ExitCode::FAILURE
+        },
+    }
+}
@@ -1,16 +1,23 @@
 use crate::{TestClient, TestRequest};
+use pointercrate_core::cache::invalidate;
+use pointercrate_core::config::ConfigSource;
 use pointercrate_core::etag::Taggable;
 use pointercrate_core::localization::LocalesLoader;
 use pointercrate_core::{permission::PermissionsManager, pool::PointercratePool};
-use pointercrate_core_api::preferences::PreferenceManager;
+use pointercrate_core_api::{cache::ResponseCacheFairing, config::ConfigFairing, preferences::PreferenceManager};
 use pointercrate_demonlist::demon::FullDemon;
 use pointercrate_demonlist::{
+    config::DemonlistConfig,
     player::{claim::PlayerClaim, FullPlayer},
     record::RecordStatus,
     submitter::Submitter,
-    LIST_ADMINISTRATOR, LIST_HELPER, LIST_MODERATOR,
+    LISTED_DEMONS, LIST_ADMINISTRATOR, LIST_HELPER, LIST_MODERATOR,
+};
+use pointercrate_demonlist_api::config::DemonlistApiConfig;
+use pointercrate_user::{
+    auth::{AuthenticatedUser, PasswordOrBrowser},
+    MODERATOR,
 };
-use pointercrate_user::auth::{AuthenticatedUser, PasswordOrBrowser};
 use pointercrate_user_pages::account::AccountPageConfig;
 use rocket::{http::Status, local::asynchronous::Client};
 use sqlx::{pool::PoolConnection, PgConnection, Pool, Postgres};
@@ -21,17 +28,27 @@ pub async fn setup_rocket(pool: Pool<Postgres>) -> (TestClient, PoolConnection<P
 
     let mut connection = pool.acquire().await.unwrap();
 
-    let permissions = PermissionsManager::new(vec![LIST_HELPER, LIST_MODERATOR, LIST_ADMINISTRATOR])
+    // MODERATOR guards the claim listing, so it needs to be known here as well
+    let permissions = PermissionsManager::new(vec![MODERATOR, LIST_HELPER, LIST_MODERATOR, LIST_ADMINISTRATOR])
         .assigns(LIST_ADMINISTRATOR, LIST_MODERATOR)
         .implies(LIST_ADMINISTRATOR, LIST_MODERATOR)
         .implies(LIST_MODERATOR, LIST_HELPER);
 
     LocalesLoader::empty();
 
-    let rocket = pointercrate_demonlist_api::setup(rocket::build().manage(PointercratePool::from(pool)))
+    // Respect list sizes set via environment variables, but ignore any configuration file
+    let list_config = ConfigSource::default().section::<DemonlistConfig>().unwrap();
+
+    let rocket = rocket::build()
+        .manage(PointercratePool::from(pool))
+        .manage(list_config)
+        .manage(DemonlistApiConfig::default());
+    let rocket = pointercrate_demonlist_api::setup(rocket)
         .manage(permissions)
         .manage(AccountPageConfig::default())
-        .manage(PreferenceManager::default().preference("locale", "en"));
+        .manage(PreferenceManager::default().preference("locale", "en"))
+        .attach(ResponseCacheFairing)
+        .attach(ConfigFairing);
 
     // generate some data
     Submitter::create_submitter(IpAddr::from_str("127.0.0.1").unwrap(), &mut connection)
@@ -44,7 +61,7 @@ pub async fn setup_rocket(pool: Pool<Postgres>) -> (TestClient, PoolConnection<P
 pub async fn add_demon(
     name: impl Into<String>, position: i16, requirement: i16, verifier_id: i32, publisher_id: i32, connection: &mut PgConnection,
 ) -> i32 {
-    sqlx::query!(
+    let id = sqlx::query!(
         "INSERT INTO demons (name, position, requirement, verifier, publisher) VALUES ($1::TEXT::CITEXT, $2, $3, $4, $5) RETURNING id",
         name.into(),
         position,
@@ -55,7 +72,12 @@ pub async fn add_demon(
     .fetch_one(&mut *connection)
     .await
     .unwrap()
-    .id
+    .id;
+
+    // Bypassing the demonlist models means we have to evict cached listings ourselves
+    invalidate(LISTED_DEMONS, connection).await.unwrap();
+
+    id
 }
 
 pub async fn put_claim(user_id: i32, player_id: i32, verified: bool, lock_submissions: bool, connection: &mut PgConnection) -> PlayerClaim {
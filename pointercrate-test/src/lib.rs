@@ -1,25 +1,42 @@
 //! Utilities for pointercrate integration tests
 
+use pointercrate_core::pagination::PaginationQuery;
+use pointercrate_core_api::pagination::LinksBuilder;
 use pointercrate_user::auth::{AuthenticatedUser, PasswordOrBrowser};
 
 use rocket::{
     http::{Header, Status},
     local::asynchronous::{Client, LocalRequest, LocalResponse},
+    Orbit, Rocket,
 };
 use serde::{de::DeserializeOwned, Serialize};
 
 use std::{collections::HashMap, fmt::Debug};
 
 pub mod demonlist;
+pub mod localization;
 pub mod user;
 
+/// Extracts the target of the link with relation `rel` from a pagination response's `Links` header
+pub fn link(links_header: &str, rel: &str) -> Option<String> {
+    links_header
+        .split(',')
+        .find(|link| link.ends_with(&format!("rel={}", rel)))
+        .map(|link| link[1..link.find('>').unwrap()].to_string())
+}
+
 pub struct TestClient(Client);
 
 impl TestClient {
-    fn new(client: Client) -> Self {
+    pub fn new(client: Client) -> Self {
         TestClient(client)
     }
 
+    /// The rocket instance handling this client's requests, e.g. for inspecting managed state
+    pub fn rocket(&self) -> &Rocket<Orbit> {
+        self.0.rocket()
+    }
+
     pub fn get(&self, url: impl Into<String>) -> TestRequest {
         TestRequest::new(self.0.get(url.into()))
     }
@@ -39,6 +56,47 @@ impl TestClient {
     pub fn delete(&self, url: impl Into<String>) -> TestRequest {
         TestRequest::new(self.0.delete(url.into()))
     }
+
+    /// Checks the `Links` headers of a listing at its boundaries: on its first and last pages, and on the empty pages
+    /// directly before its first and after its last object.
+    ///
+    /// `ids` are the pagination IDs of all objects in the listing, in ascending order. Since the "first" and "last" links
+    /// are computed from the whole table, these must also be the smallest and largest IDs in it.
+    pub async fn assert_boundary_links<Q: PaginationQuery + DeserializeOwned>(
+        &self, endpoint: &'static str, ids: &[i32], user: &AuthenticatedUser<PasswordOrBrowser>,
+    ) {
+        assert!(ids.len() > 1, "boundary tests need at least two objects, got {:?}", ids);
+
+        let (min, max) = (ids[0], ids[ids.len() - 1]);
+        let links = || LinksBuilder::new(endpoint).with_first(min - 1).with_last(max + 1);
+
+        let cases = [
+            ("limit=1".to_string(), 1, links().with_next(min)),
+            (format!("limit=1&before={}", max + 1), 1, links().with_previous(max)),
+            (format!("after={}&before={}", min - 1, max + 1), ids.len(), links()),
+            (format!("after={}", max), 0, links().with_previous(max + 1)),
+            (format!("before={}", min), 0, links().with_next(min - 1)),
+        ];
+
+        for (query_string, expected_len, expected_links) in cases {
+            let (objects, links_header) = self
+                .get(format!("{}?{}", endpoint, query_string))
+                .authorize_as(user)
+                .get_pagination_result::<serde_json::Value>()
+                .await;
+
+            let query: Q = serde_urlencoded::from_str(&query_string).unwrap();
+
+            assert_eq!(objects.len(), expected_len, "{}?{}", endpoint, query_string);
+            assert_eq!(
+                links_header,
+                expected_links.generate(&query).unwrap(),
+                "{}?{}",
+                endpoint,
+                query_string
+            );
+        }
+    }
 }
 
 pub struct TestRequest<'c> {
@@ -63,6 +121,18 @@ impl<'c> TestRequest<'c> {
         self
     }
 
+    /// Sets the body of this request to the JSON serialization of `body`, for methods that do not take a body in [`TestClient`]
+    pub fn json(mut self, body: &impl Serialize) -> Self {
+        self.request = self.request.json(body);
+        self
+    }
+
+    /// Replaces the default `Accept: application/json` header of this request
+    pub fn accept(mut self, media_type: impl Into<String>) -> Self {
+        self.request.inner_mut().replace_header(Header::new("Accept", media_type.into()));
+        self
+    }
+
     pub fn authorize_as(self, user: &AuthenticatedUser<PasswordOrBrowser>) -> Self {
         self.header("Authorization", format!("Bearer {}", user.generate_programmatic_access_token()))
     }
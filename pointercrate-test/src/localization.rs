@@ -0,0 +1,456 @@
+//! Utilities for checking pointercrate's localization files against the text ids used throughout the code base, and for
+//! generating a pseudo-locale in which hardcoded (untranslated) strings stand out.
+//!
+//! Text ids are discovered by scanning all crates for string literals passed to `tr`, `trp!` and `trp_html!` (as well as
+//! to `tr`/`trp` in javascript files, and to `Permission::new` and `RouteGroup::new`). Text ids that are constructed at
+//! runtime cannot be discovered this way, which is why unused keys are merely reported, but do not make a [`LintReport`]
+//! unclean.
+
+use fluent_syntax::{
+    ast::{CallArguments, Entry, Expression, InlineExpression, Pattern, PatternElement},
+    parser::ParserError,
+    serializer,
+};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::{Display, Formatter},
+    fs::read_dir,
+    io,
+    path::{Path, PathBuf},
+};
+
+/// The language tag of the generated pseudo-locale (see [`generate_pseudo_locale`])
+pub const PSEUDO_LOCALE: &str = "qps-ploc";
+
+/// Calls whose first argument is a text id in rust source files
+const RUST_CALLS: [&str; 5] = ["tr(", "trp!(", "trp_html!(", "Permission::new(", "RouteGroup::new("];
+
+/// Calls whose third argument is a text id in javascript source files (the first two being the category and resource)
+const JS_CALLS: [&str; 2] = ["tr(", "trp("];
+
+#[derive(thiserror::Error, Debug)]
+pub enum LintError {
+    #[error("I/O Error while scanning {0:?}: {1}")]
+    Io(PathBuf, io::Error),
+    #[error("Error(s) parsing fluent resource file {0:?}: {1:?}")]
+    FluentParsing(PathBuf, Vec<ParserError>),
+}
+
+/// A text id referenced in source code
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Usage {
+    pub text_id: String,
+    pub file: PathBuf,
+    pub line: usize,
+}
+
+#[derive(Debug, Default)]
+pub struct LintReport {
+    /// Text ids referenced in source code for which no message (or attribute) exists in any language
+    pub undefined: Vec<Usage>,
+
+    /// Messages which are never referenced by a string literal in source code. These might still be in use via
+    /// text ids constructed at runtime.
+    pub unused: Vec<String>,
+
+    /// Messages (or attributes) defined for some, but not all languages, as pairs of language and text id
+    pub missing: Vec<(String, String)>,
+
+    /// Messages (or attributes) whose variables differ between languages, together with the variables used in each language
+    pub mismatched_variables: Vec<(String, BTreeMap<String, BTreeSet<String>>)>,
+}
+
+impl LintReport {
+    /// Whether this report contains no definite errors (unused keys are not considered errors, see [`LintReport::unused`])
+    pub fn is_clean(&self) -> bool {
+        self.undefined.is_empty() && self.missing.is_empty() && self.mismatched_variables.is_empty()
+    }
+}
+
+impl Display for LintReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for usage in &self.undefined {
+            writeln!(f, "undefined: '{}' used at {}:{}", usage.text_id, usage.file.display(), usage.line)?;
+        }
+
+        for text_id in &self.unused {
+            writeln!(f, "unused: '{}'", text_id)?;
+        }
+
+        for (language, text_id) in &self.missing {
+            writeln!(f, "missing: '{}' is not defined for language {}", text_id, language)?;
+        }
+
+        for (text_id, variables) in &self.mismatched_variables {
+            writeln!(f, "mismatched variables: '{}' uses {:?}", text_id, variables)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The root directory of the pointercrate workspace, which contains all `pointercrate-*` crates
+pub fn workspace_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .expect("pointercrate-test is not part of a workspace")
+        .to_path_buf()
+}
+
+/// Checks the localization files of all crates in the given workspace against the text ids used in their sources
+pub fn lint(workspace_root: &Path) -> Result<LintReport, LintError> {
+    let mut usages = Vec::new();
+    // language -> text id -> variables
+    let mut definitions = BTreeMap::<String, BTreeMap<String, BTreeSet<String>>>::new();
+
+    for krate in crates(workspace_root)? {
+        for file in files(&krate.join("src"), "rs")? {
+            let source = read_to_string(&file)?;
+            // Test modules live at the end of a file and may define permissions (and thus text ids) purely for testing purposes
+            let source = source.split("#[cfg(test)]").next().unwrap_or_default();
+
+            usages.extend(scan(source, &RUST_CALLS, 0, &file));
+        }
+
+        for file in files(&krate.join("static"), "js")? {
+            usages.extend(scan(&read_to_string(&file)?, &JS_CALLS, 2, &file));
+        }
+
+        let ftl_dir = krate.join("static").join("ftl");
+
+        if !ftl_dir.is_dir() {
+            continue;
+        }
+
+        for language_dir in subdirectories(&ftl_dir)? {
+            let language = language_dir.file_name().unwrap_or_default().to_string_lossy().into_owned();
+
+            if language == PSEUDO_LOCALE {
+                continue;
+            }
+
+            let messages = definitions.entry(language).or_default();
+
+            for file in files(&language_dir, "ftl")? {
+                let source = read_to_string(&file)?;
+                let resource =
+                    fluent_syntax::parser::parse(source.as_str()).map_err(|(_, errors)| LintError::FluentParsing(file, errors))?;
+
+                for entry in resource.body {
+                    if let Entry::Message(message) = entry {
+                        if let Some(ref value) = message.value {
+                            messages.insert(message.id.name.to_string(), variables(value));
+                        }
+
+                        for attribute in message.attributes {
+                            messages.insert(format!("{}.{}", message.id.name, attribute.id.name), variables(&attribute.value));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    let mut report = LintReport::default();
+
+    usages.sort();
+
+    for usage in usages.iter() {
+        if definitions.values().all(|messages| !messages.contains_key(&usage.text_id)) {
+            report.undefined.push(usage.clone());
+        }
+    }
+
+    let mut all_text_ids = BTreeMap::<&String, BTreeMap<String, BTreeSet<String>>>::new();
+
+    for (language, messages) in &definitions {
+        for (text_id, variables) in messages {
+            all_text_ids.entry(text_id).or_default().insert(language.clone(), variables.clone());
+        }
+    }
+
+    for (text_id, variables) in all_text_ids {
+        for language in definitions.keys() {
+            if !variables.contains_key(language) {
+                report.missing.push((language.clone(), text_id.clone()));
+            }
+        }
+
+        if variables.values().collect::<BTreeSet<_>>().len() > 1 {
+            report.mismatched_variables.push((text_id.clone(), variables));
+        }
+
+        let message = text_id.split('.').next().unwrap_or(text_id);
+
+        let used = usages.iter().any(|usage| usage.text_id.split('.').next() == Some(message));
+
+        if !used && !report.unused.iter().any(|unused| unused == message) {
+            report.unused.push(message.to_string());
+        }
+    }
+
+    Ok(report)
+}
+
+/// Generates the pseudo-locale [`PSEUDO_LOCALE`] from the localization files of `source_language` for every crate in
+/// the given workspace, writing them to `static/ftl/qps-ploc` next to the originals.
+///
+/// Every translated string in the pseudo-locale is wrapped in `⟦⟧` and has its letters accented and its vowels
+/// doubled, so that text not going through the localization system is easily spotted, and so that the layout can be
+/// checked for robustness against longer translations.
+pub fn generate_pseudo_locale(workspace_root: &Path, source_language: &str) -> Result<(), LintError> {
+    for krate in crates(workspace_root)? {
+        let source_dir = krate.join("static").join("ftl").join(source_language);
+
+        if !source_dir.is_dir() {
+            continue;
+        }
+
+        let target_dir = krate.join("static").join("ftl").join(PSEUDO_LOCALE);
+
+        std::fs::create_dir_all(&target_dir).map_err(|err| LintError::Io(target_dir.clone(), err))?;
+
+        for file in files(&source_dir, "ftl")? {
+            let pseudo = pseudo_localize(&read_to_string(&file)?).map_err(|errors| LintError::FluentParsing(file.clone(), errors))?;
+            let target = target_dir.join(file.file_name().unwrap_or_default());
+
+            std::fs::write(&target, pseudo).map_err(|err| LintError::Io(target, err))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Pseudo-localizes the given fluent resource (see [`generate_pseudo_locale`])
+pub fn pseudo_localize(source: &str) -> Result<String, Vec<ParserError>> {
+    let mut resource = fluent_syntax::parser::parse(source.to_string()).map_err(|(_, errors)| errors)?;
+
+    // Note that plain square brackets cannot be used for wrapping, as multiline patterns whose lines start with `[` are
+    // parsed as select expression variants
+    for entry in &mut resource.body {
+        let (value, attributes) = match entry {
+            Entry::Message(message) => (message.value.as_mut(), &mut message.attributes),
+            Entry::Term(term) => (Some(&mut term.value), &mut term.attributes),
+            _ => continue,
+        };
+
+        value
+            .into_iter()
+            .chain(attributes.iter_mut().map(|attribute| &mut attribute.value))
+            .for_each(|pattern| {
+                pseudo_localize_pattern(pattern);
+
+                pattern.elements.insert(0, PatternElement::TextElement { value: "⟦".to_string() });
+                pattern.elements.push(PatternElement::TextElement { value: "⟧".to_string() });
+            });
+    }
+
+    Ok(serializer::serialize(&resource))
+}
+
+fn pseudo_localize_pattern(pattern: &mut Pattern<String>) {
+    for element in &mut pattern.elements {
+        match element {
+            PatternElement::TextElement { value } => *value = value.chars().flat_map(pseudo_localize_char).collect(),
+            PatternElement::Placeable {
+                expression: Expression::Select { variants, .. },
+            } => {
+                for variant in variants {
+                    pseudo_localize_pattern(&mut variant.value)
+                }
+            },
+            _ => (),
+        }
+    }
+}
+
+fn pseudo_localize_char(c: char) -> impl Iterator<Item = char> {
+    let (accented, repeat) = match c {
+        'a' => ('á', 2),
+        'e' => ('é', 2),
+        'i' => ('í', 2),
+        'o' => ('ó', 2),
+        'u' => ('ú', 2),
+        'A' => ('Á', 2),
+        'E' => ('É', 2),
+        'I' => ('Í', 2),
+        'O' => ('Ó', 2),
+        'U' => ('Ú', 2),
+        'c' => ('ç', 1),
+        'n' => ('ñ', 1),
+        'y' => ('ý', 1),
+        'C' => ('Ç', 1),
+        'N' => ('Ñ', 1),
+        'Y' => ('Ý', 1),
+        c => (c, 1),
+    };
+
+    std::iter::repeat_n(accented, repeat)
+}
+
+/// Collects the names of all variables referenced in the given pattern
+fn variables(pattern: &Pattern<&str>) -> BTreeSet<String> {
+    fn visit_expression(expression: &Expression<&str>, variables: &mut BTreeSet<String>) {
+        match expression {
+            Expression::Select { selector, variants } => {
+                visit_inline(selector, variables);
+
+                for variant in variants {
+                    visit_pattern(&variant.value, variables);
+                }
+            },
+            Expression::Inline(inline) => visit_inline(inline, variables),
+        }
+    }
+
+    fn visit_inline(inline: &InlineExpression<&str>, variables: &mut BTreeSet<String>) {
+        match inline {
+            InlineExpression::VariableReference { id } => {
+                variables.insert(id.name.to_string());
+            },
+            InlineExpression::FunctionReference { arguments, .. } => visit_arguments(arguments, variables),
+            InlineExpression::TermReference {
+                arguments: Some(arguments),
+                ..
+            } => visit_arguments(arguments, variables),
+            InlineExpression::Placeable { expression } => visit_expression(expression, variables),
+            _ => (),
+        }
+    }
+
+    fn visit_arguments(arguments: &CallArguments<&str>, variables: &mut BTreeSet<String>) {
+        for positional in &arguments.positional {
+            visit_inline(positional, variables);
+        }
+
+        for named in &arguments.named {
+            visit_inline(&named.value, variables);
+        }
+    }
+
+    fn visit_pattern(pattern: &Pattern<&str>, variables: &mut BTreeSet<String>) {
+        for element in &pattern.elements {
+            if let PatternElement::Placeable { expression } = element {
+                visit_expression(expression, variables);
+            }
+        }
+    }
+
+    let mut variables = BTreeSet::new();
+
+    visit_pattern(pattern, &mut variables);
+
+    variables
+}
+
+/// Finds all calls to one of `calls` in the given source whose `argument`-th argument (zero-indexed) is a string literal
+fn scan(source: &str, calls: &[&str], argument: usize, file: &Path) -> Vec<Usage> {
+    let mut usages = Vec::new();
+
+    for call in calls {
+        for (offset, _) in source.match_indices(call) {
+            let preceding = source[..offset].chars().next_back();
+
+            // Make sure we matched the entire name of the called function (e.g. `tr(`, but not `str(`, `.tr(` or `"tr(`)
+            if preceding.is_some_and(|c| c.is_alphanumeric() || c == '_' || c == '.' || c == '"') {
+                continue;
+            }
+
+            let line_start = source[..offset].rfind('\n').map(|idx| idx + 1).unwrap_or(0);
+
+            if source[line_start..offset].trim_start().starts_with("//") {
+                continue;
+            }
+
+            let mut arguments = &source[offset + call.len()..];
+            let mut text_id = None;
+
+            for idx in 0..=argument {
+                let Some((literal, rest)) = string_literal(arguments) else {
+                    break;
+                };
+
+                if idx == argument {
+                    text_id = Some(literal);
+                } else if let Some(rest) = rest.trim_start().strip_prefix(',') {
+                    arguments = rest;
+                } else {
+                    break;
+                }
+            }
+
+            if let Some(text_id) = text_id {
+                usages.push(Usage {
+                    text_id: text_id.to_string(),
+                    file: file.to_path_buf(),
+                    line: source[..offset].matches('\n').count() + 1,
+                });
+            }
+        }
+    }
+
+    usages
+}
+
+/// Parses a string literal (without escape sequences) at the start of `source`, ignoring leading whitespace. Returns
+/// the literal's content and the remainder of `source`.
+fn string_literal(source: &str) -> Option<(&str, &str)> {
+    let source = source.trim_start().strip_prefix('"')?;
+    let end = source.find('"')?;
+
+    Some((&source[..end], &source[end + 1..]))
+}
+
+fn read_to_string(path: &Path) -> Result<String, LintError> {
+    std::fs::read_to_string(path).map_err(|err| LintError::Io(path.to_path_buf(), err))
+}
+
+fn subdirectories(path: &Path) -> Result<Vec<PathBuf>, LintError> {
+    let mut subdirectories = Vec::new();
+
+    for entry in read_dir(path).map_err(|err| LintError::Io(path.to_path_buf(), err))? {
+        let entry = entry.map_err(|err| LintError::Io(path.to_path_buf(), err))?;
+
+        if entry.path().is_dir() {
+            subdirectories.push(entry.path());
+        }
+    }
+
+    subdirectories.sort();
+
+    Ok(subdirectories)
+}
+
+/// All `pointercrate-*` crates in the given workspace
+fn crates(workspace_root: &Path) -> Result<Vec<PathBuf>, LintError> {
+    Ok(subdirectories(workspace_root)?
+        .into_iter()
+        .filter(|dir| {
+            dir.file_name()
+                .is_some_and(|name| name.to_string_lossy().starts_with("pointercrate-"))
+        })
+        .collect())
+}
+
+/// All files with the given extension in the given directory and its subdirectories
+fn files(path: &Path, extension: &str) -> Result<Vec<PathBuf>, LintError> {
+    let mut found = Vec::new();
+
+    if !path.is_dir() {
+        return Ok(found);
+    }
+
+    for entry in read_dir(path).map_err(|err| LintError::Io(path.to_path_buf(), err))? {
+        let entry = entry.map_err(|err| LintError::Io(path.to_path_buf(), err))?.path();
+
+        if entry.is_dir() {
+            found.extend(files(&entry, extension)?);
+        } else if entry.extension().is_some_and(|ext| ext == extension) {
+            found.push(entry);
+        }
+    }
+
+    found.sort();
+
+    Ok(found)
+}
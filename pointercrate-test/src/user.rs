@@ -4,33 +4,43 @@ use pointercrate_core::{
     permission::{Permission, PermissionsManager},
     pool::PointercratePool,
 };
-use pointercrate_core_api::preferences::PreferenceManager;
+use pointercrate_core_api::{
+    cache::ResponseCacheFairing, config::ConfigFairing, maintenance::MaintenanceFairing, preferences::PreferenceManager,
+};
 use pointercrate_user::{
     auth::{legacy::Registration, AuthenticatedUser, PasswordOrBrowser},
+    config::UserConfig,
     ADMINISTRATOR, MODERATOR,
 };
 use pointercrate_user_pages::account::AccountPageConfig;
-use rocket::local::asynchronous::Client;
+use rocket::{local::asynchronous::Client, Build, Rocket};
 use sqlx::{pool::PoolConnection, PgConnection, Pool, Postgres};
 
 pub async fn setup_rocket(pool: Pool<Postgres>) -> (TestClient, PoolConnection<Postgres>) {
-    let _ = dotenv::dotenv();
-
     let connection = pool.acquire().await.unwrap();
 
+    (TestClient::new(Client::tracked(build_rocket(pool)).await.unwrap()), connection)
+}
+
+/// The rocket instance launched by [`setup_rocket`], for tests that need to customize it (e.g. to configure ratelimits)
+pub fn build_rocket(pool: Pool<Postgres>) -> Rocket<Build> {
+    let _ = dotenv::dotenv();
+
     let permissions = PermissionsManager::new(vec![MODERATOR, ADMINISTRATOR])
         .assigns(ADMINISTRATOR, MODERATOR)
         .implies(ADMINISTRATOR, MODERATOR);
 
     LocalesLoader::empty();
 
-    let rocket = pointercrate_user_api::setup(rocket::build())
+    pointercrate_user_api::setup(rocket::build())
         .manage(PointercratePool::from(pool))
+        .manage(UserConfig::default())
         .manage(permissions)
         .manage(AccountPageConfig::default())
-        .manage(PreferenceManager::default().preference("locale", "en"));
-
-    (TestClient::new(Client::tracked(rocket).await.unwrap()), connection)
+        .manage(PreferenceManager::default().preference("locale", "en"))
+        .attach(MaintenanceFairing::default())
+        .attach(ResponseCacheFairing)
+        .attach(ConfigFairing)
 }
 
 pub async fn system_user_with_perms(perm: Permission, connection: &mut PgConnection) -> AuthenticatedUser<PasswordOrBrowser> {
@@ -57,9 +67,15 @@ pub async fn system_user_with_perms(perm: Permission, connection: &mut PgConnect
 }
 
 pub async fn add_normal_user(connection: &mut PgConnection) -> AuthenticatedUser<PasswordOrBrowser> {
+    add_named_user("Patrick", connection).await
+}
+
+/// Registers a user without any permissions. Use this for tests that need more than one user, as the other helpers all register
+/// a user named "Patrick"
+pub async fn add_named_user(name: &str, connection: &mut PgConnection) -> AuthenticatedUser<PasswordOrBrowser> {
     AuthenticatedUser::register(
         Registration {
-            name: "Patrick".to_string(),
+            name: name.to_string(),
             password: "bad password".to_string(),
         },
         connection,
@@ -1,11 +1,14 @@
-use pointercrate_core::pagination::PaginationParameters;
+use pointercrate_core::{etag::Taggable, pagination::PaginationParameters};
 use pointercrate_core_api::pagination::LinksBuilder;
 use pointercrate_demonlist::{
-    demon::{Demon, DemonPositionPagination},
+    demon::{Demon, DemonPositionPagination, FullDemon},
     player::DatabasePlayer,
-    LIST_MODERATOR,
+    LIST_ADMINISTRATOR, LIST_MODERATOR,
+};
+use rocket::{
+    http::Status,
+    tokio::time::{sleep, Duration},
 };
-use rocket::http::Status;
 use sqlx::{Pool, Postgres};
 
 #[sqlx::test(migrations = "../migrations")]
@@ -34,6 +37,33 @@ async fn test_add_demon_ratelimits(pool: Pool<Postgres>) {
     assert_eq!(result["code"].as_i64(), Some(42900))
 }
 
+#[sqlx::test(migrations = "../migrations")]
+async fn test_add_demon_ratelimits_shared_between_instances(pool: Pool<Postgres>) {
+    let (clnt1, mut connection) = pointercrate_test::demonlist::setup_rocket(pool.clone()).await;
+    let (clnt2, _) = pointercrate_test::demonlist::setup_rocket(pool).await;
+
+    let user = pointercrate_test::user::system_user_with_perms(LIST_MODERATOR, &mut connection).await;
+
+    let demon = serde_json::json! {{"name": "Bloodbath", "requirement": 90, "position": 1, "verifier": "Riot", "publisher": "Riot", "creators": [], "level_id": 10565740}};
+
+    clnt1
+        .post("/api/v2/demons/", &demon)
+        .authorize_as(&user)
+        .expect_status(Status::Created)
+        .execute()
+        .await;
+
+    // Ratelimit state is stored in the database, so the "1 per minute" ratelimit also applies to a second instance
+    let result: serde_json::Value = clnt2
+        .post("/api/v2/demons/", &demon)
+        .authorize_as(&user)
+        .expect_status(Status::TooManyRequests)
+        .get_result()
+        .await;
+
+    assert_eq!(result["code"].as_i64(), Some(42900))
+}
+
 #[sqlx::test(migrations = "../migrations")]
 async fn test_demon_pagination(pool: Pool<Postgres>) {
     /// The URL of the endpoint we are testing
@@ -138,7 +168,8 @@ async fn test_demon_pagination(pool: Pool<Postgres>) {
 
     assert_eq!(links, expected.generate(&base).unwrap());
 
-    // Query an empty page by setting "before" and "after" to an empty range. Should result in a response with only "first" and "last" headers set
+    // Query an empty page by setting "before" and "after" to an empty range. Since demons exist on either side of the range, we should get
+    // a "next" link with after=1 (e.g. before minus one), and a "prev" link with before=2 (e.g. after plus one)
     let base = DemonPositionPagination {
         params: PaginationParameters {
             before: Some(2),
@@ -154,7 +185,7 @@ async fn test_demon_pagination(pool: Pool<Postgres>) {
 
     assert_eq!(demons.len(), 0);
 
-    let expected = LinksBuilder::new(URL).with_first(0).with_last(4);
+    let expected = LinksBuilder::new(URL).with_first(0).with_last(4).with_next(1).with_previous(2);
 
     assert_eq!(links, expected.generate(&base).unwrap());
 
@@ -192,11 +223,252 @@ async fn test_demon_pagination(pool: Pool<Postgres>) {
     assert_eq!(demons[0].base.id, id2);
     assert_eq!(demons[1].base.id, id3);
 
-    let expected = LinksBuilder::new(URL)
-        .with_first(0)
-        .with_last(4)
-        .with_next(3) // FIXME: This `next` link should not have been returned (currently we always return a "next" link if a `before` parameter is set though)
-        .with_previous(2);
+    let expected = LinksBuilder::new(URL).with_first(0).with_last(4).with_previous(2);
+
+    assert_eq!(links, expected.generate(&base).unwrap());
+
+    // Query with limit=1 and no other parameters. This is the first page, so there should be no "prev" link
+    let base = DemonPositionPagination {
+        params: PaginationParameters {
+            limit: 1,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let (demons, links) = clnt
+        .get(format!("{}?{}", URL, serde_urlencoded::to_string(&base).unwrap()))
+        .get_pagination_result::<Demon>()
+        .await;
+
+    assert_eq!(demons.len(), 1);
+    assert_eq!(demons[0].base.id, id1);
+
+    let expected = LinksBuilder::new(URL).with_first(0).with_last(4).with_next(1);
 
     assert_eq!(links, expected.generate(&base).unwrap());
+
+    // Query the last page via after=2 and limit=1. There should be no "next" link
+    let base = DemonPositionPagination {
+        params: PaginationParameters {
+            after: Some(2),
+            limit: 1,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let (demons, links) = clnt
+        .get(format!("{}?{}", URL, serde_urlencoded::to_string(&base).unwrap()))
+        .get_pagination_result::<Demon>()
+        .await;
+
+    assert_eq!(demons.len(), 1);
+    assert_eq!(demons[0].base.id, id3);
+
+    let expected = LinksBuilder::new(URL).with_first(0).with_last(4).with_previous(3);
+
+    assert_eq!(links, expected.generate(&base).unwrap());
+
+    // Query with after=0 and before=4, which encloses all demons. This page is standalone, despite both bounds being set
+    let base = DemonPositionPagination {
+        params: PaginationParameters {
+            after: Some(0),
+            before: Some(4),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let (demons, links) = clnt
+        .get(format!("{}?{}", URL, serde_urlencoded::to_string(&base).unwrap()))
+        .get_pagination_result::<Demon>()
+        .await;
+
+    assert_eq!(demons.len(), 3);
+
+    let expected = LinksBuilder::new(URL).with_first(0).with_last(4);
+
+    assert_eq!(links, expected.generate(&base).unwrap());
+
+    // Filters are respected when probing for objects around the page: No demon with requirement=50 exists before or after the second demon
+    sqlx::query!("UPDATE demons SET requirement = 50 WHERE id = $1", id2)
+        .execute(&mut *connection)
+        .await
+        .unwrap();
+
+    let base = DemonPositionPagination {
+        requirement: Some(50),
+        params: PaginationParameters {
+            after: Some(1),
+            before: Some(3),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let (demons, links) = clnt
+        .get(format!("{}?{}", URL, serde_urlencoded::to_string(&base).unwrap()))
+        .get_pagination_result::<Demon>()
+        .await;
+
+    assert_eq!(demons.len(), 1);
+    assert_eq!(demons[0].base.id, id2);
+
+    let expected = LinksBuilder::new(URL).with_first(0).with_last(4);
+
+    assert_eq!(links, expected.generate(&base).unwrap());
+}
+
+#[sqlx::test(migrations = "../migrations")]
+async fn test_demon_pagination_with_total_count(pool: Pool<Postgres>) {
+    let (clnt, mut connection) = pointercrate_test::demonlist::setup_rocket(pool).await;
+
+    let player = DatabasePlayer::by_name_or_create("stardust1971", &mut connection).await.unwrap();
+
+    pointercrate_test::demonlist::add_demon("Bloodbath", 1, 100, player.id, player.id, &mut connection).await;
+    pointercrate_test::demonlist::add_demon("Bloodlust", 2, 100, player.id, player.id, &mut connection).await;
+    pointercrate_test::demonlist::add_demon("Sonic Wave", 3, 50, player.id, player.id, &mut connection).await;
+
+    // Both demon listings count all demons matching their filters, regardless of limit
+    for url in ["/api/v2/demons/", "/api/v2/demons/listed/"] {
+        clnt.get(format!("{}?count=true&limit=1", url))
+            .expect_header("X-Total-Count", "3")
+            .execute()
+            .await;
+
+        clnt.get(format!("{}?count=true&requirement=100", url))
+            .expect_header("X-Total-Count", "2")
+            .execute()
+            .await;
+
+        clnt.get(format!("{}?count=true&name_contains=Blood&requirement__lt=100", url))
+            .expect_header("X-Total-Count", "0")
+            .execute()
+            .await;
+    }
+}
+
+#[sqlx::test(migrations = "../migrations")]
+async fn test_listed_demons_cache_invalidated_by_move(pool: Pool<Postgres>) {
+    const URL: &str = "/api/v2/demons/listed/";
+
+    let (clnt, mut connection) = pointercrate_test::demonlist::setup_rocket(pool).await;
+
+    let user = pointercrate_test::user::system_user_with_perms(LIST_MODERATOR, &mut connection).await;
+    let player = DatabasePlayer::by_name_or_create("stardust1971", &mut connection).await.unwrap();
+
+    let bloodbath = clnt.add_demon(&user, "Bloodbath", 1, 100, "Riot", "Riot").await;
+    pointercrate_test::demonlist::add_demon("Bloodlust", 2, 100, player.id, player.id, &mut connection).await;
+
+    let (demons, _) = clnt.get(URL).get_pagination_result::<Demon>().await;
+
+    assert_eq!(demons[0].base.id, bloodbath.demon.base.id);
+
+    clnt.patch(
+        format!("/api/v2/demons/{}/", bloodbath.demon.base.id),
+        &serde_json::json!({"position": 2}),
+    )
+    .authorize_as(&user)
+    .header("If-Match", bloodbath.etag_string())
+    .expect_status(Status::Ok)
+    .execute()
+    .await;
+
+    // The cached listing is evicted once the invalidation sent by the move is delivered, which happens asynchronously
+    let mut demons = Vec::new();
+
+    for _ in 0..50 {
+        (demons, _) = clnt.get(URL).get_pagination_result::<Demon>().await;
+
+        if demons[0].base.id != bloodbath.demon.base.id {
+            break;
+        }
+
+        sleep(Duration::from_millis(100)).await;
+    }
+
+    assert_eq!(demons[1].base.id, bloodbath.demon.base.id);
+}
+
+#[sqlx::test(migrations = "../migrations")]
+async fn test_creator_restoration(pool: Pool<Postgres>) {
+    let (clnt, mut connection) = pointercrate_test::demonlist::setup_rocket(pool).await;
+
+    let admin = pointercrate_test::user::system_user_with_perms(LIST_ADMINISTRATOR, &mut connection).await;
+    let demon = clnt.add_demon(&admin, "Bloodbath", 1, 100, "stardust1971", "stardust1971").await;
+    let verifier = DatabasePlayer::by_name("stardust1971", &mut connection).await.unwrap();
+    let creator = DatabasePlayer::by_name_or_create("stardust1972", &mut connection).await.unwrap();
+
+    let restore_url = format!("/api/v2/demons/{}/creators/{}/restore/", demon.demon.base.id, creator.id);
+
+    clnt.post(
+        format!("/api/v2/demons/{}/creators/", demon.demon.base.id),
+        &serde_json::json!({"creator": "stardust1972"}),
+    )
+    .authorize_as(&admin)
+    .expect_status(Status::Created)
+    .execute()
+    .await;
+
+    // Creators that were never deleted cannot be restored
+    let result: serde_json::Value = clnt
+        .post(restore_url.clone(), &())
+        .authorize_as(&admin)
+        .expect_status(Status::Conflict)
+        .get_result()
+        .await;
+
+    assert_eq!(result["code"], 40905);
+
+    clnt.delete(format!("/api/v2/demons/{}/creators/{}/", demon.demon.base.id, creator.id))
+        .authorize_as(&admin)
+        .expect_status(Status::NoContent)
+        .execute()
+        .await;
+
+    clnt.post(restore_url.clone(), &())
+        .authorize_as(&admin)
+        .expect_status(Status::Created)
+        .execute()
+        .await;
+
+    let restored: FullDemon = clnt
+        .get(format!("/api/v2/demons/{}/", demon.demon.base.id))
+        .get_success_result()
+        .await;
+
+    assert_eq!(restored.creators, vec![creator.clone()]);
+
+    let deletion = sqlx::query_scalar!(
+        "SELECT audit_id FROM creator_deletions WHERE creator = $1 AND demon = $2",
+        creator.id,
+        demon.demon.base.id
+    )
+    .fetch_one(&mut *connection)
+    .await
+    .unwrap();
+    let restorations: Vec<i32> = sqlx::query_scalar!(
+        "SELECT deletion FROM creator_restorations WHERE creator = $1 AND demon = $2",
+        creator.id,
+        demon.demon.base.id
+    )
+    .fetch_all(&mut *connection)
+    .await
+    .unwrap();
+
+    assert_eq!(restorations, vec![deletion]);
+
+    // Restoring the entry a second time conflicts with the restored one
+    clnt.post(restore_url, &())
+        .authorize_as(&admin)
+        .expect_status(Status::Conflict)
+        .execute()
+        .await;
+
+    // The verifier was never a creator, so there is nothing to restore
+    clnt.post(
+        format!("/api/v2/demons/{}/creators/{}/restore/", demon.demon.base.id, verifier.id),
+        &(),
+    )
+    .authorize_as(&admin)
+    .expect_status(Status::NotFound)
+    .execute()
+    .await;
 }
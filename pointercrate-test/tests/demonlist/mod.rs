@@ -2,3 +2,4 @@ mod demon;
 mod nationality;
 mod player;
 mod record;
+mod submitter;
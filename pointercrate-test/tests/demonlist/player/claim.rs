@@ -1,4 +1,8 @@
-use pointercrate_demonlist::player::{claim::PlayerClaim, DatabasePlayer, FullPlayer};
+use pointercrate_demonlist::player::{
+    claim::{PlayerClaim, PlayerClaimPagination},
+    DatabasePlayer, FullPlayer,
+};
+use pointercrate_user::MODERATOR;
 use rocket::http::Status;
 use sqlx::{Pool, Postgres};
 
@@ -67,3 +71,29 @@ async fn test_players_me(pool: Pool<Postgres>) {
 
     assert_eq!(claimed.player.base.id, player_id);
 }
+
+#[sqlx::test(migrations = "../migrations")]
+async fn test_claims_pagination_boundaries(pool: Pool<Postgres>) {
+    let (client, mut connection) = pointercrate_test::demonlist::setup_rocket(pool).await;
+    let moderator = pointercrate_test::user::system_user_with_perms(MODERATOR, &mut connection).await;
+    let jacob = pointercrate_test::user::add_named_user("Jacob", &mut connection).await;
+    let stardust = pointercrate_test::user::add_named_user("stardust1971", &mut connection).await;
+
+    for (user, player_name) in [(&moderator, "stardust1971"), (&jacob, "stardust1972"), (&stardust, "stardust1973")] {
+        DatabasePlayer::by_name_or_create(player_name, &mut connection)
+            .await
+            .unwrap()
+            .initiate_claim(user.user().id, &mut connection)
+            .await
+            .unwrap();
+    }
+
+    let ids: Vec<i32> = sqlx::query_scalar!("SELECT id FROM player_claims ORDER BY id")
+        .fetch_all(&mut *connection)
+        .await
+        .unwrap();
+
+    client
+        .assert_boundary_links::<PlayerClaimPagination>("/api/v1/players/claims/", &ids, &moderator)
+        .await;
+}
@@ -1,8 +1,8 @@
 use pointercrate_demonlist::record::RecordStatus;
 use pointercrate_demonlist::{
     nationality::{Nationality, Subdivision},
-    player::{DatabasePlayer, FullPlayer, Player},
-    LIST_HELPER, LIST_MODERATOR,
+    player::{DatabasePlayer, FullPlayer, Player, PlayerPagination},
+    LIST_ADMINISTRATOR, LIST_HELPER, LIST_MODERATOR,
 };
 use rocket::http::Status;
 use serde_json::json;
@@ -132,13 +132,14 @@ async fn test_patch_player_nationality(pool: Pool<Postgres>) {
     );
 
     // Patch nation, but to the one we already have. Shouldn't change anything.
-    client
+    let unchanged_player: FullPlayer = client
         .patch_player(player.id, &user, serde_json::json!({"nationality": "United Kingdom"}))
         .await
-        .expect_status(Status::NotModified)
-        .execute()
+        .get_success_result()
         .await;
 
+    assert_eq!(unchanged_player, patched_player);
+
     // Patch only nationality. Should reset subdivision
     let patched_player: FullPlayer = client
         .patch_player(player.id, &user, serde_json::json!({"nationality": "Germany"}))
@@ -222,6 +223,20 @@ async fn test_me(pool: Pool<Postgres>) {
     );
 }
 
+#[sqlx::test(migrations = "../migrations")]
+async fn test_players_pagination_boundaries(pool: Pool<Postgres>) {
+    let (client, mut connection) = pointercrate_test::demonlist::setup_rocket(pool).await;
+
+    let (banned, unbanned) = create_players(&mut connection).await;
+    let third = DatabasePlayer::by_name_or_create("stardust1973", &mut *connection).await.unwrap();
+    let helper = pointercrate_test::user::system_user_with_perms(LIST_HELPER, &mut connection).await;
+
+    // List helpers also see banned players, meaning the listing covers the entire table
+    client
+        .assert_boundary_links::<PlayerPagination>("/api/v1/players/", &[banned.id, unbanned.id, third.id], &helper)
+        .await;
+}
+
 #[sqlx::test(migrations = "../migrations")]
 async fn test_players_pagination(pool: Pool<Postgres>) {
     let (client, mut connection) = pointercrate_test::demonlist::setup_rocket(pool).await;
@@ -335,6 +350,20 @@ async fn test_players_pagination(pool: Pool<Postgres>) {
             }),
         })
     );
+
+    // The total count takes the same filters into account
+    for (query, count) in [
+        ("", "3"),
+        ("nation=GB", "2"),
+        ("subdivision=ENG", "1"),
+        ("nation=GB&name_contains=19702", "1"),
+    ] {
+        client
+            .get(format!("/api/v1/players/?count=true&limit=1&{}", query))
+            .expect_header("X-Total-Count", count)
+            .execute()
+            .await;
+    }
 }
 
 #[sqlx::test(migrations = "../migrations")]
@@ -371,3 +400,236 @@ async fn test_player_merge(pool: Pool<Postgres>) {
         .execute()
         .await;
 }
+
+#[sqlx::test(migrations = "../migrations")]
+async fn test_player_restoration(pool: Pool<Postgres>) {
+    let (client, mut connection) = pointercrate_test::demonlist::setup_rocket(pool).await;
+    let admin = pointercrate_test::user::system_user_with_perms(LIST_ADMINISTRATOR, &mut connection).await;
+
+    let player1 = DatabasePlayer::by_name_or_create("stardust1971", &mut connection).await.unwrap();
+    let player2 = DatabasePlayer::by_name_or_create("stardust1972", &mut connection).await.unwrap();
+
+    let restore_url = format!("/api/v1/players/{}/restore/", player1.id);
+
+    // Players that were never deleted cannot be restored
+    let result: serde_json::Value = client
+        .post(restore_url.clone(), &())
+        .authorize_as(&admin)
+        .expect_status(Status::Conflict)
+        .get_result()
+        .await;
+
+    assert_eq!(result["code"], 40910);
+
+    // Players are only deleted by merging them into another player
+    client
+        .patch_player(player2.id, &admin, json! {{"name": "stardust1971"}})
+        .await
+        .execute()
+        .await;
+
+    // The player it was merged into now has its name
+    let result: serde_json::Value = client
+        .post(restore_url.clone(), &())
+        .authorize_as(&admin)
+        .expect_status(Status::Conflict)
+        .get_result()
+        .await;
+
+    assert_eq!(result["code"], 40911);
+
+    client
+        .patch_player(player2.id, &admin, json! {{"name": "stardust1973"}})
+        .await
+        .execute()
+        .await;
+
+    let restored: FullPlayer = client
+        .post(restore_url, &())
+        .authorize_as(&admin)
+        .expect_status(Status::Created)
+        .get_success_result()
+        .await;
+
+    assert_eq!(restored.player.base, player1);
+
+    let deletion = sqlx::query_scalar!("SELECT audit_id FROM player_deletions WHERE id = $1", player1.id)
+        .fetch_one(&mut *connection)
+        .await
+        .unwrap();
+    let restorations: Vec<i32> = sqlx::query_scalar!("SELECT deletion FROM player_restorations WHERE id = $1", player1.id)
+        .fetch_all(&mut *connection)
+        .await
+        .unwrap();
+
+    assert_eq!(restorations, vec![deletion]);
+
+    client
+        .post("/api/v1/players/99999/restore/", &())
+        .authorize_as(&admin)
+        .expect_status(Status::NotFound)
+        .execute()
+        .await;
+}
+
+#[sqlx::test(migrations = "../migrations")]
+async fn test_player_audit_log(pool: Pool<Postgres>) {
+    let (client, mut connection) = pointercrate_test::demonlist::setup_rocket(pool).await;
+
+    let admin = pointercrate_test::user::system_user_with_perms(LIST_ADMINISTRATOR, &mut connection).await;
+    let jacob = pointercrate_test::user::add_named_user("Jacob", &mut connection).await;
+    let player = DatabasePlayer::by_name_or_create("stardust1971", &mut connection).await.unwrap();
+
+    client
+        .patch_player(player.id, &admin, json! {{"name": "stardust1972"}})
+        .await
+        .execute()
+        .await;
+
+    client
+        .get(format!("/api/v1/players/{}/audit/", player.id))
+        .authorize_as(&jacob)
+        .expect_status(Status::Forbidden)
+        .execute()
+        .await;
+
+    let log: serde_json::Value = client
+        .get(format!("/api/v1/players/{}/audit/", player.id))
+        .authorize_as(&admin)
+        .get_result()
+        .await;
+
+    let modifications: Vec<_> = log
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|entry| entry["type"].get("Modification"))
+        .collect();
+
+    // modifications record the values from before the change
+    assert_eq!(modifications.len(), 1);
+    assert_eq!(modifications[0]["name"], "stardust1971");
+
+    client
+        .get("/api/v1/players/99999/audit/")
+        .authorize_as(&admin)
+        .expect_status(Status::NotFound)
+        .execute()
+        .await;
+}
+
+#[sqlx::test(migrations = "../migrations")]
+async fn test_ranking_pagination_with_ties(pool: Pool<Postgres>) {
+    let (client, mut connection) = pointercrate_test::demonlist::setup_rocket(pool).await;
+
+    let mut ids = Vec::new();
+    for (name, score) in [("stardust1971", 10.0), ("stardust1972", 10.0), ("stardust1973", 5.0)] {
+        let player = DatabasePlayer::by_name_or_create(name, &mut *connection).await.unwrap();
+        sqlx::query!("UPDATE players SET score = $1 WHERE id = $2", score, player.id)
+            .execute(&mut *connection)
+            .await
+            .unwrap();
+        ids.push(player.id);
+    }
+
+    sqlx::query!("REFRESH MATERIALIZED VIEW player_ranks")
+        .execute(&mut *connection)
+        .await
+        .unwrap();
+
+    let (page, links) = client
+        .get("/api/v1/players/ranking/?sort=score&limit=1")
+        .get_pagination_result::<Player>()
+        .await;
+
+    assert_eq!(page.iter().map(|p| p.base.id).collect::<Vec<_>>(), [ids[0]]);
+    assert!(pointercrate_test::link(&links, "prev").is_none(), "{}", links);
+    assert!(pointercrate_test::link(&links, "next").unwrap().contains("cursor="), "{}", links);
+
+    // Players tied on score are ordered by ID, and the cursor must not skip or repeat either of them
+    let (page, links) = client
+        .get(pointercrate_test::link(&links, "next").unwrap())
+        .get_pagination_result::<Player>()
+        .await;
+
+    assert_eq!(page.iter().map(|p| p.base.id).collect::<Vec<_>>(), [ids[1]]);
+
+    let (page, links) = client
+        .get(pointercrate_test::link(&links, "next").unwrap())
+        .get_pagination_result::<Player>()
+        .await;
+
+    assert_eq!(page.iter().map(|p| p.base.id).collect::<Vec<_>>(), [ids[2]]);
+    assert!(pointercrate_test::link(&links, "next").is_none(), "{}", links);
+
+    let (page, _) = client
+        .get(pointercrate_test::link(&links, "prev").unwrap())
+        .get_pagination_result::<Player>()
+        .await;
+
+    assert_eq!(page.iter().map(|p| p.base.id).collect::<Vec<_>>(), [ids[1]]);
+
+    client
+        .get("/api/v1/players/ranking/?count=true&name_contains=1973")
+        .expect_header("X-Total-Count", "1")
+        .execute()
+        .await;
+}
+
+#[sqlx::test(migrations = "../migrations")]
+async fn test_ranking_pagination_by_rank(pool: Pool<Postgres>) {
+    let (client, mut connection) = pointercrate_test::demonlist::setup_rocket(pool).await;
+
+    let mut ids = Vec::new();
+    for (name, score) in [("stardust1971", 10.0), ("stardust1972", 10.0), ("stardust1973", 5.0)] {
+        let player = DatabasePlayer::by_name_or_create(name, &mut *connection).await.unwrap();
+        sqlx::query!("UPDATE players SET score = $1 WHERE id = $2", score, player.id)
+            .execute(&mut *connection)
+            .await
+            .unwrap();
+        ids.push(player.id);
+    }
+
+    sqlx::query!("REFRESH MATERIALIZED VIEW player_ranks")
+        .execute(&mut *connection)
+        .await
+        .unwrap();
+
+    // Links handed out before cursors existed, which clients may have kept around
+    let (page, links) = client
+        .get("/api/v1/players/ranking/?after=1&limit=1")
+        .get_pagination_result::<Player>()
+        .await;
+
+    assert_eq!(page.iter().map(|p| p.base.id).collect::<Vec<_>>(), [ids[1]]);
+    assert_eq!(
+        pointercrate_test::link(&links, "next").unwrap(),
+        "/api/v1/players/ranking/?after=2&limit=1"
+    );
+    assert_eq!(
+        pointercrate_test::link(&links, "prev").unwrap(),
+        "/api/v1/players/ranking/?before=2&limit=1"
+    );
+
+    let (page, links) = client
+        .get(pointercrate_test::link(&links, "next").unwrap())
+        .get_pagination_result::<Player>()
+        .await;
+
+    assert_eq!(page.iter().map(|p| p.base.id).collect::<Vec<_>>(), [ids[2]]);
+    assert!(pointercrate_test::link(&links, "next").is_none(), "{}", links);
+
+    let (page, _) = client
+        .get("/api/v1/players/ranking/?before=2")
+        .get_pagination_result::<Player>()
+        .await;
+
+    assert_eq!(page.iter().map(|p| p.base.id).collect::<Vec<_>>(), [ids[0]]);
+
+    // Cursors only make sense for the listing sorted by score
+    client
+        .get("/api/v1/players/ranking/?sort=score&after=1")
+        .expect_status(Status::UnprocessableEntity)
+        .execute()
+        .await;
+}
@@ -2,6 +2,7 @@
 
 use pointercrate_core::etag::Taggable;
 use pointercrate_demonlist::{
+    config::DemonlistConfig,
     player::{DatabasePlayer, FullPlayer},
     record::FullRecord,
     LIST_MODERATOR,
@@ -134,7 +135,7 @@ pub async fn test_extended_progress_records_give_no_score(pool: Pool<Postgres>)
     let helper = pointercrate_test::user::system_user_with_perms(LIST_MODERATOR, &mut connection).await;
     let player = DatabasePlayer::by_name_or_create("stardust1971", &mut connection).await.unwrap();
 
-    let list_size = std::env::var("LIST_SIZE").unwrap().parse::<i16>().unwrap();
+    let list_size = clnt.rocket().state::<DemonlistConfig>().unwrap().list_size;
 
     let mut last_demon_id = 0;
 
@@ -174,7 +175,7 @@ pub async fn test_score_resets_if_last_record_removed(pool: Pool<Postgres>) {
     let helper = pointercrate_test::user::system_user_with_perms(LIST_MODERATOR, &mut connection).await;
     let player = DatabasePlayer::by_name_or_create("stardust1971", &mut connection).await.unwrap();
 
-    let list_size = std::env::var("LIST_SIZE").unwrap().parse::<i16>().unwrap();
+    let list_size = clnt.rocket().state::<DemonlistConfig>().unwrap().list_size;
 
     let mut last_demon_id = 0;
 
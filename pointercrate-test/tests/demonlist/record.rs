@@ -3,10 +3,10 @@ use pointercrate_core::etag::Taggable;
 use pointercrate_demonlist::{
     error::DemonlistError,
     player::{DatabasePlayer, FullPlayer},
-    record::{note::Note, FullRecord, RecordStatus},
-    LIST_HELPER, LIST_MODERATOR,
+    record::{note::Note, FullRecord, RecordPagination, RecordStatus},
+    LIST_ADMINISTRATOR, LIST_HELPER, LIST_MODERATOR,
 };
-use pointercrate_test::{demonlist::add_simple_record, user::system_user_with_perms};
+use pointercrate_test::{demonlist::add_simple_record, link, user::system_user_with_perms};
 use rocket::http::Status;
 use sqlx::{PgConnection, Pool, Postgres};
 
@@ -79,6 +79,187 @@ async fn paginate_records_with_verified_claim_wrong_player(pool: Pool<Postgres>)
     assert_eq!(json.len(), 0);
 }
 
+#[sqlx::test(migrations = "../migrations")]
+async fn paginate_records_boundaries(pool: Pool<Postgres>) {
+    let (clnt, mut connection) = pointercrate_test::demonlist::setup_rocket(pool).await;
+
+    let (_, r1, r2, r3) = setup_pagination_tests(&mut connection).await;
+    let helper = system_user_with_perms(LIST_HELPER, &mut connection).await;
+
+    clnt.assert_boundary_links::<RecordPagination>("/api/v1/records/", &[r1, r2, r3], &helper)
+        .await;
+}
+
+#[sqlx::test(migrations = "../migrations")]
+async fn paginate_records_by_progress(pool: Pool<Postgres>) {
+    let (clnt, mut connection) = pointercrate_test::demonlist::setup_rocket(pool).await;
+
+    let (_p1, r1, r2, r3) = setup_pagination_tests(&mut connection).await;
+    let helper = system_user_with_perms(LIST_HELPER, &mut connection).await;
+
+    let (json, links) = clnt
+        .get("/api/v1/records/?sort=progress&limit=1")
+        .authorize_as(&helper)
+        .get_pagination_result::<serde_json::Value>()
+        .await;
+
+    assert_eq!(json.len(), 1);
+    assert_eq!(json[0]["id"].as_i64(), Some(r1 as i64));
+    assert!(link(&links, "prev").is_none(), "{}", links);
+
+    // r3 ties with r1 on progress, and is ordered after it by ID
+    let (json, links) = clnt
+        .get(link(&links, "next").unwrap())
+        .authorize_as(&helper)
+        .get_pagination_result::<serde_json::Value>()
+        .await;
+
+    assert_eq!(json.len(), 1);
+    assert_eq!(json[0]["id"].as_i64(), Some(r3 as i64));
+
+    let (json, links) = clnt
+        .get(link(&links, "next").unwrap())
+        .authorize_as(&helper)
+        .get_pagination_result::<serde_json::Value>()
+        .await;
+
+    assert_eq!(json.len(), 1);
+    assert_eq!(json[0]["id"].as_i64(), Some(r2 as i64));
+    assert!(link(&links, "next").is_none(), "{}", links);
+
+    let (json, _) = clnt
+        .get(link(&links, "prev").unwrap())
+        .authorize_as(&helper)
+        .get_pagination_result::<serde_json::Value>()
+        .await;
+
+    assert_eq!(json.len(), 1);
+    assert_eq!(json[0]["id"].as_i64(), Some(r3 as i64));
+
+    // The cursors from the above links are only valid for a listing sorted by progress
+    let (_, links) = clnt
+        .get("/api/v1/records/?sort=progress&limit=1")
+        .authorize_as(&helper)
+        .get_pagination_result::<serde_json::Value>()
+        .await;
+    let next = link(&links, "next").unwrap();
+
+    clnt.get(format!("{}&status=REJECTED", next))
+        .authorize_as(&helper)
+        .expect_status(Status::UnprocessableEntity)
+        .execute()
+        .await;
+    clnt.get(format!("{}&after=1", next))
+        .authorize_as(&helper)
+        .expect_status(Status::UnprocessableEntity)
+        .execute()
+        .await;
+    let cursor = next.split(['?', '&']).find(|param| param.starts_with("cursor=")).unwrap();
+
+    clnt.get(format!("/api/v1/records/?{}", cursor))
+        .authorize_as(&helper)
+        .expect_status(Status::UnprocessableEntity)
+        .execute()
+        .await;
+}
+
+#[sqlx::test(migrations = "../migrations")]
+async fn paginate_records_with_total_count(pool: Pool<Postgres>) {
+    let (clnt, mut connection) = pointercrate_test::demonlist::setup_rocket(pool).await;
+
+    let (p1, _, _, _) = setup_pagination_tests(&mut connection).await;
+    let helper = system_user_with_perms(LIST_HELPER, &mut connection).await;
+
+    // The count ignores the limit, and is carried over into the links
+    let (json, links) = clnt
+        .get("/api/v1/records/?count=true&limit=1")
+        .authorize_as(&helper)
+        .expect_header("X-Total-Count", "3")
+        .get_pagination_result::<serde_json::Value>()
+        .await;
+
+    assert_eq!(json.len(), 1);
+    assert!(link(&links, "next").unwrap().contains("count=true"), "{}", links);
+
+    clnt.get(link(&links, "next").unwrap())
+        .authorize_as(&helper)
+        .expect_header("X-Total-Count", "3")
+        .execute()
+        .await;
+
+    // Filters are taken into account
+    clnt.get(format!("/api/v1/records/?count=true&player={}&status=REJECTED", p1))
+        .authorize_as(&helper)
+        .expect_header("X-Total-Count", "1")
+        .execute()
+        .await;
+
+    // Unauthorized requests are only allowed to see approved records
+    clnt.get("/api/v1/records/?count=true")
+        .expect_header("X-Total-Count", "1")
+        .execute()
+        .await;
+
+    // Counting is opt-in
+    let response = clnt.get("/api/v1/records/").authorize_as(&helper).execute().await;
+
+    assert!(response.headers().get_one("X-Total-Count").is_none());
+}
+
+#[sqlx::test(migrations = "../migrations")]
+async fn export_records(pool: Pool<Postgres>) {
+    let (clnt, mut connection) = pointercrate_test::demonlist::setup_rocket(pool).await;
+
+    let (p1, r1, r2, r3) = setup_pagination_tests(&mut connection).await;
+    let helper = system_user_with_perms(LIST_HELPER, &mut connection).await;
+
+    // Exports are not subject to the limit parameter
+    let body = clnt
+        .get("/api/v1/records/export/?limit=1")
+        .authorize_as(&helper)
+        .expect_header("Content-Type", "application/x-ndjson")
+        .execute()
+        .await
+        .into_string()
+        .await
+        .unwrap();
+
+    let ids = body
+        .lines()
+        .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap()["id"].as_i64().unwrap() as i32)
+        .collect::<Vec<_>>();
+
+    assert_eq!(ids, vec![r1, r2, r3]);
+
+    // Filters are respected
+    let body = clnt
+        .get(format!("/api/v1/records/export/?player={}&status=REJECTED", p1))
+        .authorize_as(&helper)
+        .accept("text/csv")
+        .expect_header("Content-Type", "text/csv; charset=utf-8")
+        .execute()
+        .await
+        .into_string()
+        .await
+        .unwrap();
+
+    let lines = body.lines().collect::<Vec<_>>();
+
+    assert_eq!(lines.len(), 2, "{}", body);
+    assert!(lines[0].starts_with("id,progress,video,status"), "{}", body);
+    assert!(lines[1].starts_with(&format!("{},70,", r2)), "{}", body);
+
+    // Unauthorized requests are only allowed to export approved records
+    let body = clnt.get("/api/v1/records/export/").execute().await.into_string().await.unwrap();
+
+    assert_eq!(body.lines().count(), 1, "{}", body);
+
+    clnt.get("/api/v1/records/export/?status=REJECTED")
+        .expect_status(Status::Unauthorized)
+        .execute()
+        .await;
+}
+
 async fn setup_pagination_tests(connection: &mut PgConnection) -> (i32, i32, i32, i32) {
     let player1 = DatabasePlayer::by_name_or_create("stardust1971", connection).await.unwrap();
     let player2 = DatabasePlayer::by_name_or_create("stardust1972", connection).await.unwrap();
@@ -229,6 +410,95 @@ async fn test_record_note_creation_and_deletion(pool: Pool<Postgres>) {
         .await;
 }
 
+#[sqlx::test(migrations = "../migrations")]
+async fn test_record_note_audit_log(pool: Pool<Postgres>) {
+    let (clnt, mut connection) = pointercrate_test::demonlist::setup_rocket(pool).await;
+
+    let helper = system_user_with_perms(LIST_HELPER, &mut connection).await;
+    let admin = system_user_with_perms(LIST_ADMINISTRATOR, &mut connection).await;
+    let player1 = DatabasePlayer::by_name_or_create("stardust1971", &mut connection).await.unwrap();
+    let demon1 = pointercrate_test::demonlist::add_demon("Bloodbath", 1, 50, player1.id, player1.id, &mut connection).await;
+    let demon2 = pointercrate_test::demonlist::add_demon("Bloodlust", 2, 50, player1.id, player1.id, &mut connection).await;
+    let record = add_simple_record(100, player1.id, demon1, RecordStatus::Approved, &mut connection).await;
+    let other_record = add_simple_record(100, player1.id, demon2, RecordStatus::Approved, &mut connection).await;
+
+    let note: Note = clnt
+        .post(
+            format!("/api/v1/records/{}/notes/", record),
+            &serde_json::json! {{
+                "content": "My Note",
+                "is_public": false,
+            }},
+        )
+        .authorize_as(&helper)
+        .expect_status(Status::Created)
+        .get_success_result()
+        .await;
+
+    clnt.patch(
+        format!("/api/v1/records/{}/notes/{}/", record, note.id),
+        &serde_json::json! {{"content": "My edited Note"}},
+    )
+    .authorize_as(&helper)
+    .expect_status(Status::Ok)
+    .execute()
+    .await;
+
+    clnt.get(format!("/api/v1/records/{}/notes/{}/audit/", record, note.id))
+        .authorize_as(&helper)
+        .expect_status(Status::Forbidden)
+        .execute()
+        .await;
+
+    let log: serde_json::Value = clnt
+        .get(format!("/api/v1/records/{}/notes/{}/audit/", record, note.id))
+        .authorize_as(&admin)
+        .expect_status(Status::Ok)
+        .get_result()
+        .await;
+
+    let log = log.as_array().unwrap();
+
+    assert_eq!(log.len(), 2);
+    assert_eq!(log[0]["type"], "Addition");
+    assert_eq!(log[0]["user"]["name"], helper.user().name.as_str());
+    // modifications record the values from before the change
+    assert_eq!(log[1]["type"]["Modification"]["content"], "My Note");
+    assert!(log[1]["type"]["Modification"]["record"].is_null());
+
+    // The note exists, but is not attached to this record
+    clnt.get(format!("/api/v1/records/{}/notes/{}/audit/", other_record, note.id))
+        .authorize_as(&admin)
+        .expect_status(Status::NotFound)
+        .execute()
+        .await;
+
+    clnt.get(format!("/api/v1/records/{}/notes/99999/audit/", record))
+        .authorize_as(&admin)
+        .expect_status(Status::NotFound)
+        .execute()
+        .await;
+
+    clnt.delete(format!("/api/v1/records/{}/notes/{}/", record, note.id))
+        .authorize_as(&helper)
+        .expect_status(Status::NoContent)
+        .execute()
+        .await;
+
+    // The log of deleted notes stays available
+    let log: serde_json::Value = clnt
+        .get(format!("/api/v1/records/{}/notes/{}/audit/", record, note.id))
+        .authorize_as(&admin)
+        .expect_status(Status::Ok)
+        .get_result()
+        .await;
+
+    let log = log.as_array().unwrap();
+
+    assert_eq!(log.len(), 3);
+    assert_eq!(log[2]["type"], "Deletion");
+}
+
 #[sqlx::test(migrations = "../migrations")]
 async fn test_record_deletion_updates_player_score(pool: Pool<Postgres>) {
     let (clnt, mut connection) = pointercrate_test::demonlist::setup_rocket(pool).await;
@@ -269,3 +539,89 @@ async fn test_record_deletion_updates_player_score(pool: Pool<Postgres>) {
 
     assert_eq!(player.player.score, 0.0f64, "Deleting approved record failed to lower player score");
 }
+
+#[sqlx::test(migrations = "../migrations")]
+async fn test_record_restoration(pool: Pool<Postgres>) {
+    let (clnt, mut connection) = pointercrate_test::demonlist::setup_rocket(pool).await;
+
+    let admin = system_user_with_perms(LIST_ADMINISTRATOR, &mut connection).await;
+    let player = DatabasePlayer::by_name_or_create("stardust1971", &mut connection).await.unwrap();
+    let demon = clnt.add_demon(&admin, "Bloodbath", 1, 100, "stardust1972", "stardust1972").await;
+
+    let submission = serde_json::json! {{"progress": 100, "demon": demon.demon.base.id, "player": "stardust1971", "video": "https://youtube.com/watch?v=1234567890", "status": "Approved", "note": "My Note"}};
+
+    let record = clnt
+        .post("/api/v1/records/", &submission)
+        .authorize_as(&admin)
+        .expect_status(Status::Ok)
+        .get_success_result::<FullRecord>()
+        .await;
+
+    // Records that were never deleted cannot be restored
+    clnt.post(format!("/api/v1/records/{}/restore/", record.id), &())
+        .authorize_as(&admin)
+        .expect_status(Status::Conflict)
+        .execute()
+        .await;
+
+    clnt.delete(format!("/api/v1/records/{}/", record.id))
+        .authorize_as(&admin)
+        .header("If-Match", record.etag_string())
+        .expect_status(Status::NoContent)
+        .execute()
+        .await;
+
+    let restored: FullRecord = clnt
+        .post(format!("/api/v1/records/{}/restore/", record.id), &())
+        .authorize_as(&admin)
+        .expect_status(Status::Created)
+        .get_success_result()
+        .await;
+
+    assert_eq!(restored.id, record.id);
+    assert_eq!(restored.video, record.video);
+    assert_eq!(restored.status, RecordStatus::Approved);
+
+    let notes: Vec<Note> = clnt
+        .get(format!("/api/v1/records/{}/notes/", record.id))
+        .authorize_as(&admin)
+        .get_success_result()
+        .await;
+
+    assert_eq!(notes.len(), 1);
+    assert_eq!(notes[0].content, "My Note");
+
+    let player: FullPlayer = clnt
+        .get(format!("/api/v1/players/{}/", player.id))
+        .expect_status(Status::Ok)
+        .get_success_result()
+        .await;
+
+    assert_ne!(player.player.score, 0.0f64, "Restoring approved record failed to give player score");
+
+    // Deleting the record again and adding a new one in its place makes the old one unrestorable
+    clnt.delete(format!("/api/v1/records/{}/", record.id))
+        .authorize_as(&admin)
+        .header("If-Match", restored.etag_string())
+        .expect_status(Status::NoContent)
+        .execute()
+        .await;
+
+    add_simple_record(
+        100,
+        player.player.base.id,
+        demon.demon.base.id,
+        RecordStatus::Approved,
+        &mut connection,
+    )
+    .await;
+
+    let result: serde_json::Value = clnt
+        .post(format!("/api/v1/records/{}/restore/", record.id), &())
+        .authorize_as(&admin)
+        .expect_status(Status::UnprocessableEntity)
+        .get_result()
+        .await;
+
+    assert_eq!(result["code"], 42217);
+}
@@ -0,0 +1,96 @@
+use pointercrate_core::etag::Taggable;
+use pointercrate_demonlist::{
+    submitter::{Submitter, SubmitterPagination},
+    LIST_ADMINISTRATOR, LIST_MODERATOR,
+};
+use rocket::http::Status;
+use sqlx::{Pool, Postgres};
+use std::{net::IpAddr, str::FromStr};
+
+#[sqlx::test(migrations = "../migrations")]
+async fn test_submitter_pagination_boundaries(pool: Pool<Postgres>) {
+    let (client, mut connection) = pointercrate_test::demonlist::setup_rocket(pool).await;
+    let moderator = pointercrate_test::user::system_user_with_perms(LIST_MODERATOR, &mut connection).await;
+
+    // setup_rocket already created a submitter for 127.0.0.1
+    let mut ids: Vec<i32> = sqlx::query_scalar!("SELECT submitter_id FROM submitters")
+        .fetch_all(&mut *connection)
+        .await
+        .unwrap();
+
+    for ip in ["127.0.0.2", "127.0.0.3"] {
+        let submitter = Submitter::create_submitter(IpAddr::from_str(ip).unwrap(), &mut connection)
+            .await
+            .unwrap();
+
+        ids.push(submitter.id);
+    }
+
+    client
+        .assert_boundary_links::<SubmitterPagination>("/api/v1/submitters/", &ids, &moderator)
+        .await;
+}
+
+#[sqlx::test(migrations = "../migrations")]
+async fn test_submitter_audit_log(pool: Pool<Postgres>) {
+    let (client, mut connection) = pointercrate_test::demonlist::setup_rocket(pool).await;
+    let moderator = pointercrate_test::user::system_user_with_perms(LIST_MODERATOR, &mut connection).await;
+    let admin = pointercrate_test::user::system_user_with_perms(LIST_ADMINISTRATOR, &mut connection).await;
+
+    // setup_rocket already created a submitter for 127.0.0.1
+    let submitter_id = sqlx::query_scalar!("SELECT submitter_id FROM submitters")
+        .fetch_one(&mut *connection)
+        .await
+        .unwrap();
+
+    client
+        .get(format!("/api/v1/submitters/{}/audit/", submitter_id))
+        .authorize_as(&moderator)
+        .expect_status(Status::Forbidden)
+        .execute()
+        .await;
+
+    // Submitters that were never modified have an empty log
+    let log: Vec<serde_json::Value> = client
+        .get(format!("/api/v1/submitters/{}/audit/", submitter_id))
+        .authorize_as(&admin)
+        .expect_status(Status::Ok)
+        .get_result()
+        .await;
+
+    assert!(log.is_empty());
+
+    client
+        .get("/api/v1/submitters/99999/audit/")
+        .authorize_as(&admin)
+        .expect_status(Status::NotFound)
+        .execute()
+        .await;
+
+    client
+        .patch(
+            format!("/api/v1/submitters/{}/", submitter_id),
+            &serde_json::json!({ "banned": true }),
+        )
+        .authorize_as(&moderator)
+        .header(
+            "If-Match",
+            Submitter::by_id(submitter_id, &mut connection).await.unwrap().etag_string(),
+        )
+        .expect_status(Status::Ok)
+        .execute()
+        .await;
+
+    let log: Vec<serde_json::Value> = client
+        .get(format!("/api/v1/submitters/{}/audit/", submitter_id))
+        .authorize_as(&admin)
+        .expect_status(Status::Ok)
+        .get_result()
+        .await;
+
+    assert_eq!(log.len(), 1);
+    assert_eq!(log[0]["id"], submitter_id);
+    assert_eq!(log[0]["user"]["name"], moderator.user().name.as_str());
+    // modifications record the values from before the change
+    assert_eq!(log[0]["type"]["Modification"]["banned"], false);
+}
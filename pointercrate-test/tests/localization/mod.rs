@@ -0,0 +1,21 @@
+use pointercrate_test::localization::{lint, pseudo_localize, workspace_root};
+
+#[test]
+fn test_localization_files() {
+    let report = lint(&workspace_root()).unwrap();
+
+    assert!(report.is_clean(), "Localization files are inconsistent:\n{}", report);
+}
+
+#[test]
+fn test_pseudo_localize() {
+    let pseudo = pseudo_localize(
+        "# Comment\nhello = Hello { $name }!\n    .title = Greeting\ncount = { $count ->\n    [one] One demon\n   *[other] { $count } demons\n}\n",
+    )
+    .unwrap();
+
+    assert_eq!(
+        pseudo,
+        "# Comment\nhello = ⟦Hééllóó { $name }!⟧\n    .title = ⟦Gréééétííñg⟧\ncount =\n    ⟦{ $count ->\n        [one] ÓÓñéé déémóóñ\n       *[other] { $count } déémóóñs\n    }⟧\n"
+    );
+}
@@ -0,0 +1,189 @@
+use pointercrate_core::{audit::ServiceAccount, etag::Taggable, pool::PointercratePool};
+use pointercrate_user::{User, ADMINISTRATOR};
+use rocket::http::Status;
+use sqlx::{Pool, Postgres};
+
+#[sqlx::test(migrations = "../migrations")]
+pub async fn test_audit_log_feed(pool: Pool<Postgres>) {
+    let (client, mut connection) = pointercrate_test::user::setup_rocket(pool).await;
+
+    let admin = pointercrate_test::user::system_user_with_perms(ADMINISTRATOR, &mut connection).await;
+    let jacob = pointercrate_test::user::add_named_user("Jacob", &mut connection).await;
+
+    client
+        .get("/api/v1/audit/")
+        .authorize_as(&jacob)
+        .expect_status(Status::Forbidden)
+        .execute()
+        .await;
+
+    for display_name in ["Jacobo", "Jacobus"] {
+        client
+            .patch(
+                format!("/api/v1/users/{}/", jacob.user().id),
+                &serde_json::json!({ "display_name": display_name }),
+            )
+            .authorize_as(&admin)
+            .header(
+                "If-Match",
+                User::by_id(jacob.user().id, &mut connection).await.unwrap().etag_string(),
+            )
+            .expect_status(Status::Ok)
+            .execute()
+            .await;
+    }
+
+    let entries: serde_json::Value = client
+        .get(format!("/api/v1/audit/?entity=user&entity_id={}", jacob.user().id))
+        .authorize_as(&admin)
+        .get_result()
+        .await;
+
+    let entries = entries.as_array().unwrap();
+
+    assert_eq!(entries.len(), 3);
+    assert_eq!(entries[0]["action"], "addition");
+    assert_eq!(entries[1]["action"], "modification");
+    assert_eq!(entries[1]["user"]["id"], admin.user().id);
+    assert_eq!(entries[1]["user"]["name"], "Patrick");
+    // Modifications record the previous value
+    assert_eq!(entries[2]["data"], serde_json::json!({"display_name": "Jacobo"}));
+
+    let entries: serde_json::Value = client
+        .get(format!("/api/v1/audit/?user={}", admin.user().id))
+        .authorize_as(&admin)
+        .get_result()
+        .await;
+
+    assert_eq!(entries.as_array().unwrap().len(), 2);
+    assert_eq!(entries[0]["entity"], "user");
+    assert_eq!(entries[0]["id"], jacob.user().id);
+
+    let entries: serde_json::Value = client
+        .get("/api/v1/audit/?time__gt=2100-01-01T00:00:00")
+        .authorize_as(&admin)
+        .get_result()
+        .await;
+
+    assert!(entries.as_array().unwrap().is_empty());
+}
+
+#[sqlx::test(migrations = "../migrations")]
+pub async fn test_audit_log_feed_pagination(pool: Pool<Postgres>) {
+    let (client, mut connection) = pointercrate_test::user::setup_rocket(pool).await;
+
+    let admin = pointercrate_test::user::system_user_with_perms(ADMINISTRATOR, &mut connection).await;
+
+    for name in ["Jacob", "Lucas", "Amelia"] {
+        pointercrate_test::user::add_named_user(name, &mut connection).await;
+    }
+
+    let (entries, links) = client
+        .get("/api/v1/audit/?entity=user&limit=2")
+        .authorize_as(&admin)
+        .get_pagination_result::<serde_json::Value>()
+        .await;
+
+    assert_eq!(entries.len(), 2);
+    assert!(links.contains("rel=next"), "{}", links);
+
+    let after = entries[1]["entry_id"].as_i64().unwrap();
+
+    let (entries, _) = client
+        .get(format!("/api/v1/audit/?entity=user&limit=2&after={}", after))
+        .authorize_as(&admin)
+        .get_pagination_result::<serde_json::Value>()
+        .await;
+
+    // 4 additions, and the modification granting Patrick their permissions, make for 5 entries in total
+    assert_eq!(entries.len(), 2);
+    assert!(entries.iter().all(|entry| entry["entry_id"].as_i64().unwrap() > after));
+}
+
+#[sqlx::test(migrations = "../migrations")]
+pub async fn test_user_audit_log(pool: Pool<Postgres>) {
+    let (client, mut connection) = pointercrate_test::user::setup_rocket(pool).await;
+
+    let admin = pointercrate_test::user::system_user_with_perms(ADMINISTRATOR, &mut connection).await;
+    let jacob = pointercrate_test::user::add_named_user("Jacob", &mut connection).await;
+
+    for display_name in ["Jacobo", "Jacobus"] {
+        client
+            .patch(
+                format!("/api/v1/users/{}/", jacob.user().id),
+                &serde_json::json!({ "display_name": display_name }),
+            )
+            .authorize_as(&admin)
+            .header(
+                "If-Match",
+                User::by_id(jacob.user().id, &mut connection).await.unwrap().etag_string(),
+            )
+            .expect_status(Status::Ok)
+            .execute()
+            .await;
+    }
+
+    client
+        .get(format!("/api/v1/users/{}/audit/", jacob.user().id))
+        .authorize_as(&jacob)
+        .expect_status(Status::Forbidden)
+        .execute()
+        .await;
+
+    let log: serde_json::Value = client
+        .get(format!("/api/v1/users/{}/audit/", jacob.user().id))
+        .authorize_as(&admin)
+        .get_result()
+        .await;
+
+    let log = log.as_array().unwrap();
+
+    // modifications record the values from before the change
+    let modifications: Vec<_> = log.iter().filter_map(|entry| entry["type"].get("Modification")).collect();
+
+    assert_eq!(modifications.len(), 2);
+    assert_eq!(modifications[1]["display_name"], "Jacobo");
+    assert!(log.iter().all(|entry| entry["id"] == jacob.user().id));
+
+    client
+        .get("/api/v1/users/99999/audit/")
+        .authorize_as(&admin)
+        .expect_status(Status::NotFound)
+        .execute()
+        .await;
+}
+
+#[sqlx::test(migrations = "../migrations")]
+pub async fn test_service_account_attribution(pool: Pool<Postgres>) {
+    let pointercrate_pool = PointercratePool::from(pool.clone());
+    let (client, mut connection) = pointercrate_test::user::setup_rocket(pool).await;
+
+    let admin = pointercrate_test::user::system_user_with_perms(ADMINISTRATOR, &mut connection).await;
+    let jacob = pointercrate_test::user::add_named_user("Jacob", &mut connection).await;
+
+    let mut cli_connection = pointercrate_pool.connection_as(ServiceAccount::CLI).await.unwrap();
+
+    sqlx::query!("UPDATE members SET display_name = 'Jacobo' WHERE member_id = $1", jacob.user().id)
+        .execute(&mut *cli_connection)
+        .await
+        .unwrap();
+
+    let entries: serde_json::Value = client
+        .get(format!("/api/v1/audit/?entity=user&entity_id={}", jacob.user().id))
+        .authorize_as(&admin)
+        .get_result()
+        .await;
+
+    assert_eq!(entries.as_array().unwrap().len(), 2);
+    assert_eq!(entries[0]["user"], serde_json::json!({"id": 0, "name": "system", "service": true}));
+    assert_eq!(entries[1]["user"], serde_json::json!({"id": -3, "name": "cli", "service": true}));
+
+    let entries: serde_json::Value = client
+        .get(format!("/api/v1/audit/?user={}", ServiceAccount::CLI.id()))
+        .authorize_as(&admin)
+        .get_result()
+        .await;
+
+    assert_eq!(entries.as_array().unwrap().len(), 1);
+    assert_eq!(entries[0]["id"], jacob.user().id);
+}
@@ -0,0 +1,75 @@
+use pointercrate_core::cache::{invalidate, CacheRegion, ResponseCache};
+use pointercrate_user::ADMINISTRATOR;
+use rocket::{
+    http::Status,
+    tokio::time::{sleep, Duration},
+};
+use sqlx::{Connection, Pool, Postgres};
+use std::convert::Infallible;
+
+const DEMONS: CacheRegion = CacheRegion::new("demons");
+const PLAYERS: CacheRegion = CacheRegion::new("players");
+
+async fn fill(cache: &ResponseCache, region: CacheRegion) {
+    cache
+        .get_or_try_insert_with(region, "/", || async { Ok::<_, Infallible>(()) })
+        .await
+        .unwrap();
+}
+
+#[sqlx::test(migrations = "../migrations")]
+pub async fn test_cache_statistics_require_administrator(pool: Pool<Postgres>) {
+    let (client, mut connection) = pointercrate_test::user::setup_rocket(pool).await;
+
+    let user = pointercrate_test::user::add_normal_user(&mut connection).await;
+
+    client
+        .get("/api/v1/cache/")
+        .authorize_as(&user)
+        .expect_status(Status::Forbidden)
+        .execute()
+        .await;
+}
+
+#[sqlx::test(migrations = "../migrations")]
+pub async fn test_invalidation_on_commit(pool: Pool<Postgres>) {
+    let (client, mut connection) = pointercrate_test::user::setup_rocket(pool).await;
+
+    let admin = pointercrate_test::user::system_user_with_perms(ADMINISTRATOR, &mut connection).await;
+    let cache = client.rocket().state::<ResponseCache>().unwrap();
+
+    fill(cache, DEMONS).await;
+    fill(cache, DEMONS).await;
+    fill(cache, PLAYERS).await;
+
+    let statistics: serde_json::Value = client.get("/api/v1/cache/").authorize_as(&admin).get_result().await;
+
+    assert_eq!(
+        statistics,
+        serde_json::json!([
+            {"region": "demons", "entries": 1, "hits": 1, "misses": 1, "invalidations": 0},
+            {"region": "players", "entries": 1, "hits": 0, "misses": 1, "invalidations": 0}
+        ])
+    );
+
+    // Invalidations in rolled back transactions are never delivered
+    let mut transaction = connection.begin().await.unwrap();
+    invalidate(DEMONS, &mut transaction).await.unwrap();
+    transaction.rollback().await.unwrap();
+
+    invalidate(PLAYERS, &mut connection).await.unwrap();
+
+    // Notifications are delivered asynchronously, but in commit order
+    for _ in 0..50 {
+        if cache.statistics()[1].invalidations > 0 {
+            break;
+        }
+
+        sleep(Duration::from_millis(100)).await;
+    }
+
+    let statistics = cache.statistics();
+
+    assert_eq!((statistics[0].entries, statistics[0].invalidations), (1, 0));
+    assert_eq!((statistics[1].entries, statistics[1].invalidations), (0, 1));
+}
@@ -0,0 +1,213 @@
+use pointercrate_user::ADMINISTRATOR;
+use rocket::http::Status;
+use sqlx::{Pool, Postgres};
+
+#[sqlx::test(migrations = "../migrations")]
+pub async fn test_if_none_match(pool: Pool<Postgres>) {
+    let (client, mut connection) = pointercrate_test::user::setup_rocket(pool).await;
+
+    let admin = pointercrate_test::user::system_user_with_perms(ADMINISTRATOR, &mut connection).await;
+    let jacob = pointercrate_test::user::add_named_user("Jacob", &mut connection).await;
+    let url = format!("/api/v1/users/{}/", jacob.user().id);
+
+    let response = client.get(&url).authorize_as(&admin).execute().await;
+    let etag = response.headers().get_one("ETag").unwrap().to_string();
+
+    assert!(response.headers().get_one("Last-Modified").is_some());
+
+    // ETags are compared weakly, and any of the given ETags may match
+    for if_none_match in [
+        etag.clone(),
+        etag.trim_start_matches("W/").to_string(),
+        format!("W/\"v1:0;0\", {}", etag),
+        "*".to_string(),
+    ] {
+        client
+            .get(&url)
+            .authorize_as(&admin)
+            .header("If-None-Match", if_none_match)
+            .expect_status(Status::NotModified)
+            .expect_header("ETag", etag.clone())
+            .execute()
+            .await;
+    }
+
+    client
+        .get(&url)
+        .authorize_as(&admin)
+        .header("If-None-Match", "W/\"v1:0;0\"")
+        .execute()
+        .await;
+}
+
+#[sqlx::test(migrations = "../migrations")]
+pub async fn test_if_none_match_on_listing(pool: Pool<Postgres>) {
+    let (client, mut connection) = pointercrate_test::user::setup_rocket(pool).await;
+
+    let admin = pointercrate_test::user::system_user_with_perms(ADMINISTRATOR, &mut connection).await;
+    let jacob = pointercrate_test::user::add_named_user("Jacob", &mut connection).await;
+
+    let response = client.get("/api/v1/users/").authorize_as(&admin).execute().await;
+    let etag = response.headers().get_one("ETag").unwrap().to_string();
+
+    client
+        .get("/api/v1/users/")
+        .authorize_as(&admin)
+        .header("If-None-Match", etag.clone())
+        .expect_status(Status::NotModified)
+        .execute()
+        .await;
+
+    // The total count is part of the representation, so requesting it changes the ETag
+    client
+        .get("/api/v1/users/?count=true")
+        .authorize_as(&admin)
+        .header("If-None-Match", etag.clone())
+        .execute()
+        .await;
+
+    client
+        .patch(
+            format!("/api/v1/users/{}/", jacob.user().id),
+            &serde_json::json!({"display_name": "Jacobo"}),
+        )
+        .authorize_as(&admin)
+        .header("If-Match", "*")
+        .execute()
+        .await;
+
+    let response = client
+        .get("/api/v1/users/")
+        .authorize_as(&admin)
+        .header("If-None-Match", etag.clone())
+        .execute()
+        .await;
+
+    assert_ne!(response.headers().get_one("ETag"), Some(etag.as_str()));
+}
+
+#[sqlx::test(migrations = "../migrations")]
+pub async fn test_if_match(pool: Pool<Postgres>) {
+    let (client, mut connection) = pointercrate_test::user::setup_rocket(pool).await;
+
+    let admin = pointercrate_test::user::system_user_with_perms(ADMINISTRATOR, &mut connection).await;
+    let jacob = pointercrate_test::user::add_named_user("Jacob", &mut connection).await;
+    let url = format!("/api/v1/users/{}/", jacob.user().id);
+    let patch = serde_json::json!({"display_name": "Jacobo"});
+
+    client
+        .patch(&url, &patch)
+        .authorize_as(&admin)
+        .expect_status(Status::PreconditionRequired)
+        .execute()
+        .await;
+
+    client
+        .patch(&url, &patch)
+        .authorize_as(&admin)
+        .header("If-Match", "W/\"v1:0;0\"")
+        .expect_status(Status::PreconditionFailed)
+        .execute()
+        .await;
+
+    let response = client.get(&url).authorize_as(&admin).execute().await;
+    let etag = response.headers().get_one("ETag").unwrap().to_string();
+
+    let response = client
+        .patch(&url, &patch)
+        .authorize_as(&admin)
+        .header("If-Match", format!("W/\"v1:0;0\", {}", etag))
+        .execute()
+        .await;
+    let etag = response.headers().get_one("ETag").unwrap().to_string();
+
+    // A matching precondition never results in 304 NOT MODIFIED, even if the patch does not change anything
+    client
+        .patch(&url, &patch)
+        .authorize_as(&admin)
+        .header("If-Match", etag.clone())
+        .expect_header("ETag", etag)
+        .execute()
+        .await;
+
+    client
+        .patch(&url, &serde_json::json!({"display_name": "Jacobus"}))
+        .authorize_as(&admin)
+        .header("If-Match", "*")
+        .execute()
+        .await;
+
+    client
+        .delete(&url)
+        .authorize_as(&admin)
+        .header("If-Match", "W/\"v1:0;0\"")
+        .expect_status(Status::PreconditionFailed)
+        .execute()
+        .await;
+
+    client
+        .delete(&url)
+        .authorize_as(&admin)
+        .header("If-Match", "*")
+        .expect_status(Status::NoContent)
+        .execute()
+        .await;
+}
+
+#[sqlx::test(migrations = "../migrations")]
+pub async fn test_if_unmodified_since(pool: Pool<Postgres>) {
+    let (client, mut connection) = pointercrate_test::user::setup_rocket(pool).await;
+
+    let admin = pointercrate_test::user::system_user_with_perms(ADMINISTRATOR, &mut connection).await;
+    let jacob = pointercrate_test::user::add_named_user("Jacob", &mut connection).await;
+    let url = format!("/api/v1/users/{}/", jacob.user().id);
+    let patch = serde_json::json!({"display_name": "Jacobo"});
+
+    let response = client.get(&url).authorize_as(&admin).execute().await;
+    let last_modified = response.headers().get_one("Last-Modified").unwrap().to_string();
+
+    client
+        .patch(&url, &patch)
+        .authorize_as(&admin)
+        .header("If-Unmodified-Since", "Sat, 01 Jan 2000 00:00:00 GMT")
+        .expect_status(Status::PreconditionFailed)
+        .execute()
+        .await;
+
+    // Invalid dates are ignored
+    client
+        .patch(&url, &patch)
+        .authorize_as(&admin)
+        .header("If-Unmodified-Since", "yesterday")
+        .expect_status(Status::PreconditionRequired)
+        .execute()
+        .await;
+
+    let response = client
+        .patch(&url, &patch)
+        .authorize_as(&admin)
+        .header("If-Unmodified-Since", last_modified)
+        .execute()
+        .await;
+
+    // The Last-Modified time returned after a patch accounts for the patch itself
+    let last_modified = response.headers().get_one("Last-Modified").unwrap().to_string();
+
+    client
+        .patch(&url, &serde_json::json!({"display_name": "Jacobino"}))
+        .authorize_as(&admin)
+        .header("If-Unmodified-Since", last_modified)
+        .execute()
+        .await;
+
+    // If-Match takes precedence
+    let response = client.get(&url).authorize_as(&admin).execute().await;
+
+    client
+        .patch(&url, &serde_json::json!({"display_name": "Jacobus"}))
+        .authorize_as(&admin)
+        .header("If-Match", response.headers().get_one("ETag").unwrap())
+        .header("If-Unmodified-Since", "Sat, 01 Jan 2000 00:00:00 GMT")
+        .execute()
+        .await;
+}
@@ -0,0 +1,233 @@
+use pointercrate_user::{auth::legacy::Registration, ADMINISTRATOR};
+use pointercrate_user_api::route_groups::REGISTRATIONS;
+use rocket::{
+    http::Status,
+    tokio::time::{sleep, Duration},
+};
+use sqlx::{Pool, Postgres};
+
+#[sqlx::test(migrations = "../migrations")]
+pub async fn test_maintenance_requires_administrator(pool: Pool<Postgres>) {
+    let (client, mut connection) = pointercrate_test::user::setup_rocket(pool).await;
+
+    let user = pointercrate_test::user::add_normal_user(&mut connection).await;
+
+    client
+        .put("/api/v1/maintenance/")
+        .json(&serde_json::json!({}))
+        .authorize_as(&user)
+        .expect_status(Status::Forbidden)
+        .execute()
+        .await;
+}
+
+#[sqlx::test(migrations = "../migrations")]
+pub async fn test_runtime_maintenance(pool: Pool<Postgres>) {
+    let (client, mut connection) = pointercrate_test::user::setup_rocket(pool).await;
+
+    let admin = pointercrate_test::user::system_user_with_perms(ADMINISTRATOR, &mut connection).await;
+
+    let status: serde_json::Value = client.get("/api/v1/maintenance/").authorize_as(&admin).get_result().await;
+
+    assert_eq!(status["active"], false);
+    assert!(status["window"].is_null());
+
+    let status: serde_json::Value = client
+        .put("/api/v1/maintenance/")
+        .json(&serde_json::json!({"messages": {"en": "Migrating the database"}}))
+        .authorize_as(&admin)
+        .get_result()
+        .await;
+
+    assert_eq!(status["active"], true);
+
+    let error: serde_json::Value = client
+        .post("/api/v1/roles/", &serde_json::json!({"name": "maintainer"}))
+        .authorize_as(&admin)
+        .expect_status(Status::ServiceUnavailable)
+        .get_result()
+        .await;
+
+    assert_eq!(error["code"], 50301);
+    assert_eq!(error["data"]["message"], "Migrating the database");
+
+    // The maintenance API itself stays available, so maintenance can be ended early
+    client
+        .delete("/api/v1/maintenance/")
+        .authorize_as(&admin)
+        .expect_status(Status::NoContent)
+        .execute()
+        .await;
+
+    client
+        .post("/api/v1/roles/", &serde_json::json!({"name": "maintainer"}))
+        .authorize_as(&admin)
+        .expect_status(Status::Created)
+        .execute()
+        .await;
+
+    client
+        .delete("/api/v1/maintenance/")
+        .authorize_as(&admin)
+        .expect_status(Status::NotFound)
+        .execute()
+        .await;
+}
+
+#[sqlx::test(migrations = "../migrations")]
+pub async fn test_scheduled_maintenance(pool: Pool<Postgres>) {
+    let (client, mut connection) = pointercrate_test::user::setup_rocket(pool).await;
+
+    let admin = pointercrate_test::user::system_user_with_perms(ADMINISTRATOR, &mut connection).await;
+
+    client
+        .put("/api/v1/maintenance/")
+        .json(&serde_json::json!({"ends_at": "2000-01-01T00:00:00Z"}))
+        .authorize_as(&admin)
+        .expect_status(Status::UnprocessableEntity)
+        .execute()
+        .await;
+
+    let status: serde_json::Value = client
+        .put("/api/v1/maintenance/")
+        .json(&serde_json::json!({"starts_at": "2100-01-01T00:00:00Z", "ends_at": "2100-01-01T02:00:00Z"}))
+        .authorize_as(&admin)
+        .get_result()
+        .await;
+
+    assert_eq!(status["active"], false);
+    assert_eq!(status["window"]["ends_at"], "2100-01-01T02:00:00Z");
+
+    // Maintenance has not started yet
+    client
+        .post("/api/v1/roles/", &serde_json::json!({"name": "maintainer"}))
+        .authorize_as(&admin)
+        .expect_status(Status::Created)
+        .execute()
+        .await;
+
+    // Replacing the window with one that has already started activates maintenance immediately
+    let status: serde_json::Value = client
+        .put("/api/v1/maintenance/")
+        .json(&serde_json::json!({"starts_at": "2000-01-01T00:00:00Z", "ends_at": "2100-01-01T02:00:00Z"}))
+        .authorize_as(&admin)
+        .get_result()
+        .await;
+
+    assert_eq!(status["active"], true);
+
+    client
+        .post("/api/v1/roles/", &serde_json::json!({"name": "maintainer2"}))
+        .authorize_as(&admin)
+        .expect_status(Status::ServiceUnavailable)
+        .execute()
+        .await;
+}
+
+#[sqlx::test(migrations = "../migrations")]
+pub async fn test_freeze_route_group(pool: Pool<Postgres>) {
+    let (client, mut connection) = pointercrate_test::user::setup_rocket(pool).await;
+
+    let admin = pointercrate_test::user::system_user_with_perms(ADMINISTRATOR, &mut connection).await;
+
+    let groups: Vec<String> = client.get("/api/v1/maintenance/groups/").authorize_as(&admin).get_result().await;
+
+    assert!(groups.contains(&REGISTRATIONS.text_id().to_string()));
+
+    client
+        .put("/api/v1/maintenance/")
+        .json(&serde_json::json!({"frozen": ["no-such-group"]}))
+        .authorize_as(&admin)
+        .expect_status(Status::UnprocessableEntity)
+        .execute()
+        .await;
+
+    let status: serde_json::Value = client
+        .put("/api/v1/maintenance/")
+        .json(&serde_json::json!({"frozen": [REGISTRATIONS.text_id()]}))
+        .authorize_as(&admin)
+        .get_result()
+        .await;
+
+    assert_eq!(status["active"], true);
+
+    let error: serde_json::Value = client
+        .post(
+            "/api/v1/auth/register/",
+            &Registration {
+                name: "Jacob".to_string(),
+                password: "bad password".to_string(),
+            },
+        )
+        .expect_status(Status::ServiceUnavailable)
+        .get_result()
+        .await;
+
+    assert_eq!(error["code"], 50302);
+    assert_eq!(error["data"]["feature"], REGISTRATIONS.text_id());
+
+    // Everything not belonging to a frozen route group keeps working
+    client
+        .post("/api/v1/roles/", &serde_json::json!({"name": "maintainer"}))
+        .authorize_as(&admin)
+        .expect_status(Status::Created)
+        .execute()
+        .await;
+}
+
+#[sqlx::test(migrations = "../migrations")]
+pub async fn test_maintenance_shared_between_instances(pool: Pool<Postgres>) {
+    let (client1, mut connection) = pointercrate_test::user::setup_rocket(pool.clone()).await;
+    let (client2, _) = pointercrate_test::user::setup_rocket(pool).await;
+
+    let admin = pointercrate_test::user::system_user_with_perms(ADMINISTRATOR, &mut connection).await;
+
+    client1
+        .put("/api/v1/maintenance/")
+        .json(&serde_json::json!({"messages": {"en": "Migrating the database"}}))
+        .authorize_as(&admin)
+        .execute()
+        .await;
+
+    // The second instance reloads the window once it receives the change notification
+    for _ in 0..50 {
+        let status: serde_json::Value = client2.get("/api/v1/maintenance/").authorize_as(&admin).get_result().await;
+
+        if status["active"] == true {
+            break;
+        }
+
+        sleep(Duration::from_millis(100)).await;
+    }
+
+    client2
+        .post("/api/v1/roles/", &serde_json::json!({"name": "maintainer"}))
+        .authorize_as(&admin)
+        .expect_status(Status::ServiceUnavailable)
+        .execute()
+        .await;
+
+    client1
+        .delete("/api/v1/maintenance/")
+        .authorize_as(&admin)
+        .expect_status(Status::NoContent)
+        .execute()
+        .await;
+
+    for _ in 0..50 {
+        let status: serde_json::Value = client2.get("/api/v1/maintenance/").authorize_as(&admin).get_result().await;
+
+        if status["active"] == false {
+            break;
+        }
+
+        sleep(Duration::from_millis(100)).await;
+    }
+
+    client2
+        .post("/api/v1/roles/", &serde_json::json!({"name": "maintainer"}))
+        .authorize_as(&admin)
+        .expect_status(Status::Created)
+        .execute()
+        .await;
+}
@@ -1,2 +1,11 @@
+mod audit;
+mod cache;
+mod conditional;
 mod login;
+mod maintenance;
+mod paginate;
+mod permissions;
+mod ratelimits;
 mod register;
+mod roles;
+mod tokens;
@@ -0,0 +1,35 @@
+use pointercrate_user::{UserPagination, ADMINISTRATOR};
+use sqlx::{Pool, Postgres};
+
+#[sqlx::test(migrations = "../migrations")]
+pub async fn test_user_pagination_boundaries(pool: Pool<Postgres>) {
+    let (client, mut connection) = pointercrate_test::user::setup_rocket(pool).await;
+
+    let admin = pointercrate_test::user::system_user_with_perms(ADMINISTRATOR, &mut connection).await;
+    let jacob = pointercrate_test::user::add_named_user("Jacob", &mut connection).await;
+    let stardust = pointercrate_test::user::add_named_user("stardust1971", &mut connection).await;
+
+    client
+        .assert_boundary_links::<UserPagination>("/api/v1/users/", &[admin.user().id, jacob.user().id, stardust.user().id], &admin)
+        .await;
+}
+
+#[sqlx::test(migrations = "../migrations")]
+pub async fn test_user_pagination_with_total_count(pool: Pool<Postgres>) {
+    let (client, mut connection) = pointercrate_test::user::setup_rocket(pool).await;
+
+    let admin = pointercrate_test::user::system_user_with_perms(ADMINISTRATOR, &mut connection).await;
+    pointercrate_test::user::add_named_user("Jacob", &mut connection).await;
+    pointercrate_test::user::add_named_user("stardust1971", &mut connection).await;
+
+    let has_administrator = format!("has_permissions={}", ADMINISTRATOR.bit());
+
+    for (query, count) in [("", "3"), ("name_contains=Jac", "1"), (has_administrator.as_str(), "1")] {
+        client
+            .get(format!("/api/v1/users/?count=true&limit=1&{}", query))
+            .authorize_as(&admin)
+            .expect_header("X-Total-Count", count)
+            .execute()
+            .await;
+    }
+}
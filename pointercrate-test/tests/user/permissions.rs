@@ -0,0 +1,32 @@
+use pointercrate_user::{ADMINISTRATOR, MODERATOR};
+use rocket::http::Status;
+use sqlx::{Pool, Postgres};
+
+#[sqlx::test(migrations = "../migrations")]
+pub async fn test_describe_permissions(pool: Pool<Postgres>) {
+    let (client, mut connection) = pointercrate_test::user::setup_rocket(pool).await;
+
+    let admin = pointercrate_test::user::system_user_with_perms(ADMINISTRATOR, &mut connection).await;
+
+    client
+        .post(
+            "/api/v1/roles/",
+            &serde_json::json!({"name": "helper", "assigns": [MODERATOR.text_id()]}),
+        )
+        .authorize_as(&admin)
+        .expect_status(Status::Created)
+        .execute()
+        .await;
+
+    // No authentication required
+    let permissions: serde_json::Value = client.get("/api/v1/permissions/").expect_status(Status::Ok).get_result().await;
+
+    assert_eq!(
+        permissions,
+        serde_json::json!([
+            {"text_id": "helper", "bit": null, "implies": [], "assigns": [MODERATOR.text_id()]},
+            {"text_id": ADMINISTRATOR.text_id(), "bit": ADMINISTRATOR.bit(), "implies": [MODERATOR.text_id()], "assigns": [MODERATOR.text_id()]},
+            {"text_id": MODERATOR.text_id(), "bit": MODERATOR.bit(), "implies": [], "assigns": []},
+        ])
+    );
+}
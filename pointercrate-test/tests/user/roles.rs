@@ -0,0 +1,254 @@
+use pointercrate_core::{
+    etag::Taggable,
+    permission::{PermissionsError, PermissionsManager},
+};
+use pointercrate_user::{role::Role, User, ADMINISTRATOR};
+use rocket::{
+    http::Status,
+    tokio::time::{sleep, Duration},
+};
+use sqlx::{PgConnection, Pool, Postgres};
+
+async fn role_etag(name: &str, connection: &mut PgConnection) -> String {
+    Role::by_name(name, connection).await.unwrap().etag_string()
+}
+
+#[sqlx::test(migrations = "../migrations")]
+pub async fn test_builtin_permissions_are_roles(pool: Pool<Postgres>) {
+    let (client, mut connection) = pointercrate_test::user::setup_rocket(pool).await;
+
+    let admin = pointercrate_test::user::system_user_with_perms(ADMINISTRATOR, &mut connection).await;
+
+    let roles: serde_json::Value = client.get("/api/v1/roles/").authorize_as(&admin).get_result().await;
+
+    let administrator = roles
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|role| role["name"] == ADMINISTRATOR.text_id())
+        .expect("built-in permission to be synchronized into the database");
+
+    assert_eq!(administrator["bit"], ADMINISTRATOR.bit());
+
+    client
+        .delete(format!("/api/v1/roles/{}/", ADMINISTRATOR.text_id()))
+        .authorize_as(&admin)
+        .header("If-Match", role_etag(ADMINISTRATOR.text_id(), &mut connection).await)
+        .expect_status(Status::Forbidden)
+        .execute()
+        .await;
+}
+
+#[sqlx::test(migrations = "../migrations")]
+pub async fn test_role_grants_implied_permissions(pool: Pool<Postgres>) {
+    let (client, mut connection) = pointercrate_test::user::setup_rocket(pool).await;
+
+    let admin = pointercrate_test::user::system_user_with_perms(ADMINISTRATOR, &mut connection).await;
+    let user = pointercrate_test::user::add_named_user("Jacob", &mut connection).await;
+
+    client
+        .get("/api/v1/ratelimits/")
+        .authorize_as(&user)
+        .expect_status(Status::Forbidden)
+        .execute()
+        .await;
+
+    client
+        .post(
+            "/api/v1/roles/",
+            &serde_json::json!({"name": "ratelimit-admin", "implies": [ADMINISTRATOR.text_id()]}),
+        )
+        .authorize_as(&admin)
+        .expect_status(Status::Created)
+        .execute()
+        .await;
+
+    client
+        .post("/api/v1/roles/", &serde_json::json!({"name": "ratelimit-admin"}))
+        .authorize_as(&admin)
+        .expect_status(Status::Conflict)
+        .execute()
+        .await;
+
+    client
+        .patch(
+            "/api/v1/roles/ratelimit-admin/",
+            &serde_json::json!({"implies": ["does not exist"]}),
+        )
+        .authorize_as(&admin)
+        .header("If-Match", role_etag("ratelimit-admin", &mut connection).await)
+        .expect_status(Status::NotFound)
+        .execute()
+        .await;
+
+    let patch = serde_json::json!({"roles": ["ratelimit-admin"]});
+    let user_etag = User::by_id(user.user().id, &mut connection).await.unwrap().etag_string();
+
+    // Nobody can assign the new role yet
+    client
+        .patch(format!("/api/v1/users/{}/", user.user().id), &patch)
+        .authorize_as(&admin)
+        .header("If-Match", user_etag.clone())
+        .expect_status(Status::Forbidden)
+        .execute()
+        .await;
+
+    client
+        .patch(
+            format!("/api/v1/roles/{}/", ADMINISTRATOR.text_id()),
+            &serde_json::json!({"assigns": ["ratelimit-admin"]}),
+        )
+        .authorize_as(&admin)
+        .header("If-Match", role_etag(ADMINISTRATOR.text_id(), &mut connection).await)
+        .expect_status(Status::Ok)
+        .execute()
+        .await;
+
+    let patched: serde_json::Value = client
+        .patch(format!("/api/v1/users/{}/", user.user().id), &patch)
+        .authorize_as(&admin)
+        .header("If-Match", user_etag)
+        .get_success_result()
+        .await;
+
+    assert_eq!(patched["roles"], serde_json::json!(["ratelimit-admin"]));
+
+    client
+        .get("/api/v1/ratelimits/")
+        .authorize_as(&user)
+        .expect_status(Status::Ok)
+        .execute()
+        .await;
+
+    client
+        .delete("/api/v1/roles/ratelimit-admin/")
+        .authorize_as(&admin)
+        .header("If-Match", role_etag("ratelimit-admin", &mut connection).await)
+        .expect_status(Status::NoContent)
+        .execute()
+        .await;
+
+    client
+        .get("/api/v1/ratelimits/")
+        .authorize_as(&user)
+        .expect_status(Status::Forbidden)
+        .execute()
+        .await;
+}
+
+#[sqlx::test(migrations = "../migrations")]
+pub async fn test_role_unknown_to_instance_not_assignable(pool: Pool<Postgres>) {
+    let (client, mut connection) = pointercrate_test::user::setup_rocket(pool).await;
+
+    let admin = pointercrate_test::user::system_user_with_perms(ADMINISTRATOR, &mut connection).await;
+    let user = pointercrate_test::user::add_named_user("Jacob", &mut connection).await;
+
+    // Created behind the instance's back, so it never reloads its roles
+    sqlx::query!("INSERT INTO roles (name) VALUES ('ratelimit-admin')")
+        .execute(&mut *connection)
+        .await
+        .unwrap();
+
+    let user_etag = User::by_id(user.user().id, &mut connection).await.unwrap().etag_string();
+
+    client
+        .patch(
+            format!("/api/v1/users/{}/", user.user().id),
+            &serde_json::json!({"roles": ["ratelimit-admin"]}),
+        )
+        .authorize_as(&admin)
+        .header("If-Match", user_etag)
+        .expect_status(Status::NotFound)
+        .execute()
+        .await;
+
+    let roles = sqlx::query_scalar!("SELECT role FROM member_roles WHERE member_id = $1", user.user().id)
+        .fetch_all(&mut *connection)
+        .await
+        .unwrap();
+
+    assert!(roles.is_empty());
+}
+
+#[sqlx::test(migrations = "../migrations")]
+pub async fn test_role_changes_shared_between_instances(pool: Pool<Postgres>) {
+    let (client1, mut connection) = pointercrate_test::user::setup_rocket(pool.clone()).await;
+    let (client2, _) = pointercrate_test::user::setup_rocket(pool).await;
+
+    let admin = pointercrate_test::user::system_user_with_perms(ADMINISTRATOR, &mut connection).await;
+    let user = pointercrate_test::user::add_named_user("Jacob", &mut connection).await;
+
+    sqlx::query!("INSERT INTO roles (name) VALUES ('ratelimit-admin')")
+        .execute(&mut *connection)
+        .await
+        .unwrap();
+    sqlx::query!(
+        "INSERT INTO member_roles (member_id, role) VALUES ($1, 'ratelimit-admin')",
+        user.user().id
+    )
+    .execute(&mut *connection)
+    .await
+    .unwrap();
+
+    client2
+        .get("/api/v1/ratelimits/")
+        .authorize_as(&user)
+        .expect_status(Status::Forbidden)
+        .execute()
+        .await;
+
+    client1
+        .patch(
+            "/api/v1/roles/ratelimit-admin/",
+            &serde_json::json!({"implies": [ADMINISTRATOR.text_id()]}),
+        )
+        .authorize_as(&admin)
+        .header("If-Match", role_etag("ratelimit-admin", &mut connection).await)
+        .expect_status(Status::Ok)
+        .execute()
+        .await;
+
+    // The second instance reloads its roles once it receives the change notification
+    let permissions = client2.rocket().state::<PermissionsManager>().unwrap();
+    let user_with_role = User::by_id(user.user().id, &mut connection).await.unwrap();
+
+    for _ in 0..50 {
+        if permissions.require_permission(&user_with_role, ADMINISTRATOR).is_ok() {
+            break;
+        }
+
+        sleep(Duration::from_millis(100)).await;
+    }
+
+    client2
+        .get("/api/v1/ratelimits/")
+        .authorize_as(&user)
+        .expect_status(Status::Ok)
+        .execute()
+        .await;
+}
+
+#[sqlx::test(migrations = "../migrations")]
+pub async fn test_renamed_permission_rejected(pool: Pool<Postgres>) {
+    let mut connection = pool.acquire().await.unwrap();
+
+    let permissions = PermissionsManager::new(vec![ADMINISTRATOR]);
+
+    permissions.synchronize(&mut connection).await.unwrap();
+
+    sqlx::query!(
+        "UPDATE roles SET name = 'old-administrator' WHERE name = $1",
+        ADMINISTRATOR.text_id()
+    )
+    .execute(&mut *connection)
+    .await
+    .unwrap();
+
+    assert_eq!(
+        permissions.synchronize(&mut connection).await,
+        Err(PermissionsError::BitTaken {
+            permission: ADMINISTRATOR,
+            role: "old-administrator".to_string()
+        })
+    );
+}
@@ -7,7 +7,7 @@ use pointercrate_core::{
 };
 use pointercrate_core_api::error::IntoOutcome2;
 use pointercrate_core_api::{tryo_result, tryo_state};
-use pointercrate_user::auth::{AccessClaims, ApiToken, AuthenticatedUser, NonMutating, PasswordOrBrowser};
+use pointercrate_user::auth::{token::AccessToken, AccessClaims, ApiToken, AuthenticatedUser, NonMutating, PasswordOrBrowser};
 use rocket::{
     http::{Method, Status},
     request::{FromRequest, Outcome},
@@ -29,7 +29,7 @@ impl<A> Auth<A> {
     }
 
     pub fn require_permission(&self, permission: Permission) -> Result<(), CoreError> {
-        self.permissions.require_permission(self.user.user().permissions, permission)?;
+        self.permissions.require_permission(self.user.user(), permission)?;
 
         Ok(())
     }
@@ -39,7 +39,7 @@ impl<A> Auth<A> {
     }
 
     pub fn assignable_permissions(&self) -> HashSet<Permission> {
-        self.permissions.assignable_by_bits(self.user.user().permissions)
+        self.permissions.assignable_for(self.user.user())
     }
 }
 
@@ -90,7 +90,10 @@ impl<'r> FromRequest<'r> for Auth<ApiToken> {
             if let ["Bearer", token] = authorization.split(' ').collect::<Vec<_>>()[..] {
                 let access_claims = tryo_result!(AccessClaims::decode(token));
                 let user = tryo_result!(AuthenticatedUser::by_id(tryo_result!(access_claims.id()), &mut connection).await);
-                let authenticated_user = tryo_result!(user.validate_api_access(access_claims));
+                let token = tryo_result!(AccessToken::by_claims(&access_claims, &mut connection).await);
+                let mutating = !matches!(request.method(), Method::Get | Method::Head | Method::Options);
+                let authenticated_user =
+                    tryo_result!(user.validate_api_access(access_claims, token.as_ref(), mutating, &permission_manager));
 
                 tryo_result!(audit_connection(&mut connection, authenticated_user.user().id).await);
 
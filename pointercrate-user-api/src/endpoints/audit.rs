@@ -0,0 +1,15 @@
+use crate::auth::Auth;
+use pointercrate_core::audit::{AuditLogFeedEntry, AuditLogPagination};
+use pointercrate_core_api::{error::Result, pagination::pagination_response, query::Query, response::Response2};
+use pointercrate_core_macros::localized;
+use pointercrate_user::{auth::ApiToken, ADMINISTRATOR};
+use rocket::serde::json::Json;
+
+#[localized]
+#[rocket::get("/")]
+pub async fn paginate(mut auth: Auth<ApiToken>, query: Query<AuditLogPagination>) -> Result<Response2<Json<Vec<AuditLogFeedEntry>>>> {
+    // The audit log contains information about every part of the website
+    auth.require_permission(ADMINISTRATOR)?;
+
+    Ok(pagination_response("/api/v1/audit/", query.0, &mut auth.connection).await?)
+}
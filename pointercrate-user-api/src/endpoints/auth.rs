@@ -1,13 +1,19 @@
 use crate::{auth::Auth, ratelimits::UserRatelimits};
-use pointercrate_core::etag::Taggable;
+use pointercrate_core::{
+    audit::{last_modified, last_modified_after_patch},
+    etag::Taggable,
+};
 use pointercrate_core_api::{
-    error::Result,
+    error::{ErrorResponder, Result},
     etag::{Precondition, Tagged},
     response::Response2,
 };
 use pointercrate_core_macros::localized;
 use pointercrate_user::{
-    auth::{ApiToken, PasswordOrBrowser, PatchMe},
+    auth::{
+        token::{AccessToken, NewAccessToken},
+        ApiToken, PasswordOrBrowser, PatchMe,
+    },
     error::UserError,
     User,
 };
@@ -35,18 +41,22 @@ pub async fn register(
 
     let mut connection = pool.transaction().await.map_err(UserError::from)?;
 
-    ratelimits.soft_registrations(ip)?;
+    let soft_ratelimit = ratelimits.soft_registrations(ip).await?;
 
-    LegacyAuthenticatedUser::validate_password(&body.password)?;
-    User::validate_name(&body.name)?;
+    LegacyAuthenticatedUser::validate_password(&body.password)
+        .and_then(|_| User::validate_name(&body.name))
+        .map_err(|err| ErrorResponder::from(err).with_ratelimit(soft_ratelimit))?;
 
-    let user = AuthenticatedUser::register(body.0, &mut connection).await?;
+    let user = AuthenticatedUser::register(body.0, &mut connection)
+        .await
+        .map_err(|err| ErrorResponder::from(err).with_ratelimit(soft_ratelimit))?;
 
-    ratelimits.registrations(ip)?;
+    let ratelimit = ratelimits.registrations(ip).await?;
 
     connection.commit().await.map_err(UserError::from)?;
 
     Ok(Response2::tagged(user.into_user())
+        .with_ratelimit(soft_ratelimit.most_restrictive(ratelimit))
         .with_header("Location", "api/v1/auth/me")
         .status(Status::Created))
 }
@@ -56,8 +66,9 @@ pub async fn register(
 pub async fn login(
     auth: std::result::Result<Auth<PasswordOrBrowser>, CoreError>, ip: IpAddr, ratelimits: &State<UserRatelimits>,
 ) -> Result<Response2<Json<serde_json::Value>>> {
-    ratelimits.login_attempts(ip)?;
-    let auth = auth?;
+    let ratelimit = ratelimits.login_attempts(ip).await?;
+    // Failed attempts count against the ratelimit just the same, so their responses need to report it as well
+    let auth = auth.map_err(|err| ErrorResponder::from(err).with_ratelimit(ratelimit))?;
 
     Ok(Response2::json(serde_json::json! {
         {
@@ -65,7 +76,8 @@ pub async fn login(
             "token": auth.user.generate_programmatic_access_token()
         }
     })
-    .with_header("etag", auth.user.user().etag_string()))
+    .with_header("etag", auth.user.user().etag_string())
+    .with_ratelimit(ratelimit))
 }
 
 #[localized]
@@ -79,37 +91,99 @@ pub async fn invalidate(mut auth: Auth<PasswordOrBrowser>) -> Result<Status> {
 
 #[localized]
 #[rocket::get("/me/")]
-pub async fn get_me(auth: Auth<ApiToken>) -> Tagged<User> {
-    Tagged(auth.user.into_user())
+pub async fn get_me(mut auth: Auth<ApiToken>) -> Result<Response2<Tagged<User>>> {
+    let last_modified = last_modified("user", auth.user.user().id, &mut auth.connection).await?;
+
+    Ok(Response2::tagged(auth.user.into_user()).with_last_modified(last_modified))
 }
 
 #[localized]
 #[rocket::patch("/me/", data = "<patch>")]
 pub async fn patch_me(
     mut auth: Auth<PasswordOrBrowser>, patch: Json<PatchMe>, pred: Precondition,
-) -> Result<std::result::Result<Tagged<User>, Status>> {
-    pred.require_etag_match(auth.user.user())?;
+) -> Result<std::result::Result<Response2<Tagged<User>>, Status>> {
+    let user_id = auth.user.user().id;
+
+    let last_modified = last_modified("user", user_id, &mut auth.connection).await?;
+
+    pred.require(auth.user.user(), last_modified)?;
 
     let changes_password = patch.changes_password();
+    let etag = auth.user.user().etag_string();
 
     let updated_user = auth.user.apply_patch(patch.0, &mut auth.connection).await?;
+    let last_modified = last_modified_after_patch(last_modified, updated_user.etag_string() != etag);
 
     auth.connection.commit().await.map_err(UserError::from)?;
 
     if changes_password {
         Ok(Err(Status::NoContent))
     } else {
-        Ok(Ok(Tagged(updated_user)))
+        Ok(Ok(Response2::tagged(updated_user).with_last_modified(last_modified)))
     }
 }
 
 #[localized]
 #[rocket::delete("/me/")]
 pub async fn delete_me(mut auth: Auth<PasswordOrBrowser>, pred: Precondition) -> Result<Status> {
-    pred.require_etag_match(auth.user.user())?;
+    let last_modified = last_modified("user", auth.user.user().id, &mut auth.connection).await?;
+
+    pred.require(auth.user.user(), last_modified)?;
 
     auth.user.delete(&mut auth.connection).await?;
     auth.connection.commit().await.map_err(UserError::from)?;
 
     Ok(Status::NoContent)
 }
+
+#[localized]
+#[rocket::get("/tokens/")]
+pub async fn list_tokens(mut auth: Auth<ApiToken>) -> Result<Json<Vec<AccessToken>>> {
+    Ok(Json(AccessToken::by_member(auth.user.user().id, &mut auth.connection).await?))
+}
+
+#[localized]
+#[rocket::post("/tokens/", data = "<data>")]
+pub async fn create_token(mut auth: Auth<PasswordOrBrowser>, data: Json<NewAccessToken>) -> Result<Response2<Json<serde_json::Value>>> {
+    // A token can never grant more than its owner has
+    for name in data.permissions.iter().flatten() {
+        let permission = auth
+            .permissions
+            .role(name)
+            .ok_or_else(|| UserError::RoleNotFound { role: name.to_string() })?;
+
+        auth.require_permission(permission)?;
+    }
+
+    let (token, jwt) = auth.user.create_access_token(data.0, &mut auth.connection).await?;
+
+    auth.commit().await?;
+
+    let location = format!("/api/v1/auth/tokens/{}/", token.id);
+
+    Ok(Response2::json(serde_json::json! {
+        {
+            "data": token,
+            "token": jwt
+        }
+    })
+    .with_header("etag", token.etag_string())
+    .with_header("Location", location)
+    .status(Status::Created))
+}
+
+#[localized]
+#[rocket::delete("/tokens/<token_id>/")]
+pub async fn delete_token(mut auth: Auth<PasswordOrBrowser>, token_id: i32) -> Result<Status> {
+    let token = AccessToken::by_id(token_id, &mut auth.connection).await?;
+
+    // Do not leak the existence of other users' tokens
+    if token.member_id != auth.user.user().id {
+        return Err(UserError::AccessTokenNotFound { token_id }.into());
+    }
+
+    token.delete(&mut auth.connection).await?;
+    auth.commit().await?;
+
+    Ok(Status::NoContent)
+}
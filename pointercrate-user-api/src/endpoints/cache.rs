@@ -0,0 +1,15 @@
+use pointercrate_core::cache::{CacheStatistics, ResponseCache};
+use pointercrate_core_api::error::Result;
+use pointercrate_core_macros::localized;
+use pointercrate_user::{auth::ApiToken, ADMINISTRATOR};
+use rocket::{serde::json::Json, State};
+
+use crate::auth::Auth;
+
+#[localized]
+#[rocket::get("/")]
+pub async fn statistics(auth: Auth<ApiToken>, cache: &State<ResponseCache>) -> Result<Json<Vec<CacheStatistics>>> {
+    auth.require_permission(ADMINISTRATOR)?;
+
+    Ok(Json(cache.statistics()))
+}
@@ -0,0 +1,72 @@
+use pointercrate_core::{
+    error::CoreError,
+    maintenance::{Maintenance, MaintenanceStatus, MaintenanceWindow},
+    pool::PointercratePool,
+};
+use pointercrate_core_api::{error::Result, maintenance::RouteGroups};
+use pointercrate_core_macros::localized;
+use pointercrate_user::{auth::ApiToken, ADMINISTRATOR};
+use rocket::{http::Status, serde::json::Json, State};
+
+use crate::auth::Auth;
+
+/// Reloads the maintenance window after a change was committed, so that it takes effect for all subsequent requests to this
+/// instance without waiting for the invalidation notification, which reloads it on all other instances
+async fn reload_maintenance(maintenance: &Maintenance, pool: &PointercratePool) -> Result<()> {
+    maintenance.reload(&mut *pool.connection().await?).await?;
+
+    Ok(())
+}
+
+#[localized]
+#[rocket::get("/")]
+pub async fn get(auth: Auth<ApiToken>, maintenance: &State<Maintenance>) -> Result<Json<MaintenanceStatus>> {
+    auth.require_permission(ADMINISTRATOR)?;
+
+    Ok(Json(maintenance.status()))
+}
+
+#[localized]
+#[rocket::get("/groups/")]
+pub async fn groups(auth: Auth<ApiToken>, route_groups: &State<RouteGroups>) -> Result<Json<Vec<&'static str>>> {
+    auth.require_permission(ADMINISTRATOR)?;
+
+    Ok(Json(route_groups.groups().iter().map(|group| group.text_id()).collect()))
+}
+
+#[localized]
+#[rocket::put("/", data = "<window>")]
+pub async fn put(
+    mut auth: Auth<ApiToken>, maintenance: &State<Maintenance>, route_groups: &State<RouteGroups>, pool: &State<PointercratePool>,
+    window: Json<MaintenanceWindow>,
+) -> Result<Json<MaintenanceStatus>> {
+    auth.require_permission(ADMINISTRATOR)?;
+
+    if window.frozen.iter().any(|group| route_groups.find(group).is_none()) {
+        return Err(CoreError::UnprocessableEntity.into());
+    }
+
+    window.0.store(&mut auth.connection).await?;
+
+    auth.commit().await?;
+
+    reload_maintenance(maintenance, pool).await?;
+
+    Ok(Json(maintenance.status()))
+}
+
+#[localized]
+#[rocket::delete("/")]
+pub async fn delete(mut auth: Auth<ApiToken>, maintenance: &State<Maintenance>, pool: &State<PointercratePool>) -> Result<Status> {
+    auth.require_permission(ADMINISTRATOR)?;
+
+    if !MaintenanceWindow::clear(&mut auth.connection).await? {
+        return Err(CoreError::NotFound.into());
+    }
+
+    auth.commit().await?;
+
+    reload_maintenance(maintenance, pool).await?;
+
+    Ok(Status::NoContent)
+}
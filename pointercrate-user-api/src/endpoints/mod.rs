@@ -1,2 +1,8 @@
+pub(crate) mod audit;
 pub(crate) mod auth;
+pub(crate) mod cache;
+pub(crate) mod maintenance;
+pub(crate) mod permission;
+pub(crate) mod ratelimits;
+pub(crate) mod role;
 pub(crate) mod user;
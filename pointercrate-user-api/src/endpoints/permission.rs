@@ -0,0 +1,9 @@
+use pointercrate_core::permission::{PermissionDescription, PermissionsManager};
+use pointercrate_core_macros::localized;
+use rocket::{serde::json::Json, State};
+
+#[localized]
+#[rocket::get("/")]
+pub async fn list(permissions: &State<PermissionsManager>) -> Json<Vec<PermissionDescription>> {
+    Json(permissions.describe())
+}
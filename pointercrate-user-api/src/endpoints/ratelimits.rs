@@ -0,0 +1,77 @@
+use pointercrate_core::{
+    error::CoreError,
+    ratelimits::{Limiter, LimiterInfo, LimiterState, PatchLimiter, RatelimitRegistry},
+};
+use pointercrate_core_api::error::Result;
+use pointercrate_core_macros::localized;
+use pointercrate_user::{auth::ApiToken, ADMINISTRATOR};
+use rocket::{http::Status, serde::json::Json, State};
+
+use crate::auth::Auth;
+
+/// Ensures that state for specific keys is only queried for limiters that track their requests per key
+fn require_keyed(limiter: &Limiter) -> std::result::Result<(), CoreError> {
+    if !limiter.keyed {
+        return Err(CoreError::BadRequest);
+    }
+
+    Ok(())
+}
+
+#[localized]
+#[rocket::get("/")]
+pub async fn list(auth: Auth<ApiToken>, registry: &State<RatelimitRegistry>) -> Result<Json<Vec<LimiterInfo>>> {
+    auth.require_permission(ADMINISTRATOR)?;
+
+    let mut limiters = Vec::new();
+
+    for set in registry.sets() {
+        for limiter in set.limiters() {
+            limiters.push(set.info(limiter).await?);
+        }
+    }
+
+    Ok(Json(limiters))
+}
+
+#[localized]
+#[rocket::patch("/<limiter>/", data = "<patch>")]
+pub async fn patch(
+    auth: Auth<ApiToken>, registry: &State<RatelimitRegistry>, limiter: &str, patch: Json<PatchLimiter>,
+) -> Result<Json<LimiterInfo>> {
+    auth.require_permission(ADMINISTRATOR)?;
+
+    let (set, limiter) = registry.find(limiter)?;
+
+    if let Some(quota) = patch.0.quota {
+        set.set_quota_override(&limiter, quota).await?;
+    }
+
+    Ok(Json(set.info(&limiter).await?))
+}
+
+#[localized]
+#[rocket::get("/<limiter>/<key>/")]
+pub async fn get_state(auth: Auth<ApiToken>, registry: &State<RatelimitRegistry>, limiter: &str, key: &str) -> Result<Json<LimiterState>> {
+    auth.require_permission(ADMINISTRATOR)?;
+
+    let (set, limiter) = registry.find(limiter)?;
+
+    require_keyed(&limiter)?;
+
+    Ok(Json(set.state(&limiter, key.to_string()).await?))
+}
+
+#[localized]
+#[rocket::delete("/<limiter>/<key>/")]
+pub async fn clear_state(auth: Auth<ApiToken>, registry: &State<RatelimitRegistry>, limiter: &str, key: &str) -> Result<Status> {
+    auth.require_permission(ADMINISTRATOR)?;
+
+    let (set, limiter) = registry.find(limiter)?;
+
+    require_keyed(&limiter)?;
+
+    set.clear(&limiter, key.to_string()).await?;
+
+    Ok(Status::NoContent)
+}
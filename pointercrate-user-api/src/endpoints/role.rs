@@ -0,0 +1,97 @@
+use pointercrate_core::{permission::PermissionsManager, pool::PointercratePool};
+use pointercrate_core_api::{
+    error::Result,
+    etag::{Precondition, Tagged},
+    response::Response2,
+};
+use pointercrate_core_macros::localized;
+use pointercrate_user::{
+    auth::ApiToken,
+    role::{PatchRole, PostRole, Role},
+    ADMINISTRATOR,
+};
+use rocket::{http::Status, serde::json::Json, State};
+
+use crate::auth::Auth;
+
+/// Reloads the roles after a change was committed, so that it takes effect for all subsequent requests to this instance without
+/// waiting for the invalidation notification, which reloads them on all other instances
+async fn reload_roles(permissions: PermissionsManager, pool: &PointercratePool) -> Result<()> {
+    permissions.reload(&mut *pool.connection().await?).await?;
+
+    Ok(())
+}
+
+#[localized]
+#[rocket::get("/")]
+pub async fn list(mut auth: Auth<ApiToken>) -> Result<Json<Vec<Role>>> {
+    auth.require_permission(ADMINISTRATOR)?;
+
+    Ok(Json(Role::all(&mut auth.connection).await?))
+}
+
+#[localized]
+#[rocket::post("/", data = "<data>")]
+pub async fn post(mut auth: Auth<ApiToken>, pool: &State<PointercratePool>, data: Json<PostRole>) -> Result<Response2<Tagged<Role>>> {
+    auth.require_permission(ADMINISTRATOR)?;
+
+    let role = Role::create_from(data.0, &mut auth.connection).await?;
+    let permissions = auth.permissions.clone();
+
+    auth.commit().await?;
+
+    reload_roles(permissions, pool).await?;
+
+    let location = format!("/api/v1/roles/{}/", role.name);
+
+    Ok(Response2::tagged(role).status(Status::Created).with_header("Location", location))
+}
+
+#[localized]
+#[rocket::get("/<name>/")]
+pub async fn get(mut auth: Auth<ApiToken>, name: &str) -> Result<Tagged<Role>> {
+    auth.require_permission(ADMINISTRATOR)?;
+
+    Ok(Tagged(Role::by_name(name, &mut auth.connection).await?))
+}
+
+#[localized]
+#[rocket::patch("/<name>/", data = "<patch>")]
+pub async fn patch(
+    mut auth: Auth<ApiToken>, precondition: Precondition, pool: &State<PointercratePool>, name: &str, patch: Json<PatchRole>,
+) -> Result<Tagged<Role>> {
+    auth.require_permission(ADMINISTRATOR)?;
+
+    let role = Role::by_name(name, &mut auth.connection).await?;
+
+    precondition.require_etag_match(&role)?;
+
+    let role = role.apply_patch(patch.0, &mut auth.connection).await?;
+    let permissions = auth.permissions.clone();
+
+    auth.commit().await?;
+
+    reload_roles(permissions, pool).await?;
+
+    Ok(Tagged(role))
+}
+
+#[localized]
+#[rocket::delete("/<name>/")]
+pub async fn delete(mut auth: Auth<ApiToken>, precondition: Precondition, pool: &State<PointercratePool>, name: &str) -> Result<Status> {
+    auth.require_permission(ADMINISTRATOR)?;
+
+    let role = Role::by_name(name, &mut auth.connection).await?;
+
+    precondition.require_etag_match(&role)?;
+
+    role.delete(&mut auth.connection).await?;
+
+    let permissions = auth.permissions.clone();
+
+    auth.commit().await?;
+
+    reload_roles(permissions, pool).await?;
+
+    Ok(Status::NoContent)
+}
@@ -1,5 +1,9 @@
 use log::info;
-use pointercrate_core::error::CoreError;
+use pointercrate_core::{
+    audit::{last_modified, last_modified_after_patch, AuditLogEntry},
+    error::CoreError,
+    etag::Taggable,
+};
 use pointercrate_core_api::{
     error::Result,
     etag::{Precondition, Tagged},
@@ -8,8 +12,11 @@ use pointercrate_core_api::{
     response::Response2,
 };
 use pointercrate_core_macros::localized;
-use pointercrate_user::{auth::ApiToken, error::UserError, PatchUser, User, UserPagination, ADMINISTRATOR, MODERATOR};
+use pointercrate_user::{
+    audit::UserModificationData, auth::ApiToken, error::UserError, PatchUser, User, UserPagination, ADMINISTRATOR, MODERATOR,
+};
 use rocket::{http::Status, serde::json::Json};
+use std::collections::HashSet;
 
 use crate::auth::Auth;
 
@@ -40,7 +47,7 @@ pub async fn paginate(mut auth: Auth<ApiToken>, data: Query<UserPagination>) ->
 
 #[localized]
 #[rocket::get("/<user_id>/")]
-pub async fn get_user(mut auth: Auth<ApiToken>, user_id: i32) -> Result<Tagged<User>> {
+pub async fn get_user(mut auth: Auth<ApiToken>, user_id: i32) -> Result<Response2<Tagged<User>>> {
     let user = User::by_id(user_id, &mut auth.connection).await?;
 
     // We are only allowed to retrieve users who already have permissions we can set.
@@ -53,14 +60,30 @@ pub async fn get_user(mut auth: Auth<ApiToken>, user_id: i32) -> Result<Tagged<U
         }
     }
 
-    Ok(Tagged(user))
+    let last_modified = last_modified("user", user_id, &mut auth.connection).await?;
+
+    Ok(Response2::tagged(user).with_last_modified(last_modified))
+}
+
+#[localized]
+#[rocket::get("/<user_id>/audit/")]
+pub async fn audit(mut auth: Auth<ApiToken>, user_id: i32) -> Result<Json<Vec<AuditLogEntry<UserModificationData>>>> {
+    auth.require_permission(ADMINISTRATOR)?;
+
+    let log = pointercrate_user::audit::audit_log_for_user(user_id, &mut auth.connection).await?;
+
+    if log.is_empty() {
+        return Err(UserError::UserNotFound { user_id }.into());
+    }
+
+    Ok(Json(log))
 }
 
 #[localized]
 #[rocket::patch("/<user_id>/", data = "<patch>")]
 pub async fn patch_user(
     mut auth: Auth<ApiToken>, precondition: Precondition, user_id: i32, mut patch: Json<PatchUser>,
-) -> Result<Tagged<User>> {
+) -> Result<Response2<Tagged<User>>> {
     let user = User::by_id(user_id, &mut auth.connection).await?;
 
     if !auth.has_permission(MODERATOR) && !auth.has_permission(ADMINISTRATOR) {
@@ -98,17 +121,50 @@ pub async fn patch_user(
         *permissions |= user.permissions & !assignable_bitmask;
     }
 
+    if let Some(ref mut roles) = patch.roles {
+        let assignable = auth.assignable_permissions();
+        let mut non_assignable = HashSet::new();
+
+        // Roles this instance does not know about (yet) cannot be checked for assignability, so they cannot be assigned either
+        for name in roles.iter() {
+            let role = auth
+                .permissions
+                .role(name)
+                .ok_or_else(|| UserError::RoleNotFound { role: name.to_string() })?;
+
+            if !assignable.contains(&role) {
+                non_assignable.insert(role);
+            }
+        }
+
+        if !non_assignable.is_empty() {
+            return Err(UserError::PermissionNotAssignable { non_assignable }.into());
+        }
+
+        // Same as above: Roles we cannot assign are left untouched
+        roles.extend(
+            user.roles
+                .iter()
+                .filter(|role| auth.permissions.role(role).is_none_or(|role| !assignable.contains(&role)))
+                .cloned(),
+        );
+    }
+
     if user_id == auth.user.user().id {
         return Err(UserError::PatchSelf.into());
     }
 
-    precondition.require_etag_match(&user)?;
+    let last_modified = last_modified("user", user_id, &mut auth.connection).await?;
+
+    precondition.require(&user, last_modified)?;
 
+    let etag = user.etag_string();
     let user = user.apply_patch(patch.0, &mut auth.connection).await?;
+    let last_modified = last_modified_after_patch(last_modified, user.etag_string() != etag);
 
     auth.commit().await?;
 
-    Ok(Tagged(user))
+    Ok(Response2::tagged(user).with_last_modified(last_modified))
 }
 
 #[localized]
@@ -122,7 +178,7 @@ pub async fn delete_user(mut auth: Auth<ApiToken>, precondition: Precondition, u
 
     let to_delete = User::by_id(user_id, &mut auth.connection).await?;
 
-    precondition.require_etag_match(&to_delete)?;
+    precondition.require(&to_delete, last_modified("user", user_id, &mut auth.connection).await?)?;
 
     to_delete.delete(&mut auth.connection).await?;
 
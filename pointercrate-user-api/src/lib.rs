@@ -1,6 +1,16 @@
 use crate::ratelimits::UserRatelimits;
 
-use rocket::{Build, Rocket};
+use pointercrate_core::{
+    permission::{PermissionsManager, ROLES},
+    pool::PointercratePool,
+    ratelimits::PostgresRatelimitStore,
+};
+use pointercrate_core_api::{
+    cache::register_reloadable,
+    maintenance::{register_route_groups, MAINTENANCE_API},
+    ratelimits::register_ratelimits,
+};
+use rocket::{fairing::AdHoc, Build, Rocket};
 
 pub mod auth;
 mod endpoints;
@@ -8,17 +18,19 @@ mod endpoints;
 mod oauth;
 mod pages;
 mod ratelimits;
+pub mod route_groups;
 
 #[allow(unused_mut)]
 pub fn setup(mut rocket: Rocket<Build>) -> Rocket<Build> {
-    let ratelimits = UserRatelimits::new();
-
     let mut auth_routes = rocket::routes![
         endpoints::auth::login,
         endpoints::auth::invalidate,
         endpoints::auth::get_me,
         endpoints::auth::patch_me,
         endpoints::auth::delete_me,
+        endpoints::auth::list_tokens,
+        endpoints::auth::create_token,
+        endpoints::auth::delete_token,
     ];
     let mut page_routes = rocket::routes![
         pages::login_page,
@@ -39,17 +51,93 @@ pub fn setup(mut rocket: Rocket<Build>) -> Rocket<Build> {
         rocket = rocket.manage(oauth::GoogleCertificateStore::default());
     }
 
-    rocket
-        .manage(ratelimits)
+    register_route_groups(rocket, &[route_groups::REGISTRATIONS])
+        // The connection pool might only be managed after this setup function is called, so defer creating the ratelimits until ignition
+        .attach(AdHoc::try_on_ignite("User ratelimits", |rocket| async {
+            let Some(pool) = rocket.state::<PointercratePool>().map(PointercratePool::clone_inner) else {
+                log::error!("No connection pool managed, cannot set up user ratelimits");
+
+                return Err(rocket);
+            };
+
+            let ratelimits = UserRatelimits::with_store(PostgresRatelimitStore::new(pool));
+
+            Ok(register_ratelimits(rocket, ratelimits.set().clone()).manage(ratelimits))
+        }))
+        // Make sure the permissions defined in code form a valid graph and exist as roles in the database, and load the roles
+        // defined there. They are reloaded whenever another instance changes them
+        .attach(AdHoc::try_on_ignite("Database roles", |rocket| async {
+            let (Some(pool), Some(permissions)) = (rocket.state::<PointercratePool>(), rocket.state::<PermissionsManager>()) else {
+                log::error!("No connection pool or permissions manager managed, cannot load roles");
+
+                return Err(rocket);
+            };
+
+            if let Err(err) = permissions.validate() {
+                log::error!("Invalid permission setup: {}", err);
+
+                return Err(rocket);
+            }
+
+            let synchronized = match pool.connection().await {
+                Ok(mut connection) => permissions.synchronize(&mut connection).await,
+                Err(err) => Err(err.into()),
+            };
+
+            match synchronized {
+                Ok(()) => {
+                    let permissions = permissions.clone();
+
+                    Ok(register_reloadable(rocket, ROLES, permissions))
+                },
+                Err(err) => {
+                    log::error!("Failed to load roles from database: {}", err);
+
+                    Err(rocket)
+                },
+            }
+        }))
         .mount("/api/v1/auth/", auth_routes)
         .mount(
             "/api/v1/users/",
             rocket::routes![
                 endpoints::user::paginate,
                 endpoints::user::get_user,
+                endpoints::user::audit,
                 endpoints::user::patch_user,
                 endpoints::user::delete_user
             ],
         )
+        .mount(
+            "/api/v1/ratelimits/",
+            rocket::routes![
+                endpoints::ratelimits::list,
+                endpoints::ratelimits::patch,
+                endpoints::ratelimits::get_state,
+                endpoints::ratelimits::clear_state
+            ],
+        )
+        .mount(
+            MAINTENANCE_API,
+            rocket::routes![
+                endpoints::maintenance::get,
+                endpoints::maintenance::groups,
+                endpoints::maintenance::put,
+                endpoints::maintenance::delete
+            ],
+        )
+        .mount("/api/v1/cache/", rocket::routes![endpoints::cache::statistics])
+        .mount("/api/v1/audit/", rocket::routes![endpoints::audit::paginate])
+        .mount("/api/v1/permissions/", rocket::routes![endpoints::permission::list])
+        .mount(
+            "/api/v1/roles/",
+            rocket::routes![
+                endpoints::role::list,
+                endpoints::role::post,
+                endpoints::role::get,
+                endpoints::role::patch,
+                endpoints::role::delete
+            ],
+        )
         .mount("/", page_routes)
 }
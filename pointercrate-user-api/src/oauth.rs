@@ -28,11 +28,11 @@ impl From<reqwest::Error> for CertificateRefreshError {
 }
 
 impl GoogleCertificateStore {
-    pub async fn validate(&self, creds: UnvalidatedOauthCredential) -> Option<ValidatedGoogleCredentials> {
-        self.db.read().await.validate_credentials(creds)
+    pub async fn validate(&self, creds: UnvalidatedOauthCredential, client_id: &str) -> Option<ValidatedGoogleCredentials> {
+        self.db.read().await.validate_credentials(creds, client_id)
     }
 
-    pub async fn validate_with_refresh(&self, creds: UnvalidatedOauthCredential) -> Option<ValidatedGoogleCredentials> {
+    pub async fn validate_with_refresh(&self, creds: UnvalidatedOauthCredential, client_id: &str) -> Option<ValidatedGoogleCredentials> {
         if self.needs_refresh().await {
             self.refresh()
                 .await
@@ -40,7 +40,7 @@ impl GoogleCertificateStore {
                 .ok()?;
         }
 
-        self.validate(creds).await
+        self.validate(creds, client_id).await
     }
 
     pub async fn needs_refresh(&self) -> bool {
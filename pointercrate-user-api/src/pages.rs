@@ -1,10 +1,14 @@
 use crate::{auth::Auth, ratelimits::UserRatelimits};
 use pointercrate_core::error::CoreError;
 use pointercrate_core::permission::PermissionsManager;
-use pointercrate_core_api::response::Page;
+use pointercrate_core_api::{
+    error::ErrorResponder,
+    response::{Page, Response2},
+};
 use pointercrate_core_macros::localized;
 use pointercrate_user::{
     auth::{AuthenticatedUser, NonMutating, PasswordOrBrowser},
+    config::UserConfig,
     error::UserError,
 };
 use pointercrate_user_pages::account::AccountPageConfig;
@@ -52,9 +56,9 @@ fn build_cookies(user: &AuthenticatedUser<PasswordOrBrowser>, cookies: &CookieJa
 
 #[localized]
 #[rocket::get("/login/")]
-pub async fn login_page(auth: Option<Auth<NonMutating>>) -> Result<Redirect, Page> {
+pub async fn login_page(auth: Option<Auth<NonMutating>>, config: &State<UserConfig>) -> Result<Redirect, Page> {
     auth.map(|_| Redirect::to(rocket::uri!(account_page)))
-        .ok_or_else(|| Page::new(pointercrate_user_pages::login::login_page()))
+        .ok_or_else(|| Page::new(pointercrate_user_pages::login::login_page(config)))
 }
 
 // Doing the post with cookies already set will just refresh them. No point in doing that, but also not harmful.
@@ -62,20 +66,21 @@ pub async fn login_page(auth: Option<Auth<NonMutating>>) -> Result<Redirect, Pag
 #[rocket::post("/login/")]
 pub async fn login(
     auth: Result<Auth<PasswordOrBrowser>, CoreError>, ip: IpAddr, ratelimits: &State<UserRatelimits>, cookies: &CookieJar<'_>,
-) -> pointercrate_core_api::error::Result<Status> {
-    ratelimits.login_attempts(ip)?;
+) -> pointercrate_core_api::error::Result<Response2<()>> {
+    let ratelimit = ratelimits.login_attempts(ip).await?;
 
-    let auth = auth?;
+    // Failed attempts count against the ratelimit just the same, so their responses need to report it as well
+    let auth = auth.map_err(|err| ErrorResponder::from(err).with_ratelimit(ratelimit))?;
 
     build_cookies(&auth.user, cookies)?;
 
-    Ok(Status::NoContent)
+    Ok(Response2::new(()).with_ratelimit(ratelimit).status(Status::NoContent))
 }
 
 #[localized]
 #[rocket::get("/register/")]
-pub async fn register_page() -> Page {
-    Page::new(pointercrate_user_pages::register::registration_page())
+pub async fn register_page(config: &State<UserConfig>) -> Page {
+    Page::new(pointercrate_user_pages::register::registration_page(config))
 }
 
 #[cfg(feature = "legacy_accounts")]
@@ -84,23 +89,26 @@ pub async fn register_page() -> Page {
 pub async fn register(
     ip: IpAddr, ratelimits: &State<UserRatelimits>, cookies: &CookieJar<'_>, registration: Json<Registration>,
     pool: &State<PointercratePool>,
-) -> pointercrate_core_api::error::Result<Status> {
+) -> pointercrate_core_api::error::Result<Response2<()>> {
     let mut connection = pool.transaction().await.map_err(UserError::from)?;
 
-    ratelimits.soft_registrations(ip)?;
+    let soft_ratelimit = ratelimits.soft_registrations(ip).await?;
 
-    LegacyAuthenticatedUser::validate_password(&registration.password)?;
-    User::validate_name(&registration.name)?;
+    LegacyAuthenticatedUser::validate_password(&registration.password)
+        .and_then(|_| User::validate_name(&registration.name))
+        .map_err(|err| ErrorResponder::from(err).with_ratelimit(soft_ratelimit))?;
 
-    ratelimits.registrations(ip)?;
+    let ratelimit = soft_ratelimit.most_restrictive(ratelimits.registrations(ip).await?);
 
-    let user = AuthenticatedUser::register(registration.0, &mut connection).await?;
+    let user = AuthenticatedUser::register(registration.0, &mut connection)
+        .await
+        .map_err(|err| ErrorResponder::from(err).with_ratelimit(ratelimit))?;
 
     connection.commit().await.map_err(UserError::from)?;
 
     build_cookies(&user, cookies)?;
 
-    Ok(Status::Created)
+    Ok(Response2::new(()).with_ratelimit(ratelimit).status(Status::Created))
 }
 
 #[localized]
@@ -127,9 +135,13 @@ pub async fn logout(_auth: Auth<NonMutating>, cookies: &CookieJar<'_>) -> Redire
 #[rocket::post("/oauth/google/", data = "<payload>")]
 pub async fn google_oauth_login(
     payload: Json<UnvalidatedOauthCredential>, auth: Option<Auth<PasswordOrBrowser>>, key_store: &State<GoogleCertificateStore>,
-    pool: &State<PointercratePool>, cookies: &rocket::http::CookieJar<'_>,
+    pool: &State<PointercratePool>, cookies: &rocket::http::CookieJar<'_>, config: &State<UserConfig>,
 ) -> pointercrate_core_api::error::Result<Status> {
-    let validated_credentials = key_store.validate_with_refresh(payload.0).await.ok_or(CoreError::Unauthorized)?;
+    let client_id = config.google_client_id.as_deref().ok_or(CoreError::Unauthorized)?;
+    let validated_credentials = key_store
+        .validate_with_refresh(payload.0, client_id)
+        .await
+        .ok_or(CoreError::Unauthorized)?;
 
     let maybe_linked_user = AuthenticatedUser::by_validated_google_creds(&validated_credentials, &mut *pool.connection().await?).await;
 
@@ -161,10 +173,14 @@ pub async fn google_oauth_login(
 #[rocket::post("/oauth/google/register/", data = "<payload>")]
 pub async fn google_oauth_register(
     payload: Json<OauthRegistration>, key_store: &State<GoogleCertificateStore>, ip: IpAddr, pool: &State<PointercratePool>,
-    cookies: &rocket::http::CookieJar<'_>,
+    cookies: &rocket::http::CookieJar<'_>, config: &State<UserConfig>,
 ) -> pointercrate_core_api::error::Result<Status> {
     let OauthRegistration { credential, username } = payload.0;
-    let validated_credentials = key_store.validate_with_refresh(credential).await.ok_or(CoreError::Unauthorized)?;
+    let client_id = config.google_client_id.as_deref().ok_or(CoreError::Unauthorized)?;
+    let validated_credentials = key_store
+        .validate_with_refresh(credential, client_id)
+        .await
+        .ok_or(CoreError::Unauthorized)?;
 
     let mut connection = pool.transaction().await.map_err(UserError::from)?;
 
@@ -0,0 +1,14 @@
+//! [`RouteGroup`]s of the user account system that administrators can freeze for maintenance
+
+use pointercrate_core_api::maintenance::RouteGroup;
+use rocket::http::Method;
+
+/// Creation of new accounts, both legacy and via oauth
+pub const REGISTRATIONS: RouteGroup = RouteGroup::new(
+    "route-group-registrations",
+    &[
+        (Method::Post, "/api/v1/auth/register/"),
+        (Method::Post, "/register/"),
+        (Method::Post, "/oauth/google/register/"),
+    ],
+);
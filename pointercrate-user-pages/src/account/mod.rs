@@ -4,7 +4,10 @@ use pointercrate_core_pages::{
     head::{HeadLike, Script},
     PageFragment,
 };
-use pointercrate_user::auth::{AuthenticatedUser, NonMutating};
+use pointercrate_user::{
+    auth::{AuthenticatedUser, NonMutating},
+    User,
+};
 use sqlx::PgConnection;
 
 pub mod profile;
@@ -12,7 +15,7 @@ pub mod users;
 
 #[async_trait::async_trait]
 pub trait AccountPageTab {
-    fn should_display_for(&self, permissions_we_have: u16, permission_manager: &PermissionsManager) -> bool;
+    fn should_display_for(&self, user: &User, permission_manager: &PermissionsManager) -> bool;
     fn initialization_script(&self) -> String;
     fn additional_scripts(&self) -> Vec<Script> {
         vec![]
@@ -46,7 +49,7 @@ impl AccountPageConfig {
         };
 
         for tab_config in &self.tabs {
-            if tab_config.should_display_for(page.user.user().permissions, permissions) {
+            if tab_config.should_display_for(page.user.user(), permissions) {
                 let tab = tab_config.tab();
                 let content = tab_config.content(&page.user, permissions, connection).await;
 
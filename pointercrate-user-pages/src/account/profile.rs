@@ -8,15 +8,16 @@ use pointercrate_core::{
 use pointercrate_core_pages::head::Script;
 use pointercrate_user::{
     auth::{AuthenticatedUser, NonMutating},
-    config,
+    config::UserConfig,
+    User,
 };
 use sqlx::PgConnection;
 
-pub struct ProfileTab;
+pub struct ProfileTab(pub UserConfig);
 
 #[async_trait::async_trait]
 impl AccountPageTab for ProfileTab {
-    fn should_display_for(&self, _permissions_we_have: u16, _permissions: &PermissionsManager) -> bool {
+    fn should_display_for(&self, _user: &User, _permissions: &PermissionsManager) -> bool {
         true
     }
 
@@ -51,7 +52,7 @@ impl AccountPageTab for ProfileTab {
     ) -> Markup {
         let user = authenticated_user.user();
 
-        let permissions = permissions.bits_to_permissions(user.permissions);
+        let permissions = permissions.granted_to(user);
         let permission_string = permissions.iter().map(|perm| tr(perm.text_id())).collect::<Vec<_>>().join(", ");
 
         let lang = task_lang();
@@ -144,7 +145,7 @@ impl AccountPageTab for ProfileTab {
                             data-ux_mode="popup"
                             data-auto_select="true"
                             data-itp_support="true"
-                            data-client_id=(config::google_client_id())
+                            data-client_id=[self.0.google_client_id.as_deref()]
                             data-callback="googleOauthCallback" {}
 
                         script src=(format!("https://accounts.google.com/gsi/client?hl={}", &lang)) async {}
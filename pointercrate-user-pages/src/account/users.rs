@@ -7,7 +7,7 @@ use pointercrate_core::{
 use pointercrate_core_pages::util::filtered_paginator;
 use pointercrate_user::{
     auth::{AuthenticatedUser, NonMutating},
-    ADMINISTRATOR,
+    User, ADMINISTRATOR,
 };
 use sqlx::PgConnection;
 
@@ -15,9 +15,9 @@ pub struct UsersTab(pub Vec<Permission>);
 
 #[async_trait::async_trait]
 impl AccountPageTab for UsersTab {
-    fn should_display_for(&self, permissions_we_have: u16, permissions: &PermissionsManager) -> bool {
+    fn should_display_for(&self, user: &User, permissions: &PermissionsManager) -> bool {
         for perm in &self.0 {
-            if permissions.require_permission(permissions_we_have, *perm).is_ok() {
+            if permissions.require_permission(user, *perm).is_ok() {
                 return true;
             }
         }
@@ -46,9 +46,11 @@ impl AccountPageTab for UsersTab {
     async fn content(
         &self, user: &AuthenticatedUser<NonMutating>, permissions: &PermissionsManager, _connection: &mut PgConnection,
     ) -> Markup {
+        // Only permissions backed by a bit can be assigned here, database-defined roles are managed via the API
         let mut assignable_permissions = permissions
-            .assignable_by_bits(user.user().permissions)
+            .assignable_for(user.user())
             .into_iter()
+            .filter(|perm| perm.bit() != 0)
             .collect::<Vec<_>>();
         assignable_permissions.sort_by_key(|perm| perm.bit());
 
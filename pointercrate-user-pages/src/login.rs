@@ -1,9 +1,9 @@
 use maud::{html, Markup};
 use pointercrate_core::localization::{task_lang, tr};
 use pointercrate_core_pages::{head::HeadLike, trp_html, PageFragment};
-use pointercrate_user::config;
+use pointercrate_user::config::UserConfig;
 
-pub fn login_page() -> PageFragment {
+pub fn login_page(config: &UserConfig) -> PageFragment {
     let mut frag = PageFragment::new(
         "Pointercrate - Login",
         "Log in to an existing pointercrate account or register for a new one!",
@@ -12,7 +12,7 @@ pub fn login_page() -> PageFragment {
     .module("/static/core/js/modules/form.js")
     .module("/static/core/js/modules/tab.js")
     .stylesheet("/static/user/css/login.css")
-    .body(login_page_body());
+    .body(login_page_body(config));
 
     if cfg!(feature = "oauth2") {
         frag = frag.async_script("https://accounts.google.com/gsi/client");
@@ -21,7 +21,7 @@ pub fn login_page() -> PageFragment {
     frag
 }
 
-fn login_page_body() -> Markup {
+fn login_page_body(config: &UserConfig) -> Markup {
     let lang = task_lang();
 
     html! {
@@ -40,7 +40,7 @@ fn login_page_body() -> Markup {
                             data-ux_mode="popup"
                             data-auto_select="true"
                             data-itp_support="true"
-                            data-client_id=(config::google_client_id())
+                            data-client_id=[config.google_client_id.as_deref()]
                             data-callback="googleOauthCallback" {}
 
                         script src=(format!("https://accounts.google.com/gsi/client?hl={}", &lang)) async {}
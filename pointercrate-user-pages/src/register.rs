@@ -3,15 +3,15 @@ use pointercrate_core::localization::task_lang;
 use pointercrate_core::localization::tr;
 use pointercrate_core_pages::head::HeadLike;
 use pointercrate_core_pages::{trp_html, PageFragment};
-use pointercrate_user::config;
+use pointercrate_user::config::UserConfig;
 
-pub fn registration_page() -> PageFragment {
+pub fn registration_page(config: &UserConfig) -> PageFragment {
     let mut frag = PageFragment::new("Pointercrate - Registration", "Register for a new pointercrate account!")
         .module("/static/user/js/register.js")
         .module("/static/core/js/modules/form.js")
         .module("/static/core/js/modules/tab.js")
         .stylesheet("/static/user/css/login.css")
-        .body(register_page_body());
+        .body(register_page_body(config));
 
     if cfg!(feature = "oauth2") {
         frag = frag.async_script("https://accounts.google.com/gsi/client");
@@ -20,7 +20,7 @@ pub fn registration_page() -> PageFragment {
     frag
 }
 
-fn register_page_body() -> Markup {
+fn register_page_body(config: &UserConfig) -> Markup {
     let lang = task_lang();
 
     html! {
@@ -38,7 +38,7 @@ fn register_page_body() -> Markup {
                             data-ux_mode="popup"
                             data-auto_select="true"
                             data-itp_support="true"
-                            data-client_id=(config::google_client_id())
+                            data-client_id=[config.google_client_id.as_deref()]
                             data-callback="googleOauthRegisterCallback" {}
 
                         script src=(format!("https://accounts.google.com/gsi/client?hl={}", &lang)) async {}
@@ -9,6 +9,13 @@ error-user-invalidusername = Invalid display or username! The name must be at le
 error-user-invalidpassword = Invalid password! The password must be at least 10 characters long
 error-user-notyoutube = The given URL is no YouTube URL
 error-user-nonlegacyaccount = The given operation (change password) is invalid on non-legacy account, as password login is not supported for these
+error-user-rolenotfound = No role with name { $role } found
+error-user-builtinrole = Roles corresponding to built-in permissions cannot be deleted, and can only be granted via permissions
+error-user-roleexists = A role with the chosen name already exists
+error-user-invalidrolename = Invalid role name! The name must not be empty or start/end with a space
+error-user-accesstokennotfound = No access token with id { $token-id } found
+error-user-invalidtokenname = Invalid token name! The name must not be empty or start/end with a space
+error-user-tokenexpiryinpast = The expiry date of an access token must lie in the future
 
 error-user-ratelimit-registration = Too many registrations!
 error-user-ratelimit-soft-registration = Too many failed registration attempts!
@@ -143,3 +143,5 @@ profile-invalidate-tokens = Invalidate tokens
 
 profile-oauth = Link With Google
     .info = Enable signing in to your pointercrate account via Google oauth. More secure than password login, and avoids account lock-outs due to forgotten passwords. Linking a Google account is irreversible, and you cannot change the linked Google account later on!
+
+route-group-registrations = Account registrations
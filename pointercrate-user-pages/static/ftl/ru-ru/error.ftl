@@ -9,6 +9,13 @@ error-user-invalidusername = Неправильное отображаемое 
 error-user-invalidpassword = Неверный пароль! Пароль должен быть минимум 10 символов в длину
 error-user-notyoutube = Данная ссылка не является YouTube-ссылкой
 error-user-nonlegacyaccount = Данная операция (изменение пароля) не является валидной на новом типе аккаунтов, так как вход по паролю для них не поддерживается
+error-user-rolenotfound = Роль с именем { $role } не была найдена
+error-user-builtinrole = Роли, соответствующие встроенным правам, не могут быть удалены и назначаются только через права
+error-user-roleexists = Роль с выбранным именем уже существует
+error-user-invalidrolename = Неправильное название роли! Название не должно быть пустым или начинаться либо заканчиваться пробелом
+error-user-accesstokennotfound = Токен доступа с ID { $token-id } не был найден
+error-user-invalidtokenname = Неправильное название токена! Название не должно быть пустым или начинаться либо заканчиваться пробелом
+error-user-tokenexpiryinpast = Срок действия токена доступа должен истекать в будущем
 
 error-user-ratelimit-registration = Слишком много попыток регистрации!
 error-user-ratelimit-soft-registration = Слишком много проваленных попыток регистрации!
@@ -143,3 +143,5 @@ profile-invalidate-tokens = Отключить токены
 
 profile-oauth = Связь с Google
     .info = Здесь проходит включение возможности входа в ваш аккаунт pointercrate через Google. Эта опция более защищена, чем традиционный метод входа, и предотвращает потерю доступа к аккаунту из-за забытого пароля. Связь с аккаунтом Google отменить нельзя, и вы не можете поменять связанный аккаунт Google на другой!
+
+route-group-registrations = Регистрация аккаунтов
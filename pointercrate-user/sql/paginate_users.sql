@@ -1,4 +1,4 @@
-SELECT member_id, name, permissions::INTEGER, display_name::TEXT, youtube_channel::TEXT
+SELECT member_id, name, permissions::INTEGER, ARRAY(SELECT role FROM member_roles WHERE member_roles.member_id = members.member_id ORDER BY role) AS roles, display_name::TEXT, youtube_channel::TEXT
 FROM members
 WHERE (member_id < $1 OR $1 IS NULL)
   AND (member_id > $2 OR $2 is NULL)
@@ -0,0 +1,103 @@
+use crate::error::Result;
+
+use futures::StreamExt;
+use pointercrate_core::audit::{AuditLogEntry, AuditLogEntryType, NamedId};
+use serde::Serialize;
+use sqlx::PgConnection;
+
+#[derive(Serialize)]
+pub struct UserModificationData {
+    display_name: Option<String>,
+    youtube_channel: Option<String>,
+    permissions: Option<u16>,
+}
+
+/// Gets all audit log entries for the given user, in chronological order
+pub async fn audit_log_for_user(user_id: i32, connection: &mut PgConnection) -> Result<Vec<AuditLogEntry<UserModificationData>>> {
+    let mut entries = Vec::new();
+
+    let addition_row = sqlx::query!(
+        r#"SELECT time, audit_id, 
+                  userid,
+                  members.name AS "name?"
+                  FROM user_additions LEFT OUTER JOIN members ON members.member_id = userid WHERE id = $1"#,
+        user_id
+    )
+    .fetch_optional(&mut *connection)
+    .await?;
+
+    if let Some(addition) = addition_row {
+        entries.push(AuditLogEntry {
+            time: addition.time,
+            entry_id: addition.audit_id,
+            id: user_id,
+            user: NamedId {
+                name: addition.name,
+                id: addition.userid,
+            },
+            r#type: AuditLogEntryType::Addition,
+        });
+    }
+
+    {
+        let mut modification_stream = sqlx::query!(
+            r#"SELECT time, 
+                  audit_id,
+                  members.name AS "username?",
+                  userid,
+                  user_modifications.display_name::TEXT,
+                  user_modifications.youtube_channel::TEXT,
+                  user_modifications.permissions::INTEGER
+                  FROM user_modifications 
+                  LEFT OUTER JOIN members ON members.member_id = userid
+                  WHERE user_modifications.id = $1
+                  ORDER BY time"#,
+            user_id
+        )
+        .fetch(&mut *connection);
+
+        while let Some(modification) = modification_stream.next().await {
+            let modification = modification?;
+
+            entries.push(AuditLogEntry {
+                time: modification.time,
+                entry_id: modification.audit_id,
+                id: user_id,
+                r#type: AuditLogEntryType::Modification(UserModificationData {
+                    display_name: modification.display_name,
+                    youtube_channel: modification.youtube_channel,
+                    permissions: modification.permissions.map(|permissions| permissions as u16),
+                }),
+                user: NamedId {
+                    name: modification.username,
+                    id: modification.userid,
+                },
+            })
+        }
+    }
+
+    let deletion_row = sqlx::query!(
+        r#"SELECT time, audit_id, 
+                  userid,
+                  members.name AS "name?"
+                  FROM user_deletions LEFT OUTER JOIN members ON members.member_id = userid WHERE id = $1"#,
+        user_id
+    )
+    .fetch_optional(&mut *connection)
+    .await?;
+
+    if let Some(deletion) = deletion_row {
+        entries.push(AuditLogEntry {
+            time: deletion.time,
+            entry_id: deletion.audit_id,
+            id: user_id,
+            user: NamedId {
+                name: deletion.name,
+                id: deletion.userid,
+            },
+            r#type: AuditLogEntryType::Deletion,
+        });
+    }
+
+    Ok(entries)
+}
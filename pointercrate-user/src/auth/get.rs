@@ -10,7 +10,7 @@ use super::NoAuth;
 impl AuthenticatedUser<NoAuth> {
     pub async fn by_id(id: i32, connection: &mut PgConnection) -> Result<Self> {
         let row = sqlx::query!(
-            r#"SELECT member_id, members.name, permissions::integer, display_name, youtube_channel::text, password_hash, generation, google_account_id FROM members WHERE member_id = $1"#,
+            r#"SELECT member_id, members.name, permissions::integer, ARRAY(SELECT role FROM member_roles WHERE member_roles.member_id = members.member_id ORDER BY role) AS "roles!", display_name, youtube_channel::text, password_hash, generation, google_account_id FROM members WHERE member_id = $1"#,
             id
         )
         .fetch_one(connection)
@@ -42,7 +42,7 @@ impl AuthenticatedUser<NoAuth> {
 
     pub async fn by_name(name: &str, connection: &mut PgConnection) -> Result<Self> {
         let row = sqlx::query!(
-            r#"SELECT member_id, members.name, permissions::integer, display_name, youtube_channel::text, password_hash, generation, google_account_id FROM members WHERE members.name = $1"#,
+            r#"SELECT member_id, members.name, permissions::integer, ARRAY(SELECT role FROM member_roles WHERE member_roles.member_id = members.member_id ORDER BY role) AS "roles!", display_name, youtube_channel::text, password_hash, generation, google_account_id FROM members WHERE members.name = $1"#,
             name.to_string()
         )
         .fetch_one(connection)
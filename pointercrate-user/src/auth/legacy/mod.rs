@@ -24,6 +24,10 @@ impl LegacyAuthenticatedUser {
         &self.user
     }
 
+    pub(super) fn user_mut(&mut self) -> &mut User {
+        &mut self.user
+    }
+
     pub(super) fn verify(&self, password: &str) -> Result<(), CoreError> {
         let valid = bcrypt::verify(password, &self.password_hash)
             .inspect_err(|bcrypt_err| {
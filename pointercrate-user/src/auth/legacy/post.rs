@@ -46,6 +46,7 @@ mod register {
                                 id,
                                 name: registration.name,
                                 permissions: 0,
+                                roles: Vec::new(),
                                 display_name: None,
                                 youtube_channel: None,
                             },
@@ -16,6 +16,8 @@
 //!    - non-`GET`: These are authenticated using the session token, which is validated using the csrf token.
 //!      Browser-based auth allows both administrative account actions (except changing password) and API access
 //! 3. HTTP Bearer Auth: Authenticating using a bearer token allows API access, but does not allow user account actions.
+//!    Bearer tokens are either the token returned on login, which has all permissions of its user, or a personal access
+//!    token (see [`token`]), which may be restricted in scope.
 //!
 //! See [`AuthenticatedUser`] for implementation details.
 
@@ -24,7 +26,7 @@ use crate::{config, User};
 use jsonwebtoken::{DecodingKey, EncodingKey, Validation};
 use legacy::LegacyAuthenticatedUser;
 use pointercrate_core::error::Result;
-use pointercrate_core::{error::CoreError, util::csprng_u64};
+use pointercrate_core::{error::CoreError, permission::PermissionsManager, util::csprng_u64};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
@@ -34,6 +36,7 @@ pub mod legacy;
 pub mod oauth;
 mod patch;
 mod post;
+pub mod token;
 
 /// Indicates that no authentication has occurred yet
 ///
@@ -99,6 +102,10 @@ pub struct AccessClaims {
     #[serde(skip_serializing_if = "Option::is_none", default)]
     session_uuid: Option<u64>,
 
+    /// The id of the personal access token these claims belong to, if any. See [`token::AccessToken`]
+    #[serde(skip_serializing_if = "Option::is_none", default, rename = "tid")]
+    token_id: Option<i32>,
+
     /// A generation ID that allows token invalidation. Tokens are only accepted if the generation
     /// ID in the token matches the current generation id on the [`AuthenticatedUser`]
     gen: i64,
@@ -116,6 +123,10 @@ impl AccessClaims {
     pub fn id(&self) -> Result<i32> {
         self.sub.parse().map_err(|_| CoreError::Unauthorized)
     }
+
+    pub fn token_id(&self) -> Option<i32> {
+        self.token_id
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -131,13 +142,13 @@ pub fn generate_jwt<C: Serialize>(claims: &C) -> String {
     jsonwebtoken::encode(
         &jsonwebtoken::Header::default(),
         &claims,
-        &EncodingKey::from_secret(&config::secret()),
+        &EncodingKey::from_secret(config::secret()),
     )
     .unwrap()
 }
 
 pub fn decode_jwt<C: DeserializeOwned>(jwt: &str, validation: &Validation) -> Result<C> {
-    jsonwebtoken::decode::<C>(jwt, &DecodingKey::from_secret(&config::secret()), validation)
+    jsonwebtoken::decode::<C>(jwt, &DecodingKey::from_secret(config::secret()), validation)
         .map_err(|_| CoreError::Unauthorized)
         .map(|token_data| token_data.claims)
 }
@@ -161,6 +172,13 @@ impl<Auth> AuthenticatedUser<Auth> {
         }
     }
 
+    fn user_mut(&mut self) -> &mut User {
+        match &mut self.auth_type {
+            AuthenticationType::Legacy(legacy) => legacy.user_mut(),
+            AuthenticationType::Oauth2(oauth) => oauth.user_mut(),
+        }
+    }
+
     pub fn validate_jwt<C: DeserializeOwned>(&self, jwt: &str, mut validation: Validation) -> Result<C> {
         validation.sub = Some(self.user().id.to_string());
         validation.required_spec_claims.insert("sub".to_string());
@@ -174,20 +192,60 @@ impl<Auth> AuthenticatedUser<Auth> {
 }
 
 impl AuthenticatedUser<NoAuth> {
-    pub fn validate_api_access(self, claims: AccessClaims) -> Result<AuthenticatedUser<ApiToken>> {
+    /// Validates the given claims of a bearer token for API access
+    ///
+    /// If the claims belong to a personal access token, `token` must be that token (see [`token::AccessToken::by_claims`]), and
+    /// its scope is enforced: Expired tokens are rejected, read-only tokens cannot be used for `mutating` requests, and if the
+    /// token is restricted to some permissions, the returned user only holds those of them that it actually has (taking
+    /// implications into account).
+    pub fn validate_api_access(
+        mut self, claims: AccessClaims, token: Option<&token::AccessToken>, mutating: bool, permissions: &PermissionsManager,
+    ) -> Result<AuthenticatedUser<ApiToken>> {
         if claims.gen != self.gen || claims.id()? != self.user().id || claims.session_uuid.is_some() {
-            Err(CoreError::Unauthorized)
-        } else {
-            Ok(AuthenticatedUser {
-                gen: self.gen,
-                auth_type: self.auth_type,
-                auth_artifact: ApiToken,
-            })
+            return Err(CoreError::Unauthorized);
         }
+
+        if claims.token_id != token.map(|token| token.id) {
+            return Err(CoreError::Unauthorized);
+        }
+
+        if let Some(token) = token {
+            if token.member_id != self.user().id || token.is_expired() {
+                return Err(CoreError::Unauthorized);
+            }
+
+            if token.read_only && mutating {
+                return Err(CoreError::Forbidden);
+            }
+
+            if let Some(ref scope) = token.permissions {
+                let implied = permissions.implied_for(self.user());
+                let granted = scope
+                    .iter()
+                    .filter_map(|name| permissions.role(name))
+                    .filter(|perm| implied.contains(perm))
+                    .collect::<Vec<_>>();
+
+                let user = self.user_mut();
+
+                user.permissions = granted.iter().fold(0, |bits, perm| bits | perm.bit());
+                user.roles = granted
+                    .iter()
+                    .filter(|perm| perm.bit() == 0)
+                    .map(|perm| perm.text_id().to_string())
+                    .collect();
+            }
+        }
+
+        Ok(AuthenticatedUser {
+            gen: self.gen,
+            auth_type: self.auth_type,
+            auth_artifact: ApiToken,
+        })
     }
 
     pub fn validate_cookie_claims(self, claims: AccessClaims) -> Result<AuthenticatedUser<NonMutating>> {
-        if claims.gen != self.gen || claims.id()? != self.user().id || claims.session_uuid.is_none() {
+        if claims.gen != self.gen || claims.id()? != self.user().id || claims.session_uuid.is_none() || claims.token_id.is_some() {
             Err(CoreError::Unauthorized)
         } else {
             Ok(AuthenticatedUser {
@@ -238,6 +296,7 @@ impl AuthenticatedUser<PasswordOrBrowser> {
         let access_claims = AccessClaims {
             sub: self.user().id.to_string(),
             session_uuid: Some(session_uuid),
+            token_id: None,
             gen: self.gen,
         };
 
@@ -261,6 +320,7 @@ impl AuthenticatedUser<PasswordOrBrowser> {
         generate_jwt(&AccessClaims {
             sub: self.user().id.to_string(),
             session_uuid: None,
+            token_id: None,
             gen: self.gen,
         })
     }
@@ -280,7 +340,12 @@ impl AuthenticatedUser<PasswordOrBrowser> {
 
 #[cfg(test)]
 mod tests {
-    use crate::auth::{AccessClaims, AuthenticatedUser, User};
+    use crate::auth::{token::AccessToken, AccessClaims, AuthenticatedUser, User};
+    use chrono::{Duration, Utc};
+    use pointercrate_core::{
+        error::CoreError,
+        permission::{Permission, PermissionsManager},
+    };
 
     use super::{AuthenticationType, NoAuth};
 
@@ -291,6 +356,7 @@ mod tests {
                     id: 0,
                     name: "Patrick".to_string(),
                     permissions: 0,
+                    roles: Vec::new(),
                     display_name: None,
                     youtube_channel: None,
                 },
@@ -308,6 +374,7 @@ mod tests {
                     id: 1,
                     name: "".to_string(),
                     permissions: 0,
+                    roles: Vec::new(),
                     display_name: None,
                     youtube_channel: None,
                 },
@@ -327,6 +394,8 @@ mod tests {
 
     #[test]
     fn test_token_pair() {
+        let permissions = PermissionsManager::new(vec![]);
+
         let (patricks_access_token, patricks_csrf_token) = make_patrick()
             .verify_password("bad password")
             .unwrap()
@@ -345,7 +414,9 @@ mod tests {
 
         // cookie tokens don't work for ApiToken authentication
         let patricks_access_claims = AccessClaims::decode(&patricks_access_token).unwrap();
-        assert!(make_patrick().validate_api_access(patricks_access_claims).is_err());
+        assert!(make_patrick()
+            .validate_api_access(patricks_access_claims, None, false, &permissions)
+            .is_err());
 
         // cannot decode csrf token as access token
         assert!(AccessClaims::decode(&patricks_csrf_token).is_err());
@@ -378,27 +449,33 @@ mod tests {
 
     #[test]
     fn test_programmatic_access_token() {
+        let permissions = PermissionsManager::new(vec![]);
+
         let patrick = make_patrick().verify_password("bad password").unwrap();
 
         let patricks_access_token = patrick.generate_programmatic_access_token();
 
         assert!(make_patrick()
-            .validate_api_access(AccessClaims::decode(&patricks_access_token).unwrap())
+            .validate_api_access(AccessClaims::decode(&patricks_access_token).unwrap(), None, false, &permissions)
             .is_ok());
         assert!(make_jacob()
-            .validate_api_access(AccessClaims::decode(&patricks_access_token).unwrap())
+            .validate_api_access(AccessClaims::decode(&patricks_access_token).unwrap(), None, false, &permissions)
             .is_err());
     }
 
     #[test]
     fn test_generation_id_change_invalidates_tokens() {
+        let permissions = PermissionsManager::new(vec![]);
+
         let access_token = make_patrick()
             .verify_password("bad password")
             .unwrap()
             .generate_programmatic_access_token();
         let mut patrick = make_patrick();
         patrick.gen = 1;
-        assert!(patrick.validate_api_access(AccessClaims::decode(&access_token).unwrap()).is_err());
+        assert!(patrick
+            .validate_api_access(AccessClaims::decode(&access_token).unwrap(), None, false, &permissions)
+            .is_err());
 
         let (access_token, _) = make_patrick()
             .verify_password("bad password")
@@ -411,4 +488,97 @@ mod tests {
             .validate_cookie_claims(AccessClaims::decode(&access_token).unwrap())
             .is_err());
     }
+
+    const HELPER: Permission = Permission::new("helper", 0x1);
+    const MODERATOR: Permission = Permission::new("moderator", 0x2);
+
+    fn make_token(read_only: bool, permissions: Option<Vec<String>>, expires_at: Option<chrono::DateTime<Utc>>) -> AccessToken {
+        AccessToken {
+            id: 1,
+            member_id: 0,
+            name: "Patrick's token".to_string(),
+            read_only,
+            permissions,
+            created_at: Utc::now(),
+            expires_at,
+        }
+    }
+
+    fn token_claims(sub: i32, token_id: i32) -> AccessClaims {
+        AccessClaims {
+            sub: sub.to_string(),
+            session_uuid: None,
+            token_id: Some(token_id),
+            gen: 0,
+        }
+    }
+
+    #[test]
+    fn test_personal_access_token() {
+        let permissions = PermissionsManager::new(vec![]);
+        let token = make_token(false, None, None);
+
+        assert!(make_patrick()
+            .validate_api_access(token_claims(0, 1), Some(&token), false, &permissions)
+            .is_ok());
+
+        // claims of personal access tokens are only valid together with the token they belong to
+        assert!(make_patrick()
+            .validate_api_access(token_claims(0, 1), None, false, &permissions)
+            .is_err());
+        assert!(make_patrick()
+            .validate_api_access(token_claims(0, 2), Some(&token), false, &permissions)
+            .is_err());
+        assert!(make_jacob()
+            .validate_api_access(token_claims(1, 1), Some(&token), false, &permissions)
+            .is_err());
+
+        // expired tokens are rejected
+        let token = make_token(false, None, Some(Utc::now() - Duration::hours(1)));
+
+        assert!(make_patrick()
+            .validate_api_access(token_claims(0, 1), Some(&token), false, &permissions)
+            .is_err());
+    }
+
+    #[test]
+    fn test_read_only_access_token() {
+        let permissions = PermissionsManager::new(vec![]);
+        let token = make_token(true, None, Some(Utc::now() + Duration::hours(1)));
+
+        assert!(make_patrick()
+            .validate_api_access(token_claims(0, 1), Some(&token), false, &permissions)
+            .is_ok());
+        assert!(matches!(
+            make_patrick().validate_api_access(token_claims(0, 1), Some(&token), true, &permissions),
+            Err(CoreError::Forbidden)
+        ));
+    }
+
+    #[test]
+    fn test_access_token_scope() {
+        let permissions = PermissionsManager::new(vec![HELPER, MODERATOR]).implies(MODERATOR, HELPER);
+
+        let mut patrick = make_patrick();
+        patrick.user_mut().permissions = MODERATOR.bit();
+
+        // a token restricted to an implied permission only grants that permission
+        let token = make_token(false, Some(vec![HELPER.text_id().to_string()]), None);
+        let scoped = patrick
+            .validate_api_access(token_claims(0, 1), Some(&token), false, &permissions)
+            .unwrap();
+
+        assert_eq!(scoped.user().permissions, HELPER.bit());
+
+        // a token cannot grant permissions its user does not have
+        let mut patrick = make_patrick();
+        patrick.user_mut().permissions = HELPER.bit();
+
+        let token = make_token(false, Some(vec![MODERATOR.text_id().to_string()]), None);
+        let scoped = patrick
+            .validate_api_access(token_claims(0, 1), Some(&token), false, &permissions)
+            .unwrap();
+
+        assert_eq!(scoped.user().permissions, 0);
+    }
 }
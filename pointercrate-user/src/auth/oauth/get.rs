@@ -10,7 +10,7 @@ use super::ValidatedGoogleCredentials;
 impl AuthenticatedUser<PasswordOrBrowser> {
     pub async fn by_validated_google_creds(creds: &ValidatedGoogleCredentials, connection: &mut PgConnection) -> Result<Self> {
         let row = sqlx::query!(
-            r#"SELECT member_id, members.name, permissions::integer, display_name, youtube_channel::text, password_hash, generation, google_account_id FROM members WHERE google_account_id = $1"#,
+            r#"SELECT member_id, members.name, permissions::integer, ARRAY(SELECT role FROM member_roles WHERE member_roles.member_id = members.member_id ORDER BY role) AS "roles!", display_name, youtube_channel::text, password_hash, generation, google_account_id FROM members WHERE google_account_id = $1"#,
             creds.google_account_id()
         )
         .fetch_one(connection)
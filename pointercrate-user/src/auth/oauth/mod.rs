@@ -24,6 +24,10 @@ impl OA2AuthenticatedUser {
     pub fn user(&self) -> &User {
         &self.user
     }
+
+    pub(super) fn user_mut(&mut self) -> &mut User {
+        &mut self.user
+    }
 }
 
 impl AuthenticationType {
@@ -1,7 +1,7 @@
 use crate::auth::{AuthenticatedUser, AuthenticationType, PasswordOrBrowser};
 use crate::error::UserError;
 use crate::Result;
-use crate::{config, User};
+use crate::User;
 use chrono::{DateTime, Utc};
 use jsonwebtoken::{Algorithm, DecodingKey, Validation};
 use pointercrate_core::error::CoreError;
@@ -55,13 +55,14 @@ impl GoogleCertificateDatabase {
         }
     }
 
-    pub fn validate_credentials(&self, creds: UnvalidatedOauthCredential) -> Option<ValidatedGoogleCredentials> {
+    /// Validates that the given credentials were issued by google to the OAuth application with the given client ID
+    pub fn validate_credentials(&self, creds: UnvalidatedOauthCredential, client_id: &str) -> Option<ValidatedGoogleCredentials> {
         let header = jsonwebtoken::decode_header(&creds.credential).ok()?;
         let key = self.keys.iter().find(|key| Some(key.kid.as_ref()) == header.kid.as_deref())?;
 
         let mut validation = Validation::new(key.alg);
         validation.set_issuer(&["accounts.google.com", "https://accounts.google.com"]);
-        validation.set_audience(&[config::google_client_id()]);
+        validation.set_audience(&[client_id]);
         validation.required_spec_claims.extend(["iss".to_string(), "aud".to_string()]);
 
         jsonwebtoken::decode(
@@ -129,6 +130,7 @@ impl AuthenticatedUser<PasswordOrBrowser> {
                 id,
                 name: username,
                 permissions: 0,
+                roles: Vec::new(),
                 display_name: None,
                 youtube_channel: None,
             }),
@@ -58,6 +58,7 @@ impl AuthenticatedUser<PasswordOrBrowser> {
                     display_name: patch.display_name,
                     youtube_channel: patch.youtube_channel,
                     permissions: None,
+                    roles: None,
                 },
                 connection,
             )
@@ -106,6 +107,9 @@ mod tests {
     #[sqlx::test(migrations = "../migrations")]
     async fn test_password_change_invalidates_tokens(mut conn: sqlx::pool::PoolConnection<sqlx::Postgres>) {
         use crate::auth::{legacy::Registration, AccessClaims, AuthenticatedUser, PatchMe};
+        use pointercrate_core::permission::PermissionsManager;
+
+        let permissions = PermissionsManager::new(vec![]);
 
         let patrick = AuthenticatedUser::register(
             Registration {
@@ -131,6 +135,8 @@ mod tests {
             .unwrap();
         let patrick = AuthenticatedUser::by_id(patrick.id, &mut conn).await.unwrap();
 
-        assert!(patrick.validate_api_access(AccessClaims::decode(&token).unwrap()).is_err());
+        assert!(patrick
+            .validate_api_access(AccessClaims::decode(&token).unwrap(), None, false, &permissions)
+            .is_err());
     }
 }
@@ -24,6 +24,9 @@ mod tests {
     #[sqlx::test(migrations = "../migrations")]
     fn test_invalidate_all_tokens(mut conn: sqlx::pool::PoolConnection<sqlx::Postgres>) {
         use crate::auth::{legacy::Registration, AccessClaims, AuthenticatedUser};
+        use pointercrate_core::permission::PermissionsManager;
+
+        let permissions = PermissionsManager::new(vec![]);
 
         let registration = Registration {
             name: "Patrick".to_string(),
@@ -36,14 +39,14 @@ mod tests {
 
         let access_token = patrick.generate_programmatic_access_token();
         assert!(patricks_clone
-            .validate_api_access(AccessClaims::decode(&access_token).unwrap())
+            .validate_api_access(AccessClaims::decode(&access_token).unwrap(), None, false, &permissions)
             .is_ok()); // sanity check
 
         patrick.invalidate_all_tokens(&mut conn).await.unwrap();
 
         let patricks_clone = AuthenticatedUser::by_id(patricks_id, &mut conn).await.unwrap();
         assert!(patricks_clone
-            .validate_api_access(AccessClaims::decode(&access_token).unwrap())
+            .validate_api_access(AccessClaims::decode(&access_token).unwrap(), None, false, &permissions)
             .is_err());
     }
 }
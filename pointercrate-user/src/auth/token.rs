@@ -0,0 +1,150 @@
+//! Personal access tokens
+//!
+//! Unlike the token returned when logging in, personal access tokens are named, can be listed and revoked individually,
+//! can optionally expire, and can be restricted in scope (see [`AuthenticatedUser::validate_api_access`]).
+
+use crate::{
+    auth::{generate_jwt, AccessClaims, AuthenticatedUser, PasswordOrBrowser},
+    error::{Result, UserError},
+};
+use chrono::{DateTime, Utc};
+use log::info;
+use pointercrate_core::{error::CoreError, etag::Taggable};
+use serde::{Deserialize, Serialize};
+use sqlx::{Error, PgConnection};
+
+#[derive(Debug, Serialize, Hash, Eq, PartialEq)]
+pub struct AccessToken {
+    pub id: i32,
+
+    #[serde(skip)]
+    pub member_id: i32,
+
+    pub name: String,
+
+    /// Whether this token can only be used for requests that do not modify any data
+    pub read_only: bool,
+
+    /// The names of the permissions (or roles) this token is restricted to. [`None`] means the token has all permissions of
+    /// the user it belongs to.
+    pub permissions: Option<Vec<String>>,
+
+    pub created_at: DateTime<Utc>,
+
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl Taggable for AccessToken {}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NewAccessToken {
+    pub name: String,
+
+    #[serde(default)]
+    pub read_only: bool,
+
+    #[serde(default)]
+    pub permissions: Option<Vec<String>>,
+
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl AccessToken {
+    pub fn is_expired(&self) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= Utc::now())
+    }
+
+    pub async fn by_id(id: i32, connection: &mut PgConnection) -> Result<AccessToken> {
+        let row = sqlx::query_as!(
+            AccessToken,
+            "SELECT id, member_id, name, read_only, permissions, created_at, expires_at FROM access_tokens WHERE id = $1",
+            id
+        )
+        .fetch_one(connection)
+        .await;
+
+        match row {
+            Err(Error::RowNotFound) => Err(UserError::AccessTokenNotFound { token_id: id }),
+            Err(err) => Err(err.into()),
+            Ok(token) => Ok(token),
+        }
+    }
+
+    /// Loads the access token the given claims belong to, if any
+    ///
+    /// Fails with `401 UNAUTHORIZED` if the claims refer to a token that has since been revoked.
+    pub async fn by_claims(claims: &AccessClaims, connection: &mut PgConnection) -> pointercrate_core::error::Result<Option<AccessToken>> {
+        match claims.token_id() {
+            None => Ok(None),
+            Some(token_id) => match AccessToken::by_id(token_id, connection).await {
+                Ok(token) => Ok(Some(token)),
+                Err(UserError::AccessTokenNotFound { .. }) => Err(CoreError::Unauthorized),
+                Err(UserError::Core(core)) => Err(core),
+                Err(err) => Err(CoreError::internal_server_error(err.to_string())),
+            },
+        }
+    }
+
+    pub async fn by_member(member_id: i32, connection: &mut PgConnection) -> Result<Vec<AccessToken>> {
+        Ok(sqlx::query_as!(
+            AccessToken,
+            "SELECT id, member_id, name, read_only, permissions, created_at, expires_at FROM access_tokens WHERE member_id = $1 ORDER \
+             BY id",
+            member_id
+        )
+        .fetch_all(connection)
+        .await?)
+    }
+
+    /// Revokes this token
+    pub async fn delete(self, connection: &mut PgConnection) -> Result<()> {
+        info!("Revoking access token {} ('{}') of user {}", self.id, self.name, self.member_id);
+
+        sqlx::query!("DELETE FROM access_tokens WHERE id = $1", self.id)
+            .execute(connection)
+            .await?;
+
+        Ok(())
+    }
+}
+
+impl AuthenticatedUser<PasswordOrBrowser> {
+    /// Creates a new personal access token, returning it together with the JWT that can be used to authenticate with it.
+    ///
+    /// Verifying that the user actually has the permissions the token is restricted to is up to the caller.
+    pub async fn create_access_token(&self, data: NewAccessToken, connection: &mut PgConnection) -> Result<(AccessToken, String)> {
+        if data.name.is_empty() || data.name != data.name.trim() {
+            return Err(UserError::InvalidTokenName);
+        }
+
+        if data.expires_at.is_some_and(|expires_at| expires_at <= Utc::now()) {
+            return Err(UserError::TokenExpiryInPast);
+        }
+
+        let token = sqlx::query_as!(
+            AccessToken,
+            "INSERT INTO access_tokens (member_id, name, read_only, permissions, expires_at) VALUES ($1, $2, $3, $4, $5) RETURNING id, \
+             member_id, name, read_only, permissions, created_at, expires_at",
+            self.user().id,
+            data.name,
+            data.read_only,
+            data.permissions.as_deref(),
+            data.expires_at
+        )
+        .fetch_one(connection)
+        .await?;
+
+        info!("Created access token {} ('{}') for user {}", token.id, token.name, self.user());
+
+        let jwt = generate_jwt(&AccessClaims {
+            sub: self.user().id.to_string(),
+            session_uuid: None,
+            token_id: Some(token.id),
+            gen: self.gen,
+        });
+
+        Ok((token, jwt))
+    }
+}
@@ -1,25 +1,22 @@
-use std::{fs::File, io::Read};
+use pointercrate_core::config::{ConfigSection, ConfigSource};
 
-use pointercrate_core::util::from_env_or_default;
+pub(crate) use pointercrate_core::config::secret;
 
-pub(crate) fn secret() -> Vec<u8> {
-    let path: String = from_env_or_default("SECRET_FILE", ".secret".into());
+/// The `[user]` section of the configuration
+#[derive(Debug, Clone, Default)]
+pub struct UserConfig {
+    /// The client ID of this server's Google OAuth application. Required if the `oauth2` feature is enabled.
+    pub google_client_id: Option<String>,
+}
 
-    match File::open(path) {
-        Ok(file) => file.bytes().collect::<Result<Vec<u8>, _>>().unwrap(),
-        Err(err) if cfg!(debug_assertions) => {
-            // needed for integration tests/CI
-            log::error!(
-                "Failed to read secret, using an unsecure default since this is a debug build - {:?}",
-                err
-            );
+impl ConfigSection for UserConfig {
+    fn load(source: &mut ConfigSource) -> Option<Self> {
+        let google_client_id = if cfg!(feature = "oauth2") {
+            Some(source.require("user.google_client_id", "GOOGLE_CLIENT_ID")?)
+        } else {
+            source.get("user.google_client_id", "GOOGLE_CLIENT_ID")
+        };
 
-            vec![0x0; 64]
-        },
-        Err(err) => panic!("Unable to open secret file: {:?}", err),
+        Some(UserConfig { google_client_id })
     }
 }
-
-pub fn google_client_id() -> String {
-    std::env::var("GOOGLE_CLIENT_ID").expect("GOOGLE_CLIENT_ID is not set")
-}
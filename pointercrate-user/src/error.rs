@@ -39,6 +39,31 @@ pub enum UserError {
         user_name: String,
     },
 
+    /// `404 NOT FOUND` error returned if a role with the given name does not exist
+    ///
+    /// Error Code `40401`
+    RoleNotFound {
+        role: String,
+    },
+
+    /// `404 NOT FOUND` error returned if the current user has no access token with the given id
+    ///
+    /// Error Code `40401`
+    AccessTokenNotFound {
+        token_id: i32,
+    },
+
+    /// `403 FORBIDDEN` error returned when trying to delete a role that corresponds to a permission
+    /// defined in code, or to grant such a role other than via its permission bit
+    ///
+    /// Error Code `40309`
+    BuiltinRole,
+
+    /// `409 CONFLICT` error returned if a role with the given name already exists
+    ///
+    /// Error Code `40909`
+    RoleExists,
+
     /// `409 CONFLICT` error returned if a user tries to register with a name that's already taken
     ///
     /// Error Code `40902`
@@ -67,6 +92,24 @@ pub enum UserError {
     ///
     /// Error Code `42234`
     NonLegacyAccount,
+
+    /// `422 UNPROCESSABLE ENTITY` variant returned if the name of a newly created role is either
+    /// empty or contains trailing or leading whitespace
+    ///
+    /// Error Code `42236`
+    InvalidRoleName,
+
+    /// `422 UNPROCESSABLE ENTITY` variant returned if the name of a newly created access token is
+    /// either empty or contains trailing or leading whitespace
+    ///
+    /// Error Code `42237`
+    InvalidTokenName,
+
+    /// `422 UNPROCESSABLE ENTITY` variant returned if a newly created access token would already
+    /// be expired
+    ///
+    /// Error Code `42238`
+    TokenExpiryInPast,
 }
 
 impl std::error::Error for UserError {}
@@ -90,11 +133,25 @@ impl PointercrateError for UserError {
             PermissionNotAssignable { .. } => 40305,
             UserNotFound { .. } => 40401,
             UserNotFoundName { .. } => 40401,
+            RoleNotFound { .. } => 40401,
+            AccessTokenNotFound { .. } => 40401,
+            BuiltinRole => 40309,
+            RoleExists => 40909,
             NameTaken => 40902,
             InvalidUsername => 42202,
             InvalidPassword => 42204,
             NotYouTube => 42226,
             NonLegacyAccount => 42234,
+            InvalidRoleName => 42236,
+            InvalidTokenName => 42237,
+            TokenExpiryInPast => 42238,
+        }
+    }
+
+    fn headers(&self) -> Vec<(&'static str, String)> {
+        match self {
+            UserError::Core(core) => core.headers(),
+            _ => Vec::new(),
         }
     }
 }
@@ -121,11 +178,18 @@ impl Display for UserError {
                 ),
                 UserError::UserNotFound { user_id } => trp!("error-user-usernotfound", "user-id" = user_id),
                 UserError::UserNotFoundName { user_name } => trp!("error-user-usernotfoundname", "user-name" = user_name),
+                UserError::RoleNotFound { role } => trp!("error-user-rolenotfound", "role" = role),
+                UserError::AccessTokenNotFound { token_id } => trp!("error-user-accesstokennotfound", "token-id" = token_id),
+                UserError::BuiltinRole => tr("error-user-builtinrole"),
+                UserError::RoleExists => tr("error-user-roleexists"),
                 UserError::NameTaken => tr("error-user-nametaken"),
                 UserError::InvalidUsername => tr("error-user-invalidusername"),
                 UserError::InvalidPassword => tr("error-user-invalidpassword"),
                 UserError::NotYouTube => tr("error-user-notyoutube"),
                 UserError::NonLegacyAccount => tr("error-user-nonlegacyaccount"),
+                UserError::InvalidRoleName => tr("error-user-invalidrolename"),
+                UserError::InvalidTokenName => tr("error-user-invalidtokenname"),
+                UserError::TokenExpiryInPast => tr("error-user-tokenexpiryinpast"),
             }
         )
     }
@@ -9,7 +9,7 @@ pub use self::{paginate::UserPagination, patch::PatchUser};
 use crate::error::{Result, UserError};
 use pointercrate_core::{
     etag::Taggable,
-    permission::{Permission, PermissionsManager},
+    permission::{Permission, PermissionHolder, PermissionsManager},
 };
 use serde::Serialize;
 use std::{
@@ -19,12 +19,14 @@ use std::{
 
 #[macro_use]
 mod get;
+pub mod audit;
 pub mod auth;
 pub mod config;
 mod delete;
 pub mod error;
 mod paginate;
 mod patch;
+pub mod role;
 mod video;
 
 pub const ADMINISTRATOR: Permission = Permission::new("user-permissions.administrator", 0x4000);
@@ -47,6 +49,9 @@ pub struct User {
 
     pub permissions: u16,
 
+    /// The names of the roles defined in the database (as opposed to via [`Permission`] bits) that this [`User`] holds
+    pub roles: Vec<String>,
+
     /// A user-customizable name for each [`User`].
     ///
     /// If set to anything other than [`None`], the value set here will be displayed everywhere the
@@ -60,6 +65,16 @@ pub struct User {
 
 impl Taggable for User {}
 
+impl PermissionHolder for User {
+    fn permission_bits(&self) -> u16 {
+        self.permissions
+    }
+
+    fn roles(&self) -> &[String] {
+        &self.roles
+    }
+}
+
 impl Display for User {
     fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
         match self.display_name {
@@ -70,8 +85,11 @@ impl Display for User {
 }
 
 impl User {
+    /// Whether this user directly holds the given permission, either via its bit or as a database-defined role.
+    ///
+    /// Does not take implications into account, use [`PermissionsManager::require_permission`] for that
     pub fn has_permission(&self, permission: Permission) -> bool {
-        self.has_permissions(permission.bit())
+        (permission.bit() != 0 && self.has_permissions(permission.bit())) || self.roles.iter().any(|role| role == permission.text_id())
     }
 
     pub fn has_permissions(&self, perms: u16) -> bool {
@@ -97,3 +115,23 @@ impl User {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::User;
+    use pointercrate_core::etag::Taggable;
+
+    #[test]
+    fn test_pinned_etag() {
+        let user = User {
+            id: 1,
+            name: "stadust".to_string(),
+            permissions: 0x4000,
+            roles: vec!["moderator".to_string()],
+            display_name: Some("stardust1971".to_string()),
+            youtube_channel: None,
+        };
+
+        assert_eq!(user.etag_string(), "W/\"v1:17377509952462924302;17377509952462924302\"");
+    }
+}
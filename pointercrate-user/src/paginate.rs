@@ -2,12 +2,16 @@ use crate::{error::Result, User};
 use futures::StreamExt;
 use pointercrate_core::{
     first_and_last,
-    pagination::{PageContext, Paginatable, PaginationParameters, PaginationQuery, __pagination_compat},
+    pagination::{count_query, Paginatable, PaginationParameters, PaginationQuery},
     permission::Permission,
     util::{non_nullable, nullable},
 };
 use serde::{Deserialize, Serialize};
-use sqlx::{postgres::PgRow, PgConnection, Row};
+use sqlx::{
+    postgres::{PgArguments, PgRow},
+    query::Query,
+    PgConnection, Postgres, Row,
+};
 
 #[derive(Deserialize, Debug, Clone, Serialize)]
 pub struct UserPagination {
@@ -32,7 +36,7 @@ pub struct UserPagination {
 
 impl PaginationQuery for UserPagination {
     fn parameters(&self) -> PaginationParameters {
-        self.params
+        self.params.clone()
     }
 
     fn with_parameters(&self, parameters: PaginationParameters) -> Self {
@@ -41,27 +45,42 @@ impl PaginationQuery for UserPagination {
             ..self.clone()
         }
     }
+
+    fn bind_filters<'q>(&'q self, sql_query: Query<'q, Postgres, PgArguments>) -> Query<'q, Postgres, PgArguments> {
+        sql_query
+            .bind(self.name.as_ref())
+            .bind(self.display_name.as_ref())
+            .bind(self.display_name == Some(None))
+            .bind(self.has_permissions.map(|p| p as i32))
+            .bind(self.any_permissions.map(|p| p as i32))
+            .bind(self.name_contains.as_ref())
+    }
 }
 
 impl Paginatable<UserPagination> for User {
     first_and_last!("members", "member_id");
 
-    async fn page(query: &UserPagination, connection: &mut PgConnection) -> std::result::Result<(Vec<User>, PageContext), sqlx::Error> {
+    async fn count(query: &UserPagination, connection: &mut PgConnection) -> std::result::Result<i64, sqlx::Error> {
+        let sql_query = count_query(&format!(include_str!("../sql/paginate_users.sql"), "ASC"));
+
+        let sql_query = sqlx::query(&sql_query).bind(None::<i32>).bind(None::<i32>);
+
+        query
+            .bind_filters(sql_query)
+            .bind(None::<i32>)
+            .fetch_one(connection)
+            .await?
+            .try_get(0)
+    }
+
+    async fn fetch(query: &UserPagination, connection: &mut PgConnection) -> std::result::Result<Vec<User>, sqlx::Error> {
         let order = query.params.order();
 
         let sql_query = format!(include_str!("../sql/paginate_users.sql"), order);
 
-        let mut stream = sqlx::query(&sql_query)
-            .bind(query.params.before)
-            .bind(query.params.after)
-            .bind(query.name.as_ref())
-            .bind(query.display_name.as_ref())
-            .bind(query.display_name == Some(None))
-            .bind(query.has_permissions.map(|p| p as i32))
-            .bind(query.any_permissions.map(|p| p as i32))
-            .bind(query.name_contains.as_ref())
-            .bind(query.params.limit + 1)
-            .fetch(connection);
+        let sql_query = sqlx::query(&sql_query).bind(query.params.before).bind(query.params.after);
+
+        let mut stream = query.bind_filters(sql_query).bind(query.params.limit + 1).fetch(connection);
 
         let mut users = Vec::new();
 
@@ -74,12 +93,13 @@ impl Paginatable<UserPagination> for User {
                 id: row.get("member_id"),
                 name: row.get("name"),
                 permissions: perms_as_i32 as u16,
+                roles: row.get("roles"),
                 display_name: row.get("display_name"),
                 youtube_channel: row.get("youtube_channel"),
             })
         }
 
-        Ok(__pagination_compat(&query.params, users))
+        Ok(users)
     }
 
     fn pagination_id(&self) -> i32 {
@@ -95,7 +115,8 @@ impl User {
     /// Gets all users that have the given permission bits all set
     pub async fn by_permissions(permissions: u16, connection: &mut PgConnection) -> Result<Vec<User>> {
         let mut stream = sqlx::query!(
-            "SELECT member_id, name, permissions::integer, display_name, youtube_channel::text FROM members WHERE permissions & \
+            "SELECT member_id, name, permissions::integer, ARRAY(SELECT role FROM member_roles WHERE member_roles.member_id = \
+             members.member_id ORDER BY role) AS \"roles!\", display_name, youtube_channel::text FROM members WHERE permissions & \
              CAST($1::INTEGER AS BIT(16)) = CAST($1::INTEGER AS BIT(16))",
             permissions as i32
         )
@@ -110,6 +131,7 @@ impl User {
                 id: row.member_id,
                 name: row.name,
                 permissions: row.permissions.unwrap() as u16,
+                roles: row.roles,
                 display_name: row.display_name,
                 youtube_channel: row.youtube_channel,
             })
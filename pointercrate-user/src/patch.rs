@@ -1,4 +1,8 @@
-use crate::{error::Result, User};
+use crate::{
+    error::{Result, UserError},
+    role::Role,
+    User,
+};
 use log::info;
 use pointercrate_core::util::{non_nullable, nullable};
 use serde::Deserialize;
@@ -16,6 +20,10 @@ pub struct PatchUser {
     #[serde(default, deserialize_with = "non_nullable")]
     #[allow(clippy::option_option)]
     pub permissions: Option<u16>,
+
+    /// The names of the database-defined roles the user should hold
+    #[serde(default, deserialize_with = "non_nullable")]
+    pub roles: Option<Vec<String>>,
 }
 
 impl User {
@@ -27,6 +35,10 @@ impl User {
             self.set_permissions(permissions, connection).await?;
         }
 
+        if let Some(roles) = patch.roles {
+            self.set_roles(roles, connection).await?;
+        }
+
         if let Some(display_name) = patch.display_name {
             match display_name {
                 Some(display_name) => self.set_display_name(display_name, connection).await?,
@@ -58,6 +70,43 @@ impl User {
         Ok(())
     }
 
+    /// Replaces the database-defined roles this user holds
+    pub async fn set_roles(&mut self, mut roles: Vec<String>, connection: &mut PgConnection) -> Result<()> {
+        roles.sort();
+        roles.dedup();
+
+        Role::require_existence(&roles, connection).await?;
+
+        // Roles corresponding to permissions defined in code are held via permission bits instead
+        let builtin = sqlx::query!(
+            r#"SELECT EXISTS (SELECT 1 FROM roles WHERE name = ANY($1::TEXT[]) AND bit IS NOT NULL) AS "builtin!""#,
+            &roles
+        )
+        .fetch_one(&mut *connection)
+        .await?
+        .builtin;
+
+        if builtin {
+            return Err(UserError::BuiltinRole);
+        }
+
+        sqlx::query!("DELETE FROM member_roles WHERE member_id = $1", self.id)
+            .execute(&mut *connection)
+            .await?;
+
+        sqlx::query!(
+            "INSERT INTO member_roles (member_id, role) SELECT $1, UNNEST($2::TEXT[])",
+            self.id,
+            &roles
+        )
+        .execute(&mut *connection)
+        .await?;
+
+        self.roles = roles;
+
+        Ok(())
+    }
+
     pub async fn set_display_name(&mut self, display_name: String, connection: &mut PgConnection) -> Result<()> {
         Self::validate_name(&display_name)?;
 
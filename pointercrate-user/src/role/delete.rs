@@ -0,0 +1,26 @@
+use crate::{
+    error::{Result, UserError},
+    role::Role,
+};
+use log::warn;
+use pointercrate_core::{cache::invalidate, permission::ROLES};
+use sqlx::PgConnection;
+
+impl Role {
+    /// Deletes this role, revoking it from all users holding it
+    pub async fn delete(self, connection: &mut PgConnection) -> Result<()> {
+        if self.is_builtin() {
+            return Err(UserError::BuiltinRole);
+        }
+
+        warn!("Deleting {}", self);
+
+        sqlx::query!("DELETE FROM roles WHERE name = $1", self.name)
+            .execute(&mut *connection)
+            .await?;
+
+        invalidate(ROLES, connection).await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,61 @@
+use crate::{
+    error::{Result, UserError},
+    role::Role,
+};
+use sqlx::{Error, PgConnection};
+
+impl Role {
+    pub async fn by_name(name: &str, connection: &mut PgConnection) -> Result<Role> {
+        let row = sqlx::query!(
+            r#"SELECT name, bit, ARRAY(SELECT implies FROM role_implications WHERE role = name ORDER BY implies) AS "implies!", ARRAY(SELECT assigns FROM role_assignments WHERE role = name ORDER BY assigns) AS "assigns!" FROM roles WHERE name = $1"#,
+            name
+        )
+        .fetch_one(connection)
+        .await;
+
+        match row {
+            Err(Error::RowNotFound) => Err(UserError::RoleNotFound { role: name.to_string() }),
+            Err(err) => Err(err.into()),
+            Ok(row) => Ok(Role {
+                name: row.name,
+                bit: row.bit.map(|bit| bit as u16),
+                implies: row.implies,
+                assigns: row.assigns,
+            }),
+        }
+    }
+
+    pub async fn all(connection: &mut PgConnection) -> Result<Vec<Role>> {
+        let rows = sqlx::query!(
+            r#"SELECT name, bit, ARRAY(SELECT implies FROM role_implications WHERE role = name ORDER BY implies) AS "implies!", ARRAY(SELECT assigns FROM role_assignments WHERE role = name ORDER BY assigns) AS "assigns!" FROM roles ORDER BY name"#
+        )
+        .fetch_all(connection)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| Role {
+                name: row.name,
+                bit: row.bit.map(|bit| bit as u16),
+                implies: row.implies,
+                assigns: row.assigns,
+            })
+            .collect())
+    }
+
+    /// Ensures that all of the given roles exist
+    pub(crate) async fn require_existence(names: &[String], connection: &mut PgConnection) -> Result<()> {
+        for name in names {
+            let exists = sqlx::query!(r#"SELECT EXISTS (SELECT 1 FROM roles WHERE name = $1) AS "exists!""#, name)
+                .fetch_one(&mut *connection)
+                .await?
+                .exists;
+
+            if !exists {
+                return Err(UserError::RoleNotFound { role: name.to_string() });
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,56 @@
+//! Module for roles defined in the database, in addition to the [`Permission`](pointercrate_core::permission::Permission)s
+//! defined in code.
+//!
+//! Every permission defined in code also has a role in the database (see
+//! [`PermissionsManager::synchronize`](pointercrate_core::permission::PermissionsManager::synchronize)), which is held via the
+//! corresponding permission bit. All other roles are held via the `member_roles` table. Relations between roles stored here
+//! only ever extend the ones defined in code.
+
+pub use self::{patch::PatchRole, post::PostRole};
+use crate::error::{Result, UserError};
+use pointercrate_core::etag::Taggable;
+use serde::Serialize;
+use std::fmt::{Display, Formatter};
+
+mod delete;
+mod get;
+mod patch;
+mod post;
+
+#[derive(Debug, Serialize, Hash, Eq, PartialEq)]
+pub struct Role {
+    pub name: String,
+
+    /// The permission bit via which this role is held, if it corresponds to a permission defined in code
+    pub bit: Option<u16>,
+
+    /// The names of the roles implied by this one, in addition to the implications defined in code
+    pub implies: Vec<String>,
+
+    /// The names of the roles that holders of this one can assign, in addition to the assignments defined in code
+    pub assigns: Vec<String>,
+}
+
+impl Taggable for Role {}
+
+impl Display for Role {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "role '{}'", self.name)
+    }
+}
+
+impl Role {
+    pub fn validate_name(name: &str) -> Result<()> {
+        if name.is_empty() || name != name.trim() {
+            return Err(UserError::InvalidRoleName);
+        }
+
+        Ok(())
+    }
+
+    /// Whether this role corresponds to a permission defined in code. Such roles cannot be deleted, and are held via their
+    /// permission bit instead of as a role
+    pub fn is_builtin(&self) -> bool {
+        self.bit.is_some()
+    }
+}
@@ -0,0 +1,80 @@
+use crate::{error::Result, role::Role};
+use log::info;
+use pointercrate_core::{cache::invalidate, permission::ROLES, util::non_nullable};
+use serde::Deserialize;
+use sqlx::PgConnection;
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PatchRole {
+    #[serde(default, deserialize_with = "non_nullable")]
+    pub implies: Option<Vec<String>>,
+
+    #[serde(default, deserialize_with = "non_nullable")]
+    pub assigns: Option<Vec<String>>,
+}
+
+impl Role {
+    /// Must run inside a transaction
+    pub async fn apply_patch(mut self, patch: PatchRole, connection: &mut PgConnection) -> Result<Self> {
+        info!("Applying patch {:?} to {}", patch, self);
+
+        if let Some(implies) = patch.implies {
+            self.set_implies(implies, connection).await?;
+        }
+
+        if let Some(assigns) = patch.assigns {
+            self.set_assigns(assigns, connection).await?;
+        }
+
+        invalidate(ROLES, connection).await?;
+
+        Ok(self)
+    }
+
+    pub async fn set_implies(&mut self, mut implies: Vec<String>, connection: &mut PgConnection) -> Result<()> {
+        implies.sort();
+        implies.dedup();
+
+        Role::require_existence(&implies, connection).await?;
+
+        sqlx::query!("DELETE FROM role_implications WHERE role = $1", self.name)
+            .execute(&mut *connection)
+            .await?;
+
+        sqlx::query!(
+            "INSERT INTO role_implications (role, implies) SELECT $1, UNNEST($2::TEXT[])",
+            self.name,
+            &implies
+        )
+        .execute(&mut *connection)
+        .await?;
+
+        self.implies = implies;
+
+        Ok(())
+    }
+
+    pub async fn set_assigns(&mut self, mut assigns: Vec<String>, connection: &mut PgConnection) -> Result<()> {
+        assigns.sort();
+        assigns.dedup();
+
+        Role::require_existence(&assigns, connection).await?;
+
+        sqlx::query!("DELETE FROM role_assignments WHERE role = $1", self.name)
+            .execute(&mut *connection)
+            .await?;
+
+        sqlx::query!(
+            "INSERT INTO role_assignments (role, assigns) SELECT $1, UNNEST($2::TEXT[])",
+            self.name,
+            &assigns
+        )
+        .execute(&mut *connection)
+        .await?;
+
+        self.assigns = assigns;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,53 @@
+use crate::{
+    error::{Result, UserError},
+    role::Role,
+};
+use log::info;
+use pointercrate_core::{cache::invalidate, permission::ROLES};
+use serde::Deserialize;
+use sqlx::PgConnection;
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PostRole {
+    pub name: String,
+
+    #[serde(default)]
+    pub implies: Vec<String>,
+
+    #[serde(default)]
+    pub assigns: Vec<String>,
+}
+
+impl Role {
+    /// Must run inside a transaction
+    pub async fn create_from(data: PostRole, connection: &mut PgConnection) -> Result<Role> {
+        info!("Creating new role from {:?}", data);
+
+        Role::validate_name(&data.name)?;
+
+        match Role::by_name(&data.name, connection).await {
+            Ok(_) => return Err(UserError::RoleExists),
+            Err(UserError::RoleNotFound { .. }) => (),
+            Err(err) => return Err(err),
+        }
+
+        sqlx::query!("INSERT INTO roles (name) VALUES ($1)", data.name)
+            .execute(&mut *connection)
+            .await?;
+
+        let mut role = Role {
+            name: data.name,
+            bit: None,
+            implies: Vec::new(),
+            assigns: Vec::new(),
+        };
+
+        role.set_implies(data.implies, connection).await?;
+        role.set_assigns(data.assigns, connection).await?;
+
+        invalidate(ROLES, connection).await?;
+
+        Ok(role)
+    }
+}